@@ -1,14 +1,29 @@
 use axum::{
     extract::{Path as AxumPath, State},
-    routing::get,
+    http::HeaderMap,
+    routing::{delete, get},
     Json, Router,
 };
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use crate::db::models::CustomPreset;
+use crate::db::secrets::{self, REDACTED, SECRET_PREFIX};
+use crate::db::{DbError, OUTPUT_KEEP_FULL_LINES_KEY, OUTPUT_MAX_LINE_LENGTH_KEY};
 use crate::error::{AppError, AppResult};
+use crate::events;
+use crate::git::validate_relative_path;
+use crate::ralph::{
+    parse_agent_env, parse_command_allowlist, parse_max_concurrent_sessions,
+    parse_max_crash_restarts, parse_resource_limit, AGENT_ENV_KEY, AGENT_WORKDIR_KEY, BACKEND_KEY,
+    COMMAND_ALLOWLIST_KEY, CPU_TIME_LIMIT_SECS_KEY, FALLBACK_BACKEND_KEY, INTERACTIVE_INPUT_KEY,
+    MAX_CONCURRENT_SESSIONS_KEY, MAX_CRASH_RESTARTS_KEY, MEMORY_LIMIT_MB_KEY,
+};
+use crate::ws::{parse_max_message_size, WS_MAX_MESSAGE_SIZE_KEY};
 
-use super::AppState;
+use super::rate_limit::{parse_rate_limit, RATE_LIMIT_BURST_KEY, RATE_LIMIT_PER_MINUTE_KEY};
+use super::repos::{parse_max_repos, MAX_REPOS_KEY};
+use super::{require_admin, AppState};
 
 /// Response for getting all config values
 #[derive(Debug, Serialize, Deserialize)]
@@ -55,6 +70,34 @@ pub struct Preset {
     pub id: String,
     pub name: String,
     pub description: String,
+    /// Instructions for the agent (custom presets only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt: Option<String>,
+    /// Whether this is a shipped default rather than a user-defined preset
+    #[serde(default)]
+    pub builtin: bool,
+}
+
+impl From<CustomPreset> for Preset {
+    fn from(preset: CustomPreset) -> Self {
+        Self {
+            id: preset.id,
+            name: preset.name,
+            description: preset.description,
+            prompt: Some(preset.prompt),
+            builtin: false,
+        }
+    }
+}
+
+/// Request body for creating or replacing a custom preset
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreatePresetRequest {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub prompt: String,
 }
 
 /// Response for listing available presets
@@ -63,43 +106,139 @@ pub struct PresetsResponse {
     pub presets: Vec<Preset>,
 }
 
+/// Config key holding the id of the preset selected by default
+pub const PRESET_KEY: &str = "preset";
+
+/// Reject malformed values for keys with a known format, and ids that don't
+/// name a known backend or preset (blank values unset the backend or preset)
+pub(crate) fn validate_config(state: &AppState, key: &str, value: &str) -> AppResult<()> {
+    match key {
+        BACKEND_KEY | FALLBACK_BACKEND_KEY if !value.trim().is_empty() => {
+            if builtin_backends().iter().any(|b| b.id == value.trim()) {
+                Ok(())
+            } else {
+                Err(AppError::BadRequest(format!(
+                    "Invalid {}: unknown backend '{}'",
+                    key, value
+                )))
+            }
+        }
+        PRESET_KEY if !value.trim().is_empty() => {
+            let id = value.trim();
+            if builtin_presets().iter().any(|p| p.id == id) {
+                return Ok(());
+            }
+            match state.db.get_preset(id) {
+                Ok(_) => Ok(()),
+                Err(DbError::NotFound) => Err(AppError::BadRequest(format!(
+                    "Invalid {}: unknown preset '{}'",
+                    key, value
+                ))),
+                Err(e) => Err(AppError::Internal(e.to_string())),
+            }
+        }
+        COMMAND_ALLOWLIST_KEY => parse_command_allowlist(value)
+            .map(|_| ())
+            .map_err(|e| AppError::BadRequest(format!("Invalid {}: {}", key, e))),
+        MAX_REPOS_KEY => parse_max_repos(value)
+            .map(|_| ())
+            .map_err(|e| AppError::BadRequest(format!("Invalid {}: {}", key, e))),
+        RATE_LIMIT_PER_MINUTE_KEY | RATE_LIMIT_BURST_KEY => parse_rate_limit(value)
+            .map(|_| ())
+            .map_err(|e| AppError::BadRequest(format!("Invalid {}: {}", key, e))),
+        MAX_CRASH_RESTARTS_KEY => parse_max_crash_restarts(value)
+            .map(|_| ())
+            .map_err(|e| AppError::BadRequest(format!("Invalid {}: {}", key, e))),
+        MAX_CONCURRENT_SESSIONS_KEY => parse_max_concurrent_sessions(value)
+            .map(|_| ())
+            .map_err(|e| AppError::BadRequest(format!("Invalid {}: {}", key, e))),
+        WS_MAX_MESSAGE_SIZE_KEY => parse_max_message_size(value)
+            .map(|_| ())
+            .map_err(|e| AppError::BadRequest(format!("Invalid {}: {}", key, e))),
+        OUTPUT_MAX_LINE_LENGTH_KEY => match value.trim().parse::<usize>() {
+            Ok(max) if max > 0 => Ok(()),
+            _ => Err(AppError::BadRequest(format!(
+                "Invalid {}: expected a positive integer, got '{}'",
+                key, value
+            ))),
+        },
+        INTERACTIVE_INPUT_KEY | OUTPUT_KEEP_FULL_LINES_KEY => value
+            .trim()
+            .parse::<bool>()
+            .map(|_| ())
+            .map_err(|_| AppError::BadRequest(format!("Invalid {}: expected true or false", key))),
+        MEMORY_LIMIT_MB_KEY | CPU_TIME_LIMIT_SECS_KEY => parse_resource_limit(value)
+            .map(|_| ())
+            .map_err(|e| AppError::BadRequest(format!("Invalid {}: {}", key, e))),
+        AGENT_ENV_KEY if !value.trim().is_empty() => parse_agent_env(value)
+            .map(|_| ())
+            .map_err(|e| AppError::BadRequest(format!("Invalid {}: {}", key, e))),
+        AGENT_WORKDIR_KEY if !value.trim().is_empty() => validate_relative_path(value.trim())
+            .map_err(|e| AppError::BadRequest(format!("Invalid {}: {}", key, e))),
+        _ => Ok(()),
+    }
+}
+
+/// Only an admin may change protected keys
+fn authorize_write(state: &AppState, headers: &HeaderMap, key: &str) -> AppResult<()> {
+    if secrets::is_protected_key(key) {
+        require_admin(state, headers)?;
+    }
+    Ok(())
+}
+
 /// Get all config values
 async fn get_all_config(State(state): State<AppState>) -> AppResult<Json<ConfigResponse>> {
-    let entries = state
-        .db
-        .list_config()
+    let config = state
+        .config
+        .all()
         .map_err(|e| AppError::Internal(e.to_string()))?;
 
-    let config: HashMap<String, String> = entries.into_iter().collect();
-
     Ok(Json(ConfigResponse { config }))
 }
 
 /// Update multiple config values at once
 async fn update_config(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(req): Json<UpdateConfigRequest>,
 ) -> AppResult<Json<ConfigResponse>> {
+    for (key, value) in &req.config {
+        authorize_write(&state, &headers, key)?;
+        validate_config(&state, key, value)?;
+    }
+
     for (key, value) in &req.config {
         state
-            .db
-            .set_config(key, value)
+            .config
+            .set(key, value)
             .map_err(|e| AppError::Internal(e.to_string()))?;
+        events::config_changed(&state.db, &state.connections, key, false);
     }
 
     // Return updated config
     get_all_config(State(state)).await
 }
 
-/// Get a single config value by key
+/// Get a single config value by key (secret and protected values are redacted)
 async fn get_config_value(
     State(state): State<AppState>,
     AxumPath(key): AxumPath<String>,
 ) -> AppResult<Json<ConfigValueResponse>> {
-    let value = state
-        .db
-        .get_config(&key)
-        .map_err(|e| AppError::Internal(e.to_string()))?;
+    let value = if secrets::is_redacted_key(&key) {
+        // Only whether it is set; secrets are never decrypted for a client
+        state
+            .config
+            .all()
+            .map_err(|e| AppError::Internal(e.to_string()))?
+            .contains_key(&key)
+            .then(|| REDACTED.to_string())
+    } else {
+        state
+            .config
+            .get(&key)
+            .map_err(|e| AppError::Internal(e.to_string()))?
+    };
 
     Ok(Json(ConfigValueResponse { key, value }))
 }
@@ -107,13 +246,18 @@ async fn get_config_value(
 /// Set a single config value
 async fn set_config_value(
     State(state): State<AppState>,
+    headers: HeaderMap,
     AxumPath(key): AxumPath<String>,
     Json(req): Json<SetConfigValueRequest>,
 ) -> AppResult<Json<ConfigValueResponse>> {
+    authorize_write(&state, &headers, &key)?;
+    validate_config(&state, &key, &req.value)?;
+
     state
-        .db
-        .set_config(&key, &req.value)
+        .config
+        .set(&key, &req.value)
         .map_err(|e| AppError::Internal(e.to_string()))?;
+    events::config_changed(&state.db, &state.connections, &key, false);
 
     Ok(Json(ConfigValueResponse {
         key,
@@ -124,20 +268,23 @@ async fn set_config_value(
 /// Delete a config value
 async fn delete_config_value(
     State(state): State<AppState>,
+    headers: HeaderMap,
     AxumPath(key): AxumPath<String>,
 ) -> AppResult<Json<()>> {
+    authorize_write(&state, &headers, &key)?;
     state
-        .db
-        .delete_config(&key)
+        .config
+        .delete(&key)
         .map_err(|e| AppError::Internal(e.to_string()))?;
+    events::config_changed(&state.db, &state.connections, &key, true);
 
     Ok(Json(()))
 }
 
-/// List available AI backends
-async fn list_backends() -> Json<BackendsResponse> {
+/// Backends ralph can run against
+pub(crate) fn builtin_backends() -> Vec<AiBackend> {
     // These are the supported backends for Ralph/Claude Code
-    let backends = vec![
+    vec![
         AiBackend {
             id: "claude".to_string(),
             name: "Claude (Anthropic)".to_string(),
@@ -153,48 +300,153 @@ async fn list_backends() -> Json<BackendsResponse> {
             name: "Google Vertex AI".to_string(),
             description: "Claude models via Google Cloud Vertex AI".to_string(),
         },
-    ];
+    ]
+}
 
-    Json(BackendsResponse { backends })
+/// List available AI backends
+async fn list_backends() -> Json<BackendsResponse> {
+    Json(BackendsResponse {
+        backends: builtin_backends(),
+    })
 }
 
-/// List available presets
-async fn list_presets() -> Json<PresetsResponse> {
+/// Check whether a secret config value (stored under `secret/{name}`) is set.
+/// Secrets are write-only: the value comes back redacted.
+async fn get_secret_config_value(
+    state: State<AppState>,
+    AxumPath(name): AxumPath<String>,
+) -> AppResult<Json<ConfigValueResponse>> {
+    get_config_value(state, AxumPath(format!("{}{}", SECRET_PREFIX, name))).await
+}
+
+/// Set a secret config value, encrypting it at rest
+async fn set_secret_config_value(
+    state: State<AppState>,
+    headers: HeaderMap,
+    AxumPath(name): AxumPath<String>,
+    req: Json<SetConfigValueRequest>,
+) -> AppResult<Json<ConfigValueResponse>> {
+    set_config_value(state, headers, AxumPath(format!("{}{}", SECRET_PREFIX, name)), req).await
+}
+
+/// Delete a secret config value
+async fn delete_secret_config_value(
+    state: State<AppState>,
+    headers: HeaderMap,
+    AxumPath(name): AxumPath<String>,
+) -> AppResult<Json<()>> {
+    delete_config_value(state, headers, AxumPath(format!("{}{}", SECRET_PREFIX, name))).await
+}
+
+/// Shipped presets; custom presets with the same id take their place
+fn builtin_presets() -> Vec<Preset> {
     // These are common workflow presets
-    let presets = vec![
-        Preset {
-            id: "default".to_string(),
-            name: "Default".to_string(),
-            description: "Standard autonomous mode".to_string(),
-        },
-        Preset {
-            id: "tdd-red-green".to_string(),
-            name: "TDD Red-Green".to_string(),
-            description: "Test-driven development: write failing test, then implement".to_string(),
-        },
-        Preset {
-            id: "feature".to_string(),
-            name: "Feature Development".to_string(),
-            description: "Implement a new feature with proper planning".to_string(),
-        },
-        Preset {
-            id: "debug".to_string(),
-            name: "Debug".to_string(),
-            description: "Investigate and fix bugs".to_string(),
-        },
-        Preset {
-            id: "refactor".to_string(),
-            name: "Refactor".to_string(),
-            description: "Clean up and improve code structure".to_string(),
-        },
-        Preset {
-            id: "review".to_string(),
-            name: "Code Review".to_string(),
-            description: "Review code and suggest improvements".to_string(),
-        },
-    ];
+    vec![
+        builtin("default", "Default", "Standard autonomous mode"),
+        builtin(
+            "tdd-red-green",
+            "TDD Red-Green",
+            "Test-driven development: write failing test, then implement",
+        ),
+        builtin("feature", "Feature Development", "Implement a new feature with proper planning"),
+        builtin("debug", "Debug", "Investigate and fix bugs"),
+        builtin("refactor", "Refactor", "Clean up and improve code structure"),
+        builtin("review", "Code Review", "Review code and suggest improvements"),
+    ]
+}
+
+/// Shorthand for a built-in preset entry
+fn builtin(id: &str, name: &str, description: &str) -> Preset {
+    Preset {
+        id: id.to_string(),
+        name: name.to_string(),
+        description: description.to_string(),
+        prompt: None,
+        builtin: true,
+    }
+}
+
+/// List built-in presets (with any custom overrides applied) followed by custom presets
+async fn list_presets(State(state): State<AppState>) -> AppResult<Json<PresetsResponse>> {
+    let mut custom = state
+        .db
+        .list_presets()
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    let mut presets: Vec<Preset> = builtin_presets()
+        .into_iter()
+        .map(|preset| match custom.iter().position(|c| c.id == preset.id) {
+            Some(i) => custom.remove(i).into(),
+            None => preset,
+        })
+        .collect();
+    presets.extend(custom.into_iter().map(Preset::from));
+
+    Ok(Json(PresetsResponse { presets }))
+}
 
-    Json(PresetsResponse { presets })
+/// Instructions for starting a run with preset `id`.
+///
+/// Built-in presets have no stored prompt, so their description is used.
+pub(crate) fn preset_prompt(state: &AppState, id: &str) -> AppResult<String> {
+    match state.db.get_preset(id) {
+        Ok(preset) => Ok(preset.prompt),
+        Err(DbError::NotFound) => builtin_presets()
+            .into_iter()
+            .find(|p| p.id == id)
+            .map(|p| p.description)
+            .ok_or_else(|| AppError::BadRequest(format!("Unknown preset: {}", id))),
+        Err(e) => Err(AppError::Internal(e.to_string())),
+    }
+}
+
+/// Check a preset id is usable as a single URL path segment
+fn validate_preset_id(id: &str) -> AppResult<()> {
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        return Err(AppError::BadRequest(format!(
+            "Invalid preset id '{}': use letters, digits, '-' or '_'",
+            id
+        )));
+    }
+    Ok(())
+}
+
+/// Create a custom preset, or replace one with the same id (overriding a built-in)
+async fn create_preset(
+    State(state): State<AppState>,
+    Json(req): Json<CreatePresetRequest>,
+) -> AppResult<Json<Preset>> {
+    let id = req.id.trim();
+    validate_preset_id(id)?;
+    let name = req.name.trim();
+    if name.is_empty() {
+        return Err(AppError::BadRequest("Preset name cannot be empty".to_string()));
+    }
+    if req.prompt.trim().is_empty() {
+        return Err(AppError::BadRequest("Preset prompt cannot be empty".to_string()));
+    }
+
+    let preset = state
+        .db
+        .upsert_preset(id, name, req.description.trim(), &req.prompt)
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(Json(preset.into()))
+}
+
+/// Delete a custom preset; deleting an override restores the built-in preset
+async fn delete_preset(
+    State(state): State<AppState>,
+    AxumPath(id): AxumPath<String>,
+) -> AppResult<Json<()>> {
+    match state.db.delete_preset(&id) {
+        Ok(()) => Ok(Json(())),
+        Err(DbError::NotFound) if builtin_presets().iter().any(|p| p.id == id) => Err(
+            AppError::BadRequest(format!("Built-in preset '{}' cannot be deleted", id)),
+        ),
+        Err(DbError::NotFound) => Err(AppError::NotFound(format!("Preset not found: {}", id))),
+        Err(e) => Err(AppError::Internal(e.to_string())),
+    }
 }
 
 /// Create the config router
@@ -207,7 +459,14 @@ pub fn router() -> Router<AppState> {
                 .put(set_config_value)
                 .delete(delete_config_value),
         )
-        .route("/config/presets", get(list_presets))
+        .route(
+            "/config/secret/{name}",
+            get(get_secret_config_value)
+                .put(set_secret_config_value)
+                .delete(delete_secret_config_value),
+        )
+        .route("/config/presets", get(list_presets).post(create_preset))
+        .route("/config/presets/{id}", delete(delete_preset))
         .route("/config/backends", get(list_backends))
 }
 
@@ -344,6 +603,97 @@ mod tests {
         assert!(claude.is_some());
     }
 
+    #[tokio::test]
+    async fn test_secret_config_values() {
+        let db = Database::in_memory()
+            .expect("Failed to create test database")
+            .with_secret("passphrase");
+        let server = create_test_server(AppState::new(db));
+
+        server
+            .put("/config/secret/anthropic_api_key")
+            .json(&SetConfigValueRequest {
+                value: "sk-ant-123".to_string(),
+            })
+            .await
+            .assert_status_ok();
+
+        let config: ConfigResponse = server.get("/config").await.json();
+        assert_eq!(config.config["secret/anthropic_api_key"], crate::db::secrets::REDACTED);
+
+        // Write-only, also when the key is spelled out through the plain route
+        let value: ConfigValueResponse =
+            server.get("/config/secret/anthropic_api_key").await.json();
+        assert_eq!(value.value.as_deref(), Some(crate::db::secrets::REDACTED));
+        let value: ConfigValueResponse =
+            server.get("/config/secret%2Fanthropic_api_key").await.json();
+        assert_eq!(value.value.as_deref(), Some(crate::db::secrets::REDACTED));
+        let value: ConfigValueResponse = server.get("/config/secret/unset").await.json();
+        assert_eq!(value.value, None);
+
+        server.delete("/config/secret/anthropic_api_key").await.assert_status_ok();
+        let config: ConfigResponse = server.get("/config").await.json();
+        assert!(config.config.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_secret_config_requires_passphrase() {
+        let state = create_test_state();
+        let server = create_test_server(state);
+
+        let response = server
+            .put("/config/secret/anthropic_api_key")
+            .json(&SetConfigValueRequest {
+                value: "sk-ant-123".to_string(),
+            })
+            .await;
+        response.assert_status(axum::http::StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(response.text().contains("RALPHTOWN_SECRET"));
+    }
+
+    #[tokio::test]
+    async fn test_protected_keys_need_admin_to_change() {
+        let state = create_test_state().with_admin_token(Some("s3cret"));
+        let server = create_test_server(state.clone());
+        let body = SetConfigValueRequest {
+            value: "attacker".to_string(),
+        };
+
+        server
+            .put("/config/ws_auth_token")
+            .json(&body)
+            .await
+            .assert_status_unauthorized();
+        server
+            .put("/config")
+            .json(&UpdateConfigRequest {
+                config: HashMap::from([("ws_auth_token".to_string(), "attacker".to_string())]),
+            })
+            .await
+            .assert_status_unauthorized();
+        assert_eq!(state.config.get("ws_auth_token").unwrap(), None);
+
+        // The config table never grants admin access
+        state.db.set_config("admin_token", "attacker").unwrap();
+        let mut headers = HeaderMap::new();
+        headers.insert(crate::api::ADMIN_TOKEN_HEADER, "attacker".parse().unwrap());
+        assert!(require_admin(&state, &headers).is_err());
+
+        // Stored values are redacted on reads
+        state.db.set_config("ws_auth_token", "t0ken").unwrap();
+        let config: ConfigResponse = server.get("/config").await.json();
+        assert_eq!(config.config["ws_auth_token"], REDACTED);
+        let value: ConfigValueResponse = server.get("/config/ws_auth_token").await.json();
+        assert_eq!(value.value.as_deref(), Some(REDACTED));
+
+        server
+            .delete("/config/ws_auth_token")
+            .add_header(crate::api::ADMIN_TOKEN_HEADER, "s3cret")
+            .await
+            .assert_status_ok();
+        assert_eq!(state.config.get("ws_auth_token").unwrap(), None);
+    }
+
     #[tokio::test]
     async fn test_list_presets() {
         let state = create_test_state();
@@ -364,6 +714,82 @@ mod tests {
         assert!(tdd.is_some());
     }
 
+    #[tokio::test]
+    async fn test_custom_presets() {
+        let state = create_test_state();
+        let server = create_test_server(state);
+
+        // Custom preset is appended after the built-ins
+        let response = server
+            .post("/config/presets")
+            .json(&CreatePresetRequest {
+                id: "lint".to_string(),
+                name: "Lint".to_string(),
+                description: "Fix lint warnings".to_string(),
+                prompt: "Run clippy and fix every warning".to_string(),
+            })
+            .await;
+        response.assert_status_ok();
+        let created: Preset = response.json();
+        assert!(!created.builtin);
+
+        // Overriding a built-in keeps its position
+        server
+            .post("/config/presets")
+            .json(&CreatePresetRequest {
+                id: "debug".to_string(),
+                name: "Debug (strict)".to_string(),
+                description: String::new(),
+                prompt: "Reproduce the bug with a test first".to_string(),
+            })
+            .await
+            .assert_status_ok();
+
+        let result: PresetsResponse = server.get("/config/presets").await.json();
+        let ids: Vec<&str> = result.presets.iter().map(|p| p.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            vec!["default", "tdd-red-green", "feature", "debug", "refactor", "review", "lint"]
+        );
+        let debug = &result.presets[3];
+        assert_eq!(debug.name, "Debug (strict)");
+        assert!(!debug.builtin);
+        assert!(result.presets[0].builtin);
+        assert_eq!(result.presets[6].prompt.as_deref(), Some("Run clippy and fix every warning"));
+
+        // Removing the override restores the built-in, which itself can't be deleted
+        server.delete("/config/presets/debug").await.assert_status_ok();
+        let result: PresetsResponse = server.get("/config/presets").await.json();
+        assert!(result.presets[3].builtin);
+        server
+            .delete("/config/presets/debug")
+            .await
+            .assert_status(axum::http::StatusCode::BAD_REQUEST);
+
+        server.delete("/config/presets/lint").await.assert_status_ok();
+        server
+            .delete("/config/presets/lint")
+            .await
+            .assert_status(axum::http::StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_create_preset_rejects_invalid_id() {
+        let state = create_test_state();
+        let server = create_test_server(state);
+
+        let response = server
+            .post("/config/presets")
+            .json(&CreatePresetRequest {
+                id: "has spaces/slash".to_string(),
+                name: "Bad".to_string(),
+                description: String::new(),
+                prompt: "x".to_string(),
+            })
+            .await;
+        response.assert_status(axum::http::StatusCode::BAD_REQUEST);
+    }
+
     #[tokio::test]
     async fn test_update_existing_config_value() {
         let state = create_test_state();
@@ -393,4 +819,136 @@ mod tests {
         let result: ConfigValueResponse = response.json();
         assert_eq!(result.value, Some("updated".to_string()));
     }
+
+    #[tokio::test]
+    async fn test_config_writes_update_cache() {
+        let state = create_test_state();
+        let server = create_test_server(state.clone());
+
+        // Prime the cache before writing
+        assert_eq!(state.config.get("cached_key").unwrap(), None);
+
+        server
+            .put("/config/cached_key")
+            .json(&SetConfigValueRequest {
+                value: "first".to_string(),
+            })
+            .await
+            .assert_status_ok();
+        assert_eq!(state.config.get("cached_key").unwrap().as_deref(), Some("first"));
+        assert_eq!(state.db.get_config("cached_key").unwrap().as_deref(), Some("first"));
+
+        server.delete("/config/cached_key").await.assert_status_ok();
+        assert_eq!(state.config.get("cached_key").unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_set_command_allowlist_validates() {
+        let state = create_test_state();
+        let server = create_test_server(state);
+
+        let response = server
+            .put("/config/command_allowlist")
+            .json(&SetConfigValueRequest {
+                value: "git cargo".to_string(),
+            })
+            .await;
+        response.assert_status_bad_request();
+
+        let response = server
+            .put("/config/command_allowlist")
+            .json(&SetConfigValueRequest {
+                value: r#"["git","cargo"]"#.to_string(),
+            })
+            .await;
+        response.assert_status_ok();
+    }
+
+    #[tokio::test]
+    async fn test_set_output_truncation_validates() {
+        let state = create_test_state();
+        let server = create_test_server(state);
+        let set = |value: &str| SetConfigValueRequest {
+            value: value.to_string(),
+        };
+
+        for value in ["0", "-1", "long"] {
+            server
+                .put("/config/output_max_line_length")
+                .json(&set(value))
+                .await
+                .assert_status_bad_request();
+        }
+        server
+            .put("/config/output_max_line_length")
+            .json(&set("4096"))
+            .await
+            .assert_status_ok();
+
+        server
+            .put("/config/output_keep_full_lines")
+            .json(&set("yes"))
+            .await
+            .assert_status_bad_request();
+        server
+            .put("/config/output_keep_full_lines")
+            .json(&set("true"))
+            .await
+            .assert_status_ok();
+    }
+
+    #[tokio::test]
+    async fn test_set_backend_validates_against_known_backends() {
+        let state = create_test_state();
+        let server = create_test_server(state);
+        let set = |value: &str| SetConfigValueRequest {
+            value: value.to_string(),
+        };
+
+        server
+            .put("/config/backend")
+            .json(&set("claud"))
+            .await
+            .assert_status_bad_request();
+        server.put("/config/backend").json(&set("claude")).await.assert_status_ok();
+        server.put("/config/backend").json(&set("")).await.assert_status_ok();
+
+        // The bulk update is validated too
+        let mut config = HashMap::new();
+        config.insert("fallback_backend".to_string(), "gemini".to_string());
+        server
+            .put("/config")
+            .json(&UpdateConfigRequest { config })
+            .await
+            .assert_status_bad_request();
+    }
+
+    #[tokio::test]
+    async fn test_set_preset_validates_against_known_presets() {
+        let state = create_test_state();
+        let server = create_test_server(state);
+        let set = |value: &str| SetConfigValueRequest {
+            value: value.to_string(),
+        };
+
+        server
+            .put("/config/preset")
+            .json(&set("lint"))
+            .await
+            .assert_status_bad_request();
+        server.put("/config/preset").json(&set("debug")).await.assert_status_ok();
+
+        // Custom presets are accepted once they exist
+        server
+            .post("/config/presets")
+            .json(&CreatePresetRequest {
+                id: "lint".to_string(),
+                name: "Lint".to_string(),
+                description: String::new(),
+                prompt: "Fix lint warnings".to_string(),
+            })
+            .await
+            .assert_status_ok();
+        server.put("/config/preset").json(&set("lint")).await.assert_status_ok();
+    }
 }
@@ -0,0 +1,295 @@
+use std::collections::HashSet;
+use std::convert::Infallible;
+use std::str::FromStr;
+
+use axum::{
+    extract::{Query, State},
+    http::HeaderMap,
+    response::sse::{Event as SseEvent, KeepAlive, Sse},
+    routing::get,
+    Json, Router,
+};
+use futures::stream::Stream;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::db::models::{Event, EventFilter, EventKind};
+use crate::error::{AppError, AppResult};
+
+use super::AppState;
+
+/// Maximum number of missed events replayed for a reconnecting client
+const MAX_REPLAY_EVENTS: i64 = 500;
+
+/// Query parameters for the event stream
+#[derive(Debug, Deserialize)]
+pub struct EventStreamQuery {
+    /// Comma-separated event types to include (default: all)
+    pub types: Option<String>,
+}
+
+/// Default number of audit entries returned per page
+const DEFAULT_AUDIT_PAGE_SIZE: i64 = 50;
+
+/// Maximum number of audit entries returned per page
+const MAX_AUDIT_PAGE_SIZE: i64 = 500;
+
+/// Query parameters for the audit trail
+#[derive(Debug, Deserialize)]
+pub struct AuditQueryParams {
+    /// Maximum number of entries to return (default: 50, max: 500)
+    pub limit: Option<i64>,
+    /// Number of entries to skip, newest first (default: 0)
+    pub offset: Option<i64>,
+    /// Only include events of this type
+    #[serde(rename = "type")]
+    pub kind: Option<String>,
+    /// Only include events at or after this timestamp (RFC 3339)
+    pub since: Option<DateTime<Utc>>,
+    /// Only include events before this timestamp (RFC 3339)
+    pub until: Option<DateTime<Utc>>,
+}
+
+/// Response for the audit trail
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AuditResponse {
+    pub events: Vec<Event>,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+/// Parse the `types` filter into a set of event kinds
+fn parse_types(types: Option<&str>) -> AppResult<Option<HashSet<EventKind>>> {
+    let Some(types) = types.filter(|t| !t.trim().is_empty()) else {
+        return Ok(None);
+    };
+
+    types
+        .split(',')
+        .map(|t| EventKind::from_str(t.trim()).map_err(AppError::BadRequest))
+        .collect::<AppResult<HashSet<_>>>()
+        .map(Some)
+}
+
+/// Render a lifecycle event as an SSE frame
+fn to_sse(event: &Event) -> SseEvent {
+    SseEvent::default()
+        .id(event.id.to_string())
+        .event(event.kind.as_str())
+        .data(serde_json::to_string(event).unwrap_or_default())
+}
+
+/// GET /api/events/stream - Server-wide session lifecycle events (SSE)
+///
+/// Clients reconnecting with `Last-Event-ID` first receive the events they
+/// missed from the events feed.
+async fn event_stream(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<EventStreamQuery>,
+) -> AppResult<Sse<impl Stream<Item = Result<SseEvent, Infallible>>>> {
+    let kinds = parse_types(query.types.as_deref())?;
+    let wanted = move |event: &Event| kinds.as_ref().is_none_or(|k| k.contains(&event.kind));
+
+    // Subscribe before reading the backlog so nothing is missed in between
+    let mut rx = state.connections.subscribe_events();
+
+    let last_event_id = headers
+        .get("last-event-id")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<i64>().ok());
+    let backlog = match last_event_id {
+        Some(after) => state
+            .db
+            .list_events_after(after, MAX_REPLAY_EVENTS)
+            .map_err(|e| AppError::Internal(e.to_string()))?,
+        None => Vec::new(),
+    };
+
+    // Every event up to the newest one replayed was committed before the
+    // backlog was read, so only those can arrive twice. Live events are
+    // published in no particular id order, so nothing newer is skipped.
+    let replayed_up_to = backlog.last().map(|e| e.id).or(last_event_id).unwrap_or(0);
+
+    let stream = async_stream::stream! {
+        for event in backlog {
+            if wanted(&event) {
+                yield Ok(to_sse(&event));
+            }
+        }
+
+        loop {
+            match rx.recv().await {
+                Ok(event) if event.id > replayed_up_to => {
+                    if wanted(&event) {
+                        yield Ok(to_sse(&event));
+                    }
+                }
+                Ok(_) => {}
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!("Event stream subscriber lagged by {} events", skipped);
+                }
+                Err(RecvError::Closed) => break,
+            }
+        }
+    };
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+/// GET /api/audit - Recorded events, newest first, filterable by type and time range
+async fn list_audit(
+    State(state): State<AppState>,
+    Query(params): Query<AuditQueryParams>,
+) -> AppResult<Json<AuditResponse>> {
+    let kind = params
+        .kind
+        .as_deref()
+        .filter(|k| !k.trim().is_empty())
+        .map(|k| EventKind::from_str(k.trim()).map_err(AppError::BadRequest))
+        .transpose()?;
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_AUDIT_PAGE_SIZE)
+        .clamp(1, MAX_AUDIT_PAGE_SIZE);
+    let offset = params.offset.unwrap_or(0).max(0);
+
+    let filter = EventFilter {
+        kind,
+        since: params.since,
+        until: params.until,
+    };
+    let events = state
+        .db
+        .list_events(&filter, limit, offset)
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(Json(AuditResponse {
+        events,
+        limit,
+        offset,
+    }))
+}
+
+/// Create the events router
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/events/stream", get(event_stream))
+        .route("/audit", get(list_audit))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::sessions::{router as sessions_router, CreateSessionRequest};
+    use crate::db::models::{Orchestrator, Session};
+    use crate::db::Database;
+    use axum_test::TestServer;
+
+    #[test]
+    fn test_parse_types() {
+        assert!(parse_types(None).unwrap().is_none());
+        assert!(parse_types(Some("")).unwrap().is_none());
+
+        let kinds = parse_types(Some("session_created, session_completed"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(kinds.len(), 2);
+        assert!(kinds.contains(&EventKind::SessionCreated));
+        assert!(kinds.contains(&EventKind::SessionCompleted));
+
+        assert!(parse_types(Some("session_created,bogus")).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_session_lifecycle_is_published_and_recorded() {
+        let state = AppState::new(Database::in_memory().unwrap());
+        let mut rx = state.connections.subscribe_events();
+        let server = TestServer::new(
+            Router::new()
+                .merge(router())
+                .merge(sessions_router())
+                .with_state(state.clone()),
+        )
+        .unwrap();
+
+        let repo = state.db.insert_repo("/path/to/repo", "repo").unwrap();
+        let session: Session = server
+            .post("/sessions")
+            .json(&CreateSessionRequest {
+                repo_id: repo.id,
+                name: Some("Watched".to_string()),
+                orchestrator: Orchestrator::Ralph,
+                ..Default::default()
+            })
+            .await
+            .json();
+
+        let event = rx.try_recv().expect("session_created event");
+        assert_eq!(event.kind, EventKind::SessionCreated);
+        assert_eq!(event.session_id, Some(session.id));
+        assert_eq!(event.repo_id, Some(repo.id));
+        assert_eq!(event.data["name"], "Watched");
+
+        let recorded = state.db.list_events_after(0, 10).unwrap();
+        assert_eq!(recorded.len(), 1);
+        assert_eq!(recorded[0].id, event.id);
+
+        server
+            .get("/events/stream?types=nope")
+            .await
+            .assert_status_bad_request();
+    }
+
+    #[tokio::test]
+    async fn test_audit_trail() {
+        let state = AppState::new(Database::in_memory().unwrap());
+        let server = TestServer::new(
+            Router::new()
+                .merge(router())
+                .merge(sessions_router())
+                .merge(crate::api::config::router())
+                .with_state(state.clone()),
+        )
+        .unwrap();
+
+        let repo = state.db.insert_repo("/path/to/repo", "repo").unwrap();
+        server
+            .post("/sessions")
+            .json(&CreateSessionRequest {
+                repo_id: repo.id,
+                name: None,
+                orchestrator: Orchestrator::Ralph,
+                ..Default::default()
+            })
+            .await
+            .assert_status_ok();
+        server
+            .put("/config/api_key")
+            .json(&crate::api::config::SetConfigValueRequest {
+                value: "secret".to_string(),
+            })
+            .await
+            .assert_status_ok();
+
+        let audit: AuditResponse = server.get("/audit").await.json();
+        assert_eq!(audit.events.len(), 2);
+        assert_eq!(audit.events[0].kind, EventKind::ConfigChanged);
+        assert_eq!(audit.events[0].data["key"], "api_key");
+        assert!(audit.events[0].data.get("value").is_none());
+        assert_eq!(audit.events[1].kind, EventKind::SessionCreated);
+
+        let audit: AuditResponse = server.get("/audit?type=session_created").await.json();
+        assert_eq!(audit.events.len(), 1);
+
+        let audit: AuditResponse = server.get("/audit?limit=1&offset=1").await.json();
+        assert_eq!(audit.events.len(), 1);
+        assert_eq!(audit.events[0].kind, EventKind::SessionCreated);
+
+        let audit: AuditResponse = server.get("/audit?since=2999-01-01T00:00:00Z").await.json();
+        assert!(audit.events.is_empty());
+
+        server.get("/audit?type=bogus").await.assert_status_bad_request();
+    }
+}
@@ -0,0 +1,335 @@
+//! Session export and import REST API endpoints
+//!
+//! - GET /api/sessions/{id}/export - Download a session as a JSON bundle
+//! - POST /api/sessions/import - Recreate a session from a bundle
+
+use axum::{
+    body::Body,
+    extract::{DefaultBodyLimit, Path as AxumPath, Query, State},
+    http::header,
+    response::IntoResponse,
+    routing::{get, post},
+    Json, Router,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::db::models::{Message, OutputLog, Repo, Session};
+use crate::db::DbError;
+use crate::error::{AppError, AppResult};
+use crate::events;
+
+use super::git::session_filename;
+use super::AppState;
+
+/// Bundle format version written by export and required by import
+pub const BUNDLE_VERSION: u32 = 1;
+
+/// Output logs read from the database per chunk of an export
+const EXPORT_BATCH_SIZE: i64 = 1000;
+
+/// Largest bundle accepted for import (256 MiB)
+const IMPORT_BODY_LIMIT: usize = 256 * 1024 * 1024;
+
+/// A session with everything needed to recreate it on another machine
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SessionBundle {
+    pub version: u32,
+    pub session: Session,
+    /// The repository the session ran in, used to find a match on import
+    pub repo: Repo,
+    pub messages: Vec<Message>,
+    pub output_logs: Vec<OutputLog>,
+}
+
+/// Query parameters for importing a bundle
+#[derive(Debug, Default, Deserialize)]
+pub struct ImportParams {
+    /// Repository to import into, instead of matching the bundle's repo
+    pub repo_id: Option<Uuid>,
+}
+
+/// Download a session as a JSON bundle
+///
+/// Messages are loaded up front, but output logs are read off the async
+/// runtime and streamed in batches, so a session's output is never held in
+/// memory in full.
+async fn export_session(
+    State(state): State<AppState>,
+    AxumPath(id): AxumPath<Uuid>,
+) -> AppResult<impl IntoResponse> {
+    let session = state.db.get_session(id).map_err(|e| match e {
+        DbError::NotFound => AppError::NotFound(format!("Session not found: {}", id)),
+        _ => AppError::Internal(e.to_string()),
+    })?;
+    let repo = state
+        .db
+        .get_repo(session.repo_id)
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+    let db = state.db.clone();
+    let messages = tokio::task::spawn_blocking(move || db.list_messages(id))
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    // Everything but the output logs, leaving the output_logs array open
+    let head = format!(
+        "{{\"version\":{},\"session\":{},\"repo\":{},\"messages\":{},\"output_logs\":[",
+        BUNDLE_VERSION,
+        serde_json::to_string(&session).map_err(|e| AppError::Internal(e.to_string()))?,
+        serde_json::to_string(&repo).map_err(|e| AppError::Internal(e.to_string()))?,
+        serde_json::to_string(&messages).map_err(|e| AppError::Internal(e.to_string()))?,
+    );
+
+    let db = state.db.clone();
+    let body = async_stream::stream! {
+        yield Ok(head);
+
+        let mut after_id = 0;
+        loop {
+            let batch_db = db.clone();
+            let batch = tokio::task::spawn_blocking(move || {
+                batch_db.list_output_logs_batch(id, None, after_id, EXPORT_BATCH_SIZE)
+            })
+            .await
+            .map_err(|e| e.to_string())
+            .and_then(|r| r.map_err(|e| e.to_string()));
+            let logs = match batch {
+                Ok(logs) => logs,
+                Err(e) => {
+                    yield Err(std::io::Error::other(e));
+                    return;
+                }
+            };
+            let Some(last) = logs.last() else {
+                break;
+            };
+            let first_batch = after_id == 0;
+            after_id = last.id;
+
+            let mut chunk = String::new();
+            for (i, log) in logs.iter().enumerate() {
+                if !(first_batch && i == 0) {
+                    chunk.push(',');
+                }
+                match serde_json::to_string(log) {
+                    Ok(json) => chunk.push_str(&json),
+                    Err(e) => {
+                        yield Err(std::io::Error::other(e.to_string()));
+                        return;
+                    }
+                }
+            }
+            yield Ok(chunk);
+
+            if (logs.len() as i64) < EXPORT_BATCH_SIZE {
+                break;
+            }
+        }
+
+        yield Ok("]}".to_string());
+    };
+
+    let filename = session_filename(&session, Utc::now().date_naive(), "json");
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/json".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", filename),
+            ),
+        ],
+        Body::from_stream(body),
+    ))
+}
+
+/// Recreate a session from a bundle, with a new id
+///
+/// The session is attached to `repo_id` if given, otherwise to the local repo
+/// with the bundle repo's path, or failing that its name.
+async fn import_session(
+    State(state): State<AppState>,
+    Query(params): Query<ImportParams>,
+    Json(bundle): Json<SessionBundle>,
+) -> AppResult<Json<Session>> {
+    if bundle.version != BUNDLE_VERSION {
+        return Err(AppError::BadRequest(format!(
+            "Unsupported bundle version {} (expected {})",
+            bundle.version, BUNDLE_VERSION
+        )));
+    }
+
+    let repo = match params.repo_id {
+        Some(repo_id) => state.db.get_repo(repo_id).map_err(|e| match e {
+            DbError::NotFound => AppError::BadRequest(format!("Repository not found: {}", repo_id)),
+            _ => AppError::Internal(e.to_string()),
+        })?,
+        None => matching_repo(&state, &bundle.repo)?,
+    };
+
+    let session = state
+        .db
+        .import_session(repo.id, &bundle.session, &bundle.messages, &bundle.output_logs)
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+    events::session_created(&state.db, &state.connections, &session);
+
+    Ok(Json(session))
+}
+
+/// Find the local repository matching an exported one, by path then by name
+fn matching_repo(state: &AppState, exported: &Repo) -> AppResult<Repo> {
+    match state.db.get_repo_by_path(&exported.path) {
+        Ok(repo) => return Ok(repo),
+        Err(DbError::NotFound) => {}
+        Err(e) => return Err(AppError::Internal(e.to_string())),
+    }
+
+    let mut by_name: Vec<Repo> = state
+        .db
+        .list_repos()
+        .map_err(|e| AppError::Internal(e.to_string()))?
+        .into_iter()
+        .filter(|repo| repo.name == exported.name)
+        .collect();
+
+    match by_name.len() {
+        1 => Ok(by_name.remove(0)),
+        0 => Err(AppError::BadRequest(format!(
+            "No repository matches '{}' ({}); pass repo_id to choose one",
+            exported.name, exported.path
+        ))),
+        _ => Err(AppError::BadRequest(format!(
+            "Several repositories are named '{}'; pass repo_id to choose one",
+            exported.name
+        ))),
+    }
+}
+
+/// Create the session export/import router
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/sessions/{id}/export", get(export_session))
+        .route(
+            "/sessions/import",
+            post(import_session).layer(DefaultBodyLimit::max(IMPORT_BODY_LIMIT)),
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::models::{MessageRole, Orchestrator, OutputStream, SessionStatus};
+    use crate::db::Database;
+    use axum_test::TestServer;
+
+    fn create_test_server(state: AppState) -> TestServer {
+        TestServer::new(router().with_state(state)).expect("Failed to create test server")
+    }
+
+    #[tokio::test]
+    async fn test_export_then_import() {
+        let source = AppState::new(Database::in_memory().unwrap());
+        let repo = source.db.insert_repo("/src/project", "project").unwrap();
+        let session = source
+            .db
+            .insert_session(repo.id, Some("shared run"), Orchestrator::Ralph)
+            .unwrap();
+        source
+            .db
+            .update_session_status(session.id, SessionStatus::Completed)
+            .unwrap();
+        source
+            .db
+            .insert_message(session.id, MessageRole::User, "Fix the bug")
+            .unwrap();
+        for i in 0..(EXPORT_BATCH_SIZE + 5) {
+            source
+                .db
+                .insert_output_log(session.id, OutputStream::Stdout, &format!("line {}", i))
+                .unwrap();
+        }
+
+        let response = create_test_server(source)
+            .get(&format!("/sessions/{}/export", session.id))
+            .await;
+        response.assert_status_ok();
+        assert!(response
+            .header(header::CONTENT_DISPOSITION)
+            .to_str()
+            .unwrap()
+            .ends_with(".json\""));
+        let bundle: SessionBundle = response.json();
+        assert_eq!(bundle.version, BUNDLE_VERSION);
+        assert_eq!(bundle.session.id, session.id);
+        assert_eq!(bundle.repo.name, "project");
+        assert_eq!(bundle.messages.len(), 1);
+        assert_eq!(bundle.output_logs.len(), EXPORT_BATCH_SIZE as usize + 5);
+        assert_eq!(bundle.output_logs[1000].content, "line 1000");
+
+        // Another machine has the repo checked out elsewhere under the same name
+        let target = AppState::new(Database::in_memory().unwrap());
+        let local = target.db.insert_repo("/home/me/project", "project").unwrap();
+        let server = create_test_server(target.clone());
+
+        let response = server.post("/sessions/import").json(&bundle).await;
+        response.assert_status_ok();
+        let imported: Session = response.json();
+        assert_ne!(imported.id, session.id);
+        assert_eq!(imported.repo_id, local.id);
+        assert_eq!(imported.status, SessionStatus::Completed);
+        assert_eq!(target.db.list_messages(imported.id).unwrap()[0].content, "Fix the bug");
+        let logs = target.db.list_output_logs(imported.id, None, None, None).unwrap();
+        assert_eq!(logs.len(), EXPORT_BATCH_SIZE as usize + 5);
+    }
+
+    #[tokio::test]
+    async fn test_import_requires_matching_repo() {
+        let state = AppState::new(Database::in_memory().unwrap());
+        let repo = state.db.insert_repo("/src/project", "project").unwrap();
+        let session = state.db.insert_session(repo.id, None, Orchestrator::Ralph).unwrap();
+        let mut bundle = SessionBundle {
+            version: BUNDLE_VERSION,
+            session,
+            repo: Repo {
+                path: "/elsewhere/other".to_string(),
+                name: "other".to_string(),
+                ..repo.clone()
+            },
+            messages: Vec::new(),
+            output_logs: Vec::new(),
+        };
+        let server = create_test_server(state);
+
+        server
+            .post("/sessions/import")
+            .json(&bundle)
+            .await
+            .assert_status_bad_request();
+
+        // An explicit repo_id wins
+        server
+            .post(&format!("/sessions/import?repo_id={}", repo.id))
+            .json(&bundle)
+            .await
+            .assert_status_ok();
+
+        bundle.version = BUNDLE_VERSION + 1;
+        server
+            .post(&format!("/sessions/import?repo_id={}", repo.id))
+            .json(&bundle)
+            .await
+            .assert_status_bad_request();
+    }
+
+    #[tokio::test]
+    async fn test_export_unknown_session() {
+        let server = create_test_server(AppState::new(Database::in_memory().unwrap()));
+
+        server
+            .get(&format!("/sessions/{}/export", Uuid::new_v4()))
+            .await
+            .assert_status_not_found();
+    }
+}
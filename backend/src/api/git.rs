@@ -1,22 +1,43 @@
 //! Git operations REST API endpoints
 //!
 //! Provides endpoints for git operations on session repositories:
-//! - Read operations: status, log, branches, diff
-//! - Write operations: pull, push, commit, reset, checkout
+//! - Read operations: status, log, commit detail, branches (plain or with tip commits), remotes, diff,
+//!   diff range, per-file diff, diff hunks, file-diff, activity, conflicts, patch download, session summary, commit search
+//! - Write operations: pull, push, commit, amend, reset, restore-checkpoint, checkout, checkout-file,
+//!   branch create/delete, stash save/pop/drop, remote add/remove
+//! - Cross-repo aggregation: recent commits over all tracked repos
+//! - Cross-session comparison: diff between two sessions' worktrees
 
 use axum::{
     extract::{Path as AxumPath, Query, State},
-    routing::{get, post},
+    http::header,
+    response::IntoResponse,
+    routing::{delete, get, post},
     Json, Router,
 };
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::PathBuf;
 use uuid::Uuid;
 
+use crate::db::models::{MessageRole, Session};
 use crate::error::{AppError, AppResult};
-use crate::git::{Branch, Commit, CommandOutput, FileDelta, GitError, GitManager, GitStatus};
+use crate::git::{
+    append_trailer, parse_pattern_list, Branch, BranchDetail, Commit, CommandOutput, CommitActivity,
+    CommitDetail, ConflictFile, FileDelta, FileDiff, FileDiffBetween, FileHunks, GitError, GitManager,
+    GitStatus, PushOptions, Remote, StashEntry, SESSION_TRAILER,
+};
+use crate::ws::messages::ServerMessage;
 
 use super::AppState;
 
+/// Query parameters for git status
+#[derive(Debug, Deserialize)]
+pub struct StatusQueryParams {
+    /// Limit changes to files under this repository-relative directory
+    pub path: Option<String>,
+}
+
 /// Query parameters for git log
 #[derive(Debug, Deserialize)]
 pub struct LogQueryParams {
@@ -24,6 +45,116 @@ pub struct LogQueryParams {
     pub limit: Option<usize>,
 }
 
+/// Query parameters for searching commit messages
+#[derive(Debug, Deserialize)]
+pub struct SearchCommitsQueryParams {
+    /// Text to look for in commit messages (case-insensitive)
+    pub q: String,
+    /// Maximum number of matches to return (default: 20, max: 200)
+    pub limit: Option<usize>,
+}
+
+/// Default number of matches returned by a commit search
+const DEFAULT_COMMIT_SEARCH_LIMIT: usize = 20;
+
+/// Upper bound on matches returned by a commit search
+const MAX_COMMIT_SEARCH_LIMIT: usize = 200;
+
+/// Number of commits a search walks before giving up
+const COMMIT_SEARCH_SCAN_LIMIT: usize = 10_000;
+
+/// Upper bound on commits returned by the recent-commits feed
+const MAX_RECENT_COMMITS: usize = 200;
+
+/// Query parameters for commit activity
+#[derive(Debug, Deserialize)]
+pub struct ActivityQueryParams {
+    /// Number of days to cover, ending today (default: 90, max: 365)
+    pub days: Option<u32>,
+}
+
+/// Query parameters for diffing one file between two revisions
+#[derive(Debug, Deserialize)]
+pub struct FileDiffQueryParams {
+    /// Repository-relative file path
+    pub path: String,
+    /// Base revision (sha, branch, tag...)
+    pub from: String,
+    /// Target revision (default: HEAD)
+    pub to: Option<String>,
+}
+
+/// Query parameters for diffing two revisions
+#[derive(Debug, Deserialize)]
+pub struct DiffRangeQueryParams {
+    /// Base revision (sha, branch, tag...)
+    pub from: String,
+    /// Target revision (sha, branch, tag...)
+    pub to: String,
+}
+
+/// Query parameters for a single file's diff
+#[derive(Debug, Deserialize)]
+pub struct PathDiffQueryParams {
+    /// Repository-relative file path
+    pub path: String,
+    /// Diff staged changes instead of the working tree (default: false)
+    #[serde(default)]
+    pub staged: bool,
+}
+
+/// Query parameters for showing one commit
+#[derive(Debug, Deserialize)]
+pub struct CommitQueryParams {
+    /// Revision to show (sha, branch, tag, `HEAD~2`...)
+    pub rev: String,
+}
+
+/// Query parameters for structured diff hunks
+#[derive(Debug, Deserialize)]
+pub struct DiffHunksQueryParams {
+    /// Repository-relative file path
+    pub file: String,
+}
+
+/// Config key: JSON array of glob patterns staged by `stage: "patterns"` commits.
+/// Read from the repository config, falling back to the global config.
+pub const AUTO_STAGE_PATTERNS_KEY: &str = "auto_stage_patterns";
+
+/// Config key: when "true", commits made through the commit endpoint are
+/// stamped with a `Ralphtown-Session: <id>` trailer
+pub const SESSION_TRAILER_KEY: &str = "commit_session_trailer";
+
+/// What to stage before committing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StageMode {
+    /// Commit only what is already staged
+    None,
+    /// Stage all changes (git add -A)
+    All,
+    /// Stage changed files matching `auto_stage_patterns`
+    Patterns,
+}
+
+/// Which changes a downloaded patch contains
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PatchScope {
+    /// Uncommitted changes against HEAD
+    #[default]
+    Working,
+    /// Everything changed since the session's last run started, committed or not
+    Session,
+}
+
+/// Query parameters for downloading a patch
+#[derive(Debug, Deserialize)]
+pub struct PatchQueryParams {
+    #[serde(default)]
+    pub scope: PatchScope,
+}
+
 /// Request body for git commit
 #[derive(Debug, Deserialize, Serialize)]
 pub struct CommitRequest {
@@ -32,6 +163,20 @@ pub struct CommitRequest {
     /// Whether to stage all changes first (git add -A)
     #[serde(default)]
     pub stage_all: bool,
+    /// What to stage first; overrides `stage_all` when set
+    #[serde(default)]
+    pub stage: Option<StageMode>,
+}
+
+/// Request body for amending the last commit
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct AmendRequest {
+    /// New commit message; the existing message is kept when absent
+    #[serde(default)]
+    pub message: Option<String>,
+    /// Whether to stage all changes first (git add -A)
+    #[serde(default)]
+    pub stage_all: bool,
 }
 
 /// Request body for git reset
@@ -41,6 +186,15 @@ pub struct ResetRequest {
     pub confirm: bool,
 }
 
+/// Response for restoring a session's run checkpoint
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RestoreCheckpointResponse {
+    pub session_id: Uuid,
+    pub checkpoint_sha: String,
+    #[serde(flatten)]
+    pub output: CommandOutput,
+}
+
 /// Request body for git checkout
 #[derive(Debug, Deserialize, Serialize)]
 pub struct CheckoutRequest {
@@ -48,6 +202,48 @@ pub struct CheckoutRequest {
     pub branch: String,
 }
 
+/// Request body for creating a branch
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreateBranchRequest {
+    /// New branch name
+    pub name: String,
+    /// Revision to branch from (default: HEAD)
+    #[serde(default)]
+    pub from: Option<String>,
+}
+
+/// Request body for adding a remote
+#[derive(Debug, Deserialize, Serialize)]
+pub struct AddRemoteRequest {
+    pub name: String,
+    pub url: String,
+}
+
+/// Request body for stashing changes
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct StashRequest {
+    /// Optional stash message
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+/// Query parameters for deleting a branch
+#[derive(Debug, Deserialize)]
+pub struct DeleteBranchQueryParams {
+    /// Delete even if the branch is not merged into HEAD (default: false)
+    #[serde(default)]
+    pub force: bool,
+}
+
+/// Request body for restoring a single file from HEAD
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CheckoutFileRequest {
+    /// Repository-relative path of the file to restore
+    pub path: String,
+    /// Must be true to confirm discarding the file's changes
+    pub confirm: bool,
+}
+
 /// Response wrapper for git status
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GitStatusResponse {
@@ -63,6 +259,19 @@ pub struct GitLogResponse {
     pub commits: Vec<Commit>,
 }
 
+/// Response for a commit message search
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitSearchCommitsResponse {
+    pub session_id: Uuid,
+    pub query: String,
+    /// Matching commits, newest first
+    pub commits: Vec<Commit>,
+    /// Number of commits examined
+    pub scanned: usize,
+    /// Whether older history was left unsearched (limit or scan cap reached)
+    pub truncated: bool,
+}
+
 /// Response wrapper for branches
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GitBranchesResponse {
@@ -70,6 +279,34 @@ pub struct GitBranchesResponse {
     pub branches: Vec<Branch>,
 }
 
+/// Response wrapper for branches with their tip commits
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitBranchesDetailedResponse {
+    pub session_id: Uuid,
+    pub branches: Vec<BranchDetail>,
+}
+
+/// Response for a created branch
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitBranchResponse {
+    pub session_id: Uuid,
+    pub branch: Branch,
+}
+
+/// Response wrapper for remotes
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitRemotesResponse {
+    pub session_id: Uuid,
+    pub remotes: Vec<Remote>,
+}
+
+/// Response wrapper for the stash list
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitStashResponse {
+    pub session_id: Uuid,
+    pub entries: Vec<StashEntry>,
+}
+
 /// Response wrapper for diff stats
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GitDiffResponse {
@@ -79,6 +316,104 @@ pub struct GitDiffResponse {
     pub total_removed: usize,
 }
 
+/// Query parameters for comparing two sessions' working trees
+#[derive(Debug, Deserialize)]
+pub struct CompareChangesQueryParams {
+    pub a: Uuid,
+    pub b: Uuid,
+}
+
+/// Response for the diff between two sessions' worktrees, from `a` to `b`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitCompareChangesResponse {
+    pub a: Uuid,
+    pub b: Uuid,
+    pub files: Vec<FileDelta>,
+    pub total_added: usize,
+    pub total_removed: usize,
+    pub patch: String,
+}
+
+/// Response for the pull-request style summary of a session's commits
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitSessionSummaryResponse {
+    pub session_id: Uuid,
+    /// Sha the session's first run started from, where the summary starts
+    pub base_sha: String,
+    pub head_sha: String,
+    /// Commits made since the base, newest first
+    pub commits: Vec<Commit>,
+    /// Combined changes from the checkpoint to HEAD
+    pub files: Vec<FileDelta>,
+    pub files_changed: usize,
+    pub total_added: usize,
+    pub total_removed: usize,
+    /// Markdown description suitable for a pull request body
+    pub description: String,
+}
+
+/// Response wrapper for a single file's diff
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitPathDiffResponse {
+    pub session_id: Uuid,
+    #[serde(flatten)]
+    pub diff: FileDiff,
+}
+
+/// Response wrapper for structured diff hunks
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitDiffHunksResponse {
+    pub session_id: Uuid,
+    #[serde(flatten)]
+    pub diff: FileHunks,
+}
+
+/// Response wrapper for merge conflicts
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitConflictsResponse {
+    pub session_id: Uuid,
+    pub files: Vec<ConflictFile>,
+}
+
+/// A commit tagged with the tracked repository it belongs to
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RepoCommit {
+    pub repo_id: Uuid,
+    pub repo_name: String,
+    #[serde(flatten)]
+    pub commit: Commit,
+}
+
+/// A repository that could not be read while aggregating
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RepoFailure {
+    pub repo_id: Uuid,
+    pub error: String,
+}
+
+/// Response for the cross-repo recent commits feed
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RecentCommitsResponse {
+    pub commits: Vec<RepoCommit>,
+    pub failed: Vec<RepoFailure>,
+}
+
+/// Response wrapper for commit activity
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitActivityResponse {
+    pub session_id: Uuid,
+    #[serde(flatten)]
+    pub activity: CommitActivity,
+}
+
+/// Response wrapper for a single-file diff between revisions
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitFileDiffResponse {
+    pub session_id: Uuid,
+    #[serde(flatten)]
+    pub diff: FileDiffBetween,
+}
+
 /// Response wrapper for git command output
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GitCommandResponse {
@@ -87,6 +422,36 @@ pub struct GitCommandResponse {
     pub output: CommandOutput,
 }
 
+/// Response for git pull
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitPullResponse {
+    pub session_id: Uuid,
+    #[serde(flatten)]
+    pub output: CommandOutput,
+    /// Paths left conflicted in the index by the pull
+    pub conflicts: Vec<String>,
+    /// True when the pull stopped on conflicts that must be resolved before committing
+    pub needs_resolution: bool,
+}
+
+/// Response for showing a single commit
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitCommitDetailResponse {
+    pub session_id: Uuid,
+    pub commit: CommitDetail,
+}
+
+/// Response for git commit
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitCommitResponse {
+    pub session_id: Uuid,
+    #[serde(flatten)]
+    pub output: CommandOutput,
+    /// Files staged by `stage: "patterns"`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub staged: Option<Vec<String>>,
+}
+
 /// Helper to get the repo path for a session
 async fn get_session_repo_path(state: &AppState, session_id: Uuid) -> AppResult<std::path::PathBuf> {
     let session = state.db.get_session(session_id).map_err(|e| match e {
@@ -109,18 +474,27 @@ fn map_git_error(e: GitError) -> AppError {
     match e {
         GitError::NotARepo(msg) => AppError::BadRequest(format!("Not a git repository: {}", msg)),
         GitError::InvalidBranch(msg) => AppError::BadRequest(format!("Invalid branch: {}", msg)),
+        GitError::InvalidRemote(msg) => AppError::BadRequest(format!("Invalid remote: {}", msg)),
+        GitError::InvalidPath(msg) => AppError::BadRequest(format!("Invalid path: {}", msg)),
+        GitError::NotFound(msg) => AppError::NotFound(msg),
+        GitError::Stash(msg) => AppError::BadRequest(msg),
         GitError::OperationFailed(msg) => AppError::Internal(format!("Git operation failed: {}", msg)),
         GitError::CommandFailed(msg) => AppError::Internal(format!("Git command failed: {}", msg)),
     }
 }
 
-/// GET /api/sessions/{id}/git/status - Get git status
+/// GET /api/sessions/{id}/git/status - Get git status, optionally scoped to `?path=dir`
 async fn get_status(
     State(state): State<AppState>,
     AxumPath(id): AxumPath<Uuid>,
+    Query(params): Query<StatusQueryParams>,
 ) -> AppResult<Json<GitStatusResponse>> {
     let repo_path = get_session_repo_path(&state, id).await?;
-    let status = GitManager::status(&repo_path).map_err(map_git_error)?;
+    let status = match params.path.as_deref().filter(|p| !p.is_empty()) {
+        Some(dir) => GitManager::status_in(&repo_path, dir),
+        None => GitManager::status(&repo_path),
+    }
+    .map_err(map_git_error)?;
 
     Ok(Json(GitStatusResponse {
         session_id: id,
@@ -144,6 +518,70 @@ async fn get_log(
     }))
 }
 
+/// File name for a session download: the slugified session name and today's date
+pub(crate) fn session_filename(
+    session: &Session,
+    today: chrono::NaiveDate,
+    extension: &str,
+) -> String {
+    let slug = session
+        .name
+        .as_deref()
+        .unwrap_or_default()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '-' })
+        .collect::<String>()
+        .split('-')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    let slug = if slug.is_empty() {
+        format!("session-{}", &session.id.to_string()[..8])
+    } else {
+        slug
+    };
+
+    format!("{}-{}.{}", slug, today.format("%Y%m%d"), extension)
+}
+
+/// GET /api/sessions/{id}/git/patch/download - Download the session's changes as a .patch file
+async fn get_patch_download(
+    State(state): State<AppState>,
+    AxumPath(id): AxumPath<Uuid>,
+    Query(params): Query<PatchQueryParams>,
+) -> AppResult<impl IntoResponse> {
+    let repo_path = get_session_repo_path(&state, id).await?;
+    let session = state
+        .db
+        .get_session(id)
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    let base = match params.scope {
+        PatchScope::Working => None,
+        PatchScope::Session => Some(
+            state
+                .db
+                .get_session_checkpoint(id)
+                .map_err(|e| AppError::Internal(e.to_string()))?
+                .ok_or_else(|| AppError::NotFound(format!("Session {} has no checkpoint", id)))?,
+        ),
+    };
+
+    let patch = GitManager::patch(&repo_path, base.as_deref()).map_err(map_git_error)?;
+    let filename = session_filename(&session, chrono::Utc::now().date_naive(), "patch");
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/x-patch".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", filename),
+            ),
+        ],
+        patch,
+    ))
+}
+
 /// GET /api/sessions/{id}/git/branches - List branches
 async fn get_branches(
     State(state): State<AppState>,
@@ -158,367 +596,2008 @@ async fn get_branches(
     }))
 }
 
-/// GET /api/sessions/{id}/git/diff - Get diff statistics
-async fn get_diff(
+/// GET /api/sessions/{id}/git/branches/detailed - List branches with their tip commits
+async fn get_branches_detailed(
     State(state): State<AppState>,
     AxumPath(id): AxumPath<Uuid>,
-) -> AppResult<Json<GitDiffResponse>> {
+) -> AppResult<Json<GitBranchesDetailedResponse>> {
     let repo_path = get_session_repo_path(&state, id).await?;
-    let files = GitManager::diff_stats(&repo_path).map_err(map_git_error)?;
-
-    let total_added: usize = files.iter().map(|f| f.added).sum();
-    let total_removed: usize = files.iter().map(|f| f.removed).sum();
+    let branches = GitManager::branches_detailed(&repo_path).map_err(map_git_error)?;
 
-    Ok(Json(GitDiffResponse {
+    Ok(Json(GitBranchesDetailedResponse {
         session_id: id,
-        files,
-        total_added,
-        total_removed,
+        branches,
     }))
 }
 
-/// POST /api/sessions/{id}/git/pull - Execute git pull
-async fn post_pull(
+/// GET /api/sessions/{id}/git/activity - Get commit counts per day
+async fn get_activity(
     State(state): State<AppState>,
     AxumPath(id): AxumPath<Uuid>,
-) -> AppResult<Json<GitCommandResponse>> {
+    Query(params): Query<ActivityQueryParams>,
+) -> AppResult<Json<GitActivityResponse>> {
     let repo_path = get_session_repo_path(&state, id).await?;
-    let output = GitManager::pull(&repo_path).map_err(map_git_error)?;
+    let days = params.days.unwrap_or(90);
+    let today = chrono::Utc::now().date_naive();
 
-    Ok(Json(GitCommandResponse {
+    let activity = tokio::task::spawn_blocking(move || GitManager::activity(&repo_path, days, today))
+        .await
+        .map_err(|e| AppError::Internal(format!("Activity task failed: {}", e)))?
+        .map_err(map_git_error)?;
+
+    Ok(Json(GitActivityResponse {
         session_id: id,
-        output,
+        activity,
     }))
 }
 
-/// POST /api/sessions/{id}/git/push - Execute git push
-async fn post_push(
+/// GET /api/sessions/{id}/git/search-commits?q= - Find commits whose message contains a string
+async fn get_search_commits(
     State(state): State<AppState>,
     AxumPath(id): AxumPath<Uuid>,
-) -> AppResult<Json<GitCommandResponse>> {
-    let repo_path = get_session_repo_path(&state, id).await?;
-    let output = GitManager::push(&repo_path).map_err(map_git_error)?;
+    Query(params): Query<SearchCommitsQueryParams>,
+) -> AppResult<Json<GitSearchCommitsResponse>> {
+    let query = params.q.trim().to_string();
+    if query.is_empty() {
+        return Err(AppError::BadRequest("Search query cannot be empty".to_string()));
+    }
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_COMMIT_SEARCH_LIMIT)
+        .clamp(1, MAX_COMMIT_SEARCH_LIMIT);
 
-    Ok(Json(GitCommandResponse {
+    let repo_path = get_session_repo_path(&state, id).await?;
+    let needle = query.clone();
+    let search = tokio::task::spawn_blocking(move || {
+        GitManager::search_commits(&repo_path, &needle, limit, COMMIT_SEARCH_SCAN_LIMIT)
+    })
+    .await
+    .map_err(|e| AppError::Internal(format!("Commit search task failed: {}", e)))?
+    .map_err(map_git_error)?;
+
+    Ok(Json(GitSearchCommitsResponse {
         session_id: id,
-        output,
+        query,
+        commits: search.commits,
+        scanned: search.scanned,
+        truncated: search.truncated,
     }))
 }
 
-/// POST /api/sessions/{id}/git/commit - Execute git commit
-async fn post_commit(
+/// GET /api/sessions/{id}/git/commit?rev= - Show one commit with its patch
+async fn get_commit(
     State(state): State<AppState>,
     AxumPath(id): AxumPath<Uuid>,
-    Json(req): Json<CommitRequest>,
-) -> AppResult<Json<GitCommandResponse>> {
+    Query(params): Query<CommitQueryParams>,
+) -> AppResult<Json<GitCommitDetailResponse>> {
     let repo_path = get_session_repo_path(&state, id).await?;
+    let commit = GitManager::show(&repo_path, &params.rev).map_err(map_git_error)?;
 
-    // Validate message
-    if req.message.trim().is_empty() {
-        return Err(AppError::BadRequest("Commit message cannot be empty".to_string()));
-    }
-
-    // Stage all changes if requested
-    if req.stage_all {
-        GitManager::add_all(&repo_path).map_err(map_git_error)?;
-    }
-
-    let output = GitManager::commit(&repo_path, &req.message).map_err(map_git_error)?;
-
-    Ok(Json(GitCommandResponse {
+    Ok(Json(GitCommitDetailResponse {
         session_id: id,
-        output,
+        commit,
     }))
 }
 
-/// POST /api/sessions/{id}/git/reset - Execute git reset --hard
-async fn post_reset(
+/// GET /api/sessions/{id}/git/diff - Get diff statistics
+async fn get_diff(
     State(state): State<AppState>,
     AxumPath(id): AxumPath<Uuid>,
-    Json(req): Json<ResetRequest>,
-) -> AppResult<Json<GitCommandResponse>> {
-    // Require explicit confirmation for destructive operation
-    if !req.confirm {
-        return Err(AppError::BadRequest(
-            "Reset requires confirmation. Set confirm: true to proceed.".to_string(),
-        ));
-    }
-
+) -> AppResult<Json<GitDiffResponse>> {
     let repo_path = get_session_repo_path(&state, id).await?;
-    let output = GitManager::reset_hard(&repo_path).map_err(map_git_error)?;
+    let files = GitManager::diff_stats(&repo_path).map_err(map_git_error)?;
 
-    Ok(Json(GitCommandResponse {
+    let total_added: usize = files.iter().map(|f| f.added).sum();
+    let total_removed: usize = files.iter().map(|f| f.removed).sum();
+
+    Ok(Json(GitDiffResponse {
         session_id: id,
-        output,
+        files,
+        total_added,
+        total_removed,
     }))
 }
 
-/// POST /api/sessions/{id}/git/checkout - Switch branch
-async fn post_checkout(
+/// GET /api/sessions/{id}/git/diff/range - Get diff statistics between two revisions
+async fn get_diff_range(
     State(state): State<AppState>,
     AxumPath(id): AxumPath<Uuid>,
-    Json(req): Json<CheckoutRequest>,
-) -> AppResult<Json<GitCommandResponse>> {
+    Query(params): Query<DiffRangeQueryParams>,
+) -> AppResult<Json<GitDiffResponse>> {
     let repo_path = get_session_repo_path(&state, id).await?;
+    let files =
+        GitManager::diff_refs(&repo_path, &params.from, &params.to).map_err(map_git_error)?;
 
-    // Validate branch name
-    if req.branch.trim().is_empty() {
-        return Err(AppError::BadRequest("Branch name cannot be empty".to_string()));
-    }
-
-    let output = GitManager::checkout(&repo_path, &req.branch).map_err(map_git_error)?;
+    let total_added: usize = files.iter().map(|f| f.added).sum();
+    let total_removed: usize = files.iter().map(|f| f.removed).sum();
 
-    Ok(Json(GitCommandResponse {
+    Ok(Json(GitDiffResponse {
         session_id: id,
-        output,
+        files,
+        total_added,
+        total_removed,
     }))
 }
 
-/// Create the git router (nested under sessions)
-pub fn router() -> Router<AppState> {
-    Router::new()
-        .route("/sessions/{id}/git/status", get(get_status))
-        .route("/sessions/{id}/git/log", get(get_log))
-        .route("/sessions/{id}/git/branches", get(get_branches))
-        .route("/sessions/{id}/git/diff", get(get_diff))
-        .route("/sessions/{id}/git/pull", post(post_pull))
-        .route("/sessions/{id}/git/push", post(post_push))
-        .route("/sessions/{id}/git/commit", post(post_commit))
-        .route("/sessions/{id}/git/reset", post(post_reset))
-        .route("/sessions/{id}/git/checkout", post(post_checkout))
+/// GET /api/sessions/compare/changes?a=&b= - Diff between the current states of two
+/// sessions' worktrees of the same repository
+async fn get_compare_changes(
+    State(state): State<AppState>,
+    Query(params): Query<CompareChangesQueryParams>,
+) -> AppResult<Json<GitCompareChangesResponse>> {
+    let (a, b) = (params.a, params.b);
+    let path_a = get_session_repo_path(&state, a).await?;
+    let path_b = get_session_repo_path(&state, b).await?;
+
+    let common_a = GitManager::common_dir(&path_a).map_err(map_git_error)?;
+    let common_b = GitManager::common_dir(&path_b).map_err(map_git_error)?;
+    if common_a != common_b {
+        return Err(AppError::BadRequest(format!(
+            "Sessions {} and {} do not share a repository",
+            a, b
+        )));
+    }
+    if path_a == path_b {
+        return Err(AppError::BadRequest(format!(
+            "Sessions {} and {} use the same working tree; comparing requires each to use its own worktree",
+            a, b
+        )));
+    }
+
+    let diff = tokio::task::spawn_blocking(move || GitManager::diff_worktrees(&path_a, &path_b))
+        .await
+        .map_err(|e| AppError::Internal(format!("Worktree diff task failed: {}", e)))?
+        .map_err(map_git_error)?;
+
+    let total_added: usize = diff.files.iter().map(|f| f.added).sum();
+    let total_removed: usize = diff.files.iter().map(|f| f.removed).sum();
+
+    Ok(Json(GitCompareChangesResponse {
+        a,
+        b,
+        files: diff.files,
+        total_added,
+        total_removed,
+        patch: diff.patch,
+    }))
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::api::repos::{router as repos_router, AddRepoRequest};
-    use crate::api::sessions::{router as sessions_router, CreateSessionRequest};
-    use crate::db::models::{Orchestrator, Repo, Session};
-    use crate::db::Database;
-    use axum_test::TestServer;
-    use std::fs;
-    use tempfile::TempDir;
+/// GET /api/sessions/{id}/git/session-summary - Combined diff and commit list of everything
+/// committed since the session's first run started, across all of its runs
+async fn get_session_summary(
+    State(state): State<AppState>,
+    AxumPath(id): AxumPath<Uuid>,
+) -> AppResult<Json<GitSessionSummaryResponse>> {
+    let repo_path = get_session_repo_path(&state, id).await?;
+    let session = state
+        .db
+        .get_session(id)
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+    let base_sha = state
+        .db
+        .get_session_base(id)
+        .map_err(|e| AppError::Internal(e.to_string()))?
+        .ok_or_else(|| AppError::NotFound(format!("Session {} has no checkpoint", id)))?;
+
+    let head_sha = GitManager::head_sha(&repo_path)
+        .map_err(map_git_error)?
+        .ok_or_else(|| AppError::BadRequest("Repository has no commits".to_string()))?;
+    let commits = GitManager::log_range(&repo_path, &base_sha).map_err(map_git_error)?;
+    let files = GitManager::diff_refs(&repo_path, &base_sha, &head_sha).map_err(map_git_error)?;
+
+    let total_added: usize = files.iter().map(|f| f.added).sum();
+    let total_removed: usize = files.iter().map(|f| f.removed).sum();
+    let description =
+        summary_description(&session, &commits, files.len(), total_added, total_removed);
+
+    Ok(Json(GitSessionSummaryResponse {
+        session_id: id,
+        base_sha,
+        head_sha,
+        files_changed: files.len(),
+        commits,
+        files,
+        total_added,
+        total_removed,
+        description,
+    }))
+}
+
+/// Format a session's commits and change totals as a pull request description
+fn summary_description(
+    session: &Session,
+    commits: &[Commit],
+    files_changed: usize,
+    added: usize,
+    removed: usize,
+) -> String {
+    let title = session
+        .name
+        .clone()
+        .unwrap_or_else(|| format!("Session {}", &session.id.to_string()[..8]));
+
+    let mut description = format!("## {}\n\n### Commits\n\n", title);
+    if commits.is_empty() {
+        description.push_str("_No commits yet._\n");
+    }
+    // Oldest first, the order a reviewer reads them in
+    for commit in commits.iter().rev() {
+        let subject = commit.message.lines().next().unwrap_or("");
+        description.push_str(&format!("- {} ({})\n", subject, commit.short_id));
+    }
+    description.push_str(&format!(
+        "\n### Changes\n\n{} file{} changed, {} insertion{}(+), {} deletion{}(-)\n",
+        files_changed,
+        if files_changed == 1 { "" } else { "s" },
+        added,
+        if added == 1 { "" } else { "s" },
+        removed,
+        if removed == 1 { "" } else { "s" },
+    ));
+
+    description
+}
+
+/// GET /api/sessions/{id}/git/diff/file?path= - Unified diff of one file's pending changes
+async fn get_path_diff(
+    State(state): State<AppState>,
+    AxumPath(id): AxumPath<Uuid>,
+    Query(params): Query<PathDiffQueryParams>,
+) -> AppResult<Json<GitPathDiffResponse>> {
+    let repo_path = get_session_repo_path(&state, id).await?;
+    let diff =
+        GitManager::file_diff(&repo_path, &params.path, params.staged).map_err(map_git_error)?;
+
+    Ok(Json(GitPathDiffResponse {
+        session_id: id,
+        diff,
+    }))
+}
+
+/// GET /api/sessions/{id}/git/diff/hunks - Structured working-tree hunks for one file
+async fn get_diff_hunks(
+    State(state): State<AppState>,
+    AxumPath(id): AxumPath<Uuid>,
+    Query(params): Query<DiffHunksQueryParams>,
+) -> AppResult<Json<GitDiffHunksResponse>> {
+    let repo_path = get_session_repo_path(&state, id).await?;
+    let diff = GitManager::diff_hunks(&repo_path, &params.file).map_err(map_git_error)?;
+
+    Ok(Json(GitDiffHunksResponse {
+        session_id: id,
+        diff,
+    }))
+}
+
+/// GET /api/sessions/{id}/git/file-diff - Diff one file between two revisions
+async fn get_file_diff(
+    State(state): State<AppState>,
+    AxumPath(id): AxumPath<Uuid>,
+    Query(params): Query<FileDiffQueryParams>,
+) -> AppResult<Json<GitFileDiffResponse>> {
+    let repo_path = get_session_repo_path(&state, id).await?;
+    let to = params.to.as_deref().unwrap_or("HEAD");
+    let diff = GitManager::file_diff_between(&repo_path, &params.path, &params.from, to)
+        .map_err(map_git_error)?;
+
+    Ok(Json(GitFileDiffResponse {
+        session_id: id,
+        diff,
+    }))
+}
+
+/// GET /api/sessions/{id}/git/conflicts - List conflicted files with their three-way versions
+async fn get_conflicts(
+    State(state): State<AppState>,
+    AxumPath(id): AxumPath<Uuid>,
+) -> AppResult<Json<GitConflictsResponse>> {
+    let repo_path = get_session_repo_path(&state, id).await?;
+    let files = GitManager::conflicts(&repo_path).map_err(map_git_error)?;
+
+    Ok(Json(GitConflictsResponse {
+        session_id: id,
+        files,
+    }))
+}
+
+/// Tell the session's viewers that a git operation left conflicts behind
+async fn notify_conflicts(state: &AppState, id: Uuid, conflicts: &[String]) {
+    let message = format!(
+        "Pull left {} conflicted file(s) to resolve: {}",
+        conflicts.len(),
+        conflicts.join(", ")
+    );
+
+    if let Err(e) = state.db.insert_message(id, MessageRole::System, &message) {
+        tracing::warn!("Failed to record conflict message for session {}: {}", id, e);
+    }
+    state
+        .connections
+        .broadcast(id, ServerMessage::Warning { session_id: id, message })
+        .await;
+}
+
+/// POST /api/sessions/{id}/git/pull - Execute git pull and report any conflicts it left
+async fn post_pull(
+    State(state): State<AppState>,
+    AxumPath(id): AxumPath<Uuid>,
+) -> AppResult<Json<GitPullResponse>> {
+    let repo_path = get_session_repo_path(&state, id).await?;
+    let output = GitManager::pull(&repo_path).map_err(map_git_error)?;
+    let conflicts = GitManager::conflicted_paths(&repo_path).map_err(map_git_error)?;
+
+    let needs_resolution = !conflicts.is_empty();
+    if needs_resolution {
+        notify_conflicts(&state, id, &conflicts).await;
+    }
+
+    Ok(Json(GitPullResponse {
+        session_id: id,
+        output,
+        conflicts,
+        needs_resolution,
+    }))
+}
+
+/// POST /api/sessions/{id}/git/push - Execute git push
+///
+/// The body is optional; without one this is a bare `git push`.
+async fn post_push(
+    State(state): State<AppState>,
+    AxumPath(id): AxumPath<Uuid>,
+    req: Option<Json<PushOptions>>,
+) -> AppResult<Json<GitCommandResponse>> {
+    let repo_path = get_session_repo_path(&state, id).await?;
+    let options = req.map(|Json(options)| options).unwrap_or_default();
+    let output = GitManager::push(&repo_path, &options).map_err(map_git_error)?;
+
+    Ok(Json(GitCommandResponse {
+        session_id: id,
+        output,
+    }))
+}
+
+/// POST /api/sessions/{id}/git/commit - Execute git commit
+async fn post_commit(
+    State(state): State<AppState>,
+    AxumPath(id): AxumPath<Uuid>,
+    Json(req): Json<CommitRequest>,
+) -> AppResult<Json<GitCommitResponse>> {
+    let repo_path = get_session_repo_path(&state, id).await?;
+
+    // Validate message
+    if req.message.trim().is_empty() {
+        return Err(AppError::BadRequest("Commit message cannot be empty".to_string()));
+    }
+
+    let stage = req.stage.unwrap_or(if req.stage_all {
+        StageMode::All
+    } else {
+        StageMode::None
+    });
+
+    let staged = match stage {
+        StageMode::None => None,
+        StageMode::All => {
+            GitManager::add_all(&repo_path).map_err(map_git_error)?;
+            None
+        }
+        StageMode::Patterns => {
+            let patterns = auto_stage_patterns(&state, id)?;
+            Some(GitManager::add_paths(&repo_path, &patterns).map_err(map_git_error)?)
+        }
+    };
+
+    let message = stamp_commit_message(&state, id, req.message)?;
+    let output = GitManager::commit(&repo_path, &message).map_err(map_git_error)?;
+
+    Ok(Json(GitCommitResponse {
+        session_id: id,
+        output,
+        staged,
+    }))
+}
+
+/// Append the session trailer to a commit message when `commit_session_trailer` is enabled
+fn stamp_commit_message(state: &AppState, session_id: Uuid, message: String) -> AppResult<String> {
+    let stamp = state.config.get_as::<bool>(SESSION_TRAILER_KEY)?.unwrap_or(false);
+
+    Ok(if stamp {
+        append_trailer(&message, SESSION_TRAILER, &session_id.to_string())
+    } else {
+        message
+    })
+}
+
+/// POST /api/sessions/{id}/git/commit/amend - Amend the last commit
+async fn post_commit_amend(
+    State(state): State<AppState>,
+    AxumPath(id): AxumPath<Uuid>,
+    Json(req): Json<AmendRequest>,
+) -> AppResult<Json<GitCommandResponse>> {
+    let repo_path = get_session_repo_path(&state, id).await?;
+
+    let message = match req.message {
+        Some(message) if message.trim().is_empty() => {
+            return Err(AppError::BadRequest("Commit message cannot be empty".to_string()));
+        }
+        Some(message) => Some(stamp_commit_message(&state, id, message)?),
+        None => None,
+    };
+
+    if req.stage_all {
+        GitManager::add_all(&repo_path).map_err(map_git_error)?;
+    }
+
+    let output = GitManager::commit_amend(&repo_path, message.as_deref()).map_err(map_git_error)?;
+
+    Ok(Json(GitCommandResponse {
+        session_id: id,
+        output,
+    }))
+}
+
+/// Resolve the auto-stage patterns for a session's repository
+fn auto_stage_patterns(state: &AppState, session_id: Uuid) -> AppResult<Vec<String>> {
+    let session = state
+        .db
+        .get_session(session_id)
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    let raw = match state
+        .db
+        .get_repo_config(session.repo_id, AUTO_STAGE_PATTERNS_KEY)
+        .map_err(|e| AppError::Internal(e.to_string()))?
+    {
+        Some(raw) => Some(raw),
+        None => state
+            .config
+            .get(AUTO_STAGE_PATTERNS_KEY)
+            .map_err(|e| AppError::Internal(e.to_string()))?,
+    };
+
+    let patterns = raw
+        .as_deref()
+        .map(parse_pattern_list)
+        .transpose()
+        .map_err(|e| AppError::BadRequest(format!("Invalid {}: {}", AUTO_STAGE_PATTERNS_KEY, e)))?
+        .unwrap_or_default();
+
+    if patterns.is_empty() {
+        return Err(AppError::BadRequest(format!(
+            "No {} configured for this repository",
+            AUTO_STAGE_PATTERNS_KEY
+        )));
+    }
+
+    Ok(patterns)
+}
+
+/// POST /api/sessions/{id}/git/reset - Execute git reset --hard
+async fn post_reset(
+    State(state): State<AppState>,
+    AxumPath(id): AxumPath<Uuid>,
+    Json(req): Json<ResetRequest>,
+) -> AppResult<Json<GitCommandResponse>> {
+    // Require explicit confirmation for destructive operation
+    if !req.confirm {
+        return Err(AppError::BadRequest(
+            "Reset requires confirmation. Set confirm: true to proceed.".to_string(),
+        ));
+    }
+
+    let repo_path = get_session_repo_path(&state, id).await?;
+    let output = GitManager::reset_hard(&repo_path).map_err(map_git_error)?;
+
+    Ok(Json(GitCommandResponse {
+        session_id: id,
+        output,
+    }))
+}
+
+/// POST /api/sessions/{id}/git/restore-checkpoint - Hard-reset to the commit
+/// HEAD pointed at when the session's last run started
+///
+/// Untracked files created by the run are left in place.
+async fn post_restore_checkpoint(
+    State(state): State<AppState>,
+    AxumPath(id): AxumPath<Uuid>,
+    Json(req): Json<ResetRequest>,
+) -> AppResult<Json<RestoreCheckpointResponse>> {
+    if !req.confirm {
+        return Err(AppError::BadRequest(
+            "Restoring a checkpoint requires confirmation. Set confirm: true to proceed."
+                .to_string(),
+        ));
+    }
+
+    let repo_path = get_session_repo_path(&state, id).await?;
+
+    if state.ralph_manager.is_session_running(id).await {
+        return Err(AppError::Conflict(format!(
+            "Session {} is running; cancel it before restoring its checkpoint",
+            id
+        )));
+    }
+
+    let checkpoint_sha = state
+        .db
+        .get_session_checkpoint(id)
+        .map_err(|e| AppError::Internal(e.to_string()))?
+        .ok_or_else(|| AppError::NotFound(format!("Session {} has no checkpoint", id)))?;
+
+    let output = GitManager::reset_hard_to(&repo_path, &checkpoint_sha).map_err(map_git_error)?;
+
+    Ok(Json(RestoreCheckpointResponse {
+        session_id: id,
+        checkpoint_sha,
+        output,
+    }))
+}
+
+/// POST /api/sessions/{id}/git/checkout - Switch branch
+async fn post_checkout(
+    State(state): State<AppState>,
+    AxumPath(id): AxumPath<Uuid>,
+    Json(req): Json<CheckoutRequest>,
+) -> AppResult<Json<GitCommandResponse>> {
+    let repo_path = get_session_repo_path(&state, id).await?;
+
+    // Validate branch name
+    if req.branch.trim().is_empty() {
+        return Err(AppError::BadRequest("Branch name cannot be empty".to_string()));
+    }
+
+    let output = GitManager::checkout(&repo_path, &req.branch).map_err(map_git_error)?;
+
+    match GitManager::current_branch(&repo_path) {
+        Ok(branch) => {
+            if let Err(e) = state.db.update_session_branch(id, branch.as_deref()) {
+                tracing::warn!("Failed to record branch for session {}: {}", id, e);
+            }
+        }
+        Err(e) => tracing::warn!("Failed to read branch after checkout: {}", e),
+    }
+
+    Ok(Json(GitCommandResponse {
+        session_id: id,
+        output,
+    }))
+}
+
+/// POST /api/sessions/{id}/git/branch - Create a branch without switching to it
+async fn post_branch(
+    State(state): State<AppState>,
+    AxumPath(id): AxumPath<Uuid>,
+    Json(req): Json<CreateBranchRequest>,
+) -> AppResult<Json<GitBranchResponse>> {
+    let repo_path = get_session_repo_path(&state, id).await?;
+    let branch = GitManager::create_branch(&repo_path, &req.name, req.from.as_deref())
+        .map_err(map_git_error)?;
+
+    Ok(Json(GitBranchResponse {
+        session_id: id,
+        branch,
+    }))
+}
+
+/// DELETE /api/sessions/{id}/git/branch/{name} - Delete a local branch
+async fn delete_branch(
+    State(state): State<AppState>,
+    AxumPath((id, name)): AxumPath<(Uuid, String)>,
+    Query(params): Query<DeleteBranchQueryParams>,
+) -> AppResult<Json<GitBranchesResponse>> {
+    let repo_path = get_session_repo_path(&state, id).await?;
+    GitManager::delete_branch(&repo_path, &name, params.force).map_err(map_git_error)?;
+    let branches = GitManager::branches(&repo_path).map_err(map_git_error)?;
+
+    Ok(Json(GitBranchesResponse {
+        session_id: id,
+        branches,
+    }))
+}
+
+/// GET /api/sessions/{id}/git/remotes - List remotes with their fetch and push URLs
+async fn get_remotes(
+    State(state): State<AppState>,
+    AxumPath(id): AxumPath<Uuid>,
+) -> AppResult<Json<GitRemotesResponse>> {
+    let repo_path = get_session_repo_path(&state, id).await?;
+    let remotes = GitManager::list_remotes(&repo_path).map_err(map_git_error)?;
+
+    Ok(Json(GitRemotesResponse {
+        session_id: id,
+        remotes,
+    }))
+}
+
+/// POST /api/sessions/{id}/git/remotes - Add a remote
+async fn post_remote(
+    State(state): State<AppState>,
+    AxumPath(id): AxumPath<Uuid>,
+    Json(req): Json<AddRemoteRequest>,
+) -> AppResult<Json<GitRemotesResponse>> {
+    let repo_path = get_session_repo_path(&state, id).await?;
+    GitManager::add_remote(&repo_path, &req.name, &req.url).map_err(map_git_error)?;
+
+    get_remotes(State(state), AxumPath(id)).await
+}
+
+/// DELETE /api/sessions/{id}/git/remotes/{name} - Remove a remote
+async fn delete_remote(
+    State(state): State<AppState>,
+    AxumPath((id, name)): AxumPath<(Uuid, String)>,
+) -> AppResult<Json<GitRemotesResponse>> {
+    let repo_path = get_session_repo_path(&state, id).await?;
+    GitManager::remove_remote(&repo_path, &name).map_err(map_git_error)?;
+
+    get_remotes(State(state), AxumPath(id)).await
+}
+
+/// GET /api/sessions/{id}/git/stash - List stash entries
+async fn get_stash(
+    State(state): State<AppState>,
+    AxumPath(id): AxumPath<Uuid>,
+) -> AppResult<Json<GitStashResponse>> {
+    let repo_path = get_session_repo_path(&state, id).await?;
+    let entries = GitManager::stash_list(&repo_path).map_err(map_git_error)?;
+
+    Ok(Json(GitStashResponse {
+        session_id: id,
+        entries,
+    }))
+}
+
+/// POST /api/sessions/{id}/git/stash - Stash uncommitted changes
+async fn post_stash(
+    State(state): State<AppState>,
+    AxumPath(id): AxumPath<Uuid>,
+    Json(req): Json<StashRequest>,
+) -> AppResult<Json<GitStashResponse>> {
+    let repo_path = get_session_repo_path(&state, id).await?;
+    let message = req.message.as_deref().filter(|m| !m.trim().is_empty());
+    GitManager::stash_save(&repo_path, message).map_err(map_git_error)?;
+
+    get_stash(State(state), AxumPath(id)).await
+}
+
+/// POST /api/sessions/{id}/git/stash/{index}/pop - Apply and remove a stash entry
+async fn post_stash_pop(
+    State(state): State<AppState>,
+    AxumPath((id, index)): AxumPath<(Uuid, usize)>,
+) -> AppResult<Json<GitStashResponse>> {
+    let repo_path = get_session_repo_path(&state, id).await?;
+    GitManager::stash_pop(&repo_path, index).map_err(map_git_error)?;
+
+    get_stash(State(state), AxumPath(id)).await
+}
+
+/// DELETE /api/sessions/{id}/git/stash/{index} - Drop a stash entry
+async fn delete_stash(
+    State(state): State<AppState>,
+    AxumPath((id, index)): AxumPath<(Uuid, usize)>,
+) -> AppResult<Json<GitStashResponse>> {
+    let repo_path = get_session_repo_path(&state, id).await?;
+    GitManager::stash_drop(&repo_path, index).map_err(map_git_error)?;
+
+    get_stash(State(state), AxumPath(id)).await
+}
+
+/// POST /api/sessions/{id}/git/checkout-file - Discard changes to one file
+async fn post_checkout_file(
+    State(state): State<AppState>,
+    AxumPath(id): AxumPath<Uuid>,
+    Json(req): Json<CheckoutFileRequest>,
+) -> AppResult<Json<GitStatusResponse>> {
+    // Require explicit confirmation for destructive operation
+    if !req.confirm {
+        return Err(AppError::BadRequest(
+            "Checkout of a file requires confirmation. Set confirm: true to proceed.".to_string(),
+        ));
+    }
+
+    let repo_path = get_session_repo_path(&state, id).await?;
+    GitManager::checkout_file(&repo_path, &req.path).map_err(map_git_error)?;
+    let status = GitManager::status(&repo_path).map_err(map_git_error)?;
+
+    Ok(Json(GitStatusResponse {
+        session_id: id,
+        status,
+    }))
+}
+
+/// GET /api/recent-commits - Most recent commits across all tracked repos
+async fn get_recent_commits(
+    State(state): State<AppState>,
+    Query(params): Query<LogQueryParams>,
+) -> AppResult<Json<RecentCommitsResponse>> {
+    let limit = params.limit.unwrap_or(20).min(MAX_RECENT_COMMITS);
+    let repos = state
+        .db
+        .list_repos()
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    // Each repo contributes at most `limit` commits, read on the blocking pool
+    let mut seen_paths = HashSet::new();
+    let reads = repos
+        .into_iter()
+        .filter(|repo| seen_paths.insert(repo.path.clone()))
+        .map(|repo| {
+            let semaphore = state.git_semaphore.clone();
+            async move {
+                let result = match semaphore.acquire_owned().await {
+                    Ok(_permit) => {
+                        let path = PathBuf::from(&repo.path);
+                        tokio::task::spawn_blocking(move || GitManager::log(&path, limit))
+                            .await
+                            .map_err(|e| e.to_string())
+                            .and_then(|r| r.map_err(|e| e.to_string()))
+                    }
+                    Err(e) => Err(e.to_string()),
+                };
+                (repo, result)
+            }
+        });
+
+    let mut commits = Vec::new();
+    let mut failed = Vec::new();
+    for (repo, result) in futures::future::join_all(reads).await {
+        match result {
+            Ok(repo_commits) => commits.extend(repo_commits.into_iter().map(|commit| RepoCommit {
+                repo_id: repo.id,
+                repo_name: repo.name.clone(),
+                commit,
+            })),
+            Err(error) => {
+                tracing::warn!("Skipping repo {} in recent commits: {}", repo.path, error);
+                failed.push(RepoFailure {
+                    repo_id: repo.id,
+                    error,
+                });
+            }
+        }
+    }
+
+    // Timestamps are all RFC 3339 in UTC, so they order lexically
+    commits.sort_by(|a, b| b.commit.timestamp.cmp(&a.commit.timestamp));
+    commits.truncate(limit);
+
+    Ok(Json(RecentCommitsResponse { commits, failed }))
+}
+
+/// Create the git router (nested under sessions)
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/sessions/{id}/git/status", get(get_status))
+        .route("/sessions/{id}/git/log", get(get_log))
+        .route("/sessions/{id}/git/search-commits", get(get_search_commits))
+        .route("/sessions/{id}/git/branches", get(get_branches))
+        .route("/sessions/{id}/git/branches/detailed", get(get_branches_detailed))
+        .route("/sessions/{id}/git/diff", get(get_diff))
+        .route("/sessions/{id}/git/diff/hunks", get(get_diff_hunks))
+        .route("/sessions/{id}/git/diff/range", get(get_diff_range))
+        .route("/sessions/{id}/git/diff/file", get(get_path_diff))
+        .route("/sessions/{id}/git/activity", get(get_activity))
+        .route("/sessions/{id}/git/file-diff", get(get_file_diff))
+        .route("/sessions/{id}/git/conflicts", get(get_conflicts))
+        .route("/sessions/{id}/git/patch/download", get(get_patch_download))
+        .route("/sessions/{id}/git/session-summary", get(get_session_summary))
+        .route("/sessions/{id}/git/pull", post(post_pull))
+        .route("/sessions/{id}/git/push", post(post_push))
+        .route("/sessions/{id}/git/commit", get(get_commit).post(post_commit))
+        .route("/sessions/{id}/git/commit/amend", post(post_commit_amend))
+        .route("/sessions/{id}/git/reset", post(post_reset))
+        .route(
+            "/sessions/{id}/git/restore-checkpoint",
+            post(post_restore_checkpoint),
+        )
+        .route("/sessions/{id}/git/checkout", post(post_checkout))
+        .route("/sessions/{id}/git/checkout-file", post(post_checkout_file))
+        .route("/sessions/{id}/git/branch", post(post_branch))
+        .route("/sessions/{id}/git/branch/{*name}", delete(delete_branch))
+        .route("/sessions/{id}/git/remotes", get(get_remotes).post(post_remote))
+        .route("/sessions/{id}/git/remotes/{name}", delete(delete_remote))
+        .route("/sessions/{id}/git/stash", get(get_stash).post(post_stash))
+        .route("/sessions/{id}/git/stash/{index}", delete(delete_stash))
+        .route("/sessions/{id}/git/stash/{index}/pop", post(post_stash_pop))
+        .route("/sessions/compare/changes", get(get_compare_changes))
+        .route("/recent-commits", get(get_recent_commits))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::repos::{router as repos_router, AddRepoRequest};
+    use crate::api::sessions::{router as sessions_router, CreateSessionRequest, SessionDetails};
+    use crate::db::models::{Orchestrator, Repo, Session};
+    use crate::db::Database;
+    use axum_test::TestServer;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn create_test_state() -> AppState {
+        let db = Database::in_memory().expect("Failed to create test database");
+        AppState::new(db)
+    }
+
+    fn create_test_server(state: AppState) -> TestServer {
+        let app = Router::new()
+            .merge(repos_router())
+            .merge(sessions_router())
+            .merge(router())
+            .with_state(state);
+        TestServer::new(app).expect("Failed to create test server")
+    }
+
+    fn create_git_repo_with_commit() -> TempDir {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let repo = git2::Repository::init(temp_dir.path()).expect("Failed to init git repo");
+
+        // Configure user
+        let mut config = repo.config().expect("Failed to get config");
+        config.set_str("user.name", "Test User").expect("Failed to set user.name");
+        config.set_str("user.email", "test@example.com").expect("Failed to set user.email");
+
+        // Create initial commit
+        let sig = repo.signature().expect("Failed to create signature");
+        let tree_id = repo.index().expect("Failed to get index").write_tree().expect("Failed to write tree");
+        let tree = repo.find_tree(tree_id).expect("Failed to find tree");
+        repo.commit(Some("HEAD"), &sig, &sig, "Initial commit", &tree, &[])
+            .expect("Failed to create initial commit");
+
+        temp_dir
+    }
+
+    async fn create_test_session(server: &TestServer) -> (Session, TempDir) {
+        let temp_dir = create_git_repo_with_commit();
+        let path = temp_dir.path().to_string_lossy().to_string();
+
+        // Add repo
+        let response = server
+            .post("/repos")
+            .json(&AddRepoRequest {
+                path,
+                name: Some("test-repo".to_string()),
+            })
+            .await;
+        response.assert_status_ok();
+        let repo: Repo = response.json();
+
+        // Create session
+        let response = server
+            .post("/sessions")
+            .json(&CreateSessionRequest {
+                repo_id: repo.id,
+                name: Some("Test Session".to_string()),
+                orchestrator: Orchestrator::Ralph,
+                ..Default::default()
+            })
+            .await;
+        response.assert_status_ok();
+        let session: Session = response.json();
+
+        (session, temp_dir)
+    }
+
+    #[tokio::test]
+    async fn test_get_status() {
+        let state = create_test_state();
+        let server = create_test_server(state);
+        let (session, _temp_dir) = create_test_session(&server).await;
+
+        let response = server
+            .get(&format!("/sessions/{}/git/status", session.id))
+            .await;
+        response.assert_status_ok();
+
+        let status: GitStatusResponse = response.json();
+        assert_eq!(status.session_id, session.id);
+        assert!(!status.status.branch.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_status_with_untracked() {
+        let state = create_test_state();
+        let server = create_test_server(state);
+        let (session, temp_dir) = create_test_session(&server).await;
+
+        // Create an untracked file
+        fs::write(temp_dir.path().join("new_file.txt"), "content").expect("Failed to write file");
+
+        let response = server
+            .get(&format!("/sessions/{}/git/status", session.id))
+            .await;
+        response.assert_status_ok();
+
+        let status: GitStatusResponse = response.json();
+        assert!(status.status.untracked.contains(&"new_file.txt".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_get_status_scoped_to_path() {
+        let state = create_test_state();
+        let server = create_test_server(state);
+        let (session, temp_dir) = create_test_session(&server).await;
+
+        fs::create_dir_all(temp_dir.path().join("packages/app")).unwrap();
+        fs::write(temp_dir.path().join("packages/app/index.ts"), "").unwrap();
+        fs::write(temp_dir.path().join("top.txt"), "").unwrap();
+
+        let response = server
+            .get(&format!("/sessions/{}/git/status?path=packages/app", session.id))
+            .await;
+        response.assert_status_ok();
+        let status: GitStatusResponse = response.json();
+        assert_eq!(status.status.untracked, vec!["packages/app/index.ts".to_string()]);
+
+        server
+            .get(&format!("/sessions/{}/git/status?path=../elsewhere", session.id))
+            .await
+            .assert_status_bad_request();
+    }
+
+    #[tokio::test]
+    async fn test_get_log() {
+        let state = create_test_state();
+        let server = create_test_server(state);
+        let (session, _temp_dir) = create_test_session(&server).await;
+
+        let response = server
+            .get(&format!("/sessions/{}/git/log", session.id))
+            .await;
+        response.assert_status_ok();
+
+        let log: GitLogResponse = response.json();
+        assert_eq!(log.session_id, session.id);
+        assert!(!log.commits.is_empty());
+        assert_eq!(log.commits[0].message, "Initial commit");
+    }
+
+    #[tokio::test]
+    async fn test_get_log_with_limit() {
+        let state = create_test_state();
+        let server = create_test_server(state);
+        let (session, _temp_dir) = create_test_session(&server).await;
+
+        let response = server
+            .get(&format!("/sessions/{}/git/log?limit=5", session.id))
+            .await;
+        response.assert_status_ok();
+
+        let log: GitLogResponse = response.json();
+        assert!(log.commits.len() <= 5);
+    }
+
+    #[tokio::test]
+    async fn test_get_commit() {
+        let state = create_test_state();
+        let server = create_test_server(state);
+        let (session, temp_dir) = create_test_session(&server).await;
+
+        let head = GitManager::head_sha(temp_dir.path()).unwrap().unwrap();
+        let response = server
+            .get(&format!("/sessions/{}/git/commit?rev={}", session.id, &head[..8]))
+            .await;
+        response.assert_status_ok();
+        let body: GitCommitDetailResponse = response.json();
+        assert_eq!(body.commit.id, head);
+        assert_eq!(body.commit.message, "Initial commit");
+        assert!(body.commit.parents.is_empty());
+
+        // Revisions that collide with other routes' names still resolve
+        let repo = git2::Repository::open(temp_dir.path()).unwrap();
+        repo.branch("amend", &repo.head().unwrap().peel_to_commit().unwrap(), false)
+            .unwrap();
+        let body: GitCommitDetailResponse = server
+            .get(&format!("/sessions/{}/git/commit?rev=amend", session.id))
+            .await
+            .json();
+        assert_eq!(body.commit.id, head);
+
+        server
+            .get(&format!("/sessions/{}/git/commit?rev=HEAD~3", session.id))
+            .await
+            .assert_status_not_found();
+    }
+
+    #[tokio::test]
+    async fn test_get_branches() {
+        let state = create_test_state();
+        let server = create_test_server(state);
+        let (session, _temp_dir) = create_test_session(&server).await;
+
+        let response = server
+            .get(&format!("/sessions/{}/git/branches", session.id))
+            .await;
+        response.assert_status_ok();
+
+        let branches: GitBranchesResponse = response.json();
+        assert_eq!(branches.session_id, session.id);
+        assert!(!branches.branches.is_empty());
+
+        // Should have current branch marked
+        let current = branches.branches.iter().find(|b| b.is_current);
+        assert!(current.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_push_sets_upstream() {
+        let state = create_test_state();
+        let server = create_test_server(state);
+        let (session, temp_dir) = create_test_session(&server).await;
+        let url = format!("/sessions/{}/git/push", session.id);
+
+        let remote_dir = TempDir::new().unwrap();
+        git2::Repository::init_bare(remote_dir.path()).unwrap();
+        GitManager::add_remote(temp_dir.path(), "fork", &remote_dir.path().to_string_lossy())
+            .unwrap();
+
+        // No body: a bare push, which has nowhere to go
+        let response = server.post(&url).await;
+        response.assert_status_ok();
+        assert!(!response.json::<GitCommandResponse>().output.success);
+
+        let response = server
+            .post(&url)
+            .json(&PushOptions {
+                set_upstream: true,
+                remote: Some("fork".to_string()),
+                ..Default::default()
+            })
+            .await;
+        response.assert_status_ok();
+        assert!(response.json::<GitCommandResponse>().output.success);
+        let branch = GitManager::current_branch(temp_dir.path()).unwrap().unwrap();
+        assert!(git2::Repository::open(remote_dir.path())
+            .unwrap()
+            .find_branch(&branch, git2::BranchType::Local)
+            .is_ok());
+
+        server
+            .post(&url)
+            .json(&PushOptions {
+                remote: Some("nowhere".to_string()),
+                ..Default::default()
+            })
+            .await
+            .assert_status_not_found();
+    }
+
+    #[tokio::test]
+    async fn test_remotes() {
+        let state = create_test_state();
+        let server = create_test_server(state);
+        let (session, _temp_dir) = create_test_session(&server).await;
+        let url = format!("/sessions/{}/git/remotes", session.id);
+
+        let response = server
+            .post(&url)
+            .json(&AddRemoteRequest {
+                name: "fork".to_string(),
+                url: "https://example.com/me/fork.git".to_string(),
+            })
+            .await;
+        response.assert_status_ok();
+        let remotes: GitRemotesResponse = response.json();
+        assert_eq!(remotes.remotes.len(), 1);
+        assert_eq!(
+            remotes.remotes[0].push_url.as_deref(),
+            Some("https://example.com/me/fork.git")
+        );
+
+        server
+            .post(&url)
+            .json(&AddRemoteRequest {
+                name: "fork".to_string(),
+                url: "https://example.com/other.git".to_string(),
+            })
+            .await
+            .assert_status_bad_request();
+
+        let response = server.delete(&format!("{}/fork", url)).await;
+        response.assert_status_ok();
+        let remotes: GitRemotesResponse = response.json();
+        assert!(remotes.remotes.is_empty());
+
+        server
+            .delete(&format!("{}/fork", url))
+            .await
+            .assert_status_not_found();
+    }
+
+    #[tokio::test]
+    async fn test_get_branches_detailed() {
+        let state = create_test_state();
+        let server = create_test_server(state);
+        let (session, temp_dir) = create_test_session(&server).await;
+
+        let head = GitManager::head_sha(temp_dir.path()).unwrap().unwrap();
+        let response = server
+            .get(&format!("/sessions/{}/git/branches/detailed", session.id))
+            .await;
+        response.assert_status_ok();
+
+        let result: GitBranchesDetailedResponse = response.json();
+        assert_eq!(result.session_id, session.id);
+        let current = result.branches.iter().find(|b| b.branch.is_current).unwrap();
+        let tip = current.tip.as_ref().unwrap();
+        assert_eq!(tip.id, head);
+        assert_eq!(tip.short_id, head[..7]);
+        assert!(!tip.summary.is_empty());
+        assert!(!tip.summary.contains('\n'));
+        assert!(!tip.author.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_diff_no_changes() {
+        let state = create_test_state();
+        let server = create_test_server(state);
+        let (session, _temp_dir) = create_test_session(&server).await;
+
+        let response = server
+            .get(&format!("/sessions/{}/git/diff", session.id))
+            .await;
+        response.assert_status_ok();
+
+        let diff: GitDiffResponse = response.json();
+        assert_eq!(diff.session_id, session.id);
+        assert!(diff.files.is_empty());
+        assert_eq!(diff.total_added, 0);
+        assert_eq!(diff.total_removed, 0);
+    }
+
+    #[tokio::test]
+    async fn test_get_conflicts_clean_repo() {
+        let state = create_test_state();
+        let server = create_test_server(state);
+        let (session, _temp_dir) = create_test_session(&server).await;
+
+        let response = server
+            .get(&format!("/sessions/{}/git/conflicts", session.id))
+            .await;
+        response.assert_status_ok();
+
+        let conflicts: GitConflictsResponse = response.json();
+        assert_eq!(conflicts.session_id, session.id);
+        assert!(conflicts.files.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_path_diff() {
+        let state = create_test_state();
+        let server = create_test_server(state);
+        let (session, temp_dir) = create_test_session(&server).await;
+
+        fs::create_dir(temp_dir.path().join("src")).expect("Failed to create dir");
+        fs::write(temp_dir.path().join("src/lib.rs"), "fn main() {}\n").expect("Failed to write file");
+
+        let response = server
+            .get(&format!("/sessions/{}/git/diff/file?path=src/lib.rs", session.id))
+            .await;
+        response.assert_status_ok();
+        let diff: GitPathDiffResponse = response.json();
+        assert_eq!(diff.diff.path, "src/lib.rs");
+        assert!(!diff.diff.staged);
+        assert!(!diff.diff.binary);
+        assert!(diff.diff.diff.contains("+fn main() {}"));
+
+        let diff: GitPathDiffResponse = server
+            .get(&format!("/sessions/{}/git/diff/file?path=src/lib.rs&staged=true", session.id))
+            .await
+            .json();
+        assert!(diff.diff.staged);
+        assert!(diff.diff.diff.is_empty());
+
+        // Files named like other diff routes are reachable too
+        fs::write(temp_dir.path().join("hunks"), "x\n").expect("Failed to write file");
+        let diff: GitPathDiffResponse = server
+            .get(&format!("/sessions/{}/git/diff/file?path=hunks", session.id))
+            .await
+            .json();
+        assert_eq!(diff.diff.path, "hunks");
+        assert!(diff.diff.diff.contains("+x"));
+    }
+
+    #[tokio::test]
+    async fn test_get_diff_range() {
+        let state = create_test_state();
+        let server = create_test_server(state);
+        let (session, temp_dir) = create_test_session(&server).await;
+
+        let repo = git2::Repository::open(temp_dir.path()).unwrap();
+        let base = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.branch("base", &base, false).unwrap();
+        std::fs::write(temp_dir.path().join("built.txt"), "one\ntwo\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(std::path::Path::new("built.txt")).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = repo.signature().unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "Build", &tree, &[&base])
+            .unwrap();
+
+        let response = server
+            .get(&format!("/sessions/{}/git/diff/range?from=base&to=HEAD", session.id))
+            .await;
+        response.assert_status_ok();
+        let diff: GitDiffResponse = response.json();
+        assert_eq!(diff.files.len(), 1);
+        assert_eq!(diff.files[0].path, "built.txt");
+        assert_eq!(diff.total_added, 2);
+
+        server
+            .get(&format!("/sessions/{}/git/diff/range?from=base&to=missing", session.id))
+            .await
+            .assert_status_bad_request();
+    }
+
+    #[tokio::test]
+    async fn test_get_diff_hunks() {
+        let state = create_test_state();
+        let server = create_test_server(state);
+        let (session, temp_dir) = create_test_session(&server).await;
+
+        fs::write(temp_dir.path().join("new_file.txt"), "a\nb\n").expect("Failed to write file");
+
+        let response = server
+            .get(&format!("/sessions/{}/git/diff/hunks?file=new_file.txt", session.id))
+            .await;
+        response.assert_status_ok();
+        let diff: GitDiffHunksResponse = response.json();
+        assert_eq!(diff.session_id, session.id);
+        assert_eq!(diff.diff.hunks.len(), 1);
+        assert_eq!(diff.diff.hunks[0].lines.len(), 2);
+
+        let response = server
+            .get(&format!("/sessions/{}/git/diff/hunks?file=../escape", session.id))
+            .await;
+        response.assert_status_bad_request();
+    }
+
+    #[tokio::test]
+    async fn test_commit_empty_message() {
+        let state = create_test_state();
+        let server = create_test_server(state);
+        let (session, _temp_dir) = create_test_session(&server).await;
+
+        let response = server
+            .post(&format!("/sessions/{}/git/commit", session.id))
+            .json(&CommitRequest {
+                message: "  ".to_string(),
+                stage_all: false,
+                stage: None,
+            })
+            .await;
+        response.assert_status_bad_request();
+    }
+
+    #[tokio::test]
+    async fn test_reset_requires_confirm() {
+        let state = create_test_state();
+        let server = create_test_server(state);
+        let (session, _temp_dir) = create_test_session(&server).await;
+
+        let response = server
+            .post(&format!("/sessions/{}/git/reset", session.id))
+            .json(&ResetRequest { confirm: false })
+            .await;
+        response.assert_status_bad_request();
+    }
 
-    fn create_test_state() -> AppState {
-        let db = Database::in_memory().expect("Failed to create test database");
-        AppState::new(db)
+    #[tokio::test]
+    async fn test_checkout_empty_branch() {
+        let state = create_test_state();
+        let server = create_test_server(state);
+        let (session, _temp_dir) = create_test_session(&server).await;
+
+        let response = server
+            .post(&format!("/sessions/{}/git/checkout", session.id))
+            .json(&CheckoutRequest {
+                branch: "  ".to_string(),
+            })
+            .await;
+        response.assert_status_bad_request();
     }
 
-    fn create_test_server(state: AppState) -> TestServer {
-        let app = Router::new()
-            .merge(repos_router())
-            .merge(sessions_router())
-            .merge(router())
-            .with_state(state);
-        TestServer::new(app).expect("Failed to create test server")
+    #[tokio::test]
+    async fn test_compare_worktree_changes() {
+        let state = create_test_state();
+        let server = create_test_server(state);
+
+        let main_dir = create_git_repo_with_commit();
+        let wt_parent = TempDir::new().unwrap();
+        let wt_path = wt_parent.path().join("wt");
+        git2::Repository::open(main_dir.path())
+            .unwrap()
+            .worktree("wt", &wt_path, None)
+            .unwrap();
+        fs::write(wt_path.join("new.txt"), "hello\n").unwrap();
+
+        let add_session = |path: String| {
+            let server = &server;
+            async move {
+                let repo: Repo = server
+                    .post("/repos")
+                    .json(&AddRepoRequest { path, name: None })
+                    .await
+                    .json();
+                let session: Session = server
+                    .post("/sessions")
+                    .json(&CreateSessionRequest {
+                        repo_id: repo.id,
+                        name: None,
+                        orchestrator: Orchestrator::Ralph,
+                        ..Default::default()
+                    })
+                    .await
+                    .json();
+                session
+            }
+        };
+        let a = add_session(main_dir.path().to_string_lossy().to_string()).await;
+        let b = add_session(wt_path.to_string_lossy().to_string()).await;
+
+        let response = server
+            .get(&format!("/sessions/compare/changes?a={}&b={}", a.id, b.id))
+            .await;
+        response.assert_status_ok();
+        let compare: GitCompareChangesResponse = response.json();
+        assert_eq!(compare.files.len(), 1);
+        assert_eq!(compare.files[0].path, "new.txt");
+        assert_eq!(compare.total_added, 1);
+        assert!(compare.patch.contains("+hello"));
+
+        // The same working tree is not a worktree comparison
+        server
+            .get(&format!("/sessions/compare/changes?a={}&b={}", a.id, a.id))
+            .await
+            .assert_status_bad_request();
+
+        // Nor are two unrelated repositories
+        let (other, _other_dir) = create_test_session(&server).await;
+        let response = server
+            .get(&format!("/sessions/compare/changes?a={}&b={}", a.id, other.id))
+            .await;
+        response.assert_status_bad_request();
+        assert!(response.text().contains("do not share a repository"));
+
+        server
+            .get(&format!("/sessions/compare/changes?a={}&b={}", a.id, Uuid::new_v4()))
+            .await
+            .assert_status_not_found();
     }
 
-    fn create_git_repo_with_commit() -> TempDir {
-        let temp_dir = TempDir::new().expect("Failed to create temp dir");
-        let repo = git2::Repository::init(temp_dir.path()).expect("Failed to init git repo");
+    #[tokio::test]
+    async fn test_checkout_records_session_branch() {
+        let state = create_test_state();
+        let server = create_test_server(state);
+        let (session, _temp_dir) = create_test_session(&server).await;
+        assert_eq!(session.branch, None);
 
-        // Configure user
-        let mut config = repo.config().expect("Failed to get config");
-        config.set_str("user.name", "Test User").expect("Failed to set user.name");
-        config.set_str("user.email", "test@example.com").expect("Failed to set user.email");
+        server
+            .post(&format!("/sessions/{}/git/branch", session.id))
+            .json(&CreateBranchRequest {
+                name: "feature/foo".to_string(),
+                from: None,
+            })
+            .await
+            .assert_status_ok();
 
-        // Create initial commit
-        let sig = repo.signature().expect("Failed to create signature");
-        let tree_id = repo.index().expect("Failed to get index").write_tree().expect("Failed to write tree");
-        let tree = repo.find_tree(tree_id).expect("Failed to find tree");
-        repo.commit(Some("HEAD"), &sig, &sig, "Initial commit", &tree, &[])
-            .expect("Failed to create initial commit");
+        server
+            .post(&format!("/sessions/{}/git/checkout", session.id))
+            .json(&CheckoutRequest {
+                branch: "feature/foo".to_string(),
+            })
+            .await
+            .assert_status_ok();
 
-        temp_dir
+        let details: SessionDetails = server.get(&format!("/sessions/{}", session.id)).await.json();
+        assert_eq!(details.session.branch.as_deref(), Some("feature/foo"));
     }
 
-    async fn create_test_session(server: &TestServer) -> (Session, TempDir) {
-        let temp_dir = create_git_repo_with_commit();
-        let path = temp_dir.path().to_string_lossy().to_string();
+    #[tokio::test]
+    async fn test_create_and_delete_branch() {
+        let state = create_test_state();
+        let server = create_test_server(state);
+        let (session, _temp_dir) = create_test_session(&server).await;
 
-        // Add repo
         let response = server
-            .post("/repos")
-            .json(&AddRepoRequest {
-                path,
-                name: Some("test-repo".to_string()),
+            .post(&format!("/sessions/{}/git/branch", session.id))
+            .json(&CreateBranchRequest {
+                name: "feature/login".to_string(),
+                from: None,
             })
             .await;
         response.assert_status_ok();
-        let repo: Repo = response.json();
+        let created: GitBranchResponse = response.json();
+        assert_eq!(created.branch.name, "feature/login");
 
-        // Create session
         let response = server
-            .post("/sessions")
-            .json(&CreateSessionRequest {
-                repo_id: repo.id,
-                name: Some("Test Session".to_string()),
-                orchestrator: Orchestrator::Ralph,
+            .post(&format!("/sessions/{}/git/branch", session.id))
+            .json(&CreateBranchRequest {
+                name: "bad..name".to_string(),
+                from: None,
             })
             .await;
-        response.assert_status_ok();
-        let session: Session = response.json();
+        response.assert_status_bad_request();
 
-        (session, temp_dir)
+        // Names with slashes are matched by the catch-all segment
+        let response = server
+            .delete(&format!("/sessions/{}/git/branch/feature/login", session.id))
+            .await;
+        response.assert_status_ok();
+        let remaining: GitBranchesResponse = response.json();
+        assert!(remaining.branches.iter().all(|b| b.name != "feature/login"));
+
+        let current = remaining.branches.iter().find(|b| b.is_current).unwrap();
+        server
+            .delete(&format!("/sessions/{}/git/branch/{}?force=true", session.id, current.name))
+            .await
+            .assert_status_bad_request();
+
+        server
+            .delete(&format!("/sessions/{}/git/branch/missing", session.id))
+            .await
+            .assert_status_not_found();
     }
 
     #[tokio::test]
-    async fn test_get_status() {
+    async fn test_stash_endpoints() {
         let state = create_test_state();
         let server = create_test_server(state);
-        let (session, _temp_dir) = create_test_session(&server).await;
+        let (session, temp_dir) = create_test_session(&server).await;
+        let url = format!("/sessions/{}/git/stash", session.id);
 
+        // Popping an empty stash is a client error, not a git failure
+        let response = server.post(&format!("{}/0/pop", url)).await;
+        response.assert_status_bad_request();
+        assert!(response.text().contains("stash is empty"));
+
+        std::fs::write(temp_dir.path().join("wip.txt"), "work in progress").unwrap();
         let response = server
-            .get(&format!("/sessions/{}/git/status", session.id))
+            .post(&url)
+            .json(&StashRequest {
+                message: Some("before switching".to_string()),
+            })
             .await;
         response.assert_status_ok();
+        let stash: GitStashResponse = response.json();
+        assert_eq!(stash.entries.len(), 1);
+        assert!(stash.entries[0].message.contains("before switching"));
+        assert!(!temp_dir.path().join("wip.txt").exists());
+
+        server
+            .delete(&format!("{}/1", url))
+            .await
+            .assert_status_not_found();
+
+        let stash: GitStashResponse = server.post(&format!("{}/0/pop", url)).await.json();
+        assert!(stash.entries.is_empty());
+        assert!(temp_dir.path().join("wip.txt").exists());
+    }
 
-        let status: GitStatusResponse = response.json();
-        assert_eq!(status.session_id, session.id);
-        assert!(!status.status.branch.is_empty());
+    #[tokio::test]
+    async fn test_nonexistent_session() {
+        let state = create_test_state();
+        let server = create_test_server(state);
+        let fake_id = Uuid::new_v4();
+
+        let response = server
+            .get(&format!("/sessions/{}/git/status", fake_id))
+            .await;
+        response.assert_status_not_found();
     }
 
     #[tokio::test]
-    async fn test_get_status_with_untracked() {
+    async fn test_checkout_file() {
         let state = create_test_state();
         let server = create_test_server(state);
         let (session, temp_dir) = create_test_session(&server).await;
 
-        // Create an untracked file
-        fs::write(temp_dir.path().join("new_file.txt"), "content").expect("Failed to write file");
+        // Track a file, then modify it
+        let repo = git2::Repository::open(temp_dir.path()).unwrap();
+        fs::write(temp_dir.path().join("file.txt"), "original").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(std::path::Path::new("file.txt")).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = repo.signature().unwrap();
+        let parent = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "Add file", &tree, &[&parent])
+            .unwrap();
+        fs::write(temp_dir.path().join("file.txt"), "modified").unwrap();
+
+        // Requires confirmation
+        let response = server
+            .post(&format!("/sessions/{}/git/checkout-file", session.id))
+            .json(&CheckoutFileRequest {
+                path: "file.txt".to_string(),
+                confirm: false,
+            })
+            .await;
+        response.assert_status_bad_request();
 
+        // Rejects paths outside the repo
         let response = server
-            .get(&format!("/sessions/{}/git/status", session.id))
+            .post(&format!("/sessions/{}/git/checkout-file", session.id))
+            .json(&CheckoutFileRequest {
+                path: "../file.txt".to_string(),
+                confirm: true,
+            })
+            .await;
+        response.assert_status_bad_request();
+
+        let response = server
+            .post(&format!("/sessions/{}/git/checkout-file", session.id))
+            .json(&CheckoutFileRequest {
+                path: "file.txt".to_string(),
+                confirm: true,
+            })
             .await;
         response.assert_status_ok();
 
         let status: GitStatusResponse = response.json();
-        assert!(status.status.untracked.contains(&"new_file.txt".to_string()));
+        assert!(status.status.unstaged.is_empty());
+        assert_eq!(fs::read_to_string(temp_dir.path().join("file.txt")).unwrap(), "original");
     }
 
     #[tokio::test]
-    async fn test_get_log() {
+    async fn test_recent_commits_across_repos() {
         let state = create_test_state();
-        let server = create_test_server(state);
-        let (session, _temp_dir) = create_test_session(&server).await;
+        let server = create_test_server(state.clone());
+        let (_session_a, _dir_a) = create_test_session(&server).await;
+        let (_session_b, _dir_b) = create_test_session(&server).await;
 
-        let response = server
-            .get(&format!("/sessions/{}/git/log", session.id))
-            .await;
+        // A tracked repo that has since disappeared is reported, not fatal
+        let missing = state
+            .db
+            .insert_repo("/nonexistent/ralphtown-repo", "gone")
+            .unwrap();
+
+        let response = server.get("/recent-commits?limit=10").await;
         response.assert_status_ok();
 
-        let log: GitLogResponse = response.json();
-        assert_eq!(log.session_id, session.id);
-        assert!(!log.commits.is_empty());
-        assert_eq!(log.commits[0].message, "Initial commit");
+        let body: RecentCommitsResponse = response.json();
+        assert_eq!(body.commits.len(), 2);
+        assert!(body.commits.iter().all(|c| c.commit.message == "Initial commit"));
+        assert_eq!(body.failed.len(), 1);
+        assert_eq!(body.failed[0].repo_id, missing.id);
+
+        let body: RecentCommitsResponse = server.get("/recent-commits?limit=1").await.json();
+        assert_eq!(body.commits.len(), 1);
     }
 
     #[tokio::test]
-    async fn test_get_log_with_limit() {
+    async fn test_get_activity() {
         let state = create_test_state();
         let server = create_test_server(state);
         let (session, _temp_dir) = create_test_session(&server).await;
 
         let response = server
-            .get(&format!("/sessions/{}/git/log?limit=5", session.id))
+            .get(&format!("/sessions/{}/git/activity?days=30", session.id))
             .await;
         response.assert_status_ok();
 
-        let log: GitLogResponse = response.json();
-        assert!(log.commits.len() <= 5);
+        let activity: GitActivityResponse = response.json();
+        assert_eq!(activity.session_id, session.id);
+        assert_eq!(activity.activity.days.len(), 30);
+        assert_eq!(activity.activity.days.iter().map(|d| d.count).sum::<usize>(), 1);
     }
 
     #[tokio::test]
-    async fn test_get_branches() {
+    async fn test_get_file_diff() {
         let state = create_test_state();
         let server = create_test_server(state);
-        let (session, _temp_dir) = create_test_session(&server).await;
+        let (session, temp_dir) = create_test_session(&server).await;
+
+        let repo = git2::Repository::open(temp_dir.path()).unwrap();
+        fs::write(temp_dir.path().join("file.txt"), "hello\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(std::path::Path::new("file.txt")).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = repo.signature().unwrap();
+        let parent = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "Add file", &tree, &[&parent])
+            .unwrap();
 
         let response = server
-            .get(&format!("/sessions/{}/git/branches", session.id))
+            .get(&format!(
+                "/sessions/{}/git/file-diff?path=file.txt&from={}&to=HEAD",
+                session.id,
+                parent.id()
+            ))
             .await;
         response.assert_status_ok();
+        let body: GitFileDiffResponse = response.json();
+        assert!(body.diff.diff.contains("+hello"));
 
-        let branches: GitBranchesResponse = response.json();
-        assert_eq!(branches.session_id, session.id);
-        assert!(!branches.branches.is_empty());
+        let response = server
+            .get(&format!(
+                "/sessions/{}/git/file-diff?path=file.txt&from=deadbeef",
+                session.id
+            ))
+            .await;
+        response.assert_status_not_found();
 
-        // Should have current branch marked
-        let current = branches.branches.iter().find(|b| b.is_current);
-        assert!(current.is_some());
+        let response = server
+            .get(&format!(
+                "/sessions/{}/git/file-diff?path=../file.txt&from=HEAD",
+                session.id
+            ))
+            .await;
+        response.assert_status_bad_request();
     }
 
     #[tokio::test]
-    async fn test_get_diff_no_changes() {
+    async fn test_commit_stages_configured_patterns() {
         let state = create_test_state();
-        let server = create_test_server(state);
-        let (session, _temp_dir) = create_test_session(&server).await;
+        let server = create_test_server(state.clone());
+        let (session, temp_dir) = create_test_session(&server).await;
+
+        std::fs::write(temp_dir.path().join("keep.rs"), "fn keep() {}").unwrap();
+        std::fs::write(temp_dir.path().join("scratch.txt"), "notes").unwrap();
+
+        let commit = CommitRequest {
+            message: "Add sources".to_string(),
+            stage_all: false,
+            stage: Some(StageMode::Patterns),
+        };
 
+        // Patterns must be configured
         let response = server
-            .get(&format!("/sessions/{}/git/diff", session.id))
+            .post(&format!("/sessions/{}/git/commit", session.id))
+            .json(&commit)
+            .await;
+        response.assert_status_bad_request();
+
+        state
+            .db
+            .set_repo_config(session.repo_id, AUTO_STAGE_PATTERNS_KEY, r#"["*.rs"]"#)
+            .unwrap();
+
+        let response = server
+            .post(&format!("/sessions/{}/git/commit", session.id))
+            .json(&commit)
             .await;
         response.assert_status_ok();
 
-        let diff: GitDiffResponse = response.json();
-        assert_eq!(diff.session_id, session.id);
-        assert!(diff.files.is_empty());
-        assert_eq!(diff.total_added, 0);
-        assert_eq!(diff.total_removed, 0);
+        let body: GitCommitResponse = response.json();
+        assert_eq!(body.staged, Some(vec!["keep.rs".to_string()]));
+
+        let status = GitManager::status(temp_dir.path()).unwrap();
+        assert!(status.staged.is_empty());
+        assert_eq!(status.untracked, vec!["scratch.txt".to_string()]);
     }
 
     #[tokio::test]
-    async fn test_commit_empty_message() {
+    async fn test_commit_session_trailer() {
         let state = create_test_state();
-        let server = create_test_server(state);
-        let (session, _temp_dir) = create_test_session(&server).await;
+        let server = create_test_server(state.clone());
+        let (session, temp_dir) = create_test_session(&server).await;
+        let commit = |message: &str| CommitRequest {
+            message: message.to_string(),
+            stage_all: true,
+            stage: None,
+        };
+
+        std::fs::write(temp_dir.path().join("human.txt"), "by hand").unwrap();
+        server
+            .post(&format!("/sessions/{}/git/commit", session.id))
+            .json(&commit("Human change"))
+            .await
+            .assert_status_ok();
+
+        state.db.set_config(SESSION_TRAILER_KEY, "true").unwrap();
+        std::fs::write(temp_dir.path().join("agent.txt"), "by agent").unwrap();
+        server
+            .post(&format!("/sessions/{}/git/commit", session.id))
+            .json(&commit("Agent change"))
+            .await
+            .assert_status_ok();
+
+        let log: GitLogResponse = server
+            .get(&format!("/sessions/{}/git/log", session.id))
+            .await
+            .json();
+        let session_id = session.id.to_string();
+        assert_eq!(log.commits[0].session_id.as_deref(), Some(session_id.as_str()));
+        assert!(log.commits[0].message.ends_with(&format!("Ralphtown-Session: {}", session_id)));
+        assert!(log.commits[1].session_id.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_commit_rejects_malformed_trailer_config() {
+        let state = create_test_state();
+        let server = create_test_server(state.clone());
+        let (session, temp_dir) = create_test_session(&server).await;
 
+        state.db.set_config(SESSION_TRAILER_KEY, "yes").unwrap();
+        std::fs::write(temp_dir.path().join("agent.txt"), "by agent").unwrap();
         let response = server
             .post(&format!("/sessions/{}/git/commit", session.id))
             .json(&CommitRequest {
-                message: "  ".to_string(),
-                stage_all: false,
+                message: "Agent change".to_string(),
+                stage_all: true,
+                stage: None,
             })
             .await;
-        response.assert_status_bad_request();
+
+        response.assert_status(axum::http::StatusCode::UNPROCESSABLE_ENTITY);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["error"]["code"], "PARSE_ERROR");
+        assert_eq!(body["error"]["details"]["field"], SESSION_TRAILER_KEY);
+        assert_eq!(body["error"]["details"]["value"], "yes");
     }
 
     #[tokio::test]
-    async fn test_reset_requires_confirm() {
+    async fn test_commit_amend() {
         let state = create_test_state();
         let server = create_test_server(state);
-        let (session, _temp_dir) = create_test_session(&server).await;
+        let (session, temp_dir) = create_test_session(&server).await;
+        let url = format!("/sessions/{}/git/commit/amend", session.id);
 
+        std::fs::write(temp_dir.path().join("typo.txt"), "oops").unwrap();
         let response = server
-            .post(&format!("/sessions/{}/git/reset", session.id))
-            .json(&ResetRequest { confirm: false })
+            .post(&url)
+            .json(&AmendRequest {
+                message: Some("Fix typo in the message".to_string()),
+                stage_all: true,
+            })
             .await;
-        response.assert_status_bad_request();
+        response.assert_status_ok();
+        let body: GitCommandResponse = response.json();
+        assert!(body.output.success);
+
+        let log: GitLogResponse = server
+            .get(&format!("/sessions/{}/git/log", session.id))
+            .await
+            .json();
+        assert_eq!(log.commits.len(), 1);
+        assert_eq!(log.commits[0].message, "Fix typo in the message");
+        assert!(GitManager::status(temp_dir.path()).unwrap().untracked.is_empty());
+
+        server
+            .post(&url)
+            .json(&AmendRequest {
+                message: Some("  ".to_string()),
+                stage_all: false,
+            })
+            .await
+            .assert_status_bad_request();
     }
 
     #[tokio::test]
-    async fn test_checkout_empty_branch() {
+    async fn test_pull_reports_conflicts() {
+        let state = create_test_state();
+        let server = create_test_server(state.clone());
+        let (session, temp_dir) = create_test_session(&server).await;
+        let url = format!("/sessions/{}/git/pull", session.id);
+
+        let git = |dir: &std::path::Path, args: &[&str]| {
+            let status = std::process::Command::new("git")
+                .current_dir(dir)
+                .args(args)
+                .output()
+                .unwrap()
+                .status;
+            assert!(status.success(), "git {:?} failed", args);
+        };
+        let commit = |dir: &std::path::Path, content: &str| {
+            std::fs::write(dir.join("shared.txt"), content).unwrap();
+            git(dir, &["add", "shared.txt"]);
+            git(dir, &["commit", "-m", content.trim()]);
+        };
+
+        // An upstream clone that diverges from the session repo on the same line
+        commit(temp_dir.path(), "base\n");
+        let upstream = TempDir::new().unwrap();
+        git2::Repository::clone(&temp_dir.path().to_string_lossy(), upstream.path()).unwrap();
+        git(upstream.path(), &["config", "user.name", "Upstream"]);
+        git(upstream.path(), &["config", "user.email", "upstream@example.com"]);
+        commit(upstream.path(), "theirs\n");
+        commit(temp_dir.path(), "ours\n");
+
+        let branch = git2::Repository::open(temp_dir.path())
+            .unwrap()
+            .head()
+            .unwrap()
+            .shorthand()
+            .unwrap()
+            .to_string();
+        git(temp_dir.path(), &["remote", "add", "origin", &upstream.path().to_string_lossy()]);
+        git(temp_dir.path(), &["config", "pull.rebase", "false"]);
+        git(temp_dir.path(), &["fetch", "origin"]);
+        git(
+            temp_dir.path(),
+            &["branch", "--set-upstream-to", &format!("origin/{}", branch)],
+        );
+
+        let response = server.post(&url).await;
+        response.assert_status_ok();
+        let body: GitPullResponse = response.json();
+        assert!(!body.output.success);
+        assert!(body.needs_resolution);
+        assert_eq!(body.conflicts, vec!["shared.txt"]);
+
+        let messages = state.db.list_messages(session.id).unwrap();
+        assert!(messages
+            .iter()
+            .any(|m| m.role == MessageRole::System && m.content.contains("shared.txt")));
+    }
+
+    #[tokio::test]
+    async fn test_patch_download() {
+        let state = create_test_state();
+        let server = create_test_server(state.clone());
+        let (session, temp_dir) = create_test_session(&server).await;
+        let url = format!("/sessions/{}/git/patch/download", session.id);
+
+        std::fs::write(temp_dir.path().join("change.txt"), "agent change\n").unwrap();
+
+        let response = server.get(&url).await;
+        response.assert_status_ok();
+        assert_eq!(response.header("content-type"), "text/x-patch");
+        let disposition = response.header("content-disposition");
+        let disposition = disposition.to_str().unwrap();
+        assert!(disposition.starts_with("attachment; filename=\"test-session-"));
+        assert!(disposition.ends_with(".patch\""));
+        assert!(response.text().contains("+agent change"));
+
+        // The session scope needs a run checkpoint
+        server
+            .get(&format!("{}?scope=session", url))
+            .await
+            .assert_status_not_found();
+
+        let head = GitManager::head_sha(temp_dir.path()).unwrap().unwrap();
+        state.db.update_session_checkpoint(session.id, &head).unwrap();
+        let response = server.get(&format!("{}?scope=session", url)).await;
+        response.assert_status_ok();
+        assert!(response.text().contains("+++ b/change.txt"));
+    }
+
+    #[tokio::test]
+    async fn test_search_commits() {
         let state = create_test_state();
         let server = create_test_server(state);
-        let (session, _temp_dir) = create_test_session(&server).await;
+        let (session, temp_dir) = create_test_session(&server).await;
+        let url = format!("/sessions/{}/git/search-commits", session.id);
 
-        let response = server
-            .post(&format!("/sessions/{}/git/checkout", session.id))
-            .json(&CheckoutRequest {
-                branch: "  ".to_string(),
+        std::fs::write(temp_dir.path().join("a.txt"), "a").unwrap();
+        server
+            .post(&format!("/sessions/{}/git/commit", session.id))
+            .json(&CommitRequest {
+                message: "Introduce retry loop".to_string(),
+                stage_all: true,
+                stage: None,
             })
-            .await;
-        response.assert_status_bad_request();
+            .await
+            .assert_status_ok();
+
+        let response = server.get(&url).add_query_param("q", "RETRY").await;
+        response.assert_status_ok();
+        let body: GitSearchCommitsResponse = response.json();
+        assert_eq!(body.commits.len(), 1);
+        assert_eq!(body.commits[0].message, "Introduce retry loop");
+        assert_eq!(body.scanned, 2);
+        assert!(!body.truncated);
+
+        server
+            .get(&url)
+            .add_query_param("q", "  ")
+            .await
+            .assert_status_bad_request();
     }
 
     #[tokio::test]
-    async fn test_nonexistent_session() {
+    async fn test_session_summary() {
         let state = create_test_state();
-        let server = create_test_server(state);
-        let fake_id = Uuid::new_v4();
+        let server = create_test_server(state.clone());
+        let (session, temp_dir) = create_test_session(&server).await;
+        let url = format!("/sessions/{}/git/session-summary", session.id);
+
+        // The summary starts where the first run did
+        server.get(&url).await.assert_status_not_found();
+
+        let base = GitManager::head_sha(temp_dir.path()).unwrap().unwrap();
+        state.db.update_session_checkpoint(session.id, &base).unwrap();
+
+        for (name, content) in [("a.txt", "one\ntwo\n"), ("b.txt", "three\n")] {
+            std::fs::write(temp_dir.path().join(name), content).unwrap();
+            server
+                .post(&format!("/sessions/{}/git/commit", session.id))
+                .json(&CommitRequest {
+                    message: format!("Add {}\n\nDetails", name),
+                    stage_all: true,
+                    stage: None,
+                })
+                .await
+                .assert_status_ok();
+
+            // Each later run moves the run checkpoint, not the summary base
+            let head = GitManager::head_sha(temp_dir.path()).unwrap().unwrap();
+            state.db.update_session_checkpoint(session.id, &head).unwrap();
+        }
 
-        let response = server
-            .get(&format!("/sessions/{}/git/status", fake_id))
-            .await;
-        response.assert_status_not_found();
+        let response = server.get(&url).await;
+        response.assert_status_ok();
+        let summary: GitSessionSummaryResponse = response.json();
+        assert_eq!(summary.base_sha, base);
+        assert_eq!(summary.commits.len(), 2);
+        assert_eq!(summary.files_changed, 2);
+        assert_eq!(summary.total_added, 3);
+        assert_eq!(summary.total_removed, 0);
+
+        let description = &summary.description;
+        assert!(description.starts_with("## Test Session\n"));
+        let a = description.find("- Add a.txt (").unwrap();
+        let b = description.find("- Add b.txt (").unwrap();
+        assert!(a < b);
+        assert!(!description.contains("Details"));
+        assert!(description.contains("2 files changed, 3 insertions(+), 0 deletions(-)"));
+    }
+
+    #[test]
+    fn test_session_filename() {
+        let today = chrono::NaiveDate::from_ymd_opt(2026, 3, 9).unwrap();
+        let mut session = Session {
+            id: Uuid::parse_str("0b9e4a52-1234-4c3b-9a5e-000000000000").unwrap(),
+            repo_id: Uuid::new_v4(),
+            name: Some("Fix: login / OAuth bug!".to_string()),
+            orchestrator: Orchestrator::Ralph,
+            status: crate::db::models::SessionStatus::Idle,
+            archived: false,
+            branch: None,
+            exit_reason: None,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        };
+        assert_eq!(session_filename(&session, today, "patch"), "fix-login-oauth-bug-20260309.patch");
+
+        session.name = None;
+        assert_eq!(session_filename(&session, today, "patch"), "session-0b9e4a52-20260309.patch");
+    }
+
+    #[tokio::test]
+    async fn test_restore_checkpoint() {
+        let state = create_test_state();
+        let server = create_test_server(state.clone());
+        let (session, temp_dir) = create_test_session(&server).await;
+        let url = format!("/sessions/{}/git/restore-checkpoint", session.id);
+
+        server
+            .post(&url)
+            .json(&ResetRequest { confirm: false })
+            .await
+            .assert_status_bad_request();
+
+        // No run has recorded a checkpoint yet
+        server
+            .post(&url)
+            .json(&ResetRequest { confirm: true })
+            .await
+            .assert_status_not_found();
+
+        let checkpoint = GitManager::head_sha(temp_dir.path()).unwrap().unwrap();
+        state
+            .db
+            .update_session_checkpoint(session.id, &checkpoint)
+            .unwrap();
+
+        // Simulate a run that commits and leaves dirty changes
+        std::fs::write(temp_dir.path().join("run.txt"), "agent output").unwrap();
+        GitManager::add_all(temp_dir.path()).unwrap();
+        let output = GitManager::commit(temp_dir.path(), "Agent commit").unwrap();
+        assert!(output.success, "{}", output.stderr);
+        assert_ne!(GitManager::head_sha(temp_dir.path()).unwrap().unwrap(), checkpoint);
+
+        let response = server.post(&url).json(&ResetRequest { confirm: true }).await;
+        response.assert_status_ok();
+        let body: RestoreCheckpointResponse = response.json();
+        assert!(body.output.success);
+        assert_eq!(body.checkpoint_sha, checkpoint);
+        assert_eq!(GitManager::head_sha(temp_dir.path()).unwrap().unwrap(), checkpoint);
+        assert!(!temp_dir.path().join("run.txt").exists());
     }
 }
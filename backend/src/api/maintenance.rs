@@ -0,0 +1,70 @@
+//! Database maintenance REST API endpoints
+//!
+//! - GET /api/maintenance/stats - Database size and per-table row counts (admin only)
+
+use axum::{extract::State, http::HeaderMap, routing::get, Json, Router};
+
+use crate::db::models::DbStats;
+use crate::error::{AppError, AppResult};
+
+use super::{require_admin, AppState};
+
+/// Get the database size and row counts, for capacity planning (admin only
+/// when an admin token is configured)
+async fn get_stats(State(state): State<AppState>, headers: HeaderMap) -> AppResult<Json<DbStats>> {
+    if state.admin_token.is_some() {
+        require_admin(&state, &headers)?;
+    }
+
+    let stats = state
+        .db
+        .stats()
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(Json(stats))
+}
+
+/// Create the maintenance router
+pub fn router() -> Router<AppState> {
+    Router::new().route("/maintenance/stats", get(get_stats))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::ADMIN_TOKEN_HEADER;
+    use crate::db::Database;
+    use axum_test::TestServer;
+
+    #[tokio::test]
+    async fn test_stats_requires_admin_token() {
+        let state = AppState::new(Database::in_memory().unwrap());
+        let server = |state: AppState| {
+            TestServer::new(Router::new().merge(router()).with_state(state)).unwrap()
+        };
+
+        // Open to everyone while admin auth is off
+        server(state.clone())
+            .get("/maintenance/stats")
+            .await
+            .assert_status_ok();
+
+        let server = server(state.clone().with_admin_token(Some("s3cret")));
+        state.db.insert_repo("/path/to/repo", "repo").unwrap();
+        state.db.set_config("backend", "claude").unwrap();
+
+        server
+            .get("/maintenance/stats")
+            .await
+            .assert_status(axum::http::StatusCode::UNAUTHORIZED);
+
+        let response = server
+            .get("/maintenance/stats")
+            .add_header(ADMIN_TOKEN_HEADER, "s3cret")
+            .await;
+        response.assert_status_ok();
+        let stats: DbStats = response.json();
+        assert_eq!(stats.tables["repos"], 1);
+        assert_eq!(stats.tables["config"], 1);
+    }
+}
@@ -0,0 +1,88 @@
+//! Metrics REST API endpoint
+//!
+//! - GET /api/metrics - Connection, repository, session and process counts
+
+use std::collections::BTreeMap;
+
+use axum::{extract::State, routing::get, Json, Router};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{AppError, AppResult};
+use crate::ws::ConnectionManagerStats;
+
+use super::AppState;
+
+/// Session counts, in total and per status
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SessionMetrics {
+    pub total: i64,
+    pub by_status: BTreeMap<String, i64>,
+}
+
+/// Point-in-time counts for monitoring
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MetricsResponse {
+    pub websocket: ConnectionManagerStats,
+    pub repos: i64,
+    pub sessions: SessionMetrics,
+    /// Ralph processes currently running
+    pub running_processes: usize,
+}
+
+/// Report connection, repository, session and process counts
+async fn get_metrics(State(state): State<AppState>) -> AppResult<Json<MetricsResponse>> {
+    let repos = state
+        .db
+        .count_repos()
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+    let by_status = state
+        .db
+        .count_sessions_by_status()
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(Json(MetricsResponse {
+        websocket: state.connections.stats().await,
+        repos,
+        sessions: SessionMetrics {
+            total: by_status.values().sum(),
+            by_status,
+        },
+        running_processes: state.ralph_manager.running_processes().await.len(),
+    }))
+}
+
+/// Create the metrics router
+pub fn router() -> Router<AppState> {
+    Router::new().route("/metrics", get(get_metrics))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::models::Orchestrator;
+    use crate::db::Database;
+    use axum_test::TestServer;
+    use uuid::Uuid;
+
+    #[tokio::test]
+    async fn test_get_metrics() {
+        let state = AppState::new(Database::in_memory().unwrap());
+        let app = Router::new().merge(router()).with_state(state.clone());
+        let server = TestServer::new(app).unwrap();
+
+        let repo = state.db.insert_repo("/path/to/repo", "repo").unwrap();
+        state.db.insert_session(repo.id, None, Orchestrator::Ralph).unwrap();
+        state.db.insert_session(repo.id, None, Orchestrator::Ralph).unwrap();
+        let connection_id = Uuid::new_v4();
+        state.connections.register_connection(connection_id).await;
+
+        let response = server.get("/metrics").await;
+        response.assert_status_ok();
+        let metrics: MetricsResponse = response.json();
+        assert_eq!(metrics.repos, 1);
+        assert_eq!(metrics.sessions.total, 2);
+        assert_eq!(metrics.sessions.by_status["idle"], 2);
+        assert_eq!(metrics.websocket.connections, 1);
+        assert_eq!(metrics.running_processes, 0);
+    }
+}
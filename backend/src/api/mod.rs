@@ -1,29 +1,106 @@
 pub mod config;
+pub mod events;
+pub mod export;
 pub mod git;
+pub mod maintenance;
+pub mod metrics;
+pub mod rate_limit;
 pub mod repos;
 pub mod service;
 pub mod sessions;
+pub mod templates;
 
 use std::sync::Arc;
 
-use crate::db::Database;
+use axum::http::HeaderMap;
+use tokio::sync::Semaphore;
+
+use crate::db::{ConfigCache, Database};
+use crate::error::{AppError, AppResult};
+use crate::ralph::backend_health::BackendHealthChecker;
 use crate::ralph::RalphManager;
+use rate_limit::RateLimiter;
 use crate::ws::ConnectionManager;
 
+/// Maximum number of git operations run concurrently on the blocking pool
+const GIT_CONCURRENCY: usize = 4;
+
+/// Environment variable holding the token required by admin endpoints
+///
+/// It is deliberately not a config key: config is readable and writable
+/// without auth, so a token stored there would guard nothing.
+pub const ADMIN_TOKEN_ENV: &str = "RALPHTOWN_ADMIN_TOKEN";
+
+/// Request header carrying the admin token
+pub const ADMIN_TOKEN_HEADER: &str = "x-admin-token";
+
 /// Application state shared across all handlers
 #[derive(Clone)]
 pub struct AppState {
     pub db: Arc<Database>,
+    /// Cached config map; writes must go through it to stay atomic with the DB
+    pub config: ConfigCache,
     pub connections: ConnectionManager,
     pub ralph_manager: RalphManager,
+    /// Cached probes of the backend APIs, reported by readiness when enabled
+    pub backend_health: BackendHealthChecker,
+    /// Per-client token buckets for scans and git writes
+    pub rate_limiter: RateLimiter,
+    /// Bounds concurrent blocking git work (e.g. cross-repo aggregation)
+    pub git_semaphore: Arc<Semaphore>,
+    /// Token admin endpoints require; they are disabled without one
+    pub admin_token: Option<Arc<str>>,
 }
 
 impl AppState {
     pub fn new(db: Database) -> Self {
+        let db = Arc::new(db);
         Self {
-            db: Arc::new(db),
+            config: ConfigCache::new(db.clone()),
+            db,
             connections: ConnectionManager::new(),
             ralph_manager: RalphManager::new(),
+            backend_health: BackendHealthChecker::new(),
+            rate_limiter: RateLimiter::new(),
+            git_semaphore: Arc::new(Semaphore::new(GIT_CONCURRENCY)),
+            admin_token: None,
         }
     }
+
+    /// Require `token` on admin endpoints (`None` or empty disables them)
+    pub fn with_admin_token(mut self, token: Option<&str>) -> Self {
+        self.admin_token = token.filter(|token| !token.is_empty()).map(Arc::from);
+        self
+    }
+}
+
+/// Read the admin token from `RALPHTOWN_ADMIN_TOKEN`
+pub fn admin_token_from_env() -> Option<String> {
+    std::env::var(ADMIN_TOKEN_ENV).ok()
+}
+
+/// Require the configured admin token in the request headers.
+///
+/// Admin endpoints are disabled unless `RALPHTOWN_ADMIN_TOKEN` is set.
+pub(crate) fn require_admin(state: &AppState, headers: &HeaderMap) -> AppResult<()> {
+    let expected = state
+        .admin_token
+        .as_deref()
+        .ok_or_else(|| AppError::Unauthorized("Admin token is not configured".to_string()))?;
+
+    let provided = headers
+        .get(ADMIN_TOKEN_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .ok_or_else(|| AppError::Unauthorized(format!("Missing {} header", ADMIN_TOKEN_HEADER)))?;
+
+    if !constant_time_eq(provided.as_bytes(), expected.as_bytes()) {
+        return Err(AppError::Unauthorized("Invalid admin token".to_string()));
+    }
+
+    Ok(())
+}
+
+/// Compare two byte strings without short-circuiting on the first mismatch
+pub(crate) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
 }
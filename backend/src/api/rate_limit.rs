@@ -0,0 +1,251 @@
+//! Per-client rate limiting for expensive endpoints
+//!
+//! Repository scans and git write operations each spawn git work, so a client
+//! calling them in a tight loop can tie up the server. Each client IP gets a
+//! token bucket holding up to `rate_limit_burst` requests, refilled at
+//! `rate_limit_per_minute`; a request that finds the bucket empty is rejected
+//! with 429 and a `Retry-After` header. Reads are never limited, since the UI
+//! polls them.
+//!
+//! Limiting is off until `rate_limit_per_minute` is configured: a local UI
+//! shares one loopback address, so a default limit would throttle its own user.
+
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::Method,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::error::AppError;
+
+use super::AppState;
+
+/// Config key: requests per minute each client may make to limited endpoints
+/// (0 or unset disables rate limiting)
+pub const RATE_LIMIT_PER_MINUTE_KEY: &str = "rate_limit_per_minute";
+
+/// Config key: requests a client may make in a burst before being limited
+pub const RATE_LIMIT_BURST_KEY: &str = "rate_limit_burst";
+
+const DEFAULT_PER_MINUTE: u32 = 0;
+
+const DEFAULT_BURST: u32 = 10;
+
+/// Beyond this many tracked clients, refilled buckets are forgotten
+const MAX_TRACKED_CLIENTS: usize = 1024;
+
+/// Parse a rate limit setting: a non-negative integer
+pub fn parse_rate_limit(raw: &str) -> Result<u32, String> {
+    raw.trim()
+        .parse::<u32>()
+        .map_err(|_| format!("expected a non-negative integer, got '{}'", raw))
+}
+
+/// Whether a request hits an endpoint that is rate limited
+fn is_limited(method: &Method, path: &str) -> bool {
+    if method == Method::GET || method == Method::HEAD {
+        return false;
+    }
+    if path.starts_with("/api/repos/scan") {
+        return true;
+    }
+
+    // /api/sessions/{id}/git/...
+    let mut segments = path.strip_prefix("/api/sessions/").unwrap_or_default().split('/');
+    segments.next().is_some_and(|id| !id.is_empty()) && segments.next() == Some("git")
+}
+
+/// Tokens left for one client
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl Bucket {
+    /// Add the tokens earned since the last update, up to `burst`
+    fn refill(&mut self, per_minute: u32, burst: u32, now: Instant) {
+        let earned = now.duration_since(self.updated).as_secs_f64() * per_minute as f64 / 60.0;
+        self.tokens = (self.tokens + earned).min(burst as f64);
+        self.updated = now;
+    }
+}
+
+/// Token buckets per client IP
+#[derive(Clone, Default)]
+pub struct RateLimiter {
+    buckets: Arc<Mutex<HashMap<IpAddr, Bucket>>>,
+}
+
+impl RateLimiter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Take a token for `client`, or return how long until one is available
+    fn acquire(
+        &self,
+        client: IpAddr,
+        per_minute: u32,
+        burst: u32,
+        now: Instant,
+    ) -> Result<(), Duration> {
+        let mut buckets = self.buckets.lock().unwrap();
+
+        if buckets.len() >= MAX_TRACKED_CLIENTS {
+            buckets.retain(|_, bucket| {
+                bucket.refill(per_minute, burst, now);
+                bucket.tokens < burst as f64
+            });
+        }
+
+        let bucket = buckets.entry(client).or_insert(Bucket {
+            tokens: burst as f64,
+            updated: now,
+        });
+        bucket.refill(per_minute, burst, now);
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            let missing = 1.0 - bucket.tokens;
+            Err(Duration::from_secs_f64(missing * 60.0 / per_minute as f64))
+        }
+    }
+}
+
+/// Configured (per minute, burst) limits; malformed values fall back to the defaults
+fn configured_limits(state: &AppState) -> (u32, u32) {
+    let read = |key: &str, default: u32| match state.config.get(key) {
+        Ok(Some(raw)) => parse_rate_limit(&raw).unwrap_or_else(|e| {
+            tracing::warn!("Invalid {} value, ignoring: {}", key, e);
+            default
+        }),
+        Ok(None) => default,
+        Err(e) => {
+            tracing::warn!("Failed to read {}: {}", key, e);
+            default
+        }
+    };
+
+    (
+        read(RATE_LIMIT_PER_MINUTE_KEY, DEFAULT_PER_MINUTE),
+        read(RATE_LIMIT_BURST_KEY, DEFAULT_BURST),
+    )
+}
+
+/// Reject limited requests from clients that have used up their bucket
+pub async fn middleware(State(state): State<AppState>, req: Request, next: Next) -> Response {
+    if !is_limited(req.method(), req.uri().path()) {
+        return next.run(req).await;
+    }
+
+    let (per_minute, burst) = configured_limits(&state);
+    if per_minute == 0 {
+        return next.run(req).await;
+    }
+
+    // Without connection info (e.g. in-process tests) all requests share a bucket
+    let client = req
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip())
+        .unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED));
+
+    match state
+        .rate_limiter
+        .acquire(client, per_minute, burst.max(1), Instant::now())
+    {
+        Ok(()) => next.run(req).await,
+        Err(wait) => AppError::TooManyRequests {
+            message: format!(
+                "Too many requests; limited to {} per minute (burst {})",
+                per_minute, burst
+            ),
+            retry_after_secs: wait.as_secs_f64().ceil().max(1.0) as u64,
+        }
+        .into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Database;
+    use axum_test::TestServer;
+
+    #[test]
+    fn test_is_limited() {
+        assert!(is_limited(&Method::POST, "/api/repos/scan"));
+        assert!(is_limited(&Method::POST, "/api/repos/scan/preview"));
+        assert!(is_limited(&Method::POST, "/api/sessions/abc/git/commit"));
+        assert!(is_limited(&Method::DELETE, "/api/sessions/abc/git/branch/main"));
+        assert!(!is_limited(&Method::GET, "/api/sessions/abc/git/status"));
+        assert!(!is_limited(&Method::POST, "/api/sessions/abc/run"));
+        assert!(!is_limited(&Method::POST, "/api/sessions//git/commit"));
+        assert!(!is_limited(&Method::POST, "/api/repos"));
+    }
+
+    #[test]
+    fn test_acquire_refills_over_time() {
+        let limiter = RateLimiter::new();
+        let client = IpAddr::V4(Ipv4Addr::LOCALHOST);
+        let start = Instant::now();
+
+        // A burst of 2, then empty
+        assert!(limiter.acquire(client, 60, 2, start).is_ok());
+        assert!(limiter.acquire(client, 60, 2, start).is_ok());
+        let wait = limiter.acquire(client, 60, 2, start).unwrap_err();
+        assert_eq!(wait, Duration::from_secs(1));
+
+        // Other clients have their own bucket
+        assert!(limiter.acquire(IpAddr::V4(Ipv4Addr::BROADCAST), 60, 2, start).is_ok());
+
+        // One token per second at 60 per minute
+        let later = start + Duration::from_secs(1);
+        assert!(limiter.acquire(client, 60, 2, later).is_ok());
+        assert!(limiter.acquire(client, 60, 2, later).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_unlimited_by_default() {
+        let state = AppState::new(Database::in_memory().unwrap());
+        let server = TestServer::new(crate::create_app(state)).unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let body = serde_json::json!({ "directories": [dir.path()] });
+
+        for _ in 0..DEFAULT_BURST + 5 {
+            server.post("/api/repos/scan").json(&body).await.assert_status_ok();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_limited_requests_get_429() {
+        let state = AppState::new(Database::in_memory().unwrap());
+        state.config.set(RATE_LIMIT_PER_MINUTE_KEY, "1").unwrap();
+        state.config.set(RATE_LIMIT_BURST_KEY, "2").unwrap();
+        let server = TestServer::new(crate::create_app(state)).unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let body = serde_json::json!({ "directories": [dir.path()] });
+
+        for _ in 0..2 {
+            server.post("/api/repos/scan").json(&body).await.assert_status_ok();
+        }
+        let response = server.post("/api/repos/scan").json(&body).await;
+
+        response.assert_status(axum::http::StatusCode::TOO_MANY_REQUESTS);
+        let retry_after: u64 = response.header("retry-after").to_str().unwrap().parse().unwrap();
+        assert!((1..=60).contains(&retry_after));
+        let error: serde_json::Value = response.json();
+        assert_eq!(error["error"]["code"], "RATE_LIMITED");
+
+        // Reads are never limited
+        server.get("/api/repos").await.assert_status_ok();
+    }
+}
@@ -5,7 +5,7 @@ use std::pin::Pin;
 use axum::{
     extract::{Path as AxumPath, Query, State},
     response::sse::{Event, KeepAlive, Sse},
-    routing::{delete, get, post},
+    routing::{get, post, put},
     Json, Router,
 };
 use futures::stream::Stream;
@@ -13,12 +13,22 @@ use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc;
 use uuid::Uuid;
 
-use crate::db::models::Repo;
+use crate::db::models::{Orchestrator, Repo, Session};
 use crate::error::{AppError, AppResult};
-use crate::git::{CloneCredentials, CloneProgress, GitManager};
+use crate::git::{parse_pattern_list, CloneCredentials, CloneProgress, GitManager, RemoteCheck};
+use crate::ralph::{DENIED_PATHS_KEY, REVERT_DENIED_PATHS_KEY};
 
+use super::config::{ConfigResponse, ConfigValueResponse, SetConfigValueRequest};
+use super::git::AUTO_STAGE_PATTERNS_KEY;
+use super::sessions::{render_session_name, session_name_template};
 use super::AppState;
 
+/// Config key capping the number of tracked repositories (unlimited if unset)
+pub const MAX_REPOS_KEY: &str = "max_repos";
+
+/// How long a clone URL check waits for the remote to answer
+const CLONE_CHECK_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
 /// Request body for adding a new repository
 #[derive(Debug, Deserialize, Serialize)]
 pub struct AddRepoRequest {
@@ -28,11 +38,82 @@ pub struct AddRepoRequest {
     pub name: Option<String>,
 }
 
-/// Request body for cloning a repository
+/// Settings for the first session created along with a repository
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct InitialSessionRequest {
+    /// Optional session name (defaults to the session name template)
+    pub name: Option<String>,
+    #[serde(default)]
+    pub orchestrator: Orchestrator,
+}
+
+/// Request body for adding a repository together with its first session
 #[derive(Debug, Deserialize, Serialize)]
+pub struct AddRepoWithSessionRequest {
+    /// Path to the git repository
+    pub path: String,
+    /// Optional name (defaults to directory name)
+    pub name: Option<String>,
+    #[serde(default)]
+    pub session: InitialSessionRequest,
+}
+
+/// Response for adding a repository together with its first session
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RepoWithSessionResponse {
+    pub repo: Repo,
+    pub session: Session,
+}
+
+/// Request body for updating a repository
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UpdateRepoRequest {
+    /// New display name
+    pub name: String,
+}
+
+/// Query parameters for listing repositories
+#[derive(Debug, Default, Deserialize)]
+pub struct ListReposParams {
+    /// Only list repositories with this tag
+    pub tag: Option<String>,
+}
+
+/// Request body for replacing a repository's tags
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SetRepoTagsRequest {
+    pub tags: Vec<String>,
+}
+
+/// A repository's tags
+#[derive(Debug, Deserialize, Serialize)]
+pub struct RepoTagsResponse {
+    pub repo_id: Uuid,
+    /// Tags in alphabetical order
+    pub tags: Vec<String>,
+}
+
+/// Longest tag accepted, in characters
+const MAX_TAG_LENGTH: usize = 64;
+
+/// Request body for cloning a repository
+#[derive(Debug, Default, Deserialize, Serialize)]
 pub struct CloneRepoRequest {
     /// Git URL (SSH or HTTPS format)
     pub url: String,
+    /// Absolute path to clone into (defaults to `~/ralphtown/{repo name}`)
+    #[serde(default)]
+    pub dest: Option<String>,
+    /// Display name (defaults to the repository name from the URL)
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+/// Request body for checking a clone URL
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CloneCheckRequest {
+    /// Git URL (SSH or HTTPS format)
+    pub url: String,
 }
 
 /// Response for clone operation
@@ -130,12 +211,31 @@ pub struct ScanRequest {
     /// Maximum depth to scan (default: 2)
     #[serde(default = "default_scan_depth")]
     pub depth: usize,
+    /// Glob patterns of directory names to skip (default: common dependency
+    /// and build directories; pass an empty list to disable)
+    #[serde(default = "default_scan_excludes")]
+    pub exclude: Vec<String>,
 }
 
 fn default_scan_depth() -> usize {
     2
 }
 
+/// Directory names skipped by scans that don't specify `exclude`
+const DEFAULT_SCAN_EXCLUDES: &[&str] = &[
+    "node_modules",
+    "target",
+    "vendor",
+    "dist",
+    "build",
+    "__pycache__",
+    "venv",
+];
+
+fn default_scan_excludes() -> Vec<String> {
+    DEFAULT_SCAN_EXCLUDES.iter().map(|s| s.to_string()).collect()
+}
+
 /// Response for scan operation
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ScanResponse {
@@ -143,6 +243,22 @@ pub struct ScanResponse {
     pub found: Vec<FoundRepo>,
 }
 
+/// A repository found by a scan preview, with its tracking status
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ScanPreviewRepo {
+    #[serde(flatten)]
+    pub repo: FoundRepo,
+    /// Whether the repository is already tracked
+    pub already_tracked: bool,
+}
+
+/// Response for scan preview
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ScanPreviewResponse {
+    /// Repositories found during scan, de-duplicated
+    pub found: Vec<ScanPreviewRepo>,
+}
+
 /// A repository found during scanning
 #[derive(Debug, Serialize, Deserialize)]
 pub struct FoundRepo {
@@ -150,14 +266,121 @@ pub struct FoundRepo {
     pub name: String,
 }
 
-/// List all repositories
-async fn list_repos(State(state): State<AppState>) -> AppResult<Json<Vec<Repo>>> {
-    let repos = state
+/// List all repositories, or those with a tag
+async fn list_repos(
+    State(state): State<AppState>,
+    Query(params): Query<ListReposParams>,
+) -> AppResult<Json<Vec<Repo>>> {
+    let repos = match params.tag.as_deref().map(str::trim) {
+        Some(tag) if !tag.is_empty() => state.db.list_repos_by_tag(tag),
+        _ => state.db.list_repos(),
+    }
+    .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(Json(repos))
+}
+
+/// Trim and de-duplicate tags, rejecting empty or overlong ones
+fn normalize_tags(tags: Vec<String>) -> AppResult<Vec<String>> {
+    let mut normalized = std::collections::BTreeSet::new();
+    for tag in tags {
+        let tag = tag.trim();
+        if tag.is_empty() {
+            return Err(AppError::BadRequest("Tags must not be empty".to_string()));
+        }
+        if tag.chars().count() > MAX_TAG_LENGTH {
+            return Err(AppError::BadRequest(format!(
+                "Tag '{}' is longer than {} characters",
+                tag, MAX_TAG_LENGTH
+            )));
+        }
+        normalized.insert(tag.to_string());
+    }
+    Ok(normalized.into_iter().collect())
+}
+
+/// Get a repository's tags
+async fn get_repo_tags(
+    State(state): State<AppState>,
+    AxumPath(id): AxumPath<Uuid>,
+) -> AppResult<Json<RepoTagsResponse>> {
+    state.db.get_repo(id).map_err(|e| match e {
+        crate::db::DbError::NotFound => AppError::NotFound(format!("Repository not found: {}", id)),
+        _ => AppError::Internal(e.to_string()),
+    })?;
+
+    let tags = state
         .db
-        .list_repos()
+        .list_repo_tags(id)
         .map_err(|e| AppError::Internal(e.to_string()))?;
 
-    Ok(Json(repos))
+    Ok(Json(RepoTagsResponse { repo_id: id, tags }))
+}
+
+/// Replace a repository's tags
+async fn set_repo_tags(
+    State(state): State<AppState>,
+    AxumPath(id): AxumPath<Uuid>,
+    Json(req): Json<SetRepoTagsRequest>,
+) -> AppResult<Json<RepoTagsResponse>> {
+    let tags = normalize_tags(req.tags)?;
+
+    state.db.get_repo(id).map_err(|e| match e {
+        crate::db::DbError::NotFound => AppError::NotFound(format!("Repository not found: {}", id)),
+        _ => AppError::Internal(e.to_string()),
+    })?;
+    state
+        .db
+        .set_repo_tags(id, &tags)
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(Json(RepoTagsResponse { repo_id: id, tags }))
+}
+
+/// Parse a `max_repos` value: a positive integer
+pub(crate) fn parse_max_repos(value: &str) -> Result<i64, String> {
+    match value.trim().parse::<i64>() {
+        Ok(max) if max > 0 => Ok(max),
+        _ => Err(format!("expected a positive integer, got '{}'", value)),
+    }
+}
+
+/// Configured `max_repos` cap, `None` if unset or invalid
+fn configured_max_repos(state: &AppState) -> AppResult<Option<i64>> {
+    match state.config.get(MAX_REPOS_KEY) {
+        Ok(Some(value)) => match parse_max_repos(&value) {
+            Ok(max) => Ok(Some(max)),
+            Err(e) => {
+                tracing::warn!("Invalid {} value, ignoring: {}", MAX_REPOS_KEY, e);
+                Ok(None)
+            }
+        },
+        Ok(None) => Ok(None),
+        Err(e) => Err(AppError::Internal(e.to_string())),
+    }
+}
+
+/// Reject starting a clone if the configured cap is already reached,
+/// returning the cap for the insert that follows.
+///
+/// The insert enforces the cap again atomically; this only avoids cloning a
+/// repository that could not be saved.
+fn ensure_repo_capacity(state: &AppState) -> AppResult<Option<i64>> {
+    let max = configured_max_repos(state)?;
+    if let Some(max) = max {
+        let count = state
+            .db
+            .count_repos()
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+        if count >= max {
+            return Err(AppError::Conflict(format!(
+                "Repository limit reached ({} of {})",
+                count, max
+            )));
+        }
+    }
+
+    Ok(max)
 }
 
 /// Add a new repository
@@ -165,7 +388,49 @@ async fn add_repo(
     State(state): State<AppState>,
     Json(req): Json<AddRepoRequest>,
 ) -> AppResult<Json<Repo>> {
-    let path = Path::new(&req.path);
+    let (path, name) = prepare_new_repo(&state, &req.path, req.name)?;
+
+    let repo = state
+        .db
+        .insert_repo_limited(&path, &name, configured_max_repos(&state)?)
+        .map_err(AppError::from)?;
+
+    Ok(Json(repo))
+}
+
+/// Add a repository and create its first session atomically
+async fn add_repo_with_session(
+    State(state): State<AppState>,
+    Json(req): Json<AddRepoWithSessionRequest>,
+) -> AppResult<Json<RepoWithSessionResponse>> {
+    let (path, name) = prepare_new_repo(&state, &req.path, req.name)?;
+    let max_repos = configured_max_repos(&state)?;
+
+    let session_name = req.session.name;
+    let template = match session_name {
+        Some(_) => None,
+        None => session_name_template(&state)?,
+    };
+
+    let (repo, session) = state
+        .db
+        .insert_repo_with_session(&path, &name, max_repos, req.session.orchestrator, |seq| {
+            session_name.or_else(|| {
+                template.map(|t| render_session_name(&t, &name, 0, seq, chrono::Utc::now()))
+            })
+        })
+        .map_err(AppError::from)?;
+
+    Ok(Json(RepoWithSessionResponse { repo, session }))
+}
+
+/// Validate a repository to be added, returning its canonical path and name
+fn prepare_new_repo(
+    state: &AppState,
+    path: &str,
+    name: Option<String>,
+) -> AppResult<(String, String)> {
+    let path = Path::new(path);
 
     // Validate path exists and is a git repository (returns helpful errors)
     crate::git::validate_repo_path(path)?;
@@ -178,7 +443,7 @@ async fn add_repo(
     let path_str = canonical_path.to_string_lossy().to_string();
 
     // Derive name from directory if not provided
-    let name = req.name.unwrap_or_else(|| {
+    let name = name.unwrap_or_else(|| {
         canonical_path
             .file_name()
             .and_then(|n| n.to_str())
@@ -194,10 +459,37 @@ async fn add_repo(
         )));
     }
 
-    let repo = state
-        .db
-        .insert_repo(&path_str, &name)
-        .map_err(|e| AppError::Internal(e.to_string()))?;
+    Ok((path_str, name))
+}
+
+/// Get a repository by ID
+async fn get_repo(
+    State(state): State<AppState>,
+    AxumPath(id): AxumPath<Uuid>,
+) -> AppResult<Json<Repo>> {
+    let repo = state.db.get_repo(id).map_err(|e| match e {
+        crate::db::DbError::NotFound => AppError::NotFound(format!("Repository not found: {}", id)),
+        _ => AppError::Internal(e.to_string()),
+    })?;
+
+    Ok(Json(repo))
+}
+
+/// Rename a repository
+async fn update_repo(
+    State(state): State<AppState>,
+    AxumPath(id): AxumPath<Uuid>,
+    Json(req): Json<UpdateRepoRequest>,
+) -> AppResult<Json<Repo>> {
+    let name = req.name.trim();
+    if name.is_empty() {
+        return Err(AppError::BadRequest("Repository name cannot be empty".to_string()));
+    }
+
+    let repo = state.db.update_repo_name(id, name).map_err(|e| match e {
+        crate::db::DbError::NotFound => AppError::NotFound(format!("Repository not found: {}", id)),
+        _ => AppError::Internal(e.to_string()),
+    })?;
 
     Ok(Json(repo))
 }
@@ -215,22 +507,153 @@ async fn delete_repo(
     Ok(Json(()))
 }
 
+/// Validate values for repo config keys with a known format
+fn validate_repo_config(key: &str, value: &str) -> AppResult<()> {
+    match key {
+        DENIED_PATHS_KEY | AUTO_STAGE_PATTERNS_KEY => parse_pattern_list(value)
+            .map(|_| ())
+            .map_err(|e| AppError::BadRequest(format!("Invalid {}: {}", key, e))),
+        REVERT_DENIED_PATHS_KEY if value != "true" && value != "false" => Err(
+            AppError::BadRequest(format!("{} must be \"true\" or \"false\"", key)),
+        ),
+        _ => Ok(()),
+    }
+}
+
+/// Get all config values for a repository
+async fn get_repo_config(
+    State(state): State<AppState>,
+    AxumPath(id): AxumPath<Uuid>,
+) -> AppResult<Json<ConfigResponse>> {
+    state.db.get_repo(id).map_err(|e| match e {
+        crate::db::DbError::NotFound => AppError::NotFound(format!("Repository not found: {}", id)),
+        _ => AppError::Internal(e.to_string()),
+    })?;
+
+    let config = state
+        .db
+        .list_repo_config(id)
+        .map_err(|e| AppError::Internal(e.to_string()))?
+        .into_iter()
+        .collect();
+
+    Ok(Json(ConfigResponse { config }))
+}
+
+/// Set a config value for a repository
+async fn set_repo_config_value(
+    State(state): State<AppState>,
+    AxumPath((id, key)): AxumPath<(Uuid, String)>,
+    Json(req): Json<SetConfigValueRequest>,
+) -> AppResult<Json<ConfigValueResponse>> {
+    validate_repo_config(&key, &req.value)?;
+
+    state
+        .db
+        .set_repo_config(id, &key, &req.value)
+        .map_err(|e| match e {
+            crate::db::DbError::NotFound => {
+                AppError::NotFound(format!("Repository not found: {}", id))
+            }
+            _ => AppError::Internal(e.to_string()),
+        })?;
+
+    Ok(Json(ConfigValueResponse {
+        key,
+        value: Some(req.value),
+    }))
+}
+
+/// Delete a config value for a repository
+async fn delete_repo_config_value(
+    State(state): State<AppState>,
+    AxumPath((id, key)): AxumPath<(Uuid, String)>,
+) -> AppResult<Json<()>> {
+    state
+        .db
+        .delete_repo_config(id, &key)
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(Json(()))
+}
+
 /// Scan directories for git repositories
 async fn scan_repos(Json(req): Json<ScanRequest>) -> AppResult<Json<ScanResponse>> {
-    let mut found = Vec::new();
+    let found = find_repos(req).await?;
 
-    for dir in &req.directories {
-        let path = Path::new(dir);
-        if path.exists() && path.is_dir() {
-            scan_directory(path, 0, req.depth, &mut found);
+    Ok(Json(ScanResponse { found }))
+}
+
+/// Scan directories and annotate each found repository with whether it is
+/// already tracked, without adding anything
+async fn scan_repos_preview(
+    State(state): State<AppState>,
+    Json(req): Json<ScanRequest>,
+) -> AppResult<Json<ScanPreviewResponse>> {
+    let mut found = find_repos(req).await?;
+
+    // Tracked repos are stored by canonical path, and overlapping
+    // directories may find the same repo twice
+    for repo in &mut found {
+        if let Ok(canonical) = Path::new(&repo.path).canonicalize() {
+            repo.path = canonical.to_string_lossy().to_string();
         }
     }
+    let mut seen = std::collections::HashSet::new();
+    found.retain(|repo| seen.insert(repo.path.clone()));
 
-    Ok(Json(ScanResponse { found }))
+    let paths: Vec<String> = found.iter().map(|repo| repo.path.clone()).collect();
+    let tracked = state
+        .db
+        .tracked_repo_paths(&paths)
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    let found = found
+        .into_iter()
+        .map(|repo| ScanPreviewRepo {
+            already_tracked: tracked.contains(&repo.path),
+            repo,
+        })
+        .collect();
+
+    Ok(Json(ScanPreviewResponse { found }))
+}
+
+/// Walk the requested directories for git repositories
+async fn find_repos(req: ScanRequest) -> AppResult<Vec<FoundRepo>> {
+    // Walking large trees does blocking filesystem and git I/O
+    tokio::task::spawn_blocking(move || {
+        // An empty pathspec matches everything, so only build one for a non-empty list
+        let excludes = if req.exclude.is_empty() {
+            None
+        } else {
+            Some(
+                git2::Pathspec::new(req.exclude.iter())
+                    .map_err(|e| AppError::BadRequest(format!("Invalid exclude pattern: {}", e)))?,
+            )
+        };
+
+        let mut found = Vec::new();
+        for dir in &req.directories {
+            let path = Path::new(dir);
+            if path.exists() && path.is_dir() {
+                scan_directory(path, 0, req.depth, excludes.as_ref(), &mut found);
+            }
+        }
+        Ok(found)
+    })
+    .await
+    .map_err(|e| AppError::Internal(format!("Scan task failed: {}", e)))?
 }
 
 /// Recursively scan a directory for git repos
-fn scan_directory(path: &Path, current_depth: usize, max_depth: usize, found: &mut Vec<FoundRepo>) {
+fn scan_directory(
+    path: &Path,
+    current_depth: usize,
+    max_depth: usize,
+    excludes: Option<&git2::Pathspec>,
+    found: &mut Vec<FoundRepo>,
+) {
     // Check if this is a git repo
     if git2::Repository::open(path).is_ok() {
         let name = path
@@ -252,13 +675,19 @@ fn scan_directory(path: &Path, current_depth: usize, max_depth: usize, found: &m
             for entry in entries.flatten() {
                 let entry_path = entry.path();
                 if entry_path.is_dir() {
-                    // Skip hidden directories
                     if let Some(name) = entry_path.file_name() {
+                        // Skip hidden directories
                         if name.to_string_lossy().starts_with('.') {
                             continue;
                         }
+                        // Skip excluded directories
+                        if excludes.is_some_and(|spec| {
+                            spec.matches_path(Path::new(name), git2::PathspecFlags::DEFAULT)
+                        }) {
+                            continue;
+                        }
                     }
-                    scan_directory(&entry_path, current_depth + 1, max_depth, found);
+                    scan_directory(&entry_path, current_depth + 1, max_depth, excludes, found);
                 }
             }
         }
@@ -291,34 +720,66 @@ fn extract_repo_name(url: &str) -> Result<String, AppError> {
     Ok(name.to_string())
 }
 
-/// Clone a repository from a git URL
-async fn clone_repo(
-    State(state): State<AppState>,
-    Json(req): Json<CloneRepoRequest>,
-) -> AppResult<Json<CloneRepoResponse>> {
-    // Parse URL to extract repo name
-    let repo_name = extract_repo_name(&req.url)?;
-
-    // Build destination path: ~/ralphtown/{repo_name}
-    let home = dirs::home_dir()
-        .ok_or_else(|| AppError::Internal("Could not determine home directory".to_string()))?;
-    let dest: PathBuf = home.join("ralphtown").join(&repo_name);
+/// Resolve where a clone goes and make sure nothing is in the way.
+///
+/// An explicit `dest` must be absolute; otherwise the clone goes to
+/// `~/ralphtown/{repo_name}`. An existing destination is a conflict, as is one
+/// already registered as a repository. The parent directory is created.
+fn prepare_clone_destination(
+    state: &AppState,
+    dest: Option<&str>,
+    repo_name: &str,
+) -> AppResult<PathBuf> {
+    let dest = match dest.map(str::trim).filter(|d| !d.is_empty()) {
+        Some(dest) => {
+            let dest = PathBuf::from(dest);
+            if !dest.is_absolute() {
+                return Err(AppError::BadRequest(format!(
+                    "Clone destination must be an absolute path: {}",
+                    dest.display()
+                )));
+            }
+            dest
+        }
+        None => dirs::home_dir()
+            .ok_or_else(|| AppError::Internal("Could not determine home directory".to_string()))?
+            .join("ralphtown")
+            .join(repo_name),
+    };
 
-    // Check if destination already exists
     if dest.exists() {
-        return Err(AppError::BadRequest(format!(
+        return Err(AppError::Conflict(format!(
             "Directory already exists: {}",
             dest.display()
         )));
     }
+    if state.db.get_repo_by_path(&dest.to_string_lossy()).is_ok() {
+        return Err(AppError::Conflict(format!(
+            "Repository already exists: {}",
+            dest.display()
+        )));
+    }
 
-    // Create parent directory if needed
     if let Some(parent) = dest.parent() {
         std::fs::create_dir_all(parent).map_err(|e| {
             AppError::Internal(format!("Failed to create directory: {}", e))
         })?;
     }
 
+    Ok(dest)
+}
+
+/// Clone a repository from a git URL
+async fn clone_repo(
+    State(state): State<AppState>,
+    Json(req): Json<CloneRepoRequest>,
+) -> AppResult<Json<CloneRepoResponse>> {
+    // Parse URL to extract repo name
+    let repo_name = extract_repo_name(&req.url)?;
+    let max_repos = ensure_repo_capacity(&state)?;
+
+    let dest = prepare_clone_destination(&state, req.dest.as_deref(), &repo_name)?;
+
     // Clone using spawn_blocking to avoid blocking the async runtime
     let url_clone = req.url.clone();
     let dest_clone = dest.clone();
@@ -329,10 +790,11 @@ async fn clone_repo(
 
     // Insert repo into database
     let path_str = dest.to_string_lossy().to_string();
+    let name = req.name.filter(|n| !n.trim().is_empty()).unwrap_or(repo_name);
     let repo = state
         .db
-        .insert_repo(&path_str, &repo_name)
-        .map_err(|e| AppError::Internal(e.to_string()))?;
+        .insert_repo_limited(&path_str, &name, max_repos)
+        .map_err(AppError::from)?;
 
     Ok(Json(CloneRepoResponse {
         repo,
@@ -340,6 +802,35 @@ async fn clone_repo(
     }))
 }
 
+/// Check that a git URL is reachable before cloning it
+///
+/// Connects and lists the remote's refs without downloading anything. Slow
+/// remotes are reported as unreachable after `CLONE_CHECK_TIMEOUT`.
+async fn check_clone_url(Json(req): Json<CloneCheckRequest>) -> AppResult<Json<RemoteCheck>> {
+    let url = req.url.trim().to_string();
+    if url.is_empty() {
+        return Err(AppError::BadRequest("URL cannot be empty".to_string()));
+    }
+
+    let probe = tokio::task::spawn_blocking(move || GitManager::check_remote(&url));
+    let check = match tokio::time::timeout(CLONE_CHECK_TIMEOUT, probe).await {
+        Ok(result) => {
+            result.map_err(|e| AppError::Internal(format!("URL check task failed: {}", e)))?
+        }
+        Err(_) => RemoteCheck {
+            reachable: false,
+            auth_required: false,
+            default_branch: None,
+            error: Some(format!(
+                "Timed out after {}s waiting for the remote",
+                CLONE_CHECK_TIMEOUT.as_secs()
+            )),
+        },
+    };
+
+    Ok(Json(check))
+}
+
 /// Type alias for the SSE stream used in clone progress
 type SseStream = Pin<Box<dyn Stream<Item = Result<Event, Infallible>> + Send>>;
 
@@ -377,26 +868,15 @@ async fn clone_with_progress_sse(
         }
     };
 
-    // Build destination path: ~/ralphtown/{repo_name}
-    let home = match dirs::home_dir() {
-        Some(h) => h,
-        None => {
-            return error_sse("Could not determine home directory".to_string(), Vec::new());
-        }
+    let max_repos = match ensure_repo_capacity(&state) {
+        Ok(max) => max,
+        Err(e) => return error_sse(e.to_string(), Vec::new()),
     };
-    let dest: PathBuf = home.join("ralphtown").join(&repo_name);
-
-    // Check if destination already exists
-    if dest.exists() {
-        return error_sse(format!("Directory already exists: {}", dest.display()), Vec::new());
-    }
 
-    // Create parent directory if needed
-    if let Some(parent) = dest.parent() {
-        if let Err(e) = std::fs::create_dir_all(parent) {
-            return error_sse(format!("Failed to create directory: {}", e), Vec::new());
-        }
-    }
+    let dest = match prepare_clone_destination(&state, None, &repo_name) {
+        Ok(dest) => dest,
+        Err(e) => return error_sse(e.to_string(), Vec::new()),
+    };
 
     // Create bounded channel for progress updates
     let (progress_tx, mut progress_rx) = mpsc::channel::<CloneProgress>(32);
@@ -435,7 +915,7 @@ async fn clone_with_progress_sse(
             Ok(Ok(_)) => {
                 // Clone succeeded, insert repo into database
                 let path_str = dest.to_string_lossy().to_string();
-                match state.db.insert_repo(&path_str, &repo_name) {
+                match state.db.insert_repo_limited(&path_str, &repo_name, max_repos) {
                     Ok(repo) => {
                         let event = CloneEvent::Complete {
                             repo,
@@ -514,26 +994,15 @@ async fn clone_with_credentials_sse(
         }
     };
 
-    // Build destination path: ~/ralphtown/{repo_name}
-    let home = match dirs::home_dir() {
-        Some(h) => h,
-        None => {
-            return error_sse("Could not determine home directory".to_string(), Vec::new());
-        }
+    let max_repos = match ensure_repo_capacity(&state) {
+        Ok(max) => max,
+        Err(e) => return error_sse(e.to_string(), Vec::new()),
     };
-    let dest: PathBuf = home.join("ralphtown").join(&repo_name);
 
-    // Check if destination already exists
-    if dest.exists() {
-        return error_sse(format!("Directory already exists: {}", dest.display()), Vec::new());
-    }
-
-    // Create parent directory if needed
-    if let Some(parent) = dest.parent() {
-        if let Err(e) = std::fs::create_dir_all(parent) {
-            return error_sse(format!("Failed to create directory: {}", e), Vec::new());
-        }
-    }
+    let dest = match prepare_clone_destination(&state, None, &repo_name) {
+        Ok(dest) => dest,
+        Err(e) => return error_sse(e.to_string(), Vec::new()),
+    };
 
     // Convert API credentials to CloneCredentials
     let credentials = req.credentials.map(CloneCredentials::from);
@@ -574,7 +1043,7 @@ async fn clone_with_credentials_sse(
             Ok(Ok(_)) => {
                 // Clone succeeded, insert repo into database
                 let path_str = dest.to_string_lossy().to_string();
-                match state.db.insert_repo(&path_str, &repo_name) {
+                match state.db.insert_repo_limited(&path_str, &repo_name, max_repos) {
                     Ok(repo) => {
                         let event = CloneEvent::Complete {
                             repo,
@@ -641,10 +1110,22 @@ async fn clone_with_credentials_sse(
 pub fn router() -> Router<AppState> {
     Router::new()
         .route("/repos", get(list_repos).post(add_repo))
+        .route("/repos/with-session", post(add_repo_with_session))
         .route("/repos/clone", post(clone_repo))
+        .route("/repos/clone/check", post(check_clone_url))
         .route("/repos/clone-progress", get(clone_with_progress_sse).post(clone_with_credentials_sse))
-        .route("/repos/{id}", delete(delete_repo))
+        .route(
+            "/repos/{id}",
+            get(get_repo).patch(update_repo).delete(delete_repo),
+        )
+        .route("/repos/{id}/tags", get(get_repo_tags).put(set_repo_tags))
+        .route("/repos/{id}/config", get(get_repo_config))
+        .route(
+            "/repos/{id}/config/{key}",
+            put(set_repo_config_value).delete(delete_repo_config_value),
+        )
         .route("/repos/scan", post(scan_repos))
+        .route("/repos/scan/preview", post(scan_repos_preview))
 }
 
 #[cfg(test)]
@@ -676,6 +1157,80 @@ mod tests {
         assert!(repos.is_empty());
     }
 
+    #[tokio::test]
+    async fn test_repo_tags_filter_list() {
+        let state = create_test_state();
+        let server = create_test_server(state.clone());
+        let api = state.db.insert_repo("/path/to/api", "api").unwrap();
+        state.db.insert_repo("/path/to/scratch", "scratch").unwrap();
+
+        let response = server
+            .put(&format!("/repos/{}/tags", api.id))
+            .json(&serde_json::json!({ "tags": [" work ", "backend", "work"] }))
+            .await;
+        response.assert_status_ok();
+        let tags: RepoTagsResponse = response.json();
+        assert_eq!(tags.tags, vec!["backend", "work"]);
+
+        let repos: Vec<Repo> = server.get("/repos?tag=work").await.json();
+        assert_eq!(repos.len(), 1);
+        assert_eq!(repos[0].id, api.id);
+        let repos: Vec<Repo> = server.get("/repos").await.json();
+        assert_eq!(repos.len(), 2);
+
+        let tags: RepoTagsResponse = server.get(&format!("/repos/{}/tags", api.id)).await.json();
+        assert_eq!(tags.tags, vec!["backend", "work"]);
+
+        server
+            .put(&format!("/repos/{}/tags", api.id))
+            .json(&serde_json::json!({ "tags": [""] }))
+            .await
+            .assert_status_bad_request();
+        server
+            .put(&format!("/repos/{}/tags", Uuid::new_v4()))
+            .json(&serde_json::json!({ "tags": ["work"] }))
+            .await
+            .assert_status_not_found();
+    }
+
+    #[tokio::test]
+    async fn test_clone_repo_to_dest() {
+        let state = create_test_state();
+        let server = create_test_server(state);
+
+        let source = TempDir::new().expect("Failed to create temp dir");
+        git2::Repository::init(source.path()).expect("Failed to init git repo");
+        let target = TempDir::new().expect("Failed to create temp dir");
+        let dest = target.path().join("nested").join("copy");
+
+        let req = CloneRepoRequest {
+            url: source.path().to_string_lossy().to_string(),
+            dest: Some(dest.to_string_lossy().to_string()),
+            name: Some("My Copy".to_string()),
+        };
+        let response = server.post("/repos/clone").json(&req).await;
+        response.assert_status_ok();
+        let body: CloneRepoResponse = response.json();
+        assert_eq!(body.repo.name, "My Copy");
+        assert_eq!(body.repo.path, dest.to_string_lossy());
+        assert!(dest.join(".git").exists());
+
+        // Cloning over an existing destination is a conflict
+        let response = server.post("/repos/clone").json(&req).await;
+        response.assert_status(axum::http::StatusCode::CONFLICT);
+
+        // Destinations must be absolute
+        let response = server
+            .post("/repos/clone")
+            .json(&CloneRepoRequest {
+                url: req.url.clone(),
+                dest: Some("relative/copy".to_string()),
+                ..Default::default()
+            })
+            .await;
+        response.assert_status_bad_request();
+    }
+
     #[tokio::test]
     async fn test_add_repo_validates_path() {
         let state = create_test_state();
@@ -749,6 +1304,78 @@ mod tests {
         assert_eq!(repos[0].name, "test-repo");
     }
 
+    #[tokio::test]
+    async fn test_get_repo() {
+        let state = create_test_state();
+        let server = create_test_server(state);
+
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        git2::Repository::init(temp_dir.path()).expect("Failed to init git repo");
+
+        let repo: Repo = server
+            .post("/repos")
+            .json(&AddRepoRequest {
+                path: temp_dir.path().to_string_lossy().to_string(),
+                name: Some("test-repo".to_string()),
+            })
+            .await
+            .json();
+
+        let response = server.get(&format!("/repos/{}", repo.id)).await;
+        response.assert_status_ok();
+        let fetched: Repo = response.json();
+        assert_eq!(fetched.id, repo.id);
+        assert_eq!(fetched.name, "test-repo");
+
+        let response = server.get(&format!("/repos/{}", Uuid::new_v4())).await;
+        response.assert_status_not_found();
+    }
+
+    #[tokio::test]
+    async fn test_rename_repo() {
+        let state = create_test_state();
+        let server = create_test_server(state);
+
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        git2::Repository::init(temp_dir.path()).expect("Failed to init git repo");
+
+        let repo: Repo = server
+            .post("/repos")
+            .json(&AddRepoRequest {
+                path: temp_dir.path().to_string_lossy().to_string(),
+                name: Some("test-repo".to_string()),
+            })
+            .await
+            .json();
+
+        let response = server
+            .patch(&format!("/repos/{}", repo.id))
+            .json(&UpdateRepoRequest {
+                name: "  renamed  ".to_string(),
+            })
+            .await;
+        response.assert_status_ok();
+        let renamed: Repo = response.json();
+        assert_eq!(renamed.id, repo.id);
+        assert_eq!(renamed.name, "renamed");
+
+        let response = server
+            .patch(&format!("/repos/{}", repo.id))
+            .json(&UpdateRepoRequest {
+                name: "   ".to_string(),
+            })
+            .await;
+        response.assert_status_bad_request();
+
+        let response = server
+            .patch(&format!("/repos/{}", Uuid::new_v4()))
+            .json(&UpdateRepoRequest {
+                name: "missing".to_string(),
+            })
+            .await;
+        response.assert_status_not_found();
+    }
+
     #[tokio::test]
     async fn test_add_repo_duplicate() {
         let state = create_test_state();
@@ -779,6 +1406,94 @@ mod tests {
         response.assert_status_bad_request();
     }
 
+    #[tokio::test]
+    async fn test_add_repo_with_session() {
+        let state = create_test_state();
+        let server = create_test_server(state.clone());
+
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        git2::Repository::init(temp_dir.path()).expect("Failed to init git repo");
+        let path = temp_dir.path().to_string_lossy().to_string();
+
+        let response = server
+            .post("/repos/with-session")
+            .json(&AddRepoWithSessionRequest {
+                path: path.clone(),
+                name: Some("onboarded".to_string()),
+                session: InitialSessionRequest {
+                    name: Some("first".to_string()),
+                    orchestrator: Orchestrator::Ralph,
+                },
+            })
+            .await;
+        response.assert_status_ok();
+        let created: RepoWithSessionResponse = response.json();
+        assert_eq!(created.repo.name, "onboarded");
+        assert_eq!(created.session.repo_id, created.repo.id);
+        assert_eq!(created.session.name.as_deref(), Some("first"));
+
+        // Adding the same path again creates neither a repo nor a session
+        let response = server
+            .post("/repos/with-session")
+            .json(&AddRepoWithSessionRequest {
+                path,
+                name: None,
+                session: InitialSessionRequest::default(),
+            })
+            .await;
+        response.assert_status_bad_request();
+        assert_eq!(state.db.list_repos().unwrap().len(), 1);
+        assert_eq!(state.db.list_sessions(true).unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_add_repo_respects_max_repos() {
+        let state = create_test_state();
+        state.db.set_config(MAX_REPOS_KEY, "2").unwrap();
+        let server = create_test_server(state);
+
+        let temp_dirs: Vec<TempDir> = (0..3)
+            .map(|_| {
+                let temp_dir = TempDir::new().expect("Failed to create temp dir");
+                git2::Repository::init(temp_dir.path()).expect("Failed to init git repo");
+                temp_dir
+            })
+            .collect();
+
+        for temp_dir in &temp_dirs[..2] {
+            server
+                .post("/repos")
+                .json(&AddRepoRequest {
+                    path: temp_dir.path().to_string_lossy().to_string(),
+                    name: None,
+                })
+                .await
+                .assert_status_ok();
+        }
+
+        let response = server
+            .post("/repos")
+            .json(&AddRepoRequest {
+                path: temp_dirs[2].path().to_string_lossy().to_string(),
+                name: None,
+            })
+            .await;
+        response.assert_status(axum::http::StatusCode::CONFLICT);
+
+        let response = server
+            .post("/repos/with-session")
+            .json(&AddRepoWithSessionRequest {
+                path: temp_dirs[2].path().to_string_lossy().to_string(),
+                name: None,
+                session: InitialSessionRequest::default(),
+            })
+            .await;
+        response.assert_status(axum::http::StatusCode::CONFLICT);
+
+        let repos: Vec<Repo> = server.get("/repos").await.json();
+        assert_eq!(repos.len(), 2);
+    }
+
     #[tokio::test]
     async fn test_delete_repo() {
         let state = create_test_state();
@@ -834,6 +1549,7 @@ mod tests {
             .json(&ScanRequest {
                 directories: vec![temp_dir.path().to_string_lossy().to_string()],
                 depth: 2,
+                exclude: default_scan_excludes(),
             })
             .await;
 
@@ -843,6 +1559,88 @@ mod tests {
         assert_eq!(scan_result.found[0].name, "my-project");
     }
 
+    #[tokio::test]
+    async fn test_scan_repos_preview() {
+        let server = create_test_server(create_test_state());
+
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        for dir in ["tracked", "untracked"] {
+            let repo_dir = temp_dir.path().join(dir);
+            std::fs::create_dir(&repo_dir).expect("Failed to create subdir");
+            git2::Repository::init(&repo_dir).expect("Failed to init git repo");
+        }
+
+        server
+            .post("/repos")
+            .json(&AddRepoRequest {
+                path: temp_dir.path().join("tracked").to_string_lossy().to_string(),
+                name: None,
+            })
+            .await
+            .assert_status_ok();
+
+        // Overlapping roots find the same repos twice
+        let root = temp_dir.path().to_string_lossy().to_string();
+        let response = server
+            .post("/repos/scan/preview")
+            .json(&ScanRequest {
+                directories: vec![root.clone(), root],
+                depth: 2,
+                exclude: Vec::new(),
+            })
+            .await;
+        response.assert_status_ok();
+
+        let preview: ScanPreviewResponse = response.json();
+        let mut found: Vec<_> = preview
+            .found
+            .iter()
+            .map(|r| (r.repo.name.as_str(), r.already_tracked))
+            .collect();
+        found.sort();
+        assert_eq!(found, vec![("tracked", true), ("untracked", false)]);
+
+        // Previewing doesn't add anything
+        let repos: Vec<Repo> = server.get("/repos").await.json();
+        assert_eq!(repos.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_scan_repos_excludes() {
+        let server = create_test_server(create_test_state());
+
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        for dir in ["my-project", "node_modules/dep", "pkg.egg-info/inner"] {
+            let repo_dir = temp_dir.path().join(dir);
+            std::fs::create_dir_all(&repo_dir).expect("Failed to create subdir");
+            git2::Repository::init(&repo_dir).expect("Failed to init git repo");
+        }
+        let directories = vec![temp_dir.path().to_string_lossy().to_string()];
+
+        // Built-in excludes apply when the field is absent
+        let scan_result: ScanResponse = server
+            .post("/repos/scan")
+            .json(&serde_json::json!({ "directories": directories }))
+            .await
+            .json();
+        let mut names: Vec<_> = scan_result.found.iter().map(|r| r.name.as_str()).collect();
+        names.sort();
+        assert_eq!(names, vec!["inner", "my-project"]);
+
+        let scan_result: ScanResponse = server
+            .post("/repos/scan")
+            .json(&ScanRequest {
+                directories: directories.clone(),
+                depth: 2,
+                exclude: vec!["*.egg-info".to_string()],
+            })
+            .await
+            .json();
+        let mut names: Vec<_> = scan_result.found.iter().map(|r| r.name.as_str()).collect();
+        names.sort();
+        assert_eq!(names, vec!["dep", "my-project"]);
+    }
+
     #[test]
     fn test_extract_repo_name_https() {
         assert_eq!(
@@ -923,4 +1721,91 @@ mod tests {
         // will verify the full flow. Here we just verify the endpoint compiles
         // and the helper functions work.
     }
+
+    #[tokio::test]
+    async fn test_check_clone_url() {
+        let state = create_test_state();
+        let server = create_test_server(state);
+
+        let source_dir = TempDir::new().expect("Failed to create source dir");
+        git2::Repository::init(source_dir.path()).expect("Failed to init source repo");
+
+        let response = server
+            .post("/repos/clone/check")
+            .json(&CloneCheckRequest {
+                url: source_dir.path().to_string_lossy().to_string(),
+            })
+            .await;
+        response.assert_status_ok();
+        let check: RemoteCheck = response.json();
+        assert!(check.reachable);
+        assert!(!check.auth_required);
+
+        let check: RemoteCheck = server
+            .post("/repos/clone/check")
+            .json(&CloneCheckRequest {
+                url: source_dir.path().join("missing").to_string_lossy().to_string(),
+            })
+            .await
+            .json();
+        assert!(!check.reachable);
+        assert!(check.error.is_some());
+
+        server
+            .post("/repos/clone/check")
+            .json(&CloneCheckRequest { url: " ".to_string() })
+            .await
+            .assert_status_bad_request();
+    }
+
+    #[tokio::test]
+    async fn test_repo_config_denied_paths() {
+        let state = create_test_state();
+        let server = create_test_server(state);
+
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        git2::Repository::init(temp_dir.path()).expect("Failed to init git repo");
+        let repo: Repo = server
+            .post("/repos")
+            .json(&AddRepoRequest {
+                path: temp_dir.path().to_string_lossy().to_string(),
+                name: None,
+            })
+            .await
+            .json();
+
+        // Patterns must be a JSON array
+        let response = server
+            .put(&format!("/repos/{}/config/denied_paths", repo.id))
+            .json(&SetConfigValueRequest {
+                value: ".github/**".to_string(),
+            })
+            .await;
+        response.assert_status_bad_request();
+
+        let response = server
+            .put(&format!("/repos/{}/config/denied_paths", repo.id))
+            .json(&SetConfigValueRequest {
+                value: r#"[".github/**", "infra/**"]"#.to_string(),
+            })
+            .await;
+        response.assert_status_ok();
+
+        let config: ConfigResponse = server
+            .get(&format!("/repos/{}/config", repo.id))
+            .await
+            .json();
+        assert_eq!(
+            config.config.get("denied_paths").map(String::as_str),
+            Some(r#"[".github/**", "infra/**"]"#)
+        );
+
+        let response = server
+            .put(&format!("/repos/{}/config/denied_paths", Uuid::new_v4()))
+            .json(&SetConfigValueRequest {
+                value: "[]".to_string(),
+            })
+            .await;
+        response.assert_status_not_found();
+    }
 }
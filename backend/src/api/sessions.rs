@@ -1,19 +1,33 @@
 use axum::{
+    body::Body,
     extract::{Path as AxumPath, Query, State},
-    routing::{get, post},
+    http::{header, HeaderMap},
+    response::IntoResponse,
+    routing::{get, post, put},
     Json, Router,
 };
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::db::models::{Message, Orchestrator, OutputStream, OutputLog, Session, SessionStatus};
+use crate::db::models::{
+    Message, MessageRole, Orchestrator, OutputStream, OutputLog, Repo, Session, SessionError,
+    SessionStatus, SessionSummary,
+};
+use crate::db::secrets;
 use crate::error::{AppError, AppResult};
-use crate::ralph::RalphError;
+use crate::events;
+use crate::ralph::{RalphError, RalphManager, BACKEND_KEY};
+use crate::ws::messages::ServerMessage;
 
-use super::AppState;
+use super::config::{
+    preset_prompt, validate_config, ConfigResponse, ConfigValueResponse, SetConfigValueRequest,
+};
+use super::git::session_filename;
+use super::{require_admin, AppState};
 
 /// Request body for creating a new session
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Default, Deserialize, Serialize)]
 pub struct CreateSessionRequest {
     /// Repository ID to create session for
     pub repo_id: Uuid,
@@ -22,6 +36,36 @@ pub struct CreateSessionRequest {
     /// Orchestrator to use for this session (defaults to ralph)
     #[serde(default)]
     pub orchestrator: Orchestrator,
+    /// Start ralph as soon as the session is created (defaults to `auto_start_sessions`).
+    /// Also accepted as `start`.
+    #[serde(default, alias = "start")]
+    pub auto_start: Option<bool>,
+    /// Prompt for the auto-started run, recorded as the session's first user message
+    #[serde(default)]
+    pub prompt: Option<String>,
+    /// Preset whose instructions start the auto-started run
+    #[serde(default)]
+    pub preset: Option<String>,
+}
+
+/// Config key enabling auto-start for create requests that don't specify `auto_start`
+pub const AUTO_START_SESSIONS_KEY: &str = "auto_start_sessions";
+
+/// Config key holding the name template for sessions created without a name;
+/// unnamed sessions stay unnamed while it is unset
+pub const DEFAULT_SESSION_NAME_TEMPLATE_KEY: &str = "default_session_name_template";
+
+/// Config key holding how many recent messages the session context includes
+pub const CONTEXT_MESSAGE_LIMIT_KEY: &str = "context_message_limit";
+
+/// Number of recent messages in the session context when not configured
+const DEFAULT_CONTEXT_MESSAGE_LIMIT: usize = 20;
+
+/// Request body for updating a session
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UpdateSessionRequest {
+    /// New session name; null or blank clears it
+    pub name: Option<String>,
 }
 
 /// Response for session details including messages
@@ -32,6 +76,20 @@ pub struct SessionDetails {
     pub messages: Vec<Message>,
 }
 
+/// Request body for re-running a session as a new one
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct RerunSessionRequest {
+    /// Name for the new session (defaults to the configured name template)
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Start ralph on the new session right away
+    #[serde(default)]
+    pub start: bool,
+    /// Prompt to use instead of the original session's first prompt
+    #[serde(default)]
+    pub prompt: Option<String>,
+}
+
 /// Request body for running ralph on a session
 #[derive(Debug, Deserialize, Serialize)]
 pub struct RunSessionRequest {
@@ -58,6 +116,197 @@ pub struct OutputQueryParams {
     pub offset: Option<i64>,
 }
 
+/// Query parameters for downloading session output as plain text
+#[derive(Debug, Deserialize)]
+pub struct OutputDownloadParams {
+    /// Stream to include: all (default), stdout or stderr
+    pub stream: Option<String>,
+    /// Prefix each line with its RFC 3339 timestamp
+    #[serde(default)]
+    pub timestamps: bool,
+}
+
+/// Number of output lines read from the database per chunk of a download
+const OUTPUT_DOWNLOAD_BATCH_SIZE: i64 = 1000;
+
+/// Default number of messages returned per page
+const DEFAULT_MESSAGES_PAGE_SIZE: usize = 50;
+
+/// Maximum number of messages returned per page
+const MAX_MESSAGES_PAGE_SIZE: usize = 500;
+
+/// Query parameters for paging through session messages
+#[derive(Debug, Deserialize)]
+pub struct MessagesQueryParams {
+    /// Maximum number of messages to return (default: 50, max: 500)
+    pub limit: Option<usize>,
+    /// Only return messages created before this timestamp (RFC 3339)
+    pub before: Option<DateTime<Utc>>,
+}
+
+/// Response for a page of session messages
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MessagesPageResponse {
+    pub session_id: Uuid,
+    /// Messages, newest first
+    pub messages: Vec<Message>,
+    /// Cursor for the next (older) page, absent on the last page
+    pub next_before: Option<DateTime<Utc>>,
+}
+
+/// Query parameters for searching messages across sessions
+#[derive(Debug, Deserialize)]
+pub struct MessageSearchParams {
+    /// Terms that must all appear in a message
+    pub q: String,
+    /// Maximum number of matches to return (default: 50, max: 500)
+    pub limit: Option<usize>,
+}
+
+/// Response for a message search
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MessageSearchResponse {
+    pub query: String,
+    /// Matching messages from all sessions, most relevant first
+    pub messages: Vec<Message>,
+}
+
+/// Response for the recorded session command
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SessionCommandResponse {
+    pub session_id: Uuid,
+    /// Resolved command line of the last run, with secret values masked
+    pub command: Option<String>,
+    /// AI backend the last run used (after any fallback), if one was configured
+    pub backend: Option<String>,
+}
+
+/// A session that is running or being watched right now
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LiveSession {
+    pub session_id: Uuid,
+    pub repo_id: Uuid,
+    pub name: Option<String>,
+    pub status: SessionStatus,
+    /// Whether a ralph process is running for the session
+    pub running: bool,
+    /// When the current run started, if running
+    pub started_at: Option<DateTime<Utc>>,
+    /// Seconds since the current run started, if running
+    pub runtime_secs: Option<i64>,
+    /// Number of WebSocket clients subscribed to the session
+    pub subscribers: usize,
+}
+
+/// Response for the live sessions endpoint
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LiveSessionsResponse {
+    /// Running sessions (oldest run first), then sessions only being watched
+    pub sessions: Vec<LiveSession>,
+}
+
+/// Response for the session capacity endpoint
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SessionCapacityResponse {
+    /// Number of sessions with a running process
+    pub running: usize,
+    /// Configured limit on concurrently running sessions, if any
+    pub max_concurrent_sessions: Option<usize>,
+    /// Whether another session can be started now
+    pub can_start: bool,
+}
+
+/// Request body for transitioning several sessions at once
+#[derive(Debug, Deserialize, Serialize)]
+pub struct BulkStatusRequest {
+    pub ids: Vec<Uuid>,
+    pub status: SessionStatus,
+}
+
+/// Outcome of a bulk status change for one session
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BulkStatusResult {
+    pub id: Uuid,
+    pub success: bool,
+    /// Status after the request (unchanged on failure)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<SessionStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Response for the bulk status endpoint
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BulkStatusResponse {
+    pub results: Vec<BulkStatusResult>,
+}
+
+/// Request body for deleting every session with a status
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PruneSessionsRequest {
+    pub status: SessionStatus,
+}
+
+/// Response for the prune endpoint
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PruneSessionsResponse {
+    /// Number of sessions deleted
+    pub deleted: usize,
+}
+
+/// Response for resolving the repository backing a session
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SessionRepoResponse {
+    pub session_id: Uuid,
+    pub repo: Repo,
+    /// Canonical absolute path, if the path exists
+    pub canonical_path: Option<String>,
+    pub exists: bool,
+    pub is_git: bool,
+    /// Why the repo can't be used, if it fails validation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Query parameters for the session context bundle
+#[derive(Debug, Deserialize)]
+pub struct ContextQueryParams {
+    /// Number of recent messages to include (overrides `context_message_limit`)
+    pub messages: Option<usize>,
+}
+
+/// Condensed git status of a session's repository
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitStatusSummary {
+    pub branch: String,
+    pub ahead: usize,
+    pub behind: usize,
+    pub staged: usize,
+    pub unstaged: usize,
+    pub untracked: usize,
+}
+
+/// Everything an agent needs to start or resume a session
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SessionContextResponse {
+    pub session: Session,
+    pub repo: Repo,
+    /// Git status of the repo, absent if it could not be read
+    pub git: Option<GitStatusSummary>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub git_error: Option<String>,
+    /// Most recent messages, oldest first
+    pub messages: Vec<Message>,
+}
+
+/// Query parameters for listing sessions
+#[derive(Debug, Deserialize)]
+pub struct ListSessionsParams {
+    /// Include archived sessions (default: false)
+    #[serde(default)]
+    pub include_archived: bool,
+}
+
 /// Response for session output
 #[derive(Debug, Serialize, Deserialize)]
 pub struct OutputResponse {
@@ -66,11 +315,14 @@ pub struct OutputResponse {
     pub total: usize,
 }
 
-/// List all sessions
-async fn list_sessions(State(state): State<AppState>) -> AppResult<Json<Vec<Session>>> {
+/// List sessions with message activity, leaving out archived ones unless requested
+async fn list_sessions(
+    State(state): State<AppState>,
+    Query(params): Query<ListSessionsParams>,
+) -> AppResult<Json<Vec<SessionSummary>>> {
     let sessions = state
         .db
-        .list_sessions()
+        .list_session_summaries(params.include_archived)
         .map_err(|e| AppError::Internal(e.to_string()))?;
 
     Ok(Json(sessions))
@@ -90,21 +342,154 @@ async fn create_session(
     }
 
     // Verify repo exists
-    state.db.get_repo(req.repo_id).map_err(|e| match e {
+    let repo = state.db.get_repo(req.repo_id).map_err(|e| match e {
         crate::db::DbError::NotFound => {
             AppError::BadRequest(format!("Repository not found: {}", req.repo_id))
         }
         _ => AppError::Internal(e.to_string()),
     })?;
 
-    let session = state
+    let auto_start = match req.auto_start {
+        Some(auto_start) => auto_start,
+        None => state.config.get_as::<bool>(AUTO_START_SESSIONS_KEY)?.unwrap_or(false),
+    };
+
+    // Resolve the prompt up front so a bad request doesn't leave an idle session behind
+    let prompt = if auto_start {
+        Some(auto_start_prompt(&state, req.prompt.as_deref(), req.preset.as_deref())?)
+    } else {
+        None
+    };
+
+    let session = create_session_record(&state, &repo, req.name, req.orchestrator)?;
+
+    events::session_created(&state.db, &state.connections, &session);
+
+    let Some(prompt) = prompt else {
+        return Ok(Json(session));
+    };
+
+    state
         .db
-        .insert_session(req.repo_id, req.name.as_deref(), req.orchestrator)
+        .insert_message(session.id, MessageRole::User, &prompt)
         .map_err(|e| AppError::Internal(e.to_string()))?;
 
+    let session = start_new_session(&state, &session, &repo.path, &prompt, None).await?;
+
     Ok(Json(session))
 }
 
+/// Start ralph on a session that was just created, returning it as updated
+/// by the start. If ralph can't start the session is removed again, since the
+/// caller asked to create and start in one step.
+async fn start_new_session(
+    state: &AppState,
+    session: &Session,
+    repo_path: &str,
+    prompt: &str,
+    backend: Option<String>,
+) -> AppResult<Session> {
+    if let Err(e) = start_ralph(state, session, repo_path, prompt, backend).await {
+        if let Err(delete_err) = state.db.delete_session(session.id) {
+            tracing::warn!(
+                "Failed to remove session {} after failed start: {}",
+                session.id,
+                delete_err
+            );
+        }
+        return Err(e);
+    }
+
+    state
+        .db
+        .get_session(session.id)
+        .map_err(|e| AppError::Internal(e.to_string()))
+}
+
+/// Build the prompt for an auto-started run from the request's prompt and/or preset.
+///
+/// A preset's instructions come first, followed by the prompt when both are given.
+pub(crate) fn auto_start_prompt(
+    state: &AppState,
+    prompt: Option<&str>,
+    preset: Option<&str>,
+) -> AppResult<String> {
+    let prompt = prompt.map(str::trim).filter(|p| !p.is_empty());
+    let preset = match preset.map(str::trim).filter(|p| !p.is_empty()) {
+        Some(id) => Some(preset_prompt(state, id)?),
+        None => None,
+    };
+
+    match (preset, prompt) {
+        (Some(preset), Some(prompt)) => Ok(format!("{}\n\n{}", preset, prompt)),
+        (Some(preset), None) => Ok(preset),
+        (None, Some(prompt)) => Ok(prompt.to_string()),
+        (None, None) => Err(AppError::BadRequest(
+            "A prompt or preset is required to auto-start a session".to_string(),
+        )),
+    }
+}
+
+/// Insert a session for `repo`, naming it from the configured template if no
+/// name is given.
+///
+/// Supported placeholders are `{date}` (YYYY-MM-DD), `{repo}` (repo name),
+/// `{n}` (the number of sessions the repo already has) and
+/// `{seq}` (the repo's monotonic session sequence, which never reuses a number
+/// after deletions). An unset or empty template leaves the session unnamed.
+pub(crate) fn create_session_record(
+    state: &AppState,
+    repo: &Repo,
+    name: Option<String>,
+    orchestrator: Orchestrator,
+) -> AppResult<Session> {
+    let template = match name {
+        Some(_) => None,
+        None => session_name_template(state)?,
+    };
+
+    let count = match template {
+        Some(_) => state
+            .db
+            .count_sessions_by_repo(repo.id)
+            .map_err(|e| AppError::Internal(e.to_string()))?,
+        None => 0,
+    };
+
+    state
+        .db
+        .insert_session_with_seq(repo.id, orchestrator, |seq| {
+            name.or_else(|| {
+                template.map(|t| render_session_name(&t, &repo.name, count, seq, Utc::now()))
+            })
+        })
+        .map_err(|e| AppError::Internal(e.to_string()))
+}
+
+/// Configured template for unnamed sessions, `None` if unset or blank
+pub(crate) fn session_name_template(state: &AppState) -> AppResult<Option<String>> {
+    Ok(state
+        .config
+        .get(DEFAULT_SESSION_NAME_TEMPLATE_KEY)
+        .map_err(|e| AppError::Internal(e.to_string()))?
+        .filter(|t| !t.trim().is_empty()))
+}
+
+/// Substitute the session name template placeholders
+pub(crate) fn render_session_name(
+    template: &str,
+    repo_name: &str,
+    n: i64,
+    seq: i64,
+    now: DateTime<Utc>,
+) -> String {
+    template
+        .replace("{date}", &now.format("%Y-%m-%d").to_string())
+        .replace("{repo}", repo_name)
+        .replace("{seq}", &seq.to_string())
+        .replace("{n}", &n.to_string())
+}
+
 /// Get a session by ID with its messages
 async fn get_session(
     State(state): State<AppState>,
@@ -123,6 +508,27 @@ async fn get_session(
     Ok(Json(SessionDetails { session, messages }))
 }
 
+/// Update a session's name
+async fn update_session(
+    State(state): State<AppState>,
+    AxumPath(id): AxumPath<Uuid>,
+    Json(req): Json<UpdateSessionRequest>,
+) -> AppResult<Json<Session>> {
+    let name = req.name.as_deref().map(str::trim).filter(|n| !n.is_empty());
+
+    state.db.update_session_name(id, name).map_err(|e| match e {
+        crate::db::DbError::NotFound => AppError::NotFound(format!("Session not found: {}", id)),
+        _ => AppError::Internal(e.to_string()),
+    })?;
+
+    let session = state
+        .db
+        .get_session(id)
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(Json(session))
+}
+
 /// Delete a session by ID
 async fn delete_session(
     State(state): State<AppState>,
@@ -136,6 +542,70 @@ async fn delete_session(
     Ok(Json(()))
 }
 
+/// Delete every session with the given status, e.g. all completed sessions
+///
+/// Running sessions can't be pruned, since their processes are still writing
+/// to them.
+async fn prune_sessions(
+    State(state): State<AppState>,
+    Json(req): Json<PruneSessionsRequest>,
+) -> AppResult<Json<PruneSessionsResponse>> {
+    if req.status == SessionStatus::Running {
+        return Err(AppError::BadRequest(
+            "Running sessions can't be pruned; cancel them first".to_string(),
+        ));
+    }
+
+    let deleted = state
+        .db
+        .delete_sessions_by_status(req.status)
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(Json(PruneSessionsResponse { deleted }))
+}
+
+/// Archive a session, hiding it from the default listing without deleting it
+async fn archive_session(
+    State(state): State<AppState>,
+    AxumPath(id): AxumPath<Uuid>,
+) -> AppResult<Json<Session>> {
+    if state.ralph_manager.is_session_running(id).await {
+        return Err(AppError::Conflict(format!(
+            "Session {} is running; cancel it before archiving",
+            id
+        )));
+    }
+
+    set_session_archived(&state, id, true)
+}
+
+/// Restore an archived session to the default listing
+async fn unarchive_session(
+    State(state): State<AppState>,
+    AxumPath(id): AxumPath<Uuid>,
+) -> AppResult<Json<Session>> {
+    set_session_archived(&state, id, false)
+}
+
+fn set_session_archived(state: &AppState, id: Uuid, archived: bool) -> AppResult<Json<Session>> {
+    let result = if archived {
+        state.db.archive_session(id)
+    } else {
+        state.db.unarchive_session(id)
+    };
+    result.map_err(|e| match e {
+        crate::db::DbError::NotFound => AppError::NotFound(format!("Session not found: {}", id)),
+        _ => AppError::Internal(e.to_string()),
+    })?;
+
+    let session = state
+        .db
+        .get_session(id)
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(Json(session))
+}
+
 /// Run ralph on a session
 async fn run_session(
     State(state): State<AppState>,
@@ -156,14 +626,33 @@ async fn run_session(
         _ => AppError::Internal(e.to_string()),
     })?;
 
-    // Start ralph
+    start_ralph(&state, &session, &repo.path, &req.prompt, None).await?;
+
+    Ok(Json(RunSessionResponse {
+        session_id: id,
+        status: SessionStatus::Running,
+        message: "Ralph process started".to_string(),
+    }))
+}
+
+/// Start ralph for a session, mapping spawn failures to API errors
+///
+/// `backend` overrides the configured backend when given.
+async fn start_ralph(
+    state: &AppState,
+    session: &Session,
+    repo_path: &str,
+    prompt: &str,
+    backend: Option<String>,
+) -> AppResult<()> {
     state
         .ralph_manager
-        .run(
-            id,
+        .run_with_backend(
+            session.id,
             session.repo_id,
-            &repo.path,
-            &req.prompt,
+            repo_path,
+            prompt,
+            backend,
             state.db.clone(),
             state.connections.clone(),
         )
@@ -178,22 +667,169 @@ async fn run_session(
                 session_id
             )),
             RalphError::SpawnFailed(msg) => AppError::Internal(format!("Failed to start ralph: {}", msg)),
+            e @ RalphError::AtCapacity { .. } => AppError::Conflict(e.to_string()),
             RalphError::NotFound { message, help_steps } => AppError::UserActionRequired {
                 code: "RALPH_NOT_FOUND".to_string(),
                 message,
                 details: None,
                 help_steps,
             },
-            RalphError::NotRunning(_) => unreachable!(),
+            e @ (RalphError::NotRunning(_) | RalphError::InputFailed(_)) => {
+                AppError::Internal(e.to_string())
+            }
+        })
+}
+
+/// Create a new session with the same repo, orchestrator, config overrides
+/// and first prompt as an existing one, optionally starting it
+///
+/// The backend the original last ran with is pinned as the new session's
+/// backend override, whether or not it is started now.
+async fn rerun_session(
+    State(state): State<AppState>,
+    AxumPath(id): AxumPath<Uuid>,
+    Json(req): Json<RerunSessionRequest>,
+) -> AppResult<Json<SessionDetails>> {
+    let original = state.db.get_session(id).map_err(|e| match e {
+        crate::db::DbError::NotFound => AppError::NotFound(format!("Session not found: {}", id)),
+        _ => AppError::Internal(e.to_string()),
+    })?;
+
+    let repo = state.db.get_repo(original.repo_id).map_err(|e| match e {
+        crate::db::DbError::NotFound => {
+            AppError::Internal(format!("Repository not found for session: {}", id))
+        }
+        _ => AppError::Internal(e.to_string()),
+    })?;
+
+    let prompt = match req.prompt.filter(|p| !p.trim().is_empty()) {
+        Some(prompt) => Some(prompt),
+        None => state
+            .db
+            .list_messages(id)
+            .map_err(|e| AppError::Internal(e.to_string()))?
+            .into_iter()
+            .find(|m| m.role == MessageRole::User)
+            .map(|m| m.content),
+    };
+    if req.start && prompt.is_none() {
+        return Err(AppError::BadRequest(format!(
+            "Session {} has no prompt to re-run; provide one",
+            id
+        )));
+    }
+
+    let backend = state
+        .db
+        .get_session_backend(id)
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    let session = create_session_record(&state, &repo, req.name, original.orchestrator)?;
+    state
+        .db
+        .copy_session_config(id, session.id)
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+    if let Some(backend) = &backend {
+        state
+            .db
+            .set_session_config(session.id, BACKEND_KEY, backend)
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+    }
+
+    events::session_created(&state.db, &state.connections, &session);
+
+    let mut messages = Vec::new();
+    if let Some(prompt) = &prompt {
+        let message = state
+            .db
+            .insert_message(session.id, MessageRole::User, prompt)
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+        messages.push(message);
+    }
+
+    let session = match prompt.filter(|_| req.start) {
+        Some(prompt) => start_new_session(&state, &session, &repo.path, &prompt, backend).await?,
+        None => session,
+    };
+
+    Ok(Json(SessionDetails { session, messages }))
+}
+
+/// Get a session's config overrides (keys not listed fall back to global config)
+async fn get_session_config(
+    State(state): State<AppState>,
+    AxumPath(id): AxumPath<Uuid>,
+) -> AppResult<Json<ConfigResponse>> {
+    state.db.get_session(id).map_err(|e| match e {
+        crate::db::DbError::NotFound => AppError::NotFound(format!("Session not found: {}", id)),
+        _ => AppError::Internal(e.to_string()),
+    })?;
+
+    let config = state
+        .db
+        .list_session_config(id)
+        .map_err(|e| AppError::Internal(e.to_string()))?
+        .into_iter()
+        .map(|(key, value)| {
+            let value = if secrets::is_redacted_key(&key) {
+                secrets::REDACTED.to_string()
+            } else {
+                value
+            };
+            (key, value)
+        })
+        .collect();
+
+    Ok(Json(ConfigResponse { config }))
+}
+
+/// Secret and protected keys are global only: a session override would skip
+/// their encryption, redaction and admin check
+fn ensure_session_overridable(key: &str) -> AppResult<()> {
+    if secrets::is_redacted_key(key) {
+        return Err(AppError::BadRequest(format!("'{}' can't be overridden per session", key)));
+    }
+    Ok(())
+}
+
+/// Override a global config value for one session
+async fn set_session_config_value(
+    State(state): State<AppState>,
+    AxumPath((id, key)): AxumPath<(Uuid, String)>,
+    Json(req): Json<SetConfigValueRequest>,
+) -> AppResult<Json<ConfigValueResponse>> {
+    ensure_session_overridable(&key)?;
+    validate_config(&state, &key, &req.value)?;
+
+    state
+        .db
+        .set_session_config(id, &key, &req.value)
+        .map_err(|e| match e {
+            crate::db::DbError::NotFound => {
+                AppError::NotFound(format!("Session not found: {}", id))
+            }
+            _ => AppError::Internal(e.to_string()),
         })?;
 
-    Ok(Json(RunSessionResponse {
-        session_id: id,
-        status: SessionStatus::Running,
-        message: "Ralph process started".to_string(),
+    Ok(Json(ConfigValueResponse {
+        key,
+        value: Some(req.value),
     }))
 }
 
+/// Remove a session's override so the key falls back to global config
+async fn delete_session_config_value(
+    State(state): State<AppState>,
+    AxumPath((id, key)): AxumPath<(Uuid, String)>,
+) -> AppResult<Json<()>> {
+    state
+        .db
+        .delete_session_config(id, &key)
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(Json(()))
+}
+
 /// Cancel a running ralph session
 async fn cancel_session(
     State(state): State<AppState>,
@@ -232,8 +868,68 @@ pub struct CancelSessionResponse {
     pub message: String,
 }
 
-/// Get session output logs (historical)
-async fn get_session_output(
+/// Get a page of session messages, newest first
+async fn get_session_messages(
+    State(state): State<AppState>,
+    AxumPath(id): AxumPath<Uuid>,
+    Query(params): Query<MessagesQueryParams>,
+) -> AppResult<Json<MessagesPageResponse>> {
+    // Verify session exists
+    state.db.get_session(id).map_err(|e| match e {
+        crate::db::DbError::NotFound => AppError::NotFound(format!("Session not found: {}", id)),
+        _ => AppError::Internal(e.to_string()),
+    })?;
+
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_MESSAGES_PAGE_SIZE)
+        .clamp(1, MAX_MESSAGES_PAGE_SIZE);
+
+    let messages = state
+        .db
+        .list_messages_paged(id, limit, params.before)
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    let next_before = if messages.len() == limit {
+        messages.last().map(|m| m.created_at)
+    } else {
+        None
+    };
+
+    Ok(Json(MessagesPageResponse {
+        session_id: id,
+        messages,
+        next_before,
+    }))
+}
+
+/// Search message content across all sessions
+async fn search_messages(
+    State(state): State<AppState>,
+    Query(params): Query<MessageSearchParams>,
+) -> AppResult<Json<MessageSearchResponse>> {
+    if params.q.trim().is_empty() {
+        return Err(AppError::BadRequest("Search query cannot be empty".to_string()));
+    }
+
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_MESSAGES_PAGE_SIZE)
+        .clamp(1, MAX_MESSAGES_PAGE_SIZE);
+
+    let messages = state
+        .db
+        .search_messages(&params.q, limit)
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(Json(MessageSearchResponse {
+        query: params.q,
+        messages,
+    }))
+}
+
+/// Get session output logs (historical)
+async fn get_session_output(
     State(state): State<AppState>,
     AxumPath(id): AxumPath<Uuid>,
     Query(params): Query<OutputQueryParams>,
@@ -265,21 +961,425 @@ async fn get_session_output(
     }))
 }
 
+/// Download session output as a `.log` file, streamed in batches
+async fn download_session_output(
+    State(state): State<AppState>,
+    AxumPath(id): AxumPath<Uuid>,
+    Query(params): Query<OutputDownloadParams>,
+) -> AppResult<impl IntoResponse> {
+    let session = state.db.get_session(id).map_err(|e| match e {
+        crate::db::DbError::NotFound => AppError::NotFound(format!("Session not found: {}", id)),
+        _ => AppError::Internal(e.to_string()),
+    })?;
+
+    let stream_filter = match params.stream.as_deref().map(str::to_lowercase).as_deref() {
+        None | Some("all") => None,
+        Some("stdout") => Some(OutputStream::Stdout),
+        Some("stderr") => Some(OutputStream::Stderr),
+        Some(other) => {
+            return Err(AppError::BadRequest(format!(
+                "Invalid stream '{}': expected all, stdout or stderr",
+                other
+            )));
+        }
+    };
+
+    let db = state.db.clone();
+    let timestamps = params.timestamps;
+    let body = async_stream::stream! {
+        // Page by id so ordering follows the output sequence and memory stays flat
+        let mut after_id = 0;
+        loop {
+            let logs = match db.list_output_logs_batch(
+                id,
+                stream_filter,
+                after_id,
+                OUTPUT_DOWNLOAD_BATCH_SIZE,
+            ) {
+                Ok(logs) => logs,
+                Err(e) => {
+                    yield Err(std::io::Error::other(e.to_string()));
+                    break;
+                }
+            };
+            let Some(last) = logs.last() else {
+                break;
+            };
+            after_id = last.id;
+
+            let mut chunk = String::new();
+            for log in &logs {
+                if timestamps {
+                    chunk.push_str(&log.created_at.to_rfc3339());
+                    chunk.push(' ');
+                }
+                chunk.push_str(&log.content);
+                chunk.push('\n');
+            }
+            yield Ok(chunk);
+
+            if (logs.len() as i64) < OUTPUT_DOWNLOAD_BATCH_SIZE {
+                break;
+            }
+        }
+    };
+
+    let filename = session_filename(&session, Utc::now().date_naive(), "log");
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/plain; charset=utf-8".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", filename),
+            ),
+        ],
+        Body::from_stream(body),
+    ))
+}
+
+/// Get one output line, with its full text if it was truncated and kept
+async fn get_session_output_line(
+    State(state): State<AppState>,
+    AxumPath((id, log_id)): AxumPath<(Uuid, i64)>,
+) -> AppResult<Json<OutputLog>> {
+    let log = state.db.get_full_output_log(id, log_id).map_err(|e| match e {
+        crate::db::DbError::NotFound => {
+            AppError::NotFound(format!("Output line {} not found in session {}", log_id, id))
+        }
+        e => AppError::Internal(e.to_string()),
+    })?;
+
+    Ok(Json(log))
+}
+
+/// Get the command line that was executed for a session
+async fn get_session_command(
+    State(state): State<AppState>,
+    AxumPath(id): AxumPath<Uuid>,
+) -> AppResult<Json<SessionCommandResponse>> {
+    let not_found = |e| match e {
+        crate::db::DbError::NotFound => AppError::NotFound(format!("Session not found: {}", id)),
+        e => AppError::Internal(e.to_string()),
+    };
+    let command = state.db.get_session_command(id).map_err(not_found)?;
+    let backend = state.db.get_session_backend(id).map_err(not_found)?;
+
+    Ok(Json(SessionCommandResponse {
+        session_id: id,
+        command,
+        backend,
+    }))
+}
+
+/// Resolve a session's repository and verify it on disk
+async fn get_session_repo(
+    State(state): State<AppState>,
+    AxumPath(id): AxumPath<Uuid>,
+) -> AppResult<Json<SessionRepoResponse>> {
+    let session = state.db.get_session(id).map_err(|e| match e {
+        crate::db::DbError::NotFound => AppError::NotFound(format!("Session not found: {}", id)),
+        _ => AppError::Internal(e.to_string()),
+    })?;
+
+    let repo = state.db.get_repo(session.repo_id).map_err(|e| match e {
+        crate::db::DbError::NotFound => {
+            AppError::Internal(format!("Repository not found for session: {}", id))
+        }
+        _ => AppError::Internal(e.to_string()),
+    })?;
+
+    let path = std::path::Path::new(&repo.path);
+    let exists = path.exists();
+    let validation = crate::git::validate_repo_path(path);
+    let canonical_path = path
+        .canonicalize()
+        .ok()
+        .map(|p| p.to_string_lossy().to_string());
+
+    Ok(Json(SessionRepoResponse {
+        session_id: id,
+        canonical_path,
+        exists,
+        is_git: validation.is_ok(),
+        error: validation.err().map(|e| match e {
+            AppError::UserActionRequired { message, .. } => message,
+            e => e.to_string(),
+        }),
+        repo,
+    }))
+}
+
+/// Read the configured number of context messages, falling back to the default
+fn context_message_limit(state: &AppState) -> usize {
+    match state.config.get_as(CONTEXT_MESSAGE_LIMIT_KEY) {
+        Ok(Some(limit)) => limit,
+        Ok(None) => DEFAULT_CONTEXT_MESSAGE_LIMIT,
+        Err(e) => {
+            tracing::warn!("Failed to read {}: {}", CONTEXT_MESSAGE_LIMIT_KEY, e);
+            DEFAULT_CONTEXT_MESSAGE_LIMIT
+        }
+    }
+}
+
+/// Bundle repo info, git status and recent messages for feeding the agent
+async fn get_session_context(
+    State(state): State<AppState>,
+    AxumPath(id): AxumPath<Uuid>,
+    Query(params): Query<ContextQueryParams>,
+) -> AppResult<Json<SessionContextResponse>> {
+    let session = state.db.get_session(id).map_err(|e| match e {
+        crate::db::DbError::NotFound => AppError::NotFound(format!("Session not found: {}", id)),
+        _ => AppError::Internal(e.to_string()),
+    })?;
+
+    let repo = state.db.get_repo(session.repo_id).map_err(|e| match e {
+        crate::db::DbError::NotFound => {
+            AppError::Internal(format!("Repository not found for session: {}", id))
+        }
+        _ => AppError::Internal(e.to_string()),
+    })?;
+
+    let (git, git_error) = match crate::git::GitManager::status(std::path::Path::new(&repo.path)) {
+        Ok(status) => (
+            Some(GitStatusSummary {
+                branch: status.branch,
+                ahead: status.ahead,
+                behind: status.behind,
+                staged: status.staged.len(),
+                unstaged: status.unstaged.len(),
+                untracked: status.untracked.len(),
+            }),
+            None,
+        ),
+        Err(e) => (None, Some(e.to_string())),
+    };
+
+    let limit = params
+        .messages
+        .unwrap_or_else(|| context_message_limit(&state))
+        .min(MAX_MESSAGES_PAGE_SIZE);
+    let mut messages = state
+        .db
+        .list_messages_paged(id, limit, None)
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+    messages.reverse();
+
+    Ok(Json(SessionContextResponse {
+        session,
+        repo,
+        git,
+        git_error,
+        messages,
+    }))
+}
+
+/// Get the failure context (exit code, last stderr lines) of a failed session
+async fn get_session_error(
+    State(state): State<AppState>,
+    AxumPath(id): AxumPath<Uuid>,
+) -> AppResult<Json<SessionError>> {
+    let session = state.db.get_session(id).map_err(|e| match e {
+        crate::db::DbError::NotFound => AppError::NotFound(format!("Session not found: {}", id)),
+        _ => AppError::Internal(e.to_string()),
+    })?;
+
+    if session.status != SessionStatus::Error {
+        return Err(AppError::NotFound(format!("Session {} has not failed", id)));
+    }
+
+    let error = state
+        .db
+        .get_session_error(id)
+        .map_err(|e| AppError::Internal(e.to_string()))?
+        .ok_or_else(|| AppError::NotFound(format!("No error recorded for session {}", id)))?;
+
+    Ok(Json(error))
+}
+
+/// List sessions with a running process or watching clients (admin only)
+async fn list_live_sessions(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> AppResult<Json<LiveSessionsResponse>> {
+    require_admin(&state, &headers)?;
+
+    let running = state.ralph_manager.running_processes().await;
+    let mut subscribers = state.connections.subscriber_counts().await;
+
+    let mut watched: Vec<Uuid> = subscribers
+        .keys()
+        .filter(|id| !running.iter().any(|p| p.session_id == **id))
+        .copied()
+        .collect();
+    watched.sort();
+
+    let now = Utc::now();
+    let entries = running
+        .into_iter()
+        .map(|p| (p.session_id, Some(p.started_at)))
+        .chain(watched.into_iter().map(|id| (id, None)));
+
+    let mut sessions = Vec::new();
+    for (id, started_at) in entries {
+        let session = match state.db.get_session(id) {
+            Ok(session) => session,
+            // Clients may still be subscribed to a session that was deleted
+            Err(crate::db::DbError::NotFound) => continue,
+            Err(e) => return Err(AppError::Internal(e.to_string())),
+        };
+
+        sessions.push(LiveSession {
+            session_id: session.id,
+            repo_id: session.repo_id,
+            name: session.name,
+            status: session.status,
+            running: started_at.is_some(),
+            started_at,
+            runtime_secs: started_at.map(|t| (now - t).num_seconds()),
+            subscribers: subscribers.remove(&id).unwrap_or(0),
+        });
+    }
+
+    Ok(Json(LiveSessionsResponse { sessions }))
+}
+
+/// Report how many sessions are running against the concurrency limit
+async fn get_session_capacity(State(state): State<AppState>) -> Json<SessionCapacityResponse> {
+    let running = state.ralph_manager.running_count().await;
+    let max_concurrent_sessions = RalphManager::max_concurrent_sessions(&state.db);
+
+    Json(SessionCapacityResponse {
+        running,
+        max_concurrent_sessions,
+        can_start: max_concurrent_sessions.is_none_or(|limit| running < limit),
+    })
+}
+
+/// Transition several sessions to a status at once (admin only)
+///
+/// Intended for recovery, e.g. marking sessions left `running` by a crash.
+/// Each session is validated independently; sessions with a live process are
+/// never touched.
+async fn bulk_update_status(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<BulkStatusRequest>,
+) -> AppResult<Json<BulkStatusResponse>> {
+    require_admin(&state, &headers)?;
+
+    if req.status == SessionStatus::Running {
+        return Err(AppError::BadRequest(
+            "Sessions can only enter 'running' by starting a process".to_string(),
+        ));
+    }
+
+    let mut results = Vec::with_capacity(req.ids.len());
+    for id in req.ids {
+        let result = match transition_session(&state, id, req.status).await {
+            Ok(status) => BulkStatusResult {
+                id,
+                success: true,
+                status: Some(status),
+                error: None,
+            },
+            Err(error) => BulkStatusResult {
+                id,
+                success: false,
+                status: None,
+                error: Some(error),
+            },
+        };
+        results.push(result);
+    }
+
+    Ok(Json(BulkStatusResponse { results }))
+}
+
+/// Validate and apply a single status transition, broadcasting the change
+async fn transition_session(
+    state: &AppState,
+    id: Uuid,
+    status: SessionStatus,
+) -> Result<SessionStatus, String> {
+    let session = state.db.get_session(id).map_err(|e| match e {
+        crate::db::DbError::NotFound => format!("Session not found: {}", id),
+        e => e.to_string(),
+    })?;
+
+    if state.ralph_manager.is_session_running(id).await {
+        return Err(format!("Session {} has a running process", id));
+    }
+
+    if !session.status.can_transition_to(status) {
+        return Err(format!(
+            "Cannot transition from '{}' to '{}'",
+            session.status.as_str(),
+            status.as_str()
+        ));
+    }
+
+    if session.status == status {
+        return Ok(status);
+    }
+
+    state
+        .db
+        .update_session_status(id, status)
+        .map_err(|e| e.to_string())?;
+    events::status_changed(&state.db, &state.connections, id, session.repo_id, status);
+
+    state
+        .connections
+        .broadcast(
+            id,
+            ServerMessage::Status {
+                session_id: id,
+                status: status.into(),
+            },
+        )
+        .await;
+
+    Ok(status)
+}
+
 /// Create the sessions router
 pub fn router() -> Router<AppState> {
     Router::new()
         .route("/sessions", get(list_sessions).post(create_session))
-        .route("/sessions/{id}", get(get_session).delete(delete_session))
+        .route("/sessions/bulk-status", post(bulk_update_status))
+        .route("/sessions/prune", post(prune_sessions))
+        .route("/sessions/live", get(list_live_sessions))
+        .route("/sessions/capacity", get(get_session_capacity))
+        .route(
+            "/sessions/{id}",
+            get(get_session).patch(update_session).delete(delete_session),
+        )
+        .route("/sessions/{id}/archive", post(archive_session))
+        .route("/sessions/{id}/unarchive", post(unarchive_session))
         .route("/sessions/{id}/run", post(run_session))
+        .route("/sessions/{id}/rerun", post(rerun_session))
         .route("/sessions/{id}/cancel", post(cancel_session))
+        .route("/sessions/{id}/config", get(get_session_config))
+        .route(
+            "/sessions/{id}/config/{key}",
+            put(set_session_config_value).delete(delete_session_config_value),
+        )
+        .route("/sessions/{id}/messages", get(get_session_messages))
         .route("/sessions/{id}/output", get(get_session_output))
+        .route("/sessions/{id}/output/download", get(download_session_output))
+        .route("/sessions/{id}/output/{log_id}", get(get_session_output_line))
+        .route("/sessions/{id}/command", get(get_session_command))
+        .route("/sessions/{id}/error", get(get_session_error))
+        .route("/sessions/{id}/repo", get(get_session_repo))
+        .route("/sessions/{id}/context", get(get_session_context))
+        .route("/messages/search", get(search_messages))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::api::repos::{router as repos_router, AddRepoRequest};
-    use crate::db::models::Repo;
     use crate::db::Database;
     use axum_test::TestServer;
     use tempfile::TempDir;
@@ -329,6 +1429,27 @@ mod tests {
         assert!(sessions.is_empty());
     }
 
+    #[tokio::test]
+    async fn test_list_sessions_includes_message_activity() {
+        let state = create_test_state();
+        let server = create_test_server(state.clone());
+        let repo = create_test_repo(&server).await;
+        let session = state.db.insert_session(repo.id, None, Orchestrator::Ralph).unwrap();
+        let message = state
+            .db
+            .insert_message(session.id, MessageRole::User, "Hello!")
+            .unwrap();
+
+        let response = server.get("/sessions").await;
+        response.assert_status_ok();
+
+        let sessions: Vec<SessionSummary> = response.json();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].session.id, session.id);
+        assert_eq!(sessions[0].message_count, 1);
+        assert_eq!(sessions[0].last_message_at, Some(message.created_at));
+    }
+
     #[tokio::test]
     async fn test_create_session_validates_repo() {
         let state = create_test_state();
@@ -341,6 +1462,7 @@ mod tests {
                 repo_id: fake_repo_id,
                 name: None,
                 orchestrator: Orchestrator::Ralph,
+                ..Default::default()
             })
             .await;
 
@@ -355,30 +1477,327 @@ mod tests {
         // Create a repo first
         let repo = create_test_repo(&server).await;
 
-        // Create a session
+        // Create a session
+        let response = server
+            .post("/sessions")
+            .json(&CreateSessionRequest {
+                repo_id: repo.id,
+                name: Some("Test Session".to_string()),
+                orchestrator: Orchestrator::Ralph,
+                ..Default::default()
+            })
+            .await;
+
+        response.assert_status_ok();
+        let session: Session = response.json();
+        assert_eq!(session.repo_id, repo.id);
+        assert_eq!(session.name, Some("Test Session".to_string()));
+        assert_eq!(session.orchestrator, Orchestrator::Ralph);
+        assert_eq!(session.status, crate::db::models::SessionStatus::Idle);
+
+        // List sessions
+        let response = server.get("/sessions").await;
+        response.assert_status_ok();
+        let sessions: Vec<Session> = response.json();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].name, Some("Test Session".to_string()));
+        assert_eq!(sessions[0].orchestrator, Orchestrator::Ralph);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_create_session_auto_start() {
+        let mut state = create_test_state();
+        state.ralph_manager = crate::ralph::RalphManager::with_program("sh");
+        let server = create_test_server(state.clone());
+        let repo = create_test_repo(&server).await;
+        std::fs::write(std::path::Path::new(&repo.path).join("run"), "sleep 30\n").unwrap();
+
+        let response = server
+            .post("/sessions")
+            .json(&CreateSessionRequest {
+                repo_id: repo.id,
+                auto_start: Some(true),
+                prompt: Some("Start with the parser".to_string()),
+                preset: Some("debug".to_string()),
+                ..Default::default()
+            })
+            .await;
+        response.assert_status_ok();
+        let session: Session = response.json();
+        assert_eq!(session.status, crate::db::models::SessionStatus::Running);
+
+        // The preset's instructions lead the prompt
+        let command = state.db.get_session_command(session.id).unwrap().unwrap();
+        assert!(command.contains("'Investigate and fix bugs\n\nStart with the parser'"));
+
+        // The prompt is recorded as the session's first message
+        let messages = state.db.list_messages(session.id).unwrap();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].role, MessageRole::User);
+        assert_eq!(messages[0].content, "Investigate and fix bugs\n\nStart with the parser");
+
+        state
+            .ralph_manager
+            .cancel(session.id, state.db.clone(), state.connections.clone())
+            .await
+            .unwrap();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_create_session_start_alias() {
+        let mut state = create_test_state();
+        state.ralph_manager = crate::ralph::RalphManager::with_program("sh");
+        let server = create_test_server(state.clone());
+        let repo = create_test_repo(&server).await;
+        std::fs::write(std::path::Path::new(&repo.path).join("run"), "sleep 30\n").unwrap();
+
+        let response = server
+            .post("/sessions")
+            .json(&serde_json::json!({
+                "repo_id": repo.id,
+                "start": true,
+                "prompt": "Add a changelog",
+            }))
+            .await;
+        response.assert_status_ok();
+        let session: Session = response.json();
+        assert_eq!(session.status, SessionStatus::Running);
+
+        let messages = state.db.list_messages(session.id).unwrap();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].content, "Add a changelog");
+
+        state
+            .ralph_manager
+            .cancel(session.id, state.db.clone(), state.connections.clone())
+            .await
+            .unwrap();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_rerun_session() {
+        let mut state = create_test_state();
+        state.ralph_manager = crate::ralph::RalphManager::with_program("sh");
+        let server = create_test_server(state.clone());
+        let repo = create_test_repo(&server).await;
+        std::fs::write(std::path::Path::new(&repo.path).join("run"), "sleep 30\n").unwrap();
+
+        let original = state
+            .db
+            .insert_session(repo.id, Some("First try"), Orchestrator::Ralph)
+            .unwrap();
+        state
+            .db
+            .insert_message(original.id, MessageRole::User, "Fix the flaky test")
+            .unwrap();
+        state
+            .db
+            .insert_message(original.id, MessageRole::Assistant, "Done")
+            .unwrap();
+        state.db.update_session_backend(original.id, Some("bedrock")).unwrap();
+        state.db.set_session_config(original.id, "agent_workdir", "app").unwrap();
+
+        // Without `start` the copy is left idle with the original prompt and
+        // config recorded
+        let response = server
+            .post(&format!("/sessions/{}/rerun", original.id))
+            .json(&RerunSessionRequest::default())
+            .await;
+        response.assert_status_ok();
+        let copy: SessionDetails = response.json();
+        assert_ne!(copy.session.id, original.id);
+        assert_eq!(copy.session.repo_id, repo.id);
+        assert_eq!(copy.session.status, SessionStatus::Idle);
+        assert_eq!(copy.messages.len(), 1);
+        assert_eq!(copy.messages[0].content, "Fix the flaky test");
+        let mut config = state.db.list_session_config(copy.session.id).unwrap();
+        config.sort();
+        assert_eq!(
+            config,
+            [
+                ("agent_workdir".to_string(), "app".to_string()),
+                ("backend".to_string(), "bedrock".to_string()),
+            ]
+        );
+        state.db.delete_session_config(original.id, "agent_workdir").unwrap();
+
+        // With `start` it runs on the original's backend
+        let response = server
+            .post(&format!("/sessions/{}/rerun", original.id))
+            .json(&RerunSessionRequest {
+                start: true,
+                ..Default::default()
+            })
+            .await;
+        response.assert_status_ok();
+        let rerun: SessionDetails = response.json();
+        assert_eq!(rerun.session.status, SessionStatus::Running);
+        assert_eq!(
+            state.db.get_session_backend(rerun.session.id).unwrap().as_deref(),
+            Some("bedrock")
+        );
+        let command = state.db.get_session_command(rerun.session.id).unwrap().unwrap();
+        assert!(command.contains("'Fix the flaky test'"));
+
+        state
+            .ralph_manager
+            .cancel(rerun.session.id, state.db.clone(), state.connections.clone())
+            .await
+            .unwrap();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_session_config_overrides_backend() {
+        let mut state = create_test_state();
+        state.ralph_manager = crate::ralph::RalphManager::with_program("sh");
+        let server = create_test_server(state.clone());
+        let repo = create_test_repo(&server).await;
+        std::fs::write(std::path::Path::new(&repo.path).join("run"), "sleep 30\n").unwrap();
+        state.config.set(crate::ralph::BACKEND_KEY, "claude").unwrap();
+
+        let session = state
+            .db
+            .insert_session(repo.id, None, Orchestrator::Ralph)
+            .unwrap();
+        let config_url = format!("/sessions/{}/config", session.id);
+
+        server
+            .put(&format!("{}/backend", config_url))
+            .json(&SetConfigValueRequest {
+                value: "bedrock".to_string(),
+            })
+            .await
+            .assert_status_ok();
+        let config: ConfigResponse = server.get(&config_url).await.json();
+        assert_eq!(config.config.get("backend").map(String::as_str), Some("bedrock"));
+
+        // Values are validated like global config
+        server
+            .put(&format!("{}/backend", config_url))
+            .json(&SetConfigValueRequest {
+                value: "openai".to_string(),
+            })
+            .await
+            .assert_status_bad_request();
+        server
+            .put(&format!("/sessions/{}/config/backend", Uuid::new_v4()))
+            .json(&SetConfigValueRequest {
+                value: "vertex".to_string(),
+            })
+            .await
+            .assert_status_not_found();
+
+        // Secret and protected keys can't be overridden per session
+        for key in ["secret%2Fanthropic_api_key", "ws_auth_token"] {
+            server
+                .put(&format!("{}/{}", config_url, key))
+                .json(&SetConfigValueRequest {
+                    value: "leak".to_string(),
+                })
+                .await
+                .assert_status_bad_request();
+        }
+        let config: ConfigResponse = server.get(&config_url).await.json();
+        assert_eq!(config.config.len(), 1);
+
+        // The run uses the session's backend rather than the global one
+        server
+            .post(&format!("/sessions/{}/run", session.id))
+            .json(&RunSessionRequest {
+                prompt: "Go".to_string(),
+            })
+            .await
+            .assert_status_ok();
+        assert_eq!(
+            state.db.get_session_backend(session.id).unwrap().as_deref(),
+            Some("bedrock")
+        );
+        state
+            .ralph_manager
+            .cancel(session.id, state.db.clone(), state.connections.clone())
+            .await
+            .unwrap();
+
+        server
+            .delete(&format!("{}/backend", config_url))
+            .await
+            .assert_status_ok();
+        let config: ConfigResponse = server.get(&config_url).await.json();
+        assert!(config.config.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_rerun_session_requires_prompt_to_start() {
+        let state = create_test_state();
+        let server = create_test_server(state.clone());
+        let repo = create_test_repo(&server).await;
+        let original = state
+            .db
+            .insert_session(repo.id, None, Orchestrator::Ralph)
+            .unwrap();
+
+        let response = server
+            .post(&format!("/sessions/{}/rerun", original.id))
+            .json(&RerunSessionRequest {
+                start: true,
+                ..Default::default()
+            })
+            .await;
+        response.assert_status_bad_request();
+
+        let response = server
+            .post(&format!("/sessions/{}/rerun", Uuid::new_v4()))
+            .json(&RerunSessionRequest::default())
+            .await;
+        response.assert_status_not_found();
+    }
+
+    #[tokio::test]
+    async fn test_create_session_auto_start_requires_prompt() {
+        let state = create_test_state();
+        let server = create_test_server(state.clone());
+        let repo = create_test_repo(&server).await;
+
+        // Auto-start enabled through config applies when the request doesn't say
+        state.db.set_config(AUTO_START_SESSIONS_KEY, "true").unwrap();
+        let response = server
+            .post("/sessions")
+            .json(&CreateSessionRequest {
+                repo_id: repo.id,
+                ..Default::default()
+            })
+            .await;
+        response.assert_status_bad_request();
+
+        let response = server
+            .post("/sessions")
+            .json(&CreateSessionRequest {
+                repo_id: repo.id,
+                preset: Some("no-such-preset".to_string()),
+                ..Default::default()
+            })
+            .await;
+        response.assert_status_bad_request();
+
+        let sessions: Vec<Session> = server.get("/sessions").await.json();
+        assert!(sessions.is_empty());
+
+        // An explicit opt-out wins over the config default
         let response = server
             .post("/sessions")
             .json(&CreateSessionRequest {
                 repo_id: repo.id,
-                name: Some("Test Session".to_string()),
-                orchestrator: Orchestrator::Ralph,
+                auto_start: Some(false),
+                ..Default::default()
             })
             .await;
-
         response.assert_status_ok();
         let session: Session = response.json();
-        assert_eq!(session.repo_id, repo.id);
-        assert_eq!(session.name, Some("Test Session".to_string()));
-        assert_eq!(session.orchestrator, Orchestrator::Ralph);
         assert_eq!(session.status, crate::db::models::SessionStatus::Idle);
-
-        // List sessions
-        let response = server.get("/sessions").await;
-        response.assert_status_ok();
-        let sessions: Vec<Session> = response.json();
-        assert_eq!(sessions.len(), 1);
-        assert_eq!(sessions[0].name, Some("Test Session".to_string()));
-        assert_eq!(sessions[0].orchestrator, Orchestrator::Ralph);
     }
 
     #[tokio::test]
@@ -394,6 +1813,7 @@ mod tests {
                 repo_id: repo.id,
                 name: None,
                 orchestrator: Orchestrator::Ralph,
+                ..Default::default()
             })
             .await;
         response.assert_status_ok();
@@ -427,6 +1847,109 @@ mod tests {
         assert_eq!(details.messages[1].content, "Hi there!");
     }
 
+    #[tokio::test]
+    async fn test_search_messages() {
+        let state = create_test_state();
+        let server = create_test_server(state.clone());
+
+        let repo = create_test_repo(&server).await;
+        let mut sessions = Vec::new();
+        for _ in 0..2 {
+            let session: Session = server
+                .post("/sessions")
+                .json(&CreateSessionRequest {
+                    repo_id: repo.id,
+                    name: None,
+                    orchestrator: Orchestrator::Ralph,
+                    ..Default::default()
+                })
+                .await
+                .json();
+            sessions.push(session);
+        }
+
+        let role = crate::db::models::MessageRole::System;
+        state
+            .db
+            .insert_message(sessions[0].id, role, "panicked at index out of bounds")
+            .unwrap();
+        state
+            .db
+            .insert_message(sessions[1].id, role, "all tests passed")
+            .unwrap();
+
+        let response = server
+            .get("/messages/search")
+            .add_query_param("q", "panicked")
+            .await;
+        response.assert_status_ok();
+        let results: MessageSearchResponse = response.json();
+        assert_eq!(results.messages.len(), 1);
+        assert_eq!(results.messages[0].session_id, sessions[0].id);
+
+        server
+            .get("/messages/search")
+            .add_query_param("q", " ")
+            .await
+            .assert_status(axum::http::StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_get_session_messages_paged() {
+        let state = create_test_state();
+        let server = create_test_server(state.clone());
+
+        let repo = create_test_repo(&server).await;
+        let session: Session = server
+            .post("/sessions")
+            .json(&CreateSessionRequest {
+                repo_id: repo.id,
+                name: None,
+                orchestrator: Orchestrator::Ralph,
+                ..Default::default()
+            })
+            .await
+            .json();
+
+        for i in 0..5 {
+            state
+                .db
+                .insert_message(
+                    session.id,
+                    crate::db::models::MessageRole::User,
+                    &format!("message {}", i),
+                )
+                .expect("Failed to insert message");
+        }
+
+        let response = server
+            .get(&format!("/sessions/{}/messages?limit=3", session.id))
+            .await;
+        response.assert_status_ok();
+        let page: MessagesPageResponse = response.json();
+        let contents: Vec<_> = page.messages.iter().map(|m| m.content.as_str()).collect();
+        assert_eq!(contents, vec!["message 4", "message 3", "message 2"]);
+
+        let before = page
+            .next_before
+            .expect("Expected a cursor for the next page")
+            .to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true);
+        let page: MessagesPageResponse = server
+            .get(&format!("/sessions/{}/messages", session.id))
+            .add_query_param("limit", 3)
+            .add_query_param("before", before)
+            .await
+            .json();
+        let contents: Vec<_> = page.messages.iter().map(|m| m.content.as_str()).collect();
+        assert_eq!(contents, vec!["message 1", "message 0"]);
+        assert!(page.next_before.is_none());
+
+        server
+            .get(&format!("/sessions/{}/messages", Uuid::new_v4()))
+            .await
+            .assert_status_not_found();
+    }
+
     #[tokio::test]
     async fn test_get_nonexistent_session() {
         let state = create_test_state();
@@ -437,6 +1960,48 @@ mod tests {
         response.assert_status_not_found();
     }
 
+    #[tokio::test]
+    async fn test_rename_session() {
+        let state = create_test_state();
+        let server = create_test_server(state);
+
+        let repo = create_test_repo(&server).await;
+        let session: Session = server
+            .post("/sessions")
+            .json(&CreateSessionRequest {
+                repo_id: repo.id,
+                name: Some("Before".to_string()),
+                orchestrator: Orchestrator::Ralph,
+                ..Default::default()
+            })
+            .await
+            .json();
+
+        let response = server
+            .patch(&format!("/sessions/{}", session.id))
+            .json(&UpdateSessionRequest {
+                name: Some("Fixed the parser".to_string()),
+            })
+            .await;
+        response.assert_status_ok();
+        let renamed: Session = response.json();
+        assert_eq!(renamed.name.as_deref(), Some("Fixed the parser"));
+
+        let details: SessionDetails = server
+            .get(&format!("/sessions/{}", session.id))
+            .await
+            .json();
+        assert_eq!(details.session.name.as_deref(), Some("Fixed the parser"));
+
+        let response = server
+            .patch(&format!("/sessions/{}", Uuid::new_v4()))
+            .json(&UpdateSessionRequest {
+                name: Some("Missing".to_string()),
+            })
+            .await;
+        response.assert_status_not_found();
+    }
+
     #[tokio::test]
     async fn test_delete_session() {
         let state = create_test_state();
@@ -450,6 +2015,7 @@ mod tests {
                 repo_id: repo.id,
                 name: Some("To Delete".to_string()),
                 orchestrator: Orchestrator::Ralph,
+                ..Default::default()
             })
             .await;
         response.assert_status_ok();
@@ -487,6 +2053,7 @@ mod tests {
                 repo_id: repo.id,
                 name: None,
                 orchestrator: Orchestrator::Ralph,
+                ..Default::default()
             })
             .await;
         response.assert_status_ok();
@@ -516,6 +2083,7 @@ mod tests {
                 repo_id: repo.id,
                 name: None,
                 orchestrator: Orchestrator::Ralph,
+                ..Default::default()
             })
             .await;
         response.assert_status_ok();
@@ -579,6 +2147,94 @@ mod tests {
         assert_eq!(output.logs[0].content, "Hello stderr!");
     }
 
+    #[tokio::test]
+    async fn test_download_session_output() {
+        let state = create_test_state();
+        let server = create_test_server(state.clone());
+        let repo = create_test_repo(&server).await;
+        let session = state
+            .db
+            .insert_session(repo.id, Some("Fix Parser"), Orchestrator::Ralph)
+            .unwrap();
+
+        state.db.insert_output_log(session.id, OutputStream::Stdout, "first").unwrap();
+        state.db.insert_output_log(session.id, OutputStream::Stderr, "oops").unwrap();
+        state.db.insert_output_log(session.id, OutputStream::Stdout, "second").unwrap();
+
+        let response = server
+            .get(&format!("/sessions/{}/output/download", session.id))
+            .await;
+        response.assert_status_ok();
+        let disposition = response.header("content-disposition");
+        let disposition = disposition.to_str().unwrap();
+        assert!(disposition.starts_with("attachment; filename=\"fix-parser-"));
+        assert!(disposition.ends_with(".log\""));
+        assert_eq!(response.text(), "first\noops\nsecond\n");
+
+        let response = server
+            .get(&format!(
+                "/sessions/{}/output/download?stream=stdout&timestamps=true",
+                session.id
+            ))
+            .await;
+        let text = response.text();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].ends_with(" first"));
+        assert!(DateTime::parse_from_rfc3339(lines[0].split(' ').next().unwrap()).is_ok());
+
+        server
+            .get(&format!("/sessions/{}/output/download?stream=both", session.id))
+            .await
+            .assert_status_bad_request();
+    }
+
+    #[tokio::test]
+    async fn test_oversized_output_line_is_truncated() {
+        let state = create_test_state();
+        let server = create_test_server(state.clone());
+        let repo = create_test_repo(&server).await;
+        let session = state.db.insert_session(repo.id, None, Orchestrator::Ralph).unwrap();
+        state.db.set_config(crate::db::OUTPUT_MAX_LINE_LENGTH_KEY, "16").unwrap();
+        state.db.set_config(crate::db::OUTPUT_KEEP_FULL_LINES_KEY, "true").unwrap();
+
+        let long_line = format!("{{\"blob\":\"{}\"}}", "A".repeat(10_000));
+        let log = state
+            .db
+            .insert_output_log(session.id, OutputStream::Stdout, &long_line)
+            .unwrap();
+        state
+            .db
+            .insert_output_log(session.id, OutputStream::Stdout, "short line")
+            .unwrap();
+
+        let output: OutputResponse = server
+            .get(&format!("/sessions/{}/output", session.id))
+            .await
+            .json();
+        assert_eq!(
+            output.logs[0].content,
+            format!("{}{}", &long_line[..16], crate::db::TRUNCATED_MARKER)
+        );
+        assert!(output.logs[0].truncated);
+        assert_eq!(output.logs[1].content, "short line");
+        assert!(!output.logs[1].truncated);
+
+        // The full line was kept and can be fetched on demand
+        let response = server
+            .get(&format!("/sessions/{}/output/{}", session.id, log.id))
+            .await;
+        response.assert_status_ok();
+        let full: OutputLog = response.json();
+        assert_eq!(full.content, long_line);
+        assert!(!full.truncated);
+
+        server
+            .get(&format!("/sessions/{}/output/{}", Uuid::new_v4(), log.id))
+            .await
+            .assert_status_not_found();
+    }
+
     #[tokio::test]
     async fn test_get_output_nonexistent_session() {
         let state = create_test_state();
@@ -614,6 +2270,7 @@ mod tests {
                 repo_id: repo.id,
                 name: Some("Test Session".to_string()),
                 orchestrator: Orchestrator::Ralph,
+                ..Default::default()
             })
             .await;
         response.assert_status_ok();
@@ -641,9 +2298,479 @@ mod tests {
                 repo_id: repo.id,
                 name: Some("Test Session".to_string()),
                 orchestrator: Orchestrator::Gsd,
+                ..Default::default()
+            })
+            .await;
+
+        response.assert_status_bad_request();
+    }
+
+    #[test]
+    fn test_render_session_name() {
+        let now = chrono::DateTime::parse_from_rfc3339("2024-03-05T10:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        assert_eq!(
+            render_session_name("{repo} {date} #{n}", "my-repo", 3, 5, now),
+            "my-repo 2024-03-05 #3"
+        );
+        assert_eq!(
+            render_session_name("feature-{seq}", "my-repo", 3, 42, now),
+            "feature-42"
+        );
+        assert_eq!(render_session_name("Session", "my-repo", 1, 1, now), "Session");
+    }
+
+    #[tokio::test]
+    async fn test_create_session_default_name() {
+        let state = create_test_state();
+        let server = create_test_server(state.clone());
+        let repo = create_test_repo(&server).await;
+
+        let create = |name: Option<&str>| CreateSessionRequest {
+            repo_id: repo.id,
+            name: name.map(String::from),
+            orchestrator: Orchestrator::Ralph,
+            ..Default::default()
+        };
+
+        // Unnamed sessions stay unnamed when no template is configured
+        let session: Session = server.post("/sessions").json(&create(None)).await.json();
+        assert_eq!(session.name, None);
+
+        // Explicit names are kept as-is
+        let session: Session = server
+            .post("/sessions")
+            .json(&create(Some("Named")))
+            .await
+            .json();
+        assert_eq!(session.name, Some("Named".to_string()));
+
+        // Configured template is used and stored, `{n}` counting existing sessions
+        state
+            .db
+            .set_config(DEFAULT_SESSION_NAME_TEMPLATE_KEY, "{repo}-{n}")
+            .unwrap();
+        let session: Session = server.post("/sessions").json(&create(None)).await.json();
+        assert_eq!(session.name, Some("test-repo-2".to_string()));
+        let stored = state.db.get_session(session.id).unwrap();
+        assert_eq!(stored.name, Some("test-repo-2".to_string()));
+
+        // Empty template leaves sessions unnamed
+        state
+            .db
+            .set_config(DEFAULT_SESSION_NAME_TEMPLATE_KEY, "")
+            .unwrap();
+        let session: Session = server.post("/sessions").json(&create(None)).await.json();
+        assert_eq!(session.name, None);
+    }
+
+    #[tokio::test]
+    async fn test_get_session_command() {
+        let state = create_test_state();
+        let server = create_test_server(state.clone());
+        let repo = create_test_repo(&server).await;
+        let session = state
+            .db
+            .insert_session(repo.id, None, Orchestrator::Ralph)
+            .unwrap();
+
+        // Never run: no command recorded
+        let response = server.get(&format!("/sessions/{}/command", session.id)).await;
+        response.assert_status_ok();
+        let body: SessionCommandResponse = response.json();
+        assert_eq!(body.command, None);
+
+        state
+            .db
+            .update_session_command(session.id, "ralph run --autonomous --prompt hi")
+            .unwrap();
+        state.db.update_session_backend(session.id, Some("claude")).unwrap();
+        let body: SessionCommandResponse = server
+            .get(&format!("/sessions/{}/command", session.id))
+            .await
+            .json();
+        assert_eq!(body.command.as_deref(), Some("ralph run --autonomous --prompt hi"));
+        assert_eq!(body.backend.as_deref(), Some("claude"));
+
+        let response = server
+            .get(&format!("/sessions/{}/command", Uuid::new_v4()))
+            .await;
+        response.assert_status_not_found();
+    }
+
+    #[tokio::test]
+    async fn test_archive_session() {
+        let state = create_test_state();
+        let server = create_test_server(state.clone());
+        let repo = create_test_repo(&server).await;
+        let session = state
+            .db
+            .insert_session(repo.id, None, Orchestrator::Ralph)
+            .unwrap();
+        state
+            .db
+            .insert_message(session.id, crate::db::models::MessageRole::User, "keep me")
+            .unwrap();
+
+        let response = server.post(&format!("/sessions/{}/archive", session.id)).await;
+        response.assert_status_ok();
+        let archived: Session = response.json();
+        assert!(archived.archived);
+
+        let listed: Vec<Session> = server.get("/sessions").await.json();
+        assert!(listed.is_empty());
+        let listed: Vec<Session> = server
+            .get("/sessions")
+            .add_query_param("include_archived", true)
+            .await
+            .json();
+        assert_eq!(listed.len(), 1);
+        assert!(listed[0].archived);
+
+        // History is preserved and the session comes back when unarchived
+        assert_eq!(state.db.list_messages(session.id).unwrap().len(), 1);
+        let restored: Session = server
+            .post(&format!("/sessions/{}/unarchive", session.id))
+            .await
+            .json();
+        assert!(!restored.archived);
+        let listed: Vec<Session> = server.get("/sessions").await.json();
+        assert_eq!(listed.len(), 1);
+
+        server
+            .post(&format!("/sessions/{}/archive", Uuid::new_v4()))
+            .await
+            .assert_status_not_found();
+    }
+
+    #[tokio::test]
+    async fn test_list_live_sessions() {
+        let state = create_test_state();
+        let server = create_test_server(state.clone());
+        let repo = create_test_repo(&server).await;
+        let session = state
+            .db
+            .insert_session(repo.id, None, Orchestrator::Ralph)
+            .unwrap();
+
+        server
+            .get("/sessions/live")
+            .await
+            .assert_status(axum::http::StatusCode::UNAUTHORIZED);
+        let server = create_test_server(state.clone().with_admin_token(Some("s3cret")));
+
+        let live: LiveSessionsResponse = server
+            .get("/sessions/live")
+            .add_header(crate::api::ADMIN_TOKEN_HEADER, "s3cret")
+            .await
+            .json();
+        assert!(live.sessions.is_empty());
+
+        // Watchers of a deleted session are not reported
+        let connection_id = Uuid::new_v4();
+        state.connections.register_connection(connection_id).await;
+        let _rx = state.connections.subscribe(connection_id, session.id).await;
+        let _gone = state.connections.subscribe(connection_id, Uuid::new_v4()).await;
+
+        let response = server
+            .get("/sessions/live")
+            .add_header(crate::api::ADMIN_TOKEN_HEADER, "s3cret")
+            .await;
+        response.assert_status_ok();
+        let live: LiveSessionsResponse = response.json();
+        assert_eq!(live.sessions.len(), 1);
+        let entry = &live.sessions[0];
+        assert_eq!(entry.session_id, session.id);
+        assert_eq!(entry.subscribers, 1);
+        assert!(!entry.running);
+        assert_eq!(entry.runtime_secs, None);
+    }
+
+    #[tokio::test]
+    async fn test_bulk_status_requires_admin_token() {
+        let state = create_test_state();
+        let server = create_test_server(state.clone());
+        let repo = create_test_repo(&server).await;
+        let session = state
+            .db
+            .insert_session(repo.id, None, Orchestrator::Ralph)
+            .unwrap();
+
+        let req = BulkStatusRequest {
+            ids: vec![session.id],
+            status: SessionStatus::Cancelled,
+        };
+
+        // Disabled until a token is configured
+        let response = server.post("/sessions/bulk-status").json(&req).await;
+        response.assert_status(axum::http::StatusCode::UNAUTHORIZED);
+
+        let server = create_test_server(state.clone().with_admin_token(Some("s3cret")));
+
+        let response = server
+            .post("/sessions/bulk-status")
+            .add_header(crate::api::ADMIN_TOKEN_HEADER, "wrong")
+            .json(&req)
+            .await;
+        response.assert_status(axum::http::StatusCode::UNAUTHORIZED);
+
+        let response = server
+            .post("/sessions/bulk-status")
+            .add_header(crate::api::ADMIN_TOKEN_HEADER, "s3cret")
+            .json(&req)
+            .await;
+        response.assert_status_ok();
+    }
+
+    #[tokio::test]
+    async fn test_bulk_status_validates_each_transition() {
+        let state = create_test_state();
+        let server = create_test_server(state.clone().with_admin_token(Some("s3cret")));
+        let repo = create_test_repo(&server).await;
+
+        let stale = state.db.insert_session(repo.id, None, Orchestrator::Ralph).unwrap();
+        state
+            .db
+            .update_session_status(stale.id, SessionStatus::Running)
+            .unwrap();
+        let cancelled = state.db.insert_session(repo.id, None, Orchestrator::Ralph).unwrap();
+        state
+            .db
+            .update_session_status(cancelled.id, SessionStatus::Cancelled)
+            .unwrap();
+        let missing = Uuid::new_v4();
+
+        let response = server
+            .post("/sessions/bulk-status")
+            .add_header(crate::api::ADMIN_TOKEN_HEADER, "s3cret")
+            .json(&BulkStatusRequest {
+                ids: vec![stale.id, cancelled.id, missing],
+                status: SessionStatus::Error,
             })
             .await;
+        response.assert_status_ok();
+
+        let body: BulkStatusResponse = response.json();
+        assert_eq!(body.results.len(), 3);
+        assert!(body.results[0].success);
+        assert_eq!(body.results[0].status, Some(SessionStatus::Error));
+        assert!(!body.results[1].success);
+        assert!(body.results[1].error.as_ref().unwrap().contains("Cannot transition"));
+        assert!(!body.results[2].success);
+
+        assert_eq!(state.db.get_session(stale.id).unwrap().status, SessionStatus::Error);
+        assert_eq!(
+            state.db.get_session(cancelled.id).unwrap().status,
+            SessionStatus::Cancelled
+        );
+
+        // Running is reserved for the process manager
+        let response = server
+            .post("/sessions/bulk-status")
+            .add_header(crate::api::ADMIN_TOKEN_HEADER, "s3cret")
+            .json(&BulkStatusRequest {
+                ids: vec![stale.id],
+                status: SessionStatus::Running,
+            })
+            .await;
+        response.assert_status_bad_request();
+    }
+
+    #[tokio::test]
+    async fn test_session_capacity() {
+        let state = create_test_state();
+        let server = create_test_server(state.clone());
+
+        let capacity: SessionCapacityResponse = server.get("/sessions/capacity").await.json();
+        assert_eq!(capacity.running, 0);
+        assert_eq!(capacity.max_concurrent_sessions, None);
+        assert!(capacity.can_start);
+
+        state
+            .config
+            .set(crate::ralph::MAX_CONCURRENT_SESSIONS_KEY, "3")
+            .unwrap();
+        let capacity: SessionCapacityResponse = server.get("/sessions/capacity").await.json();
+        assert_eq!(capacity.max_concurrent_sessions, Some(3));
+        assert!(capacity.can_start);
+
+        // 0 means no limit
+        state
+            .config
+            .set(crate::ralph::MAX_CONCURRENT_SESSIONS_KEY, "0")
+            .unwrap();
+        let capacity: SessionCapacityResponse = server.get("/sessions/capacity").await.json();
+        assert_eq!(capacity.max_concurrent_sessions, None);
+    }
 
+    #[tokio::test]
+    async fn test_prune_sessions() {
+        let state = create_test_state();
+        let server = create_test_server(state.clone());
+        let repo = create_test_repo(&server).await;
+        let mut completed = Vec::new();
+        for _ in 0..2 {
+            let session = state.db.insert_session(repo.id, None, Orchestrator::Ralph).unwrap();
+            state
+                .db
+                .update_session_status(session.id, SessionStatus::Completed)
+                .unwrap();
+            completed.push(session);
+        }
+        let idle = state.db.insert_session(repo.id, None, Orchestrator::Ralph).unwrap();
+
+        let response = server
+            .post("/sessions/prune")
+            .json(&serde_json::json!({ "status": "completed" }))
+            .await;
+        response.assert_status_ok();
+        let body: PruneSessionsResponse = response.json();
+        assert_eq!(body.deleted, 2);
+
+        for session in &completed {
+            server
+                .get(&format!("/sessions/{}", session.id))
+                .await
+                .assert_status_not_found();
+        }
+        server
+            .get(&format!("/sessions/{}", idle.id))
+            .await
+            .assert_status_ok();
+
+        let response = server
+            .post("/sessions/prune")
+            .json(&serde_json::json!({ "status": "running" }))
+            .await;
         response.assert_status_bad_request();
     }
+
+    #[tokio::test]
+    async fn test_get_session_error() {
+        let state = create_test_state();
+        let server = create_test_server(state.clone());
+        let repo = create_test_repo(&server).await;
+        let session = state
+            .db
+            .insert_session(repo.id, None, Orchestrator::Ralph)
+            .unwrap();
+
+        // Not failed yet
+        server
+            .get(&format!("/sessions/{}/error", session.id))
+            .await
+            .assert_status_not_found();
+
+        state
+            .db
+            .upsert_session_error(session.id, Some(2), "exit status: 2", &["boom".to_string()])
+            .unwrap();
+        state
+            .db
+            .update_session_status(session.id, SessionStatus::Error)
+            .unwrap();
+
+        let response = server.get(&format!("/sessions/{}/error", session.id)).await;
+        response.assert_status_ok();
+        let error: SessionError = response.json();
+        assert_eq!(error.exit_code, Some(2));
+        assert_eq!(error.stderr_tail, vec!["boom"]);
+
+        server
+            .get(&format!("/sessions/{}/error", Uuid::new_v4()))
+            .await
+            .assert_status_not_found();
+    }
+
+    #[tokio::test]
+    async fn test_get_session_repo() {
+        let state = create_test_state();
+        let server = create_test_server(state.clone());
+        let repo = create_test_repo(&server).await;
+        let session = state
+            .db
+            .insert_session(repo.id, None, Orchestrator::Ralph)
+            .unwrap();
+
+        let response = server.get(&format!("/sessions/{}/repo", session.id)).await;
+        response.assert_status_ok();
+        let resolved: SessionRepoResponse = response.json();
+        assert_eq!(resolved.repo.id, repo.id);
+        assert!(resolved.exists);
+        assert!(resolved.is_git);
+        assert!(resolved.error.is_none());
+        assert_eq!(resolved.canonical_path.as_deref(), Some(repo.path.as_str()));
+
+        // A repo whose folder has disappeared
+        let gone = state.db.insert_repo("/nonexistent/ralphtown-repo", "gone").unwrap();
+        let session = state.db.insert_session(gone.id, None, Orchestrator::Ralph).unwrap();
+        let resolved: SessionRepoResponse = server
+            .get(&format!("/sessions/{}/repo", session.id))
+            .await
+            .json();
+        assert!(!resolved.exists);
+        assert!(!resolved.is_git);
+        assert!(resolved.canonical_path.is_none());
+        assert!(resolved.error.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_get_session_context() {
+        let state = create_test_state();
+        let server = create_test_server(state.clone());
+        let repo = create_test_repo(&server).await;
+        let session = state
+            .db
+            .insert_session(repo.id, None, Orchestrator::Ralph)
+            .unwrap();
+
+        // Give the repo a commit and an untracked file
+        {
+            let git_repo = git2::Repository::open(&repo.path).unwrap();
+            let sig = git2::Signature::now("Test User", "test@example.com").unwrap();
+            let tree_id = git_repo.index().unwrap().write_tree().unwrap();
+            let tree = git_repo.find_tree(tree_id).unwrap();
+            git_repo
+                .commit(Some("HEAD"), &sig, &sig, "Initial commit", &tree, &[])
+                .unwrap();
+        }
+        std::fs::write(std::path::Path::new(&repo.path).join("notes.txt"), "todo").unwrap();
+
+        for i in 0..5 {
+            state
+                .db
+                .insert_message(
+                    session.id,
+                    crate::db::models::MessageRole::User,
+                    &format!("message {}", i),
+                )
+                .unwrap();
+        }
+        state.db.set_config(CONTEXT_MESSAGE_LIMIT_KEY, "3").unwrap();
+
+        let response = server.get(&format!("/sessions/{}/context", session.id)).await;
+        response.assert_status_ok();
+        let context: SessionContextResponse = response.json();
+        assert_eq!(context.session.id, session.id);
+        assert_eq!(context.repo.id, repo.id);
+        let git = context.git.expect("Expected git status");
+        assert_eq!(git.untracked, 1);
+        assert!(context.git_error.is_none());
+        let contents: Vec<_> = context.messages.iter().map(|m| m.content.as_str()).collect();
+        assert_eq!(contents, vec!["message 2", "message 3", "message 4"]);
+
+        let context: SessionContextResponse = server
+            .get(&format!("/sessions/{}/context?messages=1", session.id))
+            .await
+            .json();
+        assert_eq!(context.messages.len(), 1);
+        assert_eq!(context.messages[0].content, "message 4");
+
+        server
+            .get(&format!("/sessions/{}/context", Uuid::new_v4()))
+            .await
+            .assert_status_not_found();
+    }
 }
@@ -0,0 +1,423 @@
+use axum::{
+    extract::{Path as AxumPath, State},
+    routing::{get, post},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::db::models::{MessageRole, SessionTemplate, SessionTemplateFields};
+use crate::error::{AppError, AppResult};
+use crate::events;
+use crate::git::validate_relative_path;
+use crate::ralph::{AGENT_ENV_KEY, AGENT_WORKDIR_KEY, BACKEND_KEY, MODEL_KEY};
+
+use super::config::{validate_config, PRESET_KEY};
+use super::sessions::{auto_start_prompt, create_session_record, SessionDetails};
+use super::AppState;
+
+/// Request body for creating a session from a template
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreateFromTemplateRequest {
+    /// Repository ID to create the session for
+    pub repo_id: Uuid,
+    /// Optional session name (defaults to the configured name template)
+    pub name: Option<String>,
+}
+
+/// Validate template fields before storing them
+fn validate_template(state: &AppState, fields: &SessionTemplateFields) -> AppResult<()> {
+    if fields.name.trim().is_empty() {
+        return Err(AppError::BadRequest("Template name cannot be empty".to_string()));
+    }
+
+    if !fields.orchestrator.is_available() {
+        return Err(AppError::BadRequest(format!(
+            "Orchestrator '{}' is not yet available",
+            fields.orchestrator.as_str()
+        )));
+    }
+
+    if let Some(workdir) = &fields.workdir {
+        validate_relative_path(workdir)
+            .map_err(|e| AppError::BadRequest(format!("Invalid workdir: {}", e)))?;
+    }
+
+    if fields.env.keys().any(|k| k.is_empty() || k.contains('=')) {
+        return Err(AppError::BadRequest(
+            "Environment variable names cannot be empty or contain '='".to_string(),
+        ));
+    }
+
+    if let Some(backend) = &fields.backend {
+        validate_config(state, BACKEND_KEY, backend)?;
+    }
+    if let Some(preset) = &fields.preset {
+        validate_config(state, PRESET_KEY, preset)?;
+    }
+
+    Ok(())
+}
+
+/// Store a template's run settings as config overrides for a new session
+fn apply_template_config(
+    state: &AppState,
+    session_id: Uuid,
+    fields: &SessionTemplateFields,
+) -> AppResult<()> {
+    let env = if fields.env.is_empty() {
+        None
+    } else {
+        Some(serde_json::to_string(&fields.env).map_err(|e| AppError::Internal(e.to_string()))?)
+    };
+    let overrides = [
+        (BACKEND_KEY, fields.backend.clone()),
+        (MODEL_KEY, fields.model.clone()),
+        (PRESET_KEY, fields.preset.clone()),
+        (AGENT_ENV_KEY, env),
+        (AGENT_WORKDIR_KEY, fields.workdir.clone()),
+    ];
+
+    for (key, value) in overrides {
+        if let Some(value) = value.filter(|v| !v.trim().is_empty()) {
+            state
+                .db
+                .set_session_config(session_id, key, &value)
+                .map_err(|e| AppError::Internal(e.to_string()))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// List all session templates
+async fn list_templates(State(state): State<AppState>) -> AppResult<Json<Vec<SessionTemplate>>> {
+    let templates = state
+        .db
+        .list_session_templates()
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(Json(templates))
+}
+
+/// Create a new session template
+async fn create_template(
+    State(state): State<AppState>,
+    Json(fields): Json<SessionTemplateFields>,
+) -> AppResult<Json<SessionTemplate>> {
+    validate_template(&state, &fields)?;
+
+    let template = state.db.insert_session_template(&fields)?;
+
+    Ok(Json(template))
+}
+
+/// Get a session template by ID
+async fn get_template(
+    State(state): State<AppState>,
+    AxumPath(id): AxumPath<Uuid>,
+) -> AppResult<Json<SessionTemplate>> {
+    let template = state.db.get_session_template(id).map_err(|e| match e {
+        crate::db::DbError::NotFound => AppError::NotFound(format!("Template not found: {}", id)),
+        _ => AppError::Internal(e.to_string()),
+    })?;
+
+    Ok(Json(template))
+}
+
+/// Replace a session template
+async fn update_template(
+    State(state): State<AppState>,
+    AxumPath(id): AxumPath<Uuid>,
+    Json(fields): Json<SessionTemplateFields>,
+) -> AppResult<Json<SessionTemplate>> {
+    validate_template(&state, &fields)?;
+
+    let template = state
+        .db
+        .update_session_template(id, &fields)
+        .map_err(|e| match e {
+            crate::db::DbError::NotFound => AppError::NotFound(format!("Template not found: {}", id)),
+            e => e.into(),
+        })?;
+
+    Ok(Json(template))
+}
+
+/// Delete a session template
+async fn delete_template(
+    State(state): State<AppState>,
+    AxumPath(id): AxumPath<Uuid>,
+) -> AppResult<Json<()>> {
+    state.db.delete_session_template(id).map_err(|e| match e {
+        crate::db::DbError::NotFound => AppError::NotFound(format!("Template not found: {}", id)),
+        _ => AppError::Internal(e.to_string()),
+    })?;
+
+    Ok(Json(()))
+}
+
+/// Create a session from a template.
+///
+/// The template's backend, model, preset, environment and working directory
+/// become the session's config overrides, and its prompt (after the preset's
+/// instructions) is recorded as the first message.
+async fn create_session_from_template(
+    State(state): State<AppState>,
+    AxumPath(template_id): AxumPath<Uuid>,
+    Json(req): Json<CreateFromTemplateRequest>,
+) -> AppResult<Json<SessionDetails>> {
+    let template = state.db.get_session_template(template_id).map_err(|e| match e {
+        crate::db::DbError::NotFound => {
+            AppError::NotFound(format!("Template not found: {}", template_id))
+        }
+        _ => AppError::Internal(e.to_string()),
+    })?;
+
+    let repo = state.db.get_repo(req.repo_id).map_err(|e| match e {
+        crate::db::DbError::NotFound => {
+            AppError::BadRequest(format!("Repository not found: {}", req.repo_id))
+        }
+        _ => AppError::Internal(e.to_string()),
+    })?;
+
+    let fields = &template.fields;
+    let prompt = fields.prompt.as_deref().filter(|p| !p.trim().is_empty());
+    let preset = fields.preset.as_deref().filter(|p| !p.trim().is_empty());
+    let first_message = match (prompt, preset) {
+        (None, None) => None,
+        _ => Some(auto_start_prompt(&state, prompt, preset)?),
+    };
+
+    let session = create_session_record(&state, &repo, req.name, fields.orchestrator)?;
+    apply_template_config(&state, session.id, fields)?;
+
+    events::session_created(&state.db, &state.connections, &session);
+
+    let mut messages = Vec::new();
+    if let Some(content) = first_message {
+        let message = state
+            .db
+            .insert_message(session.id, MessageRole::User, &content)
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+        messages.push(message);
+    }
+
+    Ok(Json(SessionDetails { session, messages }))
+}
+
+/// Create the session templates router
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/session-templates", get(list_templates).post(create_template))
+        .route(
+            "/session-templates/{id}",
+            get(get_template).put(update_template).delete(delete_template),
+        )
+        .route(
+            "/sessions/from-template/{template_id}",
+            post(create_session_from_template),
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::models::{Orchestrator, Repo};
+    use crate::db::Database;
+    use axum_test::TestServer;
+    use std::collections::HashMap;
+
+    fn create_test_state() -> AppState {
+        let db = Database::in_memory().expect("Failed to create test database");
+        AppState::new(db)
+    }
+
+    fn create_test_server(state: AppState) -> TestServer {
+        let app = Router::new().merge(router()).with_state(state);
+        TestServer::new(app).expect("Failed to create test server")
+    }
+
+    fn template_fields(name: &str) -> SessionTemplateFields {
+        SessionTemplateFields {
+            name: name.to_string(),
+            orchestrator: Orchestrator::Ralph,
+            backend: Some("claude".to_string()),
+            model: None,
+            preset: Some("tdd-red-green".to_string()),
+            env: HashMap::new(),
+            workdir: None,
+            prompt: Some("Add tests for the parser".to_string()),
+        }
+    }
+
+    fn insert_repo(state: &AppState) -> Repo {
+        state
+            .db
+            .insert_repo("/path/to/repo", "repo")
+            .expect("Failed to insert repo")
+    }
+
+    #[tokio::test]
+    async fn test_template_crud() {
+        let state = create_test_state();
+        let server = create_test_server(state);
+
+        let response = server
+            .post("/session-templates")
+            .json(&template_fields("TDD"))
+            .await;
+        response.assert_status_ok();
+        let template: SessionTemplate = response.json();
+        assert_eq!(template.fields.preset.as_deref(), Some("tdd-red-green"));
+
+        // Duplicate names conflict
+        let response = server
+            .post("/session-templates")
+            .json(&template_fields("TDD"))
+            .await;
+        response.assert_status(axum::http::StatusCode::CONFLICT);
+
+        let mut fields = template_fields("TDD");
+        fields.workdir = Some("../elsewhere".to_string());
+        let response = server
+            .put(&format!("/session-templates/{}", template.id))
+            .json(&fields)
+            .await;
+        response.assert_status_bad_request();
+
+        fields.workdir = Some("backend".to_string());
+        let updated: SessionTemplate = server
+            .put(&format!("/session-templates/{}", template.id))
+            .json(&fields)
+            .await
+            .json();
+        assert_eq!(updated.fields.workdir.as_deref(), Some("backend"));
+
+        let templates: Vec<SessionTemplate> = server.get("/session-templates").await.json();
+        assert_eq!(templates.len(), 1);
+
+        server
+            .delete(&format!("/session-templates/{}", template.id))
+            .await
+            .assert_status_ok();
+        server
+            .get(&format!("/session-templates/{}", template.id))
+            .await
+            .assert_status_not_found();
+    }
+
+    #[tokio::test]
+    async fn test_create_session_from_template() {
+        let state = create_test_state();
+        let server = create_test_server(state.clone());
+        let repo = insert_repo(&state);
+
+        let template: SessionTemplate = server
+            .post("/session-templates")
+            .json(&template_fields("Tests"))
+            .await
+            .json();
+
+        let response = server
+            .post(&format!("/sessions/from-template/{}", template.id))
+            .json(&CreateFromTemplateRequest {
+                repo_id: repo.id,
+                name: Some("From template".to_string()),
+            })
+            .await;
+        response.assert_status_ok();
+
+        let details: SessionDetails = response.json();
+        assert_eq!(details.session.repo_id, repo.id);
+        assert_eq!(details.session.name.as_deref(), Some("From template"));
+        assert_eq!(details.messages.len(), 1);
+        assert_eq!(details.messages[0].role, MessageRole::User);
+        assert!(details.messages[0].content.ends_with("\n\nAdd tests for the parser"));
+
+        // Run settings become the session's config overrides
+        let overrides: HashMap<String, String> = state
+            .db
+            .list_session_config(details.session.id)
+            .unwrap()
+            .into_iter()
+            .collect();
+        assert_eq!(overrides.get(BACKEND_KEY).map(String::as_str), Some("claude"));
+        assert_eq!(overrides.get(PRESET_KEY).map(String::as_str), Some("tdd-red-green"));
+        assert!(!overrides.contains_key(MODEL_KEY));
+
+        let response = server
+            .post(&format!("/sessions/from-template/{}", Uuid::new_v4()))
+            .json(&CreateFromTemplateRequest {
+                repo_id: repo.id,
+                name: None,
+            })
+            .await;
+        response.assert_status_not_found();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_template_settings_reach_the_agent() {
+        let mut state = create_test_state();
+        state.ralph_manager = crate::ralph::RalphManager::with_program("sh");
+        let server = create_test_server(state.clone());
+
+        // The agent script only exists in the template's workdir and records
+        // its arguments and environment
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        git2::Repository::init(temp_dir.path()).unwrap();
+        std::fs::create_dir(temp_dir.path().join("app")).unwrap();
+        std::fs::write(
+            temp_dir.path().join("app/run"),
+            "printf '%s\\n' \"$*\" \"$GREETING\" > seen\n",
+        )
+        .unwrap();
+        let repo_path = temp_dir.path().to_string_lossy().to_string();
+        let repo = state.db.insert_repo(&repo_path, "repo").unwrap();
+
+        let mut fields = template_fields("Agent settings");
+        fields.backend = Some("bedrock".to_string());
+        fields.model = Some("opus".to_string());
+        fields.env = HashMap::from([("GREETING".to_string(), "hello".to_string())]);
+        fields.workdir = Some("app".to_string());
+        let template: SessionTemplate = server
+            .post("/session-templates")
+            .json(&fields)
+            .await
+            .json();
+        let details: SessionDetails = server
+            .post(&format!("/sessions/from-template/{}", template.id))
+            .json(&CreateFromTemplateRequest {
+                repo_id: repo.id,
+                name: None,
+            })
+            .await
+            .json();
+
+        state
+            .ralph_manager
+            .run(
+                details.session.id,
+                repo.id,
+                &repo_path,
+                "go",
+                state.db.clone(),
+                state.connections.clone(),
+            )
+            .await
+            .unwrap();
+
+        let seen = temp_dir.path().join("app/seen");
+        let mut output = String::new();
+        for _ in 0..100 {
+            output = std::fs::read_to_string(&seen).unwrap_or_default();
+            if output.lines().count() == 2 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+        assert!(output.contains("--backend bedrock --model opus"), "{}", output);
+        assert!(output.ends_with("\nhello\n"), "{}", output);
+    }
+}
@@ -1,17 +1,70 @@
+use std::path::PathBuf;
+
 use axum::{
     body::Body,
     http::{header, Request, Response, StatusCode},
     response::IntoResponse,
+    Router,
 };
 use rust_embed::Embed;
+use tower_http::services::{ServeDir, ServeFile};
+
+use crate::error::AppError;
 
 #[derive(Embed)]
 #[folder = "../frontend/dist"]
 struct FrontendAssets;
 
+/// Environment variable pointing at a built frontend to serve from disk
+/// instead of the copy embedded at build time
+pub const STATIC_DIR_ENV: &str = "RALPHTOWN_STATIC_DIR";
+
+/// Directory named by `RALPHTOWN_STATIC_DIR`, if set
+pub fn static_dir_from_env() -> Option<PathBuf> {
+    std::env::var_os(STATIC_DIR_ENV)
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+}
+
+/// Serve the frontend for routes the API doesn't handle: from `static_dir` if
+/// given, otherwise the embedded build. Unknown `/api` routes stay 404s.
+pub fn with_frontend(router: Router, static_dir: Option<PathBuf>) -> Router {
+    match static_dir {
+        Some(dir) => {
+            tracing::info!("Serving frontend from {}", dir.display());
+            let serve_dir = ServeDir::new(&dir).fallback(ServeFile::new(dir.join("index.html")));
+            router.fallback(move |req: Request<Body>| serve_from_dir(serve_dir.clone(), req))
+        }
+        None => router.fallback(serve_frontend),
+    }
+}
+
+/// 404 for paths under `/api` that no route matched, instead of the SPA page
+fn api_not_found(path: &str) -> Option<Response<Body>> {
+    (path == "/api" || path.starts_with("/api/"))
+        .then(|| AppError::NotFound(format!("No API route for {}", path)).into_response())
+}
+
+/// Serve a file from a frontend build on disk, with index.html as the SPA fallback
+async fn serve_from_dir(mut serve_dir: ServeDir<ServeFile>, req: Request<Body>) -> Response<Body> {
+    if let Some(response) = api_not_found(req.uri().path()) {
+        return response;
+    }
+
+    match serve_dir.try_call(req).await {
+        Ok(response) => response.map(Body::new),
+        Err(e) => {
+            AppError::Internal(format!("Failed to serve frontend file: {}", e)).into_response()
+        }
+    }
+}
+
 /// Serve embedded frontend assets or fall back to index.html for SPA routing
 pub async fn serve_frontend(req: Request<Body>) -> impl IntoResponse {
     let path = req.uri().path();
+    if let Some(response) = api_not_found(path) {
+        return response;
+    }
 
     // Remove leading slash
     let path = path.trim_start_matches('/');
@@ -51,3 +104,40 @@ pub async fn serve_frontend(req: Request<Body>) -> impl IntoResponse {
         .body(Body::from("Frontend not found"))
         .unwrap()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum_test::TestServer;
+
+    #[tokio::test]
+    async fn test_serves_static_dir_with_spa_fallback() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("index.html"), "<html>app</html>").unwrap();
+        std::fs::create_dir(dir.path().join("assets")).unwrap();
+        std::fs::write(dir.path().join("assets").join("app.js"), "console.log(1)").unwrap();
+
+        let app = with_frontend(Router::new(), Some(dir.path().to_path_buf()));
+        let server = TestServer::new(app).unwrap();
+
+        let response = server.get("/assets/app.js").await;
+        response.assert_status_ok();
+        assert_eq!(response.text(), "console.log(1)");
+
+        let response = server.get("/sessions/123").await;
+        response.assert_status_ok();
+        assert_eq!(response.text(), "<html>app</html>");
+
+        server.get("/api/nope").await.assert_status_not_found();
+    }
+
+    #[tokio::test]
+    async fn test_unknown_api_routes_are_not_spa_routes() {
+        let server = TestServer::new(with_frontend(Router::new(), None)).unwrap();
+
+        let response = server.get("/api/nope").await;
+        response.assert_status_not_found();
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["error"]["code"], "NOT_FOUND");
+    }
+}
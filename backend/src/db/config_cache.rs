@@ -0,0 +1,158 @@
+//! In-memory cache of the global config map
+//!
+//! Handlers resolve defaults from config on nearly every request, so the map
+//! is kept in memory and only reloaded when `Database::config_generation`
+//! shows it was changed by a writer that bypassed the cache.
+
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+
+use super::{parse_config_value, secrets, Database, DbResult};
+
+/// Config map snapshot tagged with the generation it was loaded at
+struct Snapshot {
+    generation: u64,
+    entries: HashMap<String, String>,
+}
+
+/// Read-through cache of the `config` table, shared across handlers
+#[derive(Clone)]
+pub struct ConfigCache {
+    db: Arc<Database>,
+    snapshot: Arc<RwLock<Option<Snapshot>>>,
+}
+
+impl ConfigCache {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self {
+            db,
+            snapshot: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    /// Get a config value, decrypting it if it is a secret
+    pub fn get(&self, key: &str) -> DbResult<Option<String>> {
+        self.read(|entries| entries.get(key).cloned())?
+            .map(|stored| self.db.reveal_config(key, stored))
+            .transpose()
+    }
+
+    /// Get a config value parsed as `T`
+    pub fn get_as<T>(&self, key: &str) -> DbResult<Option<T>>
+    where
+        T: FromStr,
+        T::Err: std::fmt::Display,
+    {
+        self.get(key)?
+            .map(|value| parse_config_value(key, value))
+            .transpose()
+    }
+
+    /// Get a copy of the whole config map with secret and protected values redacted
+    pub fn all(&self) -> DbResult<HashMap<String, String>> {
+        self.read(|entries| {
+            entries
+                .iter()
+                .map(|(key, value)| {
+                    let value = if secrets::is_redacted_key(key) {
+                        secrets::REDACTED.to_string()
+                    } else {
+                        value.clone()
+                    };
+                    (key.clone(), value)
+                })
+                .collect()
+        })
+    }
+
+    /// Set a config value in the database and the cache
+    pub fn set(&self, key: &str, value: &str) -> DbResult<()> {
+        let mut snapshot = self.snapshot.write().unwrap();
+        self.db.set_config(key, value)?;
+        *snapshot = Some(self.load()?);
+        Ok(())
+    }
+
+    /// Delete a config value from the database and the cache
+    pub fn delete(&self, key: &str) -> DbResult<()> {
+        let mut snapshot = self.snapshot.write().unwrap();
+        self.db.delete_config(key)?;
+        *snapshot = Some(self.load()?);
+        Ok(())
+    }
+
+    fn read<T>(&self, f: impl Fn(&HashMap<String, String>) -> T) -> DbResult<T> {
+        let generation = self.db.config_generation();
+        {
+            let snapshot = self.snapshot.read().unwrap();
+            if let Some(snapshot) = snapshot.as_ref()
+                && snapshot.generation == generation
+            {
+                return Ok(f(&snapshot.entries));
+            }
+        }
+
+        let mut snapshot = self.snapshot.write().unwrap();
+        let fresh = self.load()?;
+        let value = f(&fresh.entries);
+        *snapshot = Some(fresh);
+        Ok(value)
+    }
+
+    fn load(&self) -> DbResult<Snapshot> {
+        // Read the generation first: a write racing with the load leaves the
+        // snapshot looking stale, which only costs an extra reload.
+        let generation = self.db.config_generation();
+        let entries = self.db.list_config()?.into_iter().collect();
+        Ok(Snapshot {
+            generation,
+            entries,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_writes_are_reflected_in_cached_reads() {
+        let db = Arc::new(Database::in_memory().unwrap());
+        let cache = ConfigCache::new(db.clone());
+
+        assert_eq!(cache.get("backend").unwrap(), None);
+
+        cache.set("backend", "claude").unwrap();
+        assert_eq!(cache.get("backend").unwrap().as_deref(), Some("claude"));
+        assert_eq!(db.get_config("backend").unwrap().as_deref(), Some("claude"));
+
+        // Writes that bypass the cache are picked up on the next read
+        db.set_config("backend", "bedrock").unwrap();
+        assert_eq!(cache.get("backend").unwrap().as_deref(), Some("bedrock"));
+
+        cache.delete("backend").unwrap();
+        assert!(cache.all().unwrap().is_empty());
+        assert_eq!(db.get_config("backend").unwrap(), None);
+    }
+
+    #[test]
+    fn test_secret_values_are_decrypted_on_get_and_redacted_in_all() {
+        let db = Arc::new(Database::in_memory().unwrap().with_secret("passphrase"));
+        let cache = ConfigCache::new(db.clone());
+
+        cache.set("secret/anthropic_api_key", "sk-ant-123").unwrap();
+        assert_eq!(
+            cache.get("secret/anthropic_api_key").unwrap().as_deref(),
+            Some("sk-ant-123")
+        );
+        assert_eq!(
+            cache.all().unwrap().get("secret/anthropic_api_key").map(String::as_str),
+            Some(secrets::REDACTED)
+        );
+
+        // Only ciphertext reaches the table
+        let (_, stored) = db.list_config().unwrap().pop().unwrap();
+        assert!(!stored.contains("sk-ant-123"));
+    }
+}
@@ -0,0 +1,364 @@
+//! Versioned schema migrations
+//!
+//! A fresh database is created directly at `SCHEMA_VERSION` from
+//! `CREATE_TABLES`. An existing database first gets any tables it is missing
+//! from `CREATE_TABLES`, then every migration newer than its recorded version
+//! is applied in order, each in its own transaction together with the row
+//! recording it. Indexes on columns added by a migration must be created by
+//! that migration, since `CREATE_TABLES` runs before it.
+
+use std::fmt;
+
+use rusqlite::{params, Connection, OptionalExtension};
+
+use super::schema::{
+    CREATE_TABLES, GET_SCHEMA_VERSION, MIGRATE_V1_TO_V2, MIGRATE_V2_TO_V3, MIGRATE_V3_TO_V4,
+    MIGRATE_V4_TO_V5, MIGRATE_V5_TO_V6, MIGRATE_V6_TO_V7, MIGRATE_V7_TO_V8, MIGRATE_V8_TO_V9,
+    MIGRATE_V9_TO_V10, MIGRATE_V10_TO_V11, MIGRATE_V11_TO_V12, MIGRATE_V12_TO_V13,
+    MIGRATE_V13_TO_V14, MIGRATE_V14_TO_V15, RECORD_SCHEMA_VERSION, SCHEMA_VERSION,
+};
+use super::DbResult;
+
+/// One step in the schema history
+#[derive(Debug, Clone, Copy)]
+pub struct Migration {
+    /// Schema version the database is at once this step is applied
+    pub version: i32,
+    pub description: &'static str,
+    pub sql: &'static str,
+}
+
+/// Every migration, in the order they are applied
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 2,
+        description: "Add orchestrator column to sessions",
+        sql: MIGRATE_V1_TO_V2,
+    },
+    Migration {
+        version: 3,
+        description: "Record the resolved agent command line per session",
+        sql: MIGRATE_V2_TO_V3,
+    },
+    Migration {
+        version: 4,
+        description: "Flag gzip-compressed output log content",
+        sql: MIGRATE_V3_TO_V4,
+    },
+    Migration {
+        version: 5,
+        description: "Add run checkpoint sha to sessions",
+        sql: MIGRATE_V4_TO_V5,
+    },
+    Migration {
+        version: 6,
+        description: "Add per-repo session sequence counter",
+        sql: MIGRATE_V5_TO_V6,
+    },
+    Migration {
+        version: 7,
+        description: "Record the AI backend of each session's last run",
+        sql: MIGRATE_V6_TO_V7,
+    },
+    Migration {
+        version: 8,
+        description: "Flag assistant messages still being generated",
+        sql: MIGRATE_V7_TO_V8,
+    },
+    Migration {
+        version: 9,
+        description: "Backfill the full-text index over message content",
+        sql: MIGRATE_V8_TO_V9,
+    },
+    Migration {
+        version: 10,
+        description: "Add archived flag to sessions",
+        sql: MIGRATE_V9_TO_V10,
+    },
+    Migration {
+        version: 11,
+        description: "Record the branch each session works on",
+        sql: MIGRATE_V10_TO_V11,
+    },
+    Migration {
+        version: 12,
+        description: "Flag truncated output lines and keep their full content",
+        sql: MIGRATE_V11_TO_V12,
+    },
+    Migration {
+        version: 13,
+        description: "Classify how each session's last run ended",
+        sql: MIGRATE_V12_TO_V13,
+    },
+    Migration {
+        version: 14,
+        description: "Leave incomplete messages out of the full-text index",
+        sql: MIGRATE_V13_TO_V14,
+    },
+    Migration {
+        version: 15,
+        description: "Record the sha each session's first run started from",
+        sql: MIGRATE_V14_TO_V15,
+    },
+];
+
+/// Version recorded in the database, or `None` if it has never been initialized
+///
+/// A database with tables but no recorded version predates version tracking
+/// and is treated as version 1.
+pub fn recorded_version(conn: &Connection) -> DbResult<Option<i32>> {
+    if !table_exists(conn, "schema_version")? {
+        return Ok(table_exists(conn, "repos")?.then_some(1));
+    }
+
+    let version: Option<i32> = conn.query_row(GET_SCHEMA_VERSION, [], |row| row.get(0))?;
+    match version {
+        Some(version) => Ok(Some(version)),
+        None => Ok(table_exists(conn, "repos")?.then_some(1)),
+    }
+}
+
+/// Migrations that would be applied to a database at `version`
+pub fn pending(version: i32) -> impl Iterator<Item = &'static Migration> {
+    MIGRATIONS.iter().filter(move |m| m.version > version)
+}
+
+/// What `migrate` would do to a database, without doing it
+#[derive(Debug, Clone)]
+pub struct MigrationPlan {
+    /// Current version, `None` for a database that does not exist yet
+    pub from: Option<i32>,
+    pub to: i32,
+    /// Migrations that would run, in order (empty for a new database)
+    pub steps: Vec<&'static Migration>,
+}
+
+impl MigrationPlan {
+    /// Plan for a database that has not been created yet
+    pub fn fresh() -> Self {
+        Self {
+            from: None,
+            to: SCHEMA_VERSION,
+            steps: Vec::new(),
+        }
+    }
+
+    /// Plan for an existing database
+    pub fn for_connection(conn: &Connection) -> DbResult<Self> {
+        let Some(from) = recorded_version(conn)? else {
+            return Ok(Self::fresh());
+        };
+
+        Ok(Self {
+            from: Some(from),
+            to: SCHEMA_VERSION,
+            steps: pending(from).collect(),
+        })
+    }
+}
+
+impl fmt::Display for MigrationPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.from {
+            None => write!(f, "New database would be created at schema v{}", self.to),
+            Some(from) if self.steps.is_empty() => {
+                write!(f, "Schema is at v{}; no migrations would run", from)
+            }
+            Some(from) => {
+                write!(f, "Schema would migrate from v{} to v{}:", from, self.to)?;
+                for step in &self.steps {
+                    write!(f, "\n  v{}: {}", step.version, step.description)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Create or upgrade the schema to `SCHEMA_VERSION`
+pub fn migrate(conn: &mut Connection) -> DbResult<()> {
+    let Some(version) = recorded_version(conn)? else {
+        let tx = conn.transaction()?;
+        tx.execute_batch(CREATE_TABLES)?;
+        tx.execute(RECORD_SCHEMA_VERSION, params![SCHEMA_VERSION])?;
+        tx.commit()?;
+        return Ok(());
+    };
+
+    conn.execute_batch(CREATE_TABLES)?;
+
+    for migration in pending(version) {
+        tracing::info!(
+            "Migrating database to v{}: {}",
+            migration.version,
+            migration.description
+        );
+        let tx = conn.transaction()?;
+        tx.execute_batch(migration.sql)?;
+        tx.execute(RECORD_SCHEMA_VERSION, params![migration.version])?;
+        tx.commit()?;
+    }
+
+    Ok(())
+}
+
+fn table_exists(conn: &Connection, name: &str) -> DbResult<bool> {
+    let found = conn
+        .query_row(
+            "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?1",
+            params![name],
+            |_| Ok(()),
+        )
+        .optional()?;
+    Ok(found.is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Database;
+
+    /// Schema as shipped at version 1
+    const V1_SCHEMA: &str = r#"
+        CREATE TABLE repos (
+            id TEXT PRIMARY KEY,
+            path TEXT NOT NULL UNIQUE,
+            name TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL
+        );
+        CREATE TABLE sessions (
+            id TEXT PRIMARY KEY,
+            repo_id TEXT NOT NULL,
+            name TEXT,
+            status TEXT NOT NULL DEFAULT 'idle',
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL,
+            FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE
+        );
+        CREATE TABLE messages (
+            id TEXT PRIMARY KEY,
+            session_id TEXT NOT NULL,
+            role TEXT NOT NULL,
+            content TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE
+        );
+        CREATE TABLE output_logs (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            session_id TEXT NOT NULL,
+            stream TEXT NOT NULL,
+            content TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE
+        );
+        CREATE TABLE config (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL,
+            updated_at TEXT NOT NULL
+        );
+        CREATE TABLE schema_version (
+            version INTEGER PRIMARY KEY
+        );
+        INSERT INTO schema_version (version) VALUES (1);
+    "#;
+
+    #[test]
+    fn test_migrations_are_ordered_up_to_schema_version() {
+        let versions: Vec<i32> = MIGRATIONS.iter().map(|m| m.version).collect();
+        assert!(versions.windows(2).all(|w| w[1] == w[0] + 1));
+        assert_eq!(versions.last(), Some(&SCHEMA_VERSION));
+    }
+
+    #[test]
+    fn test_migrates_v1_database_forward() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("v1.db");
+        {
+            let conn = Connection::open(&path).unwrap();
+            conn.execute_batch(V1_SCHEMA).unwrap();
+            conn.execute_batch(
+                "INSERT INTO repos VALUES ('00000000-0000-0000-0000-00000000000a', '/path/to/repo', 'repo', '2024-01-01T00:00:00+00:00', '2024-01-01T00:00:00+00:00');
+                 INSERT INTO sessions VALUES ('00000000-0000-0000-0000-000000000001', '00000000-0000-0000-0000-00000000000a', 'old', 'completed', '2024-01-01T00:00:00+00:00', '2024-01-01T00:00:00+00:00');
+                 INSERT INTO messages VALUES ('00000000-0000-0000-0000-000000000002', '00000000-0000-0000-0000-000000000001', 'user', 'legacy prompt', '2024-01-01T00:00:00+00:00');",
+            )
+            .unwrap();
+        }
+
+        let db = Database::new(path.clone()).unwrap();
+
+        let conn = db.conn().unwrap();
+        assert_eq!(recorded_version(&conn).unwrap(), Some(SCHEMA_VERSION));
+        let applied: Vec<i32> = conn
+            .prepare("SELECT version FROM schema_version ORDER BY version")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(applied, (1..=SCHEMA_VERSION).collect::<Vec<_>>());
+        // The session sequence continues after the repo's existing sessions
+        let session_seq: i64 = conn
+            .query_row("SELECT session_seq FROM repos", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(session_seq, 1);
+        drop(conn);
+
+        // Existing rows survive with the new columns' defaults
+        let session = db
+            .get_session("00000000-0000-0000-0000-000000000001".parse().unwrap())
+            .unwrap();
+        assert_eq!(session.name.as_deref(), Some("old"));
+        assert!(!session.archived);
+        let matches = db.search_messages("legacy", 10).unwrap();
+        assert_eq!(matches.len(), 1);
+        assert!(!matches[0].incomplete);
+
+        // Reopening an up-to-date database applies nothing
+        drop(db);
+        let db = Database::new(path).unwrap();
+        assert_eq!(db.list_sessions(true).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_plan_lists_pending_migrations() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(V1_SCHEMA).unwrap();
+
+        let plan = MigrationPlan::for_connection(&conn).unwrap();
+        assert_eq!(plan.from, Some(1));
+        assert_eq!(plan.steps.len(), MIGRATIONS.len());
+        let text = plan.to_string();
+        assert!(text.starts_with(&format!("Schema would migrate from v1 to v{}:", SCHEMA_VERSION)));
+        assert!(text.contains("\n  v2: Add orchestrator column to sessions"));
+
+        // Planning leaves the database untouched
+        assert_eq!(recorded_version(&conn).unwrap(), Some(1));
+
+        let mut conn = conn;
+        migrate(&mut conn).unwrap();
+        let plan = MigrationPlan::for_connection(&conn).unwrap();
+        assert!(plan.steps.is_empty());
+        assert_eq!(
+            plan.to_string(),
+            format!("Schema is at v{}; no migrations would run", SCHEMA_VERSION)
+        );
+    }
+
+    #[test]
+    fn test_fresh_database_starts_at_schema_version() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        assert_eq!(recorded_version(&conn).unwrap(), None);
+
+        migrate(&mut conn).unwrap();
+        assert_eq!(recorded_version(&conn).unwrap(), Some(SCHEMA_VERSION));
+        assert_eq!(pending(SCHEMA_VERSION).count(), 0);
+
+        // Planning for a missing file does not create it
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("missing.db");
+        let plan = Database::plan_migrations(&path).unwrap();
+        assert_eq!(plan.from, None);
+        assert!(!path.exists());
+    }
+}
@@ -1,16 +1,61 @@
+mod config_cache;
+pub mod migrations;
 pub mod models;
 pub mod schema;
+pub mod secrets;
 
+pub use config_cache::ConfigCache;
+
+use std::collections::HashSet;
+use std::io::{Read, Write};
+use std::str::FromStr;
 use std::path::PathBuf;
-use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
 use chrono::{DateTime, Utc};
-use rusqlite::{params, Connection};
+use r2d2::{Pool, PooledConnection};
+use r2d2_sqlite::SqliteConnectionManager;
+use rusqlite::{params, Connection, TransactionBehavior};
 use thiserror::Error;
 use uuid::Uuid;
 
-use models::{Message, MessageRole, Orchestrator, OutputStream, OutputLog, Repo, Session, SessionStatus};
-use schema::{CREATE_TABLES, GET_SCHEMA_VERSION, MIGRATE_V1_TO_V2, SCHEMA_VERSION, UPSERT_SCHEMA_VERSION};
+use models::{
+    CustomPreset, DbStats, ExitReason, Event, EventFilter, EventKind, Message, MessageRole, Orchestrator, OutputStream, OutputLog, Repo, Session, SessionError,
+    SessionStatus, SessionSummary, SessionTemplate, SessionTemplateFields,
+};
+use secrets::SecretBox;
+
+/// Config key: output log lines larger than this many bytes are stored
+/// gzip-compressed. Compression is disabled when unset.
+pub const OUTPUT_COMPRESSION_THRESHOLD_KEY: &str = "output_compression_threshold";
+
+/// Config key: output lines longer than this many bytes are truncated before
+/// they are stored or broadcast. Lines are kept whole when unset.
+pub const OUTPUT_MAX_LINE_LENGTH_KEY: &str = "output_max_line_length";
+
+/// Config key: when "true", the full text of truncated lines is kept
+/// gzip-compressed alongside the truncated line
+pub const OUTPUT_KEEP_FULL_LINES_KEY: &str = "output_keep_full_lines";
+
+/// Appended to output lines cut by `OUTPUT_MAX_LINE_LENGTH_KEY`
+pub const TRUNCATED_MARKER: &str = "…[truncated]";
+
+/// Maximum number of pooled connections to a database file
+const POOL_SIZE: u32 = 4;
+
+/// Per-connection settings, applied to every connection the pool opens
+///
+/// The busy timeout lets a writer wait for another connection's write to
+/// finish instead of failing immediately with `SQLITE_BUSY`. `synchronous =
+/// NORMAL` is safe in WAL mode and avoids an fsync on every commit.
+const CONNECTION_PRAGMAS: &str =
+    "PRAGMA foreign_keys = ON; PRAGMA busy_timeout = 5000; PRAGMA synchronous = NORMAL;";
+
+/// Tables whose row counts are reported by `Database::stats`
+const STATS_TABLES: &[&str] = &["repos", "sessions", "messages", "output_logs", "config"];
 
 /// Database error types
 #[derive(Debug, Error)]
@@ -24,6 +69,9 @@ pub enum DbError {
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 
+    #[error("Connection pool error: {0}")]
+    Pool(#[from] r2d2::Error),
+
     #[error("Record not found")]
     NotFound,
 
@@ -42,10 +90,29 @@ pub enum DbError {
 
     #[error("Connection error: {0}")]
     ConnectionError(String),
+
+    #[error("Secret error: {0}")]
+    Secret(String),
 }
 
 pub type DbResult<T> = Result<T, DbError>;
 
+/// Parse a config value, reporting failures as `DbError::ParseError` on the key
+pub(crate) fn parse_config_value<T>(key: &str, value: String) -> DbResult<T>
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    match value.trim().parse() {
+        Ok(parsed) => Ok(parsed),
+        Err(e) => Err(DbError::ParseError {
+            message: format!("Invalid value for config '{}': {}", key, e),
+            value,
+            field: key.to_string(),
+        }),
+    }
+}
+
 /// Parse a UUID from a database row with descriptive error
 fn parse_uuid(row: &rusqlite::Row, idx: usize, field: &str) -> rusqlite::Result<Uuid> {
     let value: String = row.get(idx)?;
@@ -80,6 +147,18 @@ fn parse_datetime(row: &rusqlite::Row, idx: usize, field: &str) -> rusqlite::Res
         })
 }
 
+/// Parse a nullable DateTime column, leaving NULL as `None`
+fn parse_optional_datetime(
+    row: &rusqlite::Row,
+    idx: usize,
+    field: &str,
+) -> rusqlite::Result<Option<DateTime<Utc>>> {
+    match row.get::<_, Option<String>>(idx)? {
+        Some(_) => parse_datetime(row, idx, field).map(Some),
+        None => Ok(None),
+    }
+}
+
 /// Parse an enum from a database row with descriptive error
 fn parse_enum<T, F>(row: &rusqlite::Row, idx: usize, field: &str, parser: F) -> rusqlite::Result<T>
 where
@@ -99,10 +178,184 @@ where
     })
 }
 
+/// Parse a nullable enum column, leaving NULL as `None`
+fn parse_optional_enum<T, F>(
+    row: &rusqlite::Row,
+    idx: usize,
+    field: &str,
+    parser: F,
+) -> rusqlite::Result<Option<T>>
+where
+    F: FnOnce(&str) -> Result<T, String>,
+{
+    match row.get::<_, Option<String>>(idx)? {
+        Some(_) => parse_enum(row, idx, field, parser).map(Some),
+        None => Ok(None),
+    }
+}
+
+/// Map a `sessions` row (id, repo_id, name, orchestrator, status, archived,
+/// branch, exit_reason, created_at, updated_at)
+fn session_from_row(row: &rusqlite::Row) -> rusqlite::Result<Session> {
+    Ok(Session {
+        id: parse_uuid(row, 0, "id")?,
+        repo_id: parse_uuid(row, 1, "repo_id")?,
+        name: row.get(2)?,
+        orchestrator: parse_enum(row, 3, "orchestrator", Orchestrator::from_str)?,
+        status: parse_enum(row, 4, "status", SessionStatus::from_str)?,
+        archived: row.get(5)?,
+        branch: row.get(6)?,
+        exit_reason: parse_optional_enum(row, 7, "exit_reason", ExitReason::from_str)?,
+        created_at: parse_datetime(row, 8, "created_at")?,
+        updated_at: parse_datetime(row, 9, "updated_at")?,
+    })
+}
+
+/// Map a `messages` row (id, session_id, role, content, incomplete, created_at)
+fn message_from_row(row: &rusqlite::Row) -> rusqlite::Result<Message> {
+    Ok(Message {
+        id: parse_uuid(row, 0, "id")?,
+        session_id: parse_uuid(row, 1, "session_id")?,
+        role: parse_enum(row, 2, "role", MessageRole::from_str)?,
+        content: row.get(3)?,
+        incomplete: row.get(4)?,
+        created_at: parse_datetime(row, 5, "created_at")?,
+    })
+}
+
+/// Build an FTS5 query matching every term of `query` as a quoted string
+///
+/// Returns `None` when the query has no terms.
+fn fts_phrase_query(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+        .collect();
+
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
+/// Map an `events` row (id, event_type, session_id, repo_id, data, created_at)
+fn event_from_row(row: &rusqlite::Row) -> rusqlite::Result<Event> {
+    let session_id: Option<String> = row.get(2)?;
+    let repo_id: Option<String> = row.get(3)?;
+    Ok(Event {
+        id: row.get(0)?,
+        kind: parse_enum(row, 1, "event_type", EventKind::from_str)?,
+        session_id: match session_id {
+            Some(_) => Some(parse_uuid(row, 2, "session_id")?),
+            None => None,
+        },
+        repo_id: match repo_id {
+            Some(_) => Some(parse_uuid(row, 3, "repo_id")?),
+            None => None,
+        },
+        data: parse_json(row, 4, "data")?,
+        created_at: parse_datetime(row, 5, "created_at")?,
+    })
+}
+
+/// Parse a JSON column from a database row with descriptive error
+fn parse_json<T: serde::de::DeserializeOwned>(
+    row: &rusqlite::Row,
+    idx: usize,
+    field: &str,
+) -> rusqlite::Result<T> {
+    let value: String = row.get(idx)?;
+    serde_json::from_str(&value).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(
+            idx,
+            rusqlite::types::Type::Text,
+            Box::new(DbError::ParseError {
+                message: e.to_string(),
+                value,
+                field: field.to_string(),
+            }),
+        )
+    })
+}
+
+/// Gzip-compress output log content for storage
+fn compress_content(content: &str) -> DbResult<Vec<u8>> {
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(content.as_bytes())?;
+    Ok(encoder.finish()?)
+}
+
+/// Read output log content, decompressing it if the row is flagged as compressed
+fn parse_log_content(
+    row: &rusqlite::Row,
+    content_idx: usize,
+    compressed_idx: usize,
+) -> rusqlite::Result<String> {
+    let compressed: bool = row.get(compressed_idx)?;
+    if !compressed {
+        return row.get(content_idx);
+    }
+
+    let bytes: Vec<u8> = row.get(content_idx)?;
+    let mut content = String::new();
+    flate2::read::GzDecoder::new(bytes.as_slice())
+        .read_to_string(&mut content)
+        .map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(
+                content_idx,
+                rusqlite::types::Type::Blob,
+                Box::new(DbError::InvalidData(format!(
+                    "corrupt compressed output log: {}",
+                    e
+                ))),
+            )
+        })?;
+    Ok(content)
+}
+
+/// Map an `output_logs` row (id, session_id, stream, content, compressed,
+/// truncated, created_at)
+fn output_log_from_row(row: &rusqlite::Row) -> rusqlite::Result<OutputLog> {
+    Ok(OutputLog {
+        id: row.get(0)?,
+        session_id: parse_uuid(row, 1, "session_id")?,
+        stream: parse_enum(row, 2, "stream", OutputStream::from_str)?,
+        content: parse_log_content(row, 3, 4)?,
+        truncated: row.get(5)?,
+        created_at: parse_datetime(row, 6, "created_at")?,
+    })
+}
+
+/// Cut a line longer than `max_len` bytes (at a char boundary) and append
+/// `TRUNCATED_MARKER`; returns `None` if the line fits
+pub fn truncate_line(line: &str, max_len: usize) -> Option<String> {
+    if line.len() <= max_len {
+        return None;
+    }
+    let mut end = max_len;
+    while !line.is_char_boundary(end) {
+        end -= 1;
+    }
+    Some(format!("{}{}", &line[..end], TRUNCATED_MARKER))
+}
+
+/// Map a UNIQUE/CHECK constraint failure to `DbError::ConstraintViolation`
+fn map_constraint_error(e: rusqlite::Error, message: &str) -> DbError {
+    match e {
+        rusqlite::Error::SqliteFailure(err, _)
+            if err.code == rusqlite::ErrorCode::ConstraintViolation =>
+        {
+            DbError::ConstraintViolation(message.to_string())
+        }
+        _ => DbError::Sqlite(e),
+    }
+}
+
 /// Database wrapper with connection management
 #[derive(Clone)]
 pub struct Database {
-    conn: Arc<Mutex<Connection>>,
+    pool: Pool<SqliteConnectionManager>,
+    /// Bumped on every config write so caches can detect stale copies
+    config_generation: Arc<AtomicU64>,
+    /// Cipher for `secret/` config values, if `RALPHTOWN_SECRET` is set
+    secrets: Option<SecretBox>,
 }
 
 impl Database {
@@ -113,79 +366,204 @@ impl Database {
             std::fs::create_dir_all(parent)?;
         }
 
-        let conn = Connection::open(&path)?;
-        conn.execute_batch("PRAGMA foreign_keys = ON;")?;
+        let manager = SqliteConnectionManager::file(&path)
+            .with_init(|conn| conn.execute_batch(CONNECTION_PRAGMAS));
+        let pool = Pool::builder().max_size(POOL_SIZE).build(manager)?;
 
-        let db = Self {
-            conn: Arc::new(Mutex::new(conn)),
-        };
+        // WAL lets readers proceed while a writer holds the lock. The mode is
+        // stored in the database file, so setting it once covers every connection.
+        pool.get()?
+            .query_row("PRAGMA journal_mode = WAL", [], |row| row.get::<_, String>(0))?;
 
-        db.init_schema()?;
+        let mut db = Self::with_pool(pool)?;
+        if let Some(passphrase) = secrets::passphrase_from_env() {
+            db.secrets = Some(SecretBox::new(&passphrase, &db.secret_salt()?)?);
+        }
         Ok(db)
     }
 
     /// Create an in-memory database (for testing)
+    ///
+    /// Uses a single connection: connections to a shared in-memory database
+    /// fail with `SQLITE_LOCKED` rather than waiting on each other.
     pub fn in_memory() -> DbResult<Self> {
-        let conn = Connection::open_in_memory()?;
-        conn.execute_batch("PRAGMA foreign_keys = ON;")?;
+        let manager =
+            SqliteConnectionManager::memory().with_init(|conn| conn.execute_batch(CONNECTION_PRAGMAS));
+        let pool = Pool::builder().max_size(1).build(manager)?;
+
+        Self::with_pool(pool)
+    }
 
+    fn with_pool(pool: Pool<SqliteConnectionManager>) -> DbResult<Self> {
         let db = Self {
-            conn: Arc::new(Mutex::new(conn)),
+            pool,
+            config_generation: Arc::new(AtomicU64::new(0)),
+            secrets: None,
         };
 
         db.init_schema()?;
         Ok(db)
     }
 
+    /// Use `passphrase` for secret config values instead of `RALPHTOWN_SECRET`
+    #[cfg(test)]
+    pub(crate) fn with_secret(mut self, passphrase: &str) -> Self {
+        let salt = self.secret_salt().unwrap();
+        self.secrets = Some(SecretBox::new(passphrase, &salt).unwrap());
+        self
+    }
+
+    /// Salt for the secret config key, generated and stored on first use
+    fn secret_salt(&self) -> DbResult<Vec<u8>> {
+        let conn = self.conn()?;
+        conn.execute(
+            "INSERT OR IGNORE INTO secret_salt (id, salt) VALUES (1, ?1)",
+            params![BASE64.encode(secrets::generate_salt())],
+        )?;
+        let salt: String =
+            conn.query_row("SELECT salt FROM secret_salt WHERE id = 1", [], |row| row.get(0))?;
+
+        BASE64
+            .decode(salt)
+            .map_err(|e| DbError::Secret(format!("Malformed secret salt: {}", e)))
+    }
+
+    /// Report the migrations opening the database at `path` would apply,
+    /// without creating or changing it
+    pub fn plan_migrations(path: &std::path::Path) -> DbResult<migrations::MigrationPlan> {
+        if !path.exists() {
+            return Ok(migrations::MigrationPlan::fresh());
+        }
+
+        let conn = Connection::open_with_flags(path, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+        migrations::MigrationPlan::for_connection(&conn)
+    }
+
+    /// Check out a connection from the pool
+    fn conn(&self) -> DbResult<PooledConnection<SqliteConnectionManager>> {
+        Ok(self.pool.get()?)
+    }
+
     /// Get the default database path based on platform
     pub fn default_path() -> DbResult<PathBuf> {
         let data_dir = dirs::data_dir().ok_or(DbError::NoDataDir)?;
         Ok(data_dir.join("ralphtown").join("ralphtown.db"))
     }
 
-    /// Initialize database schema
-    fn init_schema(&self) -> DbResult<()> {
-        let conn = self.conn.lock().unwrap();
-
-        // Create tables
-        conn.execute_batch(CREATE_TABLES)?;
-
-        // Check and update schema version
-        let current_version: Option<i32> = conn
-            .query_row(GET_SCHEMA_VERSION, [], |row| row.get(0))
-            .ok();
-
-        let version = current_version.unwrap_or(0);
-
-        // Run migrations
-        if version < 2 {
-            // V1 to V2: Add orchestrator column to sessions
-            // Only run if table exists and column doesn't exist
-            let has_orchestrator: bool = conn
-                .query_row(
-                    "SELECT COUNT(*) FROM pragma_table_info('sessions') WHERE name = 'orchestrator'",
-                    [],
-                    |row| row.get::<_, i32>(0).map(|c| c > 0),
-                )
-                .unwrap_or(false);
+    /// Verify the connection can execute a trivial query
+    pub fn ping(&self) -> DbResult<()> {
+        let conn = self.conn()?;
+        conn.query_row("SELECT 1", [], |row| row.get::<_, i64>(0))?;
+        Ok(())
+    }
 
-            if !has_orchestrator {
-                conn.execute_batch(MIGRATE_V1_TO_V2)?;
+    /// Report the database size and per-table row counts
+    pub fn stats(&self) -> DbResult<DbStats> {
+        let conn = self.conn()?;
+        let page_count = conn.query_row("PRAGMA page_count", [], |row| row.get(0))?;
+        let page_size = conn.query_row("PRAGMA page_size", [], |row| row.get(0))?;
+        let file_size = match conn.path().filter(|path| !path.is_empty()) {
+            Some(path) => {
+                // Pages committed since the last checkpoint live in the WAL file
+                let wal_size = std::fs::metadata(format!("{}-wal", path)).map_or(0, |m| m.len());
+                Some(std::fs::metadata(path)?.len() + wal_size)
             }
-        }
+            None => None,
+        };
 
-        if version < SCHEMA_VERSION {
-            conn.execute(UPSERT_SCHEMA_VERSION, params![SCHEMA_VERSION])?;
+        let mut tables = std::collections::BTreeMap::new();
+        for table in STATS_TABLES {
+            let count = conn.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| {
+                row.get(0)
+            })?;
+            tables.insert(table.to_string(), count);
         }
 
-        Ok(())
+        Ok(DbStats {
+            file_size,
+            page_count,
+            page_size,
+            tables,
+        })
+    }
+
+    /// Create the schema or migrate it to the current version
+    fn init_schema(&self) -> DbResult<()> {
+        let mut conn = self.conn()?;
+        migrations::migrate(&mut conn)
     }
 
     // ==================== Repo Operations ====================
 
     /// Insert a new repository
     pub fn insert_repo(&self, path: &str, name: &str) -> DbResult<Repo> {
-        let conn = self.conn.lock().unwrap();
+        self.insert_repo_limited(path, name, None)
+    }
+
+    /// Insert a new repository unless `max_repos` repositories already exist.
+    ///
+    /// The count and insert share a write transaction, so concurrent inserts
+    /// can't overshoot the limit; reaching it is a `ConstraintViolation`.
+    pub fn insert_repo_limited(
+        &self,
+        path: &str,
+        name: &str,
+        max_repos: Option<i64>,
+    ) -> DbResult<Repo> {
+        let mut conn = self.conn()?;
+        let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+
+        Self::check_repo_limit(&tx, max_repos)?;
+        let repo = Self::insert_repo_row(&tx, path, name)?;
+        tx.commit()?;
+
+        Ok(repo)
+    }
+
+    /// Insert a new repository and its first session in one transaction, so a
+    /// failure leaves neither. Enforces `max_repos` like `insert_repo_limited`.
+    pub fn insert_repo_with_session<F>(
+        &self,
+        path: &str,
+        name: &str,
+        max_repos: Option<i64>,
+        orchestrator: Orchestrator,
+        session_name: F,
+    ) -> DbResult<(Repo, Session)>
+    where
+        F: FnOnce(i64) -> Option<String>,
+    {
+        let mut conn = self.conn()?;
+        let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+
+        Self::check_repo_limit(&tx, max_repos)?;
+        let repo = Self::insert_repo_row(&tx, path, name)?;
+        let seq = Self::next_session_seq(&tx, repo.id)?;
+        let session_name = session_name(seq);
+        let session = Self::insert_session_row(&tx, repo.id, session_name.as_deref(), orchestrator)?;
+        tx.commit()?;
+
+        Ok((repo, session))
+    }
+
+    /// Fail if `max_repos` repositories already exist
+    fn check_repo_limit(conn: &Connection, max_repos: Option<i64>) -> DbResult<()> {
+        let Some(max) = max_repos else {
+            return Ok(());
+        };
+
+        let count: i64 = conn.query_row("SELECT COUNT(*) FROM repos", [], |row| row.get(0))?;
+        if count >= max {
+            return Err(DbError::ConstraintViolation(format!(
+                "Repository limit reached ({} of {})",
+                count, max
+            )));
+        }
+
+        Ok(())
+    }
+
+    fn insert_repo_row(conn: &Connection, path: &str, name: &str) -> DbResult<Repo> {
         let now = Utc::now();
         let id = Uuid::new_v4();
 
@@ -211,7 +589,7 @@ impl Database {
 
     /// Get a repository by ID
     pub fn get_repo(&self, id: Uuid) -> DbResult<Repo> {
-        let conn = self.conn.lock().unwrap();
+        let conn = self.conn()?;
 
         conn.query_row(
             "SELECT id, path, name, created_at, updated_at FROM repos WHERE id = ?1",
@@ -232,9 +610,28 @@ impl Database {
         })
     }
 
+    /// Rename a repository, returning the updated record
+    pub fn update_repo_name(&self, id: Uuid, name: &str) -> DbResult<Repo> {
+        {
+            let conn = self.conn()?;
+            let now = Utc::now();
+
+            let affected = conn.execute(
+                "UPDATE repos SET name = ?1, updated_at = ?2 WHERE id = ?3",
+                params![name, now.to_rfc3339(), id.to_string()],
+            )?;
+
+            if affected == 0 {
+                return Err(DbError::NotFound);
+            }
+        }
+
+        self.get_repo(id)
+    }
+
     /// Get a repository by path
     pub fn get_repo_by_path(&self, path: &str) -> DbResult<Repo> {
-        let conn = self.conn.lock().unwrap();
+        let conn = self.conn()?;
 
         conn.query_row(
             "SELECT id, path, name, created_at, updated_at FROM repos WHERE path = ?1",
@@ -255,9 +652,61 @@ impl Database {
         })
     }
 
+    /// Return which of `paths` are already tracked repositories
+    pub fn tracked_repo_paths(&self, paths: &[String]) -> DbResult<HashSet<String>> {
+        if paths.is_empty() {
+            return Ok(HashSet::new());
+        }
+
+        let conn = self.conn()?;
+        let placeholders = vec!["?"; paths.len()].join(", ");
+        let mut stmt = conn.prepare(&format!(
+            "SELECT path FROM repos WHERE path IN ({})",
+            placeholders
+        ))?;
+
+        let tracked = stmt
+            .query_map(rusqlite::params_from_iter(paths), |row| row.get(0))?
+            .collect::<Result<HashSet<String>, _>>()?;
+
+        Ok(tracked)
+    }
+
+    /// Count tracked repositories
+    pub fn count_repos(&self) -> DbResult<i64> {
+        let conn = self.conn()?;
+        let count = conn.query_row("SELECT COUNT(*) FROM repos", [], |row| row.get(0))?;
+        Ok(count)
+    }
+
+    /// Count sessions per status, including statuses with no sessions
+    pub fn count_sessions_by_status(&self) -> DbResult<std::collections::BTreeMap<String, i64>> {
+        let conn = self.conn()?;
+
+        let mut counts: std::collections::BTreeMap<String, i64> = [
+            SessionStatus::Idle,
+            SessionStatus::Running,
+            SessionStatus::Completed,
+            SessionStatus::Error,
+            SessionStatus::Cancelled,
+        ]
+        .iter()
+        .map(|status| (status.as_str().to_string(), 0))
+        .collect();
+
+        let mut stmt = conn.prepare("SELECT status, COUNT(*) FROM sessions GROUP BY status")?;
+        let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?)))?;
+        for row in rows {
+            let (status, count) = row?;
+            counts.insert(status, count);
+        }
+
+        Ok(counts)
+    }
+
     /// List all repositories
     pub fn list_repos(&self) -> DbResult<Vec<Repo>> {
-        let conn = self.conn.lock().unwrap();
+        let conn = self.conn()?;
         let mut stmt =
             conn.prepare("SELECT id, path, name, created_at, updated_at FROM repos ORDER BY name")?;
 
@@ -278,7 +727,7 @@ impl Database {
 
     /// Delete a repository by ID
     pub fn delete_repo(&self, id: Uuid) -> DbResult<()> {
-        let conn = self.conn.lock().unwrap();
+        let conn = self.conn()?;
         let affected = conn.execute("DELETE FROM repos WHERE id = ?1", params![id.to_string()])?;
 
         if affected == 0 {
@@ -291,7 +740,54 @@ impl Database {
 
     /// Insert a new session
     pub fn insert_session(&self, repo_id: Uuid, name: Option<&str>, orchestrator: Orchestrator) -> DbResult<Session> {
-        let conn = self.conn.lock().unwrap();
+        let conn = self.conn()?;
+        Self::insert_session_row(&conn, repo_id, name, orchestrator)
+    }
+
+    /// Insert a new session, bumping the repo's session sequence in the same
+    /// transaction and naming the session from the new sequence number
+    pub fn insert_session_with_seq<F>(
+        &self,
+        repo_id: Uuid,
+        orchestrator: Orchestrator,
+        name: F,
+    ) -> DbResult<Session>
+    where
+        F: FnOnce(i64) -> Option<String>,
+    {
+        let mut conn = self.conn()?;
+        let tx = conn.transaction()?;
+
+        let seq = Self::next_session_seq(&tx, repo_id)?;
+        let name = name(seq);
+        let session = Self::insert_session_row(&tx, repo_id, name.as_deref(), orchestrator)?;
+        tx.commit()?;
+
+        Ok(session)
+    }
+
+    /// Bump and return a repo's session sequence number
+    fn next_session_seq(conn: &Connection, repo_id: Uuid) -> DbResult<i64> {
+        let affected = conn.execute(
+            "UPDATE repos SET session_seq = session_seq + 1 WHERE id = ?1",
+            params![repo_id.to_string()],
+        )?;
+        if affected == 0 {
+            return Err(DbError::NotFound);
+        }
+        Ok(conn.query_row(
+            "SELECT session_seq FROM repos WHERE id = ?1",
+            params![repo_id.to_string()],
+            |row| row.get(0),
+        )?)
+    }
+
+    fn insert_session_row(
+        conn: &Connection,
+        repo_id: Uuid,
+        name: Option<&str>,
+        orchestrator: Orchestrator,
+    ) -> DbResult<Session> {
         let now = Utc::now();
         let id = Uuid::new_v4();
 
@@ -314,6 +810,9 @@ impl Database {
             name: name.map(String::from),
             orchestrator,
             status: SessionStatus::Idle,
+            archived: false,
+            branch: None,
+            exit_reason: None,
             created_at: now,
             updated_at: now,
         })
@@ -321,22 +820,12 @@ impl Database {
 
     /// Get a session by ID
     pub fn get_session(&self, id: Uuid) -> DbResult<Session> {
-        let conn = self.conn.lock().unwrap();
+        let conn = self.conn()?;
 
         conn.query_row(
-            "SELECT id, repo_id, name, orchestrator, status, created_at, updated_at FROM sessions WHERE id = ?1",
+            "SELECT id, repo_id, name, orchestrator, status, archived, branch, exit_reason, created_at, updated_at FROM sessions WHERE id = ?1",
             params![id.to_string()],
-            |row| {
-                Ok(Session {
-                    id: parse_uuid(row, 0, "id")?,
-                    repo_id: parse_uuid(row, 1, "repo_id")?,
-                    name: row.get(2)?,
-                    orchestrator: parse_enum(row, 3, "orchestrator", Orchestrator::from_str)?,
-                    status: parse_enum(row, 4, "status", SessionStatus::from_str)?,
-                    created_at: parse_datetime(row, 5, "created_at")?,
-                    updated_at: parse_datetime(row, 6, "updated_at")?,
-                })
-            },
+            session_from_row,
         )
         .map_err(|e| match e {
             rusqlite::Error::QueryReturnedNoRows => DbError::NotFound,
@@ -344,57 +833,83 @@ impl Database {
         })
     }
 
-    /// List all sessions
-    pub fn list_sessions(&self) -> DbResult<Vec<Session>> {
-        let conn = self.conn.lock().unwrap();
+    /// List sessions, leaving out archived ones unless `include_archived`
+    pub fn list_sessions(&self, include_archived: bool) -> DbResult<Vec<Session>> {
+        let conn = self.conn()?;
         let mut stmt = conn.prepare(
-            "SELECT id, repo_id, name, orchestrator, status, created_at, updated_at FROM sessions ORDER BY updated_at DESC",
+            "SELECT id, repo_id, name, orchestrator, status, archived, branch, exit_reason, created_at, updated_at FROM sessions
+             WHERE ?1 OR archived = 0 ORDER BY updated_at DESC",
         )?;
 
         let sessions = stmt
-            .query_map([], |row| {
-                Ok(Session {
-                    id: parse_uuid(row, 0, "id")?,
-                    repo_id: parse_uuid(row, 1, "repo_id")?,
-                    name: row.get(2)?,
-                    orchestrator: parse_enum(row, 3, "orchestrator", Orchestrator::from_str)?,
-                    status: parse_enum(row, 4, "status", SessionStatus::from_str)?,
-                    created_at: parse_datetime(row, 5, "created_at")?,
-                    updated_at: parse_datetime(row, 6, "updated_at")?,
+            .query_map(params![include_archived], session_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(sessions)
+    }
+
+    /// List sessions with their message count and newest message time
+    ///
+    /// Like `list_sessions`, but ordered by the latest of the session's last
+    /// status change and its newest message, so sessions still being talked to
+    /// come first.
+    pub fn list_session_summaries(&self, include_archived: bool) -> DbResult<Vec<SessionSummary>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT s.id, s.repo_id, s.name, s.orchestrator, s.status, s.archived, s.branch, s.exit_reason, s.created_at, s.updated_at,
+                    COALESCE(m.message_count, 0), m.last_message_at
+             FROM sessions s
+             LEFT JOIN (
+                 SELECT session_id, COUNT(*) AS message_count, MAX(created_at) AS last_message_at
+                 FROM messages GROUP BY session_id
+             ) m ON m.session_id = s.id
+             WHERE ?1 OR s.archived = 0
+             ORDER BY MAX(s.updated_at, COALESCE(m.last_message_at, s.updated_at)) DESC",
+        )?;
+
+        let summaries = stmt
+            .query_map(params![include_archived], |row| {
+                Ok(SessionSummary {
+                    session: session_from_row(row)?,
+                    message_count: row.get::<_, i64>(10)? as usize,
+                    last_message_at: parse_optional_datetime(row, 11, "last_message_at")?,
                 })
             })?
             .collect::<Result<Vec<_>, _>>()?;
 
-        Ok(sessions)
+        Ok(summaries)
     }
 
     /// List sessions for a specific repository
     pub fn list_sessions_by_repo(&self, repo_id: Uuid) -> DbResult<Vec<Session>> {
-        let conn = self.conn.lock().unwrap();
+        let conn = self.conn()?;
         let mut stmt = conn.prepare(
-            "SELECT id, repo_id, name, orchestrator, status, created_at, updated_at FROM sessions WHERE repo_id = ?1 ORDER BY updated_at DESC",
+            "SELECT id, repo_id, name, orchestrator, status, archived, branch, exit_reason, created_at, updated_at FROM sessions WHERE repo_id = ?1 ORDER BY updated_at DESC",
         )?;
 
         let sessions = stmt
-            .query_map(params![repo_id.to_string()], |row| {
-                Ok(Session {
-                    id: parse_uuid(row, 0, "id")?,
-                    repo_id: parse_uuid(row, 1, "repo_id")?,
-                    name: row.get(2)?,
-                    orchestrator: parse_enum(row, 3, "orchestrator", Orchestrator::from_str)?,
-                    status: parse_enum(row, 4, "status", SessionStatus::from_str)?,
-                    created_at: parse_datetime(row, 5, "created_at")?,
-                    updated_at: parse_datetime(row, 6, "updated_at")?,
-                })
-            })?
+            .query_map(params![repo_id.to_string()], session_from_row)?
             .collect::<Result<Vec<_>, _>>()?;
 
         Ok(sessions)
     }
 
+    /// Count sessions for a specific repository
+    pub fn count_sessions_by_repo(&self, repo_id: Uuid) -> DbResult<i64> {
+        let conn = self.conn()?;
+
+        let count = conn.query_row(
+            "SELECT COUNT(*) FROM sessions WHERE repo_id = ?1",
+            params![repo_id.to_string()],
+            |row| row.get(0),
+        )?;
+
+        Ok(count)
+    }
+
     /// Update session status
     pub fn update_session_status(&self, id: Uuid, status: SessionStatus) -> DbResult<()> {
-        let conn = self.conn.lock().unwrap();
+        let conn = self.conn()?;
         let now = Utc::now();
 
         let affected = conn.execute(
@@ -408,11 +923,15 @@ impl Database {
         Ok(())
     }
 
-    /// Delete a session by ID
-    pub fn delete_session(&self, id: Uuid) -> DbResult<()> {
-        let conn = self.conn.lock().unwrap();
-        let affected =
-            conn.execute("DELETE FROM sessions WHERE id = ?1", params![id.to_string()])?;
+    /// Update a session's name (None clears it)
+    pub fn update_session_name(&self, id: Uuid, name: Option<&str>) -> DbResult<()> {
+        let conn = self.conn()?;
+        let now = Utc::now();
+
+        let affected = conn.execute(
+            "UPDATE sessions SET name = ?1, updated_at = ?2 WHERE id = ?3",
+            params![name, now.to_rfc3339(), id.to_string()],
+        )?;
 
         if affected == 0 {
             return Err(DbError::NotFound);
@@ -420,105 +939,733 @@ impl Database {
         Ok(())
     }
 
-    // ==================== Message Operations ====================
+    /// Archive a session, hiding it from the default session listing
+    pub fn archive_session(&self, id: Uuid) -> DbResult<()> {
+        self.set_session_archived(id, true)
+    }
 
-    /// Insert a new message
-    pub fn insert_message(
-        &self,
-        session_id: Uuid,
-        role: MessageRole,
-        content: &str,
-    ) -> DbResult<Message> {
-        let conn = self.conn.lock().unwrap();
+    /// Restore an archived session to the default session listing
+    pub fn unarchive_session(&self, id: Uuid) -> DbResult<()> {
+        self.set_session_archived(id, false)
+    }
+
+    fn set_session_archived(&self, id: Uuid, archived: bool) -> DbResult<()> {
+        let conn = self.conn()?;
         let now = Utc::now();
-        let id = Uuid::new_v4();
 
-        conn.execute(
-            "INSERT INTO messages (id, session_id, role, content, created_at) VALUES (?1, ?2, ?3, ?4, ?5)",
-            params![
-                id.to_string(),
-                session_id.to_string(),
-                role.as_str(),
-                content,
-                now.to_rfc3339()
-            ],
+        let affected = conn.execute(
+            "UPDATE sessions SET archived = ?1, updated_at = ?2 WHERE id = ?3",
+            params![archived, now.to_rfc3339(), id.to_string()],
         )?;
 
-        Ok(Message {
-            id,
-            session_id,
-            role,
-            content: content.to_string(),
-            created_at: now,
-        })
+        if affected == 0 {
+            return Err(DbError::NotFound);
+        }
+        Ok(())
     }
 
-    /// List messages for a session
-    pub fn list_messages(&self, session_id: Uuid) -> DbResult<Vec<Message>> {
-        let conn = self.conn.lock().unwrap();
-        let mut stmt = conn.prepare(
-            "SELECT id, session_id, role, content, created_at FROM messages WHERE session_id = ?1 ORDER BY created_at",
-        )?;
+    /// Record the branch a session is working on (None for a detached HEAD)
+    pub fn update_session_branch(&self, id: Uuid, branch: Option<&str>) -> DbResult<()> {
+        let conn = self.conn()?;
 
-        let messages = stmt
-            .query_map(params![session_id.to_string()], |row| {
-                Ok(Message {
-                    id: parse_uuid(row, 0, "id")?,
-                    session_id: parse_uuid(row, 1, "session_id")?,
-                    role: parse_enum(row, 2, "role", MessageRole::from_str)?,
-                    content: row.get(3)?,
-                    created_at: parse_datetime(row, 4, "created_at")?,
-                })
-            })?
-            .collect::<Result<Vec<_>, _>>()?;
+        let affected = conn.execute(
+            "UPDATE sessions SET branch = ?1 WHERE id = ?2",
+            params![branch, id.to_string()],
+        )?;
 
-        Ok(messages)
+        if affected == 0 {
+            return Err(DbError::NotFound);
+        }
+        Ok(())
     }
 
-    // ==================== Config Operations ====================
+    /// Record how a session's last run ended (`None` while a run is in progress)
+    pub fn update_session_exit_reason(
+        &self,
+        id: Uuid,
+        exit_reason: Option<ExitReason>,
+    ) -> DbResult<()> {
+        let conn = self.conn()?;
 
-    /// Get a config value
-    pub fn get_config(&self, key: &str) -> DbResult<Option<String>> {
-        let conn = self.conn.lock().unwrap();
+        let affected = conn.execute(
+            "UPDATE sessions SET exit_reason = ?1 WHERE id = ?2",
+            params![exit_reason.map(|r| r.as_str()), id.to_string()],
+        )?;
 
-        match conn.query_row(
-            "SELECT value FROM config WHERE key = ?1",
-            params![key],
-            |row| row.get::<_, String>(0),
-        ) {
-            Ok(value) => Ok(Some(value)),
-            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
-            Err(e) => Err(DbError::Sqlite(e)),
+        if affected == 0 {
+            return Err(DbError::NotFound);
         }
+        Ok(())
     }
 
-    /// Set a config value
-    pub fn set_config(&self, key: &str, value: &str) -> DbResult<()> {
-        let conn = self.conn.lock().unwrap();
-        let now = Utc::now();
+    /// Record the resolved command line used to run a session
+    pub fn update_session_command(&self, id: Uuid, command: &str) -> DbResult<()> {
+        let conn = self.conn()?;
 
-        conn.execute(
-            "INSERT OR REPLACE INTO config (key, value, updated_at) VALUES (?1, ?2, ?3)",
-            params![key, value, now.to_rfc3339()],
+        let affected = conn.execute(
+            "UPDATE sessions SET command = ?1 WHERE id = ?2",
+            params![command, id.to_string()],
         )?;
 
+        if affected == 0 {
+            return Err(DbError::NotFound);
+        }
+        Ok(())
+    }
+
+    /// Get the recorded command line for a session (None if it never ran)
+    pub fn get_session_command(&self, id: Uuid) -> DbResult<Option<String>> {
+        let conn = self.conn()?;
+
+        conn.query_row(
+            "SELECT command FROM sessions WHERE id = ?1",
+            params![id.to_string()],
+            |row| row.get(0),
+        )
+        .map_err(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => DbError::NotFound,
+            _ => DbError::Sqlite(e),
+        })
+    }
+
+    /// Record the AI backend a session's run used (None for the CLI default)
+    pub fn update_session_backend(&self, id: Uuid, backend: Option<&str>) -> DbResult<()> {
+        let conn = self.conn()?;
+
+        let affected = conn.execute(
+            "UPDATE sessions SET backend = ?1 WHERE id = ?2",
+            params![backend, id.to_string()],
+        )?;
+
+        if affected == 0 {
+            return Err(DbError::NotFound);
+        }
+        Ok(())
+    }
+
+    /// Get the AI backend a session's last run used
+    pub fn get_session_backend(&self, id: Uuid) -> DbResult<Option<String>> {
+        let conn = self.conn()?;
+
+        conn.query_row(
+            "SELECT backend FROM sessions WHERE id = ?1",
+            params![id.to_string()],
+            |row| row.get(0),
+        )
+        .map_err(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => DbError::NotFound,
+            _ => DbError::Sqlite(e),
+        })
+    }
+
+    /// Record the HEAD sha a session's run started from; the first one
+    /// recorded also becomes the session's base
+    pub fn update_session_checkpoint(&self, id: Uuid, sha: &str) -> DbResult<()> {
+        let conn = self.conn()?;
+
+        let affected = conn.execute(
+            "UPDATE sessions SET checkpoint_sha = ?1, base_sha = COALESCE(base_sha, ?1)
+             WHERE id = ?2",
+            params![sha, id.to_string()],
+        )?;
+
+        if affected == 0 {
+            return Err(DbError::NotFound);
+        }
+        Ok(())
+    }
+
+    /// Get the recorded checkpoint sha for a session (None if it never ran)
+    pub fn get_session_checkpoint(&self, id: Uuid) -> DbResult<Option<String>> {
+        let conn = self.conn()?;
+
+        conn.query_row(
+            "SELECT checkpoint_sha FROM sessions WHERE id = ?1",
+            params![id.to_string()],
+            |row| row.get(0),
+        )
+        .map_err(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => DbError::NotFound,
+            _ => DbError::Sqlite(e),
+        })
+    }
+
+    /// Get the sha a session's first run started from (None if it never ran)
+    pub fn get_session_base(&self, id: Uuid) -> DbResult<Option<String>> {
+        let conn = self.conn()?;
+
+        conn.query_row(
+            "SELECT base_sha FROM sessions WHERE id = ?1",
+            params![id.to_string()],
+            |row| row.get(0),
+        )
+        .map_err(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => DbError::NotFound,
+            _ => DbError::Sqlite(e),
+        })
+    }
+
+    /// Delete a session by ID
+    pub fn delete_session(&self, id: Uuid) -> DbResult<()> {
+        let conn = self.conn()?;
+        let affected =
+            conn.execute("DELETE FROM sessions WHERE id = ?1", params![id.to_string()])?;
+
+        if affected == 0 {
+            return Err(DbError::NotFound);
+        }
+        Ok(())
+    }
+
+    /// Delete every session with the given status, returning how many were removed
+    ///
+    /// Messages, output logs and other per-session rows go with them.
+    pub fn delete_sessions_by_status(&self, status: SessionStatus) -> DbResult<usize> {
+        let conn = self.conn()?;
+        let affected =
+            conn.execute("DELETE FROM sessions WHERE status = ?1", params![status.as_str()])?;
+        Ok(affected)
+    }
+
+    /// Recreate a session under `repo_id` with a new id, along with its
+    /// messages and output logs, in one transaction
+    ///
+    /// Names, statuses and timestamps are kept; message and log ids are
+    /// reassigned. A session that was running when exported is imported as
+    /// cancelled, since its process doesn't exist here.
+    pub fn import_session(
+        &self,
+        repo_id: Uuid,
+        session: &Session,
+        messages: &[Message],
+        output_logs: &[OutputLog],
+    ) -> DbResult<Session> {
+        let mut conn = self.conn()?;
+        let tx = conn.transaction()?;
+        let id = Uuid::new_v4();
+        let status = match session.status {
+            SessionStatus::Running => SessionStatus::Cancelled,
+            status => status,
+        };
+
+        tx.execute(
+            "INSERT INTO sessions (id, repo_id, name, orchestrator, status, archived, branch, exit_reason, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            params![
+                id.to_string(),
+                repo_id.to_string(),
+                session.name,
+                session.orchestrator.as_str(),
+                status.as_str(),
+                session.archived,
+                session.branch,
+                session.exit_reason.map(|r| r.as_str()),
+                session.created_at.to_rfc3339(),
+                session.updated_at.to_rfc3339()
+            ],
+        )
+        .map_err(|e| match e {
+            rusqlite::Error::SqliteFailure(err, _)
+                if err.code == rusqlite::ErrorCode::ConstraintViolation =>
+            {
+                DbError::NotFound
+            }
+            _ => DbError::Sqlite(e),
+        })?;
+
+        {
+            let mut insert_message = tx.prepare(
+                "INSERT INTO messages (id, session_id, role, content, incomplete, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            )?;
+            for message in messages {
+                insert_message.execute(params![
+                    Uuid::new_v4().to_string(),
+                    id.to_string(),
+                    message.role.as_str(),
+                    message.content,
+                    message.incomplete,
+                    message.created_at.to_rfc3339()
+                ])?;
+            }
+
+            let mut insert_log = tx.prepare(
+                "INSERT INTO output_logs (session_id, stream, content, truncated, created_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+            )?;
+            for log in output_logs {
+                insert_log.execute(params![
+                    id.to_string(),
+                    log.stream.as_str(),
+                    log.content,
+                    log.truncated,
+                    log.created_at.to_rfc3339()
+                ])?;
+            }
+        }
+
+        let imported = tx.query_row(
+            "SELECT id, repo_id, name, orchestrator, status, archived, branch, exit_reason, created_at, updated_at FROM sessions WHERE id = ?1",
+            params![id.to_string()],
+            session_from_row,
+        )?;
+        tx.commit()?;
+
+        Ok(imported)
+    }
+
+    // ==================== Message Operations ====================
+
+    /// Insert a new message
+    pub fn insert_message(
+        &self,
+        session_id: Uuid,
+        role: MessageRole,
+        content: &str,
+    ) -> DbResult<Message> {
+        let conn = self.conn()?;
+        let now = Utc::now();
+        let id = Uuid::new_v4();
+
+        conn.execute(
+            "INSERT INTO messages (id, session_id, role, content, created_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                id.to_string(),
+                session_id.to_string(),
+                role.as_str(),
+                content,
+                now.to_rfc3339()
+            ],
+        )?;
+
+        Ok(Message {
+            id,
+            session_id,
+            role,
+            content: content.to_string(),
+            incomplete: false,
+            created_at: now,
+        })
+    }
+
+    /// Insert a message with a caller-chosen id, or replace the content and
+    /// incomplete flag of an existing one
+    ///
+    /// Used to checkpoint a response while it is still being generated.
+    pub fn upsert_message(
+        &self,
+        id: Uuid,
+        session_id: Uuid,
+        role: MessageRole,
+        content: &str,
+        incomplete: bool,
+    ) -> DbResult<Message> {
+        let conn = self.conn()?;
+        let now = Utc::now();
+
+        conn.execute(
+            "INSERT INTO messages (id, session_id, role, content, incomplete, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT(id) DO UPDATE SET content = excluded.content, incomplete = excluded.incomplete",
+            params![
+                id.to_string(),
+                session_id.to_string(),
+                role.as_str(),
+                content,
+                incomplete,
+                now.to_rfc3339()
+            ],
+        )?;
+
+        let message = conn.query_row(
+            "SELECT id, session_id, role, content, incomplete, created_at FROM messages WHERE id = ?1",
+            params![id.to_string()],
+            message_from_row,
+        )?;
+
+        Ok(message)
+    }
+
+    /// List messages for a session
+    pub fn list_messages(&self, session_id: Uuid) -> DbResult<Vec<Message>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, session_id, role, content, incomplete, created_at FROM messages WHERE session_id = ?1 ORDER BY created_at",
+        )?;
+
+        let messages = stmt
+            .query_map(params![session_id.to_string()], message_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(messages)
+    }
+
+    /// List a page of messages for a session, newest first
+    ///
+    /// Returns at most `limit` messages created strictly before `before`, if
+    /// given. Pass the `created_at` of the last message as the next cursor.
+    pub fn list_messages_paged(
+        &self,
+        session_id: Uuid,
+        limit: usize,
+        before: Option<DateTime<Utc>>,
+    ) -> DbResult<Vec<Message>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, session_id, role, content, incomplete, created_at FROM messages
+             WHERE session_id = ?1 AND (?2 IS NULL OR created_at < ?2)
+             ORDER BY created_at DESC, rowid DESC LIMIT ?3",
+        )?;
+
+        let messages = stmt
+            .query_map(
+                params![
+                    session_id.to_string(),
+                    before.map(|b| b.to_rfc3339()),
+                    limit as i64
+                ],
+                message_from_row,
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(messages)
+    }
+
+    /// Full-text search over message content across all sessions
+    ///
+    /// Every whitespace-separated term in `query` must appear in a message for
+    /// it to match; terms are matched literally, so FTS5 operators in user
+    /// input are not interpreted. Results are ordered by relevance.
+    pub fn search_messages(&self, query: &str, limit: usize) -> DbResult<Vec<Message>> {
+        let Some(query) = fts_phrase_query(query) else {
+            return Ok(Vec::new());
+        };
+
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT m.id, m.session_id, m.role, m.content, m.incomplete, m.created_at
+             FROM messages_fts JOIN messages m ON m.rowid = messages_fts.rowid
+             WHERE messages_fts MATCH ?1
+             ORDER BY messages_fts.rank LIMIT ?2",
+        )?;
+
+        let messages = stmt
+            .query_map(params![query, limit as i64], message_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(messages)
+    }
+
+    // ==================== Config Operations ====================
+
+    /// Counter that changes whenever a config value is written or deleted
+    pub fn config_generation(&self) -> u64 {
+        self.config_generation.load(Ordering::Acquire)
+    }
+
+    /// Get a config value, decrypting it if it is a secret
+    pub fn get_config(&self, key: &str) -> DbResult<Option<String>> {
+        let conn = self.conn()?;
+
+        match conn.query_row(
+            "SELECT value FROM config WHERE key = ?1",
+            params![key],
+            |row| row.get::<_, String>(0),
+        ) {
+            Ok(value) => self.reveal_config(key, value).map(Some),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(DbError::Sqlite(e)),
+        }
+    }
+
+    /// Get a config value parsed as `T`
+    pub fn get_config_as<T>(&self, key: &str) -> DbResult<Option<T>>
+    where
+        T: FromStr,
+        T::Err: std::fmt::Display,
+    {
+        self.get_config(key)?
+            .map(|value| parse_config_value(key, value))
+            .transpose()
+    }
+
+    /// Decrypt a value as stored in the config table (as returned by `list_config`)
+    pub fn reveal_config(&self, key: &str, stored: String) -> DbResult<String> {
+        if !secrets::is_secret_key(key) {
+            return Ok(stored);
+        }
+        self.secrets
+            .as_ref()
+            .ok_or_else(|| secrets::missing_secret_error(key))?
+            .open(key, &stored)
+    }
+
+    /// Set a config value, encrypting it if it is a secret
+    pub fn set_config(&self, key: &str, value: &str) -> DbResult<()> {
+        let sealed;
+        let value = if secrets::is_secret_key(key) {
+            sealed = self
+                .secrets
+                .as_ref()
+                .ok_or_else(|| secrets::missing_secret_error(key))?
+                .seal(key, value)?;
+            sealed.as_str()
+        } else {
+            value
+        };
+
+        let conn = self.conn()?;
+        let now = Utc::now();
+
+        conn.execute(
+            "INSERT OR REPLACE INTO config (key, value, updated_at) VALUES (?1, ?2, ?3)",
+            params![key, value, now.to_rfc3339()],
+        )?;
+        self.config_generation.fetch_add(1, Ordering::AcqRel);
+
+        Ok(())
+    }
+
+    /// Delete a config value
+    pub fn delete_config(&self, key: &str) -> DbResult<()> {
+        let conn = self.conn()?;
+        conn.execute("DELETE FROM config WHERE key = ?1", params![key])?;
+        self.config_generation.fetch_add(1, Ordering::AcqRel);
+        Ok(())
+    }
+
+    /// List all config values as stored; secret values stay encrypted
+    pub fn list_config(&self) -> DbResult<Vec<(String, String)>> {
+        let conn = self.conn()?;
+
+        let mut stmt = conn.prepare("SELECT key, value FROM config")?;
+        let config = stmt
+            .query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(config)
+    }
+
+    // ==================== Repo Config Operations ====================
+
+    /// Get a config value scoped to a repository
+    pub fn get_repo_config(&self, repo_id: Uuid, key: &str) -> DbResult<Option<String>> {
+        let conn = self.conn()?;
+
+        match conn.query_row(
+            "SELECT value FROM repo_config WHERE repo_id = ?1 AND key = ?2",
+            params![repo_id.to_string(), key],
+            |row| row.get::<_, String>(0),
+        ) {
+            Ok(value) => Ok(Some(value)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(DbError::Sqlite(e)),
+        }
+    }
+
+    /// Set a config value scoped to a repository
+    pub fn set_repo_config(&self, repo_id: Uuid, key: &str, value: &str) -> DbResult<()> {
+        let conn = self.conn()?;
+        let now = Utc::now();
+
+        conn.execute(
+            "INSERT OR REPLACE INTO repo_config (repo_id, key, value, updated_at) VALUES (?1, ?2, ?3, ?4)",
+            params![repo_id.to_string(), key, value, now.to_rfc3339()],
+        )
+        .map_err(|e| match e {
+            rusqlite::Error::SqliteFailure(err, _)
+                if err.code == rusqlite::ErrorCode::ConstraintViolation =>
+            {
+                DbError::NotFound
+            }
+            _ => DbError::Sqlite(e),
+        })?;
+
+        Ok(())
+    }
+
+    /// Delete a config value scoped to a repository
+    pub fn delete_repo_config(&self, repo_id: Uuid, key: &str) -> DbResult<()> {
+        let conn = self.conn()?;
+        conn.execute(
+            "DELETE FROM repo_config WHERE repo_id = ?1 AND key = ?2",
+            params![repo_id.to_string(), key],
+        )?;
+        Ok(())
+    }
+
+    /// List all config values for a repository
+    pub fn list_repo_config(&self, repo_id: Uuid) -> DbResult<Vec<(String, String)>> {
+        let conn = self.conn()?;
+
+        let mut stmt = conn.prepare("SELECT key, value FROM repo_config WHERE repo_id = ?1")?;
+        let config = stmt
+            .query_map(params![repo_id.to_string()], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(config)
+    }
+
+    // ==================== Repo Tag Operations ====================
+
+    /// Tag a repository; adding a tag it already has is a no-op
+    pub fn add_repo_tag(&self, repo_id: Uuid, tag: &str) -> DbResult<()> {
+        let conn = self.conn()?;
+        Self::insert_repo_tag(&conn, repo_id, tag)
+    }
+
+    fn insert_repo_tag(conn: &Connection, repo_id: Uuid, tag: &str) -> DbResult<()> {
+        conn.execute(
+            "INSERT OR IGNORE INTO repo_tags (repo_id, tag, created_at) VALUES (?1, ?2, ?3)",
+            params![repo_id.to_string(), tag, Utc::now().to_rfc3339()],
+        )
+        .map_err(|e| match e {
+            rusqlite::Error::SqliteFailure(err, _)
+                if err.code == rusqlite::ErrorCode::ConstraintViolation =>
+            {
+                DbError::NotFound
+            }
+            _ => DbError::Sqlite(e),
+        })?;
+        Ok(())
+    }
+
+    /// Remove a tag from a repository
+    pub fn remove_repo_tag(&self, repo_id: Uuid, tag: &str) -> DbResult<()> {
+        let conn = self.conn()?;
+        let affected = conn.execute(
+            "DELETE FROM repo_tags WHERE repo_id = ?1 AND tag = ?2",
+            params![repo_id.to_string(), tag],
+        )?;
+
+        if affected == 0 {
+            return Err(DbError::NotFound);
+        }
+        Ok(())
+    }
+
+    /// Replace all of a repository's tags
+    pub fn set_repo_tags(&self, repo_id: Uuid, tags: &[String]) -> DbResult<()> {
+        let mut conn = self.conn()?;
+        let tx = conn.transaction()?;
+
+        tx.execute(
+            "DELETE FROM repo_tags WHERE repo_id = ?1",
+            params![repo_id.to_string()],
+        )?;
+        for tag in tags {
+            Self::insert_repo_tag(&tx, repo_id, tag)?;
+        }
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    /// List a repository's tags, alphabetically
+    pub fn list_repo_tags(&self, repo_id: Uuid) -> DbResult<Vec<String>> {
+        let conn = self.conn()?;
+        let mut stmt =
+            conn.prepare("SELECT tag FROM repo_tags WHERE repo_id = ?1 ORDER BY tag")?;
+
+        let tags = stmt
+            .query_map(params![repo_id.to_string()], |row| row.get(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(tags)
+    }
+
+    /// List repositories with the given tag
+    pub fn list_repos_by_tag(&self, tag: &str) -> DbResult<Vec<Repo>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT r.id, r.path, r.name, r.created_at, r.updated_at FROM repos r
+             JOIN repo_tags t ON t.repo_id = r.id
+             WHERE t.tag = ?1 ORDER BY r.name",
+        )?;
+
+        let repos = stmt
+            .query_map(params![tag], |row| {
+                Ok(Repo {
+                    id: parse_uuid(row, 0, "id")?,
+                    path: row.get(1)?,
+                    name: row.get(2)?,
+                    created_at: parse_datetime(row, 3, "created_at")?,
+                    updated_at: parse_datetime(row, 4, "updated_at")?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(repos)
+    }
+
+    // ==================== Session Config Operations ====================
+
+    /// Get a config value set on a session (without falling back to global config)
+    pub fn get_session_config(&self, session_id: Uuid, key: &str) -> DbResult<Option<String>> {
+        let conn = self.conn()?;
+
+        match conn.query_row(
+            "SELECT value FROM session_config WHERE session_id = ?1 AND key = ?2",
+            params![session_id.to_string(), key],
+            |row| row.get::<_, String>(0),
+        ) {
+            Ok(value) => Ok(Some(value)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(DbError::Sqlite(e)),
+        }
+    }
+
+    /// Set a config value overriding global config for one session
+    pub fn set_session_config(&self, session_id: Uuid, key: &str, value: &str) -> DbResult<()> {
+        let conn = self.conn()?;
+        let now = Utc::now();
+
+        conn.execute(
+            "INSERT OR REPLACE INTO session_config (session_id, key, value, updated_at) VALUES (?1, ?2, ?3, ?4)",
+            params![session_id.to_string(), key, value, now.to_rfc3339()],
+        )
+        .map_err(|e| match e {
+            rusqlite::Error::SqliteFailure(err, _)
+                if err.code == rusqlite::ErrorCode::ConstraintViolation =>
+            {
+                DbError::NotFound
+            }
+            _ => DbError::Sqlite(e),
+        })?;
+
         Ok(())
     }
 
-    /// Delete a config value
-    pub fn delete_config(&self, key: &str) -> DbResult<()> {
-        let conn = self.conn.lock().unwrap();
-        conn.execute("DELETE FROM config WHERE key = ?1", params![key])?;
+    /// Delete a session's override, so the key falls back to global config
+    pub fn delete_session_config(&self, session_id: Uuid, key: &str) -> DbResult<()> {
+        let conn = self.conn()?;
+        conn.execute(
+            "DELETE FROM session_config WHERE session_id = ?1 AND key = ?2",
+            params![session_id.to_string(), key],
+        )?;
         Ok(())
     }
 
-    /// List all config values
-    pub fn list_config(&self) -> DbResult<Vec<(String, String)>> {
-        let conn = self.conn.lock().unwrap();
+    /// Copy every config override of session `from` to session `to`
+    pub fn copy_session_config(&self, from: Uuid, to: Uuid) -> DbResult<()> {
+        let conn = self.conn()?;
+        conn.execute(
+            "INSERT OR REPLACE INTO session_config (session_id, key, value, updated_at)
+             SELECT ?2, key, value, ?3 FROM session_config WHERE session_id = ?1",
+            params![from.to_string(), to.to_string(), Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
 
-        let mut stmt = conn.prepare("SELECT key, value FROM config")?;
+    /// List all config overrides for a session
+    pub fn list_session_config(&self, session_id: Uuid) -> DbResult<Vec<(String, String)>> {
+        let conn = self.conn()?;
+
+        let mut stmt =
+            conn.prepare("SELECT key, value FROM session_config WHERE session_id = ?1")?;
         let config = stmt
-            .query_map([], |row| {
+            .query_map(params![session_id.to_string()], |row| {
                 Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
             })?
             .collect::<Result<Vec<_>, _>>()?;
@@ -526,6 +1673,231 @@ impl Database {
         Ok(config)
     }
 
+    /// Resolve a config value for a session: its own override if set,
+    /// otherwise the global value
+    pub fn resolve_session_config(&self, session_id: Uuid, key: &str) -> DbResult<Option<String>> {
+        match self.get_session_config(session_id, key)? {
+            Some(value) => Ok(Some(value)),
+            None => self.get_config(key),
+        }
+    }
+
+    // ==================== Session Template Operations ====================
+
+    const SESSION_TEMPLATE_COLUMNS: &'static str = "id, name, orchestrator, backend, model, preset, env, workdir, prompt, created_at, updated_at";
+
+    fn session_template_from_row(row: &rusqlite::Row) -> rusqlite::Result<SessionTemplate> {
+        Ok(SessionTemplate {
+            id: parse_uuid(row, 0, "id")?,
+            fields: SessionTemplateFields {
+                name: row.get(1)?,
+                orchestrator: parse_enum(row, 2, "orchestrator", Orchestrator::from_str)?,
+                backend: row.get(3)?,
+                model: row.get(4)?,
+                preset: row.get(5)?,
+                env: parse_json(row, 6, "env")?,
+                workdir: row.get(7)?,
+                prompt: row.get(8)?,
+            },
+            created_at: parse_datetime(row, 9, "created_at")?,
+            updated_at: parse_datetime(row, 10, "updated_at")?,
+        })
+    }
+
+    /// Insert a new session template
+    pub fn insert_session_template(&self, fields: &SessionTemplateFields) -> DbResult<SessionTemplate> {
+        let conn = self.conn()?;
+        let now = Utc::now();
+        let id = Uuid::new_v4();
+        let env = serde_json::to_string(&fields.env).map_err(|e| DbError::InvalidData(e.to_string()))?;
+
+        conn.execute(
+            "INSERT INTO session_templates (id, name, orchestrator, backend, model, preset, env, workdir, prompt, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            params![
+                id.to_string(),
+                fields.name,
+                fields.orchestrator.as_str(),
+                fields.backend,
+                fields.model,
+                fields.preset,
+                env,
+                fields.workdir,
+                fields.prompt,
+                now.to_rfc3339(),
+                now.to_rfc3339()
+            ],
+        )
+        .map_err(|e| map_constraint_error(e, &format!("Template name already exists: {}", fields.name)))?;
+
+        Ok(SessionTemplate {
+            id,
+            fields: fields.clone(),
+            created_at: now,
+            updated_at: now,
+        })
+    }
+
+    /// Get a session template by ID
+    pub fn get_session_template(&self, id: Uuid) -> DbResult<SessionTemplate> {
+        let conn = self.conn()?;
+
+        conn.query_row(
+            &format!(
+                "SELECT {} FROM session_templates WHERE id = ?1",
+                Self::SESSION_TEMPLATE_COLUMNS
+            ),
+            params![id.to_string()],
+            Self::session_template_from_row,
+        )
+        .map_err(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => DbError::NotFound,
+            _ => DbError::Sqlite(e),
+        })
+    }
+
+    /// List all session templates
+    pub fn list_session_templates(&self) -> DbResult<Vec<SessionTemplate>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM session_templates ORDER BY name",
+            Self::SESSION_TEMPLATE_COLUMNS
+        ))?;
+
+        let templates = stmt
+            .query_map([], Self::session_template_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(templates)
+    }
+
+    /// Replace the fields of an existing session template
+    pub fn update_session_template(
+        &self,
+        id: Uuid,
+        fields: &SessionTemplateFields,
+    ) -> DbResult<SessionTemplate> {
+        {
+            let conn = self.conn()?;
+            let now = Utc::now();
+            let env =
+                serde_json::to_string(&fields.env).map_err(|e| DbError::InvalidData(e.to_string()))?;
+
+            let affected = conn
+                .execute(
+                    "UPDATE session_templates SET name = ?1, orchestrator = ?2, backend = ?3, model = ?4, preset = ?5, env = ?6, workdir = ?7, prompt = ?8, updated_at = ?9 WHERE id = ?10",
+                    params![
+                        fields.name,
+                        fields.orchestrator.as_str(),
+                        fields.backend,
+                        fields.model,
+                        fields.preset,
+                        env,
+                        fields.workdir,
+                        fields.prompt,
+                        now.to_rfc3339(),
+                        id.to_string()
+                    ],
+                )
+                .map_err(|e| {
+                    map_constraint_error(e, &format!("Template name already exists: {}", fields.name))
+                })?;
+
+            if affected == 0 {
+                return Err(DbError::NotFound);
+            }
+        }
+
+        self.get_session_template(id)
+    }
+
+    /// Delete a session template by ID
+    pub fn delete_session_template(&self, id: Uuid) -> DbResult<()> {
+        let conn = self.conn()?;
+        let affected = conn.execute(
+            "DELETE FROM session_templates WHERE id = ?1",
+            params![id.to_string()],
+        )?;
+
+        if affected == 0 {
+            return Err(DbError::NotFound);
+        }
+        Ok(())
+    }
+
+    // ==================== Preset Operations ====================
+
+    fn preset_from_row(row: &rusqlite::Row) -> rusqlite::Result<CustomPreset> {
+        Ok(CustomPreset {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            description: row.get(2)?,
+            prompt: row.get(3)?,
+            created_at: parse_datetime(row, 4, "created_at")?,
+            updated_at: parse_datetime(row, 5, "updated_at")?,
+        })
+    }
+
+    /// Create a custom preset, or replace the one with the same id
+    pub fn upsert_preset(
+        &self,
+        id: &str,
+        name: &str,
+        description: &str,
+        prompt: &str,
+    ) -> DbResult<CustomPreset> {
+        {
+            let conn = self.conn()?;
+            let now = Utc::now().to_rfc3339();
+            conn.execute(
+                "INSERT INTO presets (id, name, description, prompt, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?5)
+                 ON CONFLICT(id) DO UPDATE SET name = ?2, description = ?3, prompt = ?4, updated_at = ?5",
+                params![id, name, description, prompt, now],
+            )?;
+        }
+
+        self.get_preset(id)
+    }
+
+    /// Get a custom preset by id
+    pub fn get_preset(&self, id: &str) -> DbResult<CustomPreset> {
+        let conn = self.conn()?;
+
+        conn.query_row(
+            "SELECT id, name, description, prompt, created_at, updated_at FROM presets WHERE id = ?1",
+            params![id],
+            Self::preset_from_row,
+        )
+        .map_err(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => DbError::NotFound,
+            _ => DbError::Sqlite(e),
+        })
+    }
+
+    /// List all custom presets
+    pub fn list_presets(&self) -> DbResult<Vec<CustomPreset>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, name, description, prompt, created_at, updated_at FROM presets ORDER BY name",
+        )?;
+
+        let presets = stmt
+            .query_map([], Self::preset_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(presets)
+    }
+
+    /// Delete a custom preset by id
+    pub fn delete_preset(&self, id: &str) -> DbResult<()> {
+        let conn = self.conn()?;
+        let affected = conn.execute("DELETE FROM presets WHERE id = ?1", params![id])?;
+
+        if affected == 0 {
+            return Err(DbError::NotFound);
+        }
+        Ok(())
+    }
+
     // ==================== Output Log Operations ====================
 
     /// Insert a new output log entry
@@ -535,18 +1907,59 @@ impl Database {
         stream: OutputStream,
         content: &str,
     ) -> DbResult<OutputLog> {
-        let conn = self.conn.lock().unwrap();
+        let conn = self.conn()?;
         let now = Utc::now();
 
-        conn.execute(
-            "INSERT INTO output_logs (session_id, stream, content, created_at) VALUES (?1, ?2, ?3, ?4)",
-            params![
-                session_id.to_string(),
-                stream.as_str(),
-                content,
-                now.to_rfc3339()
-            ],
-        )?;
+        let config_value = |key: &str| {
+            conn.query_row(
+                "SELECT value FROM config WHERE key = ?1",
+                params![key],
+                |row| row.get::<_, String>(0),
+            )
+            .ok()
+        };
+        let threshold = config_value(OUTPUT_COMPRESSION_THRESHOLD_KEY)
+            .and_then(|value| value.trim().parse::<usize>().ok());
+        let max_line_length = config_value(OUTPUT_MAX_LINE_LENGTH_KEY)
+            .and_then(|value| value.trim().parse::<usize>().ok())
+            .filter(|&max_len| max_len > 0);
+
+        let truncated = max_line_length.and_then(|max_len| truncate_line(content, max_len));
+        let full_content = match &truncated {
+            Some(_)
+                if config_value(OUTPUT_KEEP_FULL_LINES_KEY)
+                    .is_some_and(|value| value.trim() == "true") =>
+            {
+                Some(compress_content(content)?)
+            }
+            _ => None,
+        };
+        let content = truncated.as_deref().unwrap_or(content);
+
+        match threshold {
+            Some(threshold) if content.len() > threshold => conn.execute(
+                "INSERT INTO output_logs (session_id, stream, content, compressed, truncated, full_content, created_at) VALUES (?1, ?2, ?3, 1, ?4, ?5, ?6)",
+                params![
+                    session_id.to_string(),
+                    stream.as_str(),
+                    compress_content(content)?,
+                    truncated.is_some(),
+                    full_content,
+                    now.to_rfc3339()
+                ],
+            )?,
+            _ => conn.execute(
+                "INSERT INTO output_logs (session_id, stream, content, truncated, full_content, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    session_id.to_string(),
+                    stream.as_str(),
+                    content,
+                    truncated.is_some(),
+                    full_content,
+                    now.to_rfc3339()
+                ],
+            )?,
+        };
 
         let id = conn.last_insert_rowid();
 
@@ -555,10 +1968,39 @@ impl Database {
             session_id,
             stream,
             content: content.to_string(),
+            truncated: truncated.is_some(),
             created_at: now,
         })
     }
 
+    /// Get an output log entry with the full text of a truncated line, if it was kept
+    pub fn get_full_output_log(&self, session_id: Uuid, id: i64) -> DbResult<OutputLog> {
+        let conn = self.conn()?;
+
+        let (mut log, full_content) = conn
+            .query_row(
+                "SELECT id, session_id, stream, content, compressed, truncated, created_at, full_content
+                 FROM output_logs WHERE session_id = ?1 AND id = ?2",
+                params![session_id.to_string(), id],
+                |row| Ok((output_log_from_row(row)?, row.get::<_, Option<Vec<u8>>>(7)?)),
+            )
+            .map_err(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => DbError::NotFound,
+                e => DbError::Sqlite(e),
+            })?;
+
+        if let Some(bytes) = full_content {
+            let mut content = String::new();
+            flate2::read::GzDecoder::new(bytes.as_slice())
+                .read_to_string(&mut content)
+                .map_err(|e| DbError::InvalidData(format!("corrupt full output line: {}", e)))?;
+            log.content = content;
+            log.truncated = false;
+        }
+
+        Ok(log)
+    }
+
     /// List output logs for a session
     ///
     /// # Arguments
@@ -573,68 +2015,249 @@ impl Database {
         limit: Option<i64>,
         offset: Option<i64>,
     ) -> DbResult<Vec<OutputLog>> {
-        let conn = self.conn.lock().unwrap();
+        let conn = self.conn()?;
+
+        let base_query = "SELECT id, session_id, stream, content, compressed, truncated, created_at FROM output_logs WHERE session_id = ?1";
+
+        // SQLite requires LIMIT when using OFFSET, so use -1 (unlimited) when only offset is provided
+        let query = match (stream_filter, limit, offset) {
+            (Some(_), Some(lim), Some(off)) => format!(
+                "{} AND stream = ?2 ORDER BY id LIMIT {} OFFSET {}",
+                base_query, lim, off
+            ),
+            (Some(_), Some(lim), None) => {
+                format!("{} AND stream = ?2 ORDER BY id LIMIT {}", base_query, lim)
+            }
+            (Some(_), None, Some(off)) => {
+                format!("{} AND stream = ?2 ORDER BY id LIMIT -1 OFFSET {}", base_query, off)
+            }
+            (Some(_), None, None) => format!("{} AND stream = ?2 ORDER BY id", base_query),
+            (None, Some(lim), Some(off)) => {
+                format!("{} ORDER BY id LIMIT {} OFFSET {}", base_query, lim, off)
+            }
+            (None, Some(lim), None) => format!("{} ORDER BY id LIMIT {}", base_query, lim),
+            (None, None, Some(off)) => format!("{} ORDER BY id LIMIT -1 OFFSET {}", base_query, off),
+            (None, None, None) => format!("{} ORDER BY id", base_query),
+        };
+
+        let logs = if let Some(stream) = stream_filter {
+            let mut stmt = conn.prepare(&query)?;
+            stmt.query_map(params![session_id.to_string(), stream.as_str()], output_log_from_row)?
+            .collect::<Result<Vec<_>, _>>()?
+        } else {
+            let mut stmt = conn.prepare(&query)?;
+            stmt.query_map(params![session_id.to_string()], output_log_from_row)?
+            .collect::<Result<Vec<_>, _>>()?
+        };
+
+        Ok(logs)
+    }
+
+    /// Id after which only a session's newest `count` output logs remain
+    /// (0 if it has no more than `count`)
+    pub fn output_log_tail_start(&self, session_id: Uuid, count: i64) -> DbResult<i64> {
+        let conn = self.conn()?;
+        match conn.query_row(
+            "SELECT id FROM output_logs WHERE session_id = ?1
+             ORDER BY id DESC LIMIT 1 OFFSET ?2",
+            params![session_id.to_string(), count],
+            |row| row.get(0),
+        ) {
+            Ok(id) => Ok(id),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(0),
+            Err(e) => Err(DbError::Sqlite(e)),
+        }
+    }
+
+    /// List up to `limit` output logs with an id greater than `after_id`,
+    /// oldest first, optionally for one stream only
+    pub fn list_output_logs_batch(
+        &self,
+        session_id: Uuid,
+        stream_filter: Option<OutputStream>,
+        after_id: i64,
+        limit: i64,
+    ) -> DbResult<Vec<OutputLog>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, session_id, stream, content, compressed, truncated, created_at FROM output_logs
+             WHERE session_id = ?1 AND id > ?2 AND (?3 IS NULL OR stream = ?3)
+             ORDER BY id LIMIT ?4",
+        )?;
+
+        let logs = stmt
+            .query_map(
+                params![
+                    session_id.to_string(),
+                    after_id,
+                    stream_filter.map(|s| s.as_str()),
+                    limit
+                ],
+                output_log_from_row,
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(logs)
+    }
+
+    /// Delete output logs for a session
+    pub fn delete_output_logs(&self, session_id: Uuid) -> DbResult<()> {
+        let conn = self.conn()?;
+        conn.execute(
+            "DELETE FROM output_logs WHERE session_id = ?1",
+            params![session_id.to_string()],
+        )?;
+        Ok(())
+    }
+
+    /// Get the last `limit` lines of a stream for a session, oldest first
+    pub fn tail_output_lines(
+        &self,
+        session_id: Uuid,
+        stream: OutputStream,
+        limit: i64,
+    ) -> DbResult<Vec<String>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT content, compressed FROM output_logs WHERE session_id = ?1 AND stream = ?2 ORDER BY id DESC LIMIT ?3",
+        )?;
+
+        let mut lines = stmt
+            .query_map(params![session_id.to_string(), stream.as_str(), limit], |row| {
+                parse_log_content(row, 0, 1)
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        lines.reverse();
+
+        Ok(lines)
+    }
+
+    // ==================== Event Operations ====================
+
+    /// Append an event to the lifecycle feed
+    pub fn insert_event(
+        &self,
+        kind: EventKind,
+        session_id: Option<Uuid>,
+        repo_id: Option<Uuid>,
+        data: serde_json::Value,
+    ) -> DbResult<Event> {
+        let conn = self.conn()?;
+        let now = Utc::now();
+
+        conn.execute(
+            "INSERT INTO events (event_type, session_id, repo_id, data, created_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                kind.as_str(),
+                session_id.map(|id| id.to_string()),
+                repo_id.map(|id| id.to_string()),
+                data.to_string(),
+                now.to_rfc3339()
+            ],
+        )?;
+
+        Ok(Event {
+            id: conn.last_insert_rowid(),
+            kind,
+            session_id,
+            repo_id,
+            data,
+            created_at: now,
+        })
+    }
+
+    /// List events with an id greater than `after_id`, oldest first
+    pub fn list_events_after(&self, after_id: i64, limit: i64) -> DbResult<Vec<Event>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, event_type, session_id, repo_id, data, created_at FROM events WHERE id > ?1 ORDER BY id LIMIT ?2",
+        )?;
+
+        let events = stmt
+            .query_map(params![after_id, limit], event_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(events)
+    }
+
+    /// List events matching `filter`, newest first
+    pub fn list_events(&self, filter: &EventFilter, limit: i64, offset: i64) -> DbResult<Vec<Event>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, event_type, session_id, repo_id, data, created_at FROM events
+             WHERE (?1 IS NULL OR event_type = ?1)
+               AND (?2 IS NULL OR created_at >= ?2)
+               AND (?3 IS NULL OR created_at < ?3)
+             ORDER BY id DESC LIMIT ?4 OFFSET ?5",
+        )?;
+
+        let events = stmt
+            .query_map(
+                params![
+                    filter.kind.map(|k| k.as_str()),
+                    filter.since.map(|t| t.to_rfc3339()),
+                    filter.until.map(|t| t.to_rfc3339()),
+                    limit,
+                    offset
+                ],
+                event_from_row,
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(events)
+    }
 
-        let base_query = "SELECT id, session_id, stream, content, created_at FROM output_logs WHERE session_id = ?1";
+    // ==================== Session Error Operations ====================
 
-        // SQLite requires LIMIT when using OFFSET, so use -1 (unlimited) when only offset is provided
-        let query = match (stream_filter, limit, offset) {
-            (Some(_), Some(lim), Some(off)) => format!(
-                "{} AND stream = ?2 ORDER BY id LIMIT {} OFFSET {}",
-                base_query, lim, off
-            ),
-            (Some(_), Some(lim), None) => {
-                format!("{} AND stream = ?2 ORDER BY id LIMIT {}", base_query, lim)
-            }
-            (Some(_), None, Some(off)) => {
-                format!("{} AND stream = ?2 ORDER BY id LIMIT -1 OFFSET {}", base_query, off)
-            }
-            (Some(_), None, None) => format!("{} AND stream = ?2 ORDER BY id", base_query),
-            (None, Some(lim), Some(off)) => {
-                format!("{} ORDER BY id LIMIT {} OFFSET {}", base_query, lim, off)
-            }
-            (None, Some(lim), None) => format!("{} ORDER BY id LIMIT {}", base_query, lim),
-            (None, None, Some(off)) => format!("{} ORDER BY id LIMIT -1 OFFSET {}", base_query, off),
-            (None, None, None) => format!("{} ORDER BY id", base_query),
-        };
+    /// Record the failure context of a session's run, replacing any previous one
+    pub fn upsert_session_error(
+        &self,
+        session_id: Uuid,
+        exit_code: Option<i32>,
+        reason: &str,
+        stderr_tail: &[String],
+    ) -> DbResult<SessionError> {
+        let conn = self.conn()?;
+        let now = Utc::now();
+        let tail = serde_json::to_string(stderr_tail)
+            .map_err(|e| DbError::InvalidData(e.to_string()))?;
 
-        let logs = if let Some(stream) = stream_filter {
-            let mut stmt = conn.prepare(&query)?;
-            stmt.query_map(params![session_id.to_string(), stream.as_str()], |row| {
-                Ok(OutputLog {
-                    id: row.get(0)?,
-                    session_id: parse_uuid(row, 1, "session_id")?,
-                    stream: parse_enum(row, 2, "stream", OutputStream::from_str)?,
-                    content: row.get(3)?,
-                    created_at: parse_datetime(row, 4, "created_at")?,
-                })
-            })?
-            .collect::<Result<Vec<_>, _>>()?
-        } else {
-            let mut stmt = conn.prepare(&query)?;
-            stmt.query_map(params![session_id.to_string()], |row| {
-                Ok(OutputLog {
-                    id: row.get(0)?,
-                    session_id: parse_uuid(row, 1, "session_id")?,
-                    stream: parse_enum(row, 2, "stream", OutputStream::from_str)?,
-                    content: row.get(3)?,
-                    created_at: parse_datetime(row, 4, "created_at")?,
-                })
-            })?
-            .collect::<Result<Vec<_>, _>>()?
-        };
+        conn.execute(
+            "INSERT INTO session_errors (session_id, exit_code, reason, stderr_tail, created_at) VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(session_id) DO UPDATE SET exit_code = ?2, reason = ?3, stderr_tail = ?4, created_at = ?5",
+            params![session_id.to_string(), exit_code, reason, tail, now.to_rfc3339()],
+        )?;
 
-        Ok(logs)
+        Ok(SessionError {
+            session_id,
+            exit_code,
+            reason: reason.to_string(),
+            stderr_tail: stderr_tail.to_vec(),
+            created_at: now,
+        })
     }
 
-    /// Delete output logs for a session
-    pub fn delete_output_logs(&self, session_id: Uuid) -> DbResult<()> {
-        let conn = self.conn.lock().unwrap();
-        conn.execute(
-            "DELETE FROM output_logs WHERE session_id = ?1",
+    /// Get the recorded failure context for a session
+    pub fn get_session_error(&self, session_id: Uuid) -> DbResult<Option<SessionError>> {
+        let conn = self.conn()?;
+
+        match conn.query_row(
+            "SELECT session_id, exit_code, reason, stderr_tail, created_at FROM session_errors WHERE session_id = ?1",
             params![session_id.to_string()],
-        )?;
-        Ok(())
+            |row| {
+                Ok(SessionError {
+                    session_id: parse_uuid(row, 0, "session_id")?,
+                    exit_code: row.get(1)?,
+                    reason: row.get(2)?,
+                    stderr_tail: parse_json(row, 3, "stderr_tail")?,
+                    created_at: parse_datetime(row, 4, "created_at")?,
+                })
+            },
+        ) {
+            Ok(error) => Ok(Some(error)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(DbError::Sqlite(e)),
+        }
     }
 }
 
@@ -650,7 +2273,7 @@ mod tests {
         let repos = db.list_repos().expect("Failed to list repos");
         assert!(repos.is_empty());
 
-        let sessions = db.list_sessions().expect("Failed to list sessions");
+        let sessions = db.list_sessions(false).expect("Failed to list sessions");
         assert!(sessions.is_empty());
     }
 
@@ -676,6 +2299,24 @@ mod tests {
             .expect("Failed to get repo by path");
         assert_eq!(fetched_by_path.id, repo.id);
 
+        // Rename
+        let renamed = db
+            .update_repo_name(repo.id, "renamed")
+            .expect("Failed to rename repo");
+        assert_eq!(renamed.name, "renamed");
+        assert!(renamed.updated_at >= repo.updated_at);
+        assert!(matches!(
+            db.update_repo_name(Uuid::new_v4(), "missing"),
+            Err(DbError::NotFound)
+        ));
+
+        // Batch path lookup
+        let tracked = db
+            .tracked_repo_paths(&["/path/to/repo".to_string(), "/elsewhere".to_string()])
+            .expect("Failed to look up paths");
+        assert_eq!(tracked.len(), 1);
+        assert!(tracked.contains("/path/to/repo"));
+
         // List
         let repos = db.list_repos().expect("Failed to list repos");
         assert_eq!(repos.len(), 1);
@@ -714,8 +2355,22 @@ mod tests {
         let updated = db.get_session(session.id).expect("Failed to get session");
         assert_eq!(updated.status, SessionStatus::Running);
 
+        // Rename
+        db.update_session_name(session.id, Some("Renamed"))
+            .expect("Failed to rename session");
+        let renamed = db.get_session(session.id).expect("Failed to get session");
+        assert_eq!(renamed.name.as_deref(), Some("Renamed"));
+        assert!(renamed.updated_at >= updated.updated_at);
+        db.update_session_name(session.id, None)
+            .expect("Failed to clear session name");
+        assert!(db.get_session(session.id).unwrap().name.is_none());
+        assert!(matches!(
+            db.update_session_name(Uuid::new_v4(), Some("Missing")),
+            Err(DbError::NotFound)
+        ));
+
         // List
-        let sessions = db.list_sessions().expect("Failed to list sessions");
+        let sessions = db.list_sessions(false).expect("Failed to list sessions");
         assert_eq!(sessions.len(), 1);
 
         // List by repo
@@ -727,10 +2382,393 @@ mod tests {
         // Delete
         db.delete_session(session.id)
             .expect("Failed to delete session");
-        let sessions = db.list_sessions().expect("Failed to list sessions");
+        let sessions = db.list_sessions(false).expect("Failed to list sessions");
         assert!(sessions.is_empty());
     }
 
+    #[test]
+    fn test_insert_session_with_seq_concurrent() {
+        let db = Arc::new(Database::in_memory().expect("Failed to create in-memory database"));
+        let repo = db.insert_repo("/path/to/repo", "my-repo").unwrap();
+
+        let handles: Vec<_> = (0..16)
+            .map(|_| {
+                let db = db.clone();
+                std::thread::spawn(move || {
+                    db.insert_session_with_seq(repo.id, Orchestrator::Ralph, |seq| {
+                        Some(format!("feature-{}", seq))
+                    })
+                    .unwrap()
+                })
+            })
+            .collect();
+
+        let mut names: Vec<String> = handles
+            .into_iter()
+            .map(|h| h.join().unwrap().name.unwrap())
+            .collect();
+        names.sort_by_key(|n| n["feature-".len()..].parse::<i64>().unwrap());
+        let expected: Vec<String> = (1..=16).map(|i| format!("feature-{}", i)).collect();
+        assert_eq!(names, expected);
+
+        // Sequences are per repo
+        let other = db.insert_repo("/path/to/other", "other").unwrap();
+        let session = db
+            .insert_session_with_seq(other.id, Orchestrator::Ralph, |seq| Some(seq.to_string()))
+            .unwrap();
+        assert_eq!(session.name.as_deref(), Some("1"));
+
+        assert!(matches!(
+            db.insert_session_with_seq(Uuid::new_v4(), Orchestrator::Ralph, |_| None),
+            Err(DbError::NotFound)
+        ));
+    }
+
+    #[test]
+    fn test_insert_repo_with_session() {
+        let db = Database::in_memory().expect("Failed to create in-memory database");
+
+        let (repo, session) = db
+            .insert_repo_with_session(
+                "/path/to/repo",
+                "my-repo",
+                None,
+                Orchestrator::Ralph,
+                |seq| Some(format!("first-{}", seq)),
+            )
+            .unwrap();
+        assert_eq!(session.repo_id, repo.id);
+        assert_eq!(session.name.as_deref(), Some("first-1"));
+        assert_eq!(db.get_repo(repo.id).unwrap().path, "/path/to/repo");
+
+        // A failed insert leaves neither row behind
+        assert!(db
+            .insert_repo_with_session("/path/to/repo", "dup", None, Orchestrator::Ralph, |_| None)
+            .is_err());
+        assert_eq!(db.list_repos().unwrap().len(), 1);
+        assert_eq!(db.list_sessions(true).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_insert_repo_limited() {
+        let db = Database::in_memory().expect("Failed to create in-memory database");
+
+        db.insert_repo_limited("/path/to/a", "a", Some(2)).unwrap();
+        db.insert_repo_limited("/path/to/b", "b", Some(2)).unwrap();
+        assert!(matches!(
+            db.insert_repo_limited("/path/to/c", "c", Some(2)),
+            Err(DbError::ConstraintViolation(_))
+        ));
+        assert!(matches!(
+            db.insert_repo_with_session("/path/to/c", "c", Some(2), Orchestrator::Ralph, |_| None),
+            Err(DbError::ConstraintViolation(_))
+        ));
+        assert_eq!(db.count_repos().unwrap(), 2);
+
+        db.insert_repo_limited("/path/to/c", "c", None).unwrap();
+    }
+
+    #[test]
+    fn test_session_command() {
+        let db = Database::in_memory().expect("Failed to create in-memory database");
+        let repo = db
+            .insert_repo("/path/to/repo", "my-repo")
+            .expect("Failed to insert repo");
+        let session = db
+            .insert_session(repo.id, None, Orchestrator::Ralph)
+            .expect("Failed to insert session");
+
+        assert_eq!(db.get_session_command(session.id).unwrap(), None);
+
+        db.update_session_command(session.id, "ralph run --autonomous")
+            .expect("Failed to update command");
+        assert_eq!(
+            db.get_session_command(session.id).unwrap(),
+            Some("ralph run --autonomous".to_string())
+        );
+
+        assert!(matches!(
+            db.get_session_command(Uuid::new_v4()),
+            Err(DbError::NotFound)
+        ));
+    }
+
+    #[test]
+    fn test_session_backend() {
+        let db = Database::in_memory().expect("Failed to create in-memory database");
+        let repo = db.insert_repo("/path/to/repo", "my-repo").unwrap();
+        let session = db.insert_session(repo.id, None, Orchestrator::Ralph).unwrap();
+
+        assert_eq!(db.get_session_backend(session.id).unwrap(), None);
+        db.update_session_backend(session.id, Some("bedrock")).unwrap();
+        assert_eq!(
+            db.get_session_backend(session.id).unwrap().as_deref(),
+            Some("bedrock")
+        );
+        db.update_session_backend(session.id, None).unwrap();
+        assert_eq!(db.get_session_backend(session.id).unwrap(), None);
+
+        assert!(matches!(
+            db.update_session_backend(Uuid::new_v4(), None),
+            Err(DbError::NotFound)
+        ));
+    }
+
+    #[test]
+    fn test_session_checkpoint() {
+        let db = Database::in_memory().expect("Failed to create in-memory database");
+        let repo = db
+            .insert_repo("/path/to/repo", "my-repo")
+            .expect("Failed to insert repo");
+        let session = db
+            .insert_session(repo.id, None, Orchestrator::Ralph)
+            .expect("Failed to insert session");
+
+        assert_eq!(db.get_session_checkpoint(session.id).unwrap(), None);
+
+        db.update_session_checkpoint(session.id, "abc123")
+            .expect("Failed to update checkpoint");
+        assert_eq!(
+            db.get_session_checkpoint(session.id).unwrap(),
+            Some("abc123".to_string())
+        );
+
+        // Later runs move the checkpoint but keep the base
+        db.update_session_checkpoint(session.id, "def456").unwrap();
+        assert_eq!(
+            db.get_session_checkpoint(session.id).unwrap(),
+            Some("def456".to_string())
+        );
+        assert_eq!(db.get_session_base(session.id).unwrap(), Some("abc123".to_string()));
+
+        assert!(matches!(
+            db.update_session_checkpoint(Uuid::new_v4(), "abc123"),
+            Err(DbError::NotFound)
+        ));
+    }
+
+    #[test]
+    fn test_upsert_message_checkpoints() {
+        let db = Database::in_memory().expect("Failed to create in-memory database");
+        let repo = db.insert_repo("/path/to/repo", "my-repo").unwrap();
+        let session = db.insert_session(repo.id, None, Orchestrator::Ralph).unwrap();
+        let id = Uuid::new_v4();
+
+        let partial = db
+            .upsert_message(id, session.id, MessageRole::Assistant, "Working", true)
+            .unwrap();
+        assert!(partial.incomplete);
+
+        let done = db
+            .upsert_message(id, session.id, MessageRole::Assistant, "Working... done", false)
+            .unwrap();
+        assert!(!done.incomplete);
+        assert_eq!(done.created_at, partial.created_at);
+
+        let messages = db.list_messages(session.id).unwrap();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].content, "Working... done");
+        assert!(!messages[0].incomplete);
+    }
+
+    #[test]
+    fn test_file_database_uses_wal() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::new(dir.path().join("test.db")).unwrap();
+
+        let conn = db.conn().unwrap();
+        let mode: String = conn
+            .query_row("PRAGMA journal_mode", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(mode, "wal");
+        let synchronous: i64 = conn
+            .query_row("PRAGMA synchronous", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(synchronous, 1, "expected synchronous = NORMAL");
+    }
+
+    #[test]
+    fn test_concurrent_reads_do_not_deadlock() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::new(dir.path().join("test.db")).unwrap();
+        let repo = db.insert_repo("/path/to/repo", "my-repo").unwrap();
+        for _ in 0..3 {
+            db.insert_session(repo.id, None, Orchestrator::Ralph).unwrap();
+        }
+
+        let handles: Vec<_> = (0..POOL_SIZE * 2)
+            .map(|i| {
+                let db = db.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..20 {
+                        if i % 4 == 0 {
+                            db.insert_session(repo.id, None, Orchestrator::Ralph).unwrap();
+                        }
+                        assert!(db.list_sessions(false).unwrap().len() >= 3);
+                    }
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            handle.join().expect("list_sessions panicked");
+        }
+        assert_eq!(db.list_sessions(false).unwrap().len(), 3 + 2 * 20);
+    }
+
+    #[test]
+    fn test_update_session_branch() {
+        let db = Database::in_memory().expect("Failed to create in-memory database");
+        let repo = db.insert_repo("/path/to/repo", "my-repo").unwrap();
+        let session = db.insert_session(repo.id, None, Orchestrator::Ralph).unwrap();
+        assert_eq!(session.branch, None);
+
+        db.update_session_branch(session.id, Some("feature/foo")).unwrap();
+        assert_eq!(
+            db.get_session(session.id).unwrap().branch.as_deref(),
+            Some("feature/foo")
+        );
+        assert_eq!(
+            db.list_sessions(false).unwrap()[0].branch.as_deref(),
+            Some("feature/foo")
+        );
+
+        db.update_session_branch(session.id, None).unwrap();
+        assert_eq!(db.get_session(session.id).unwrap().branch, None);
+        assert!(matches!(
+            db.update_session_branch(Uuid::new_v4(), None),
+            Err(DbError::NotFound)
+        ));
+    }
+
+    #[test]
+    fn test_list_session_summaries() {
+        let db = Database::in_memory().expect("Failed to create in-memory database");
+        let repo = db.insert_repo("/path/to/repo", "my-repo").unwrap();
+        let talked_to = db.insert_session(repo.id, None, Orchestrator::Ralph).unwrap();
+        let quiet = db.insert_session(repo.id, None, Orchestrator::Ralph).unwrap();
+        db.insert_message(talked_to.id, MessageRole::User, "Hello!").unwrap();
+        let last = db
+            .insert_message(talked_to.id, MessageRole::Assistant, "Hi!")
+            .unwrap();
+
+        let summaries = db.list_session_summaries(false).unwrap();
+
+        // The newer message outranks the quiet session's later creation
+        assert_eq!(summaries.len(), 2);
+        assert_eq!(summaries[0].session.id, talked_to.id);
+        assert_eq!(summaries[0].message_count, 2);
+        assert_eq!(summaries[0].last_message_at, Some(last.created_at));
+        assert_eq!(summaries[1].session.id, quiet.id);
+        assert_eq!(summaries[1].message_count, 0);
+        assert_eq!(summaries[1].last_message_at, None);
+    }
+
+    #[test]
+    fn test_archive_session() {
+        let db = Database::in_memory().expect("Failed to create in-memory database");
+        let repo = db.insert_repo("/path/to/repo", "my-repo").unwrap();
+        let kept = db.insert_session(repo.id, None, Orchestrator::Ralph).unwrap();
+        let archived = db.insert_session(repo.id, None, Orchestrator::Ralph).unwrap();
+
+        db.archive_session(archived.id).unwrap();
+        assert!(db.get_session(archived.id).unwrap().archived);
+
+        let visible = db.list_sessions(false).unwrap();
+        assert_eq!(visible.len(), 1);
+        assert_eq!(visible[0].id, kept.id);
+        assert_eq!(db.list_sessions(true).unwrap().len(), 2);
+
+        db.unarchive_session(archived.id).unwrap();
+        assert_eq!(db.list_sessions(false).unwrap().len(), 2);
+
+        assert!(matches!(
+            db.archive_session(Uuid::new_v4()),
+            Err(DbError::NotFound)
+        ));
+    }
+
+    #[test]
+    fn test_search_messages() {
+        let db = Database::in_memory().expect("Failed to create in-memory database");
+        let repo = db.insert_repo("/path/to/repo", "my-repo").unwrap();
+        let first = db.insert_session(repo.id, None, Orchestrator::Ralph).unwrap();
+        let second = db.insert_session(repo.id, None, Orchestrator::Ralph).unwrap();
+
+        db.insert_message(first.id, MessageRole::User, "Fix the build")
+            .unwrap();
+        db.insert_message(first.id, MessageRole::System, "error: connection refused")
+            .unwrap();
+        db.insert_message(second.id, MessageRole::System, "Connection refused by proxy")
+            .unwrap();
+
+        let matches = db.search_messages("connection refused", 10).unwrap();
+        let mut sessions: Vec<Uuid> = matches.iter().map(|m| m.session_id).collect();
+        sessions.sort();
+        let mut expected = vec![first.id, second.id];
+        expected.sort();
+        assert_eq!(sessions, expected);
+
+        // Operators and quotes in user input are matched literally
+        assert_eq!(db.search_messages("error: \"", 10).unwrap().len(), 1);
+        assert!(db.search_messages("   ", 10).unwrap().is_empty());
+
+        // Checkpointed content is re-indexed on update
+        // Incomplete responses are indexed only once complete
+        let id = Uuid::new_v4();
+        db.upsert_message(id, second.id, MessageRole::Assistant, "Working", true)
+            .unwrap();
+        db.upsert_message(id, second.id, MessageRole::Assistant, "Working on deploy", true)
+            .unwrap();
+        assert!(db.search_messages("working", 10).unwrap().is_empty());
+        db.upsert_message(id, second.id, MessageRole::Assistant, "Deployed", false)
+            .unwrap();
+        assert!(db.search_messages("working", 10).unwrap().is_empty());
+        assert_eq!(db.search_messages("deployed", 10).unwrap()[0].id, id);
+        db.upsert_message(Uuid::new_v4(), second.id, MessageRole::Assistant, "Pending", true)
+            .unwrap();
+
+        // Deleting a session removes its rows from the index
+        db.delete_session(second.id).unwrap();
+        let matches = db.search_messages("refused", 10).unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].session_id, first.id);
+        let conn = db.conn().unwrap();
+        conn.execute_batch("INSERT INTO messages_fts(messages_fts) VALUES ('integrity-check')")
+            .expect("FTS index out of sync with messages");
+    }
+
+    #[test]
+    fn test_list_messages_paged() {
+        let db = Database::in_memory().expect("Failed to create in-memory database");
+        let repo = db.insert_repo("/path/to/repo", "my-repo").unwrap();
+        let session = db.insert_session(repo.id, None, Orchestrator::Ralph).unwrap();
+        let other = db.insert_session(repo.id, None, Orchestrator::Ralph).unwrap();
+        db.insert_message(other.id, MessageRole::User, "elsewhere").unwrap();
+
+        for i in 0..50 {
+            db.insert_message(session.id, MessageRole::User, &format!("message {}", i))
+                .unwrap();
+        }
+
+        let mut seen = Vec::new();
+        let mut before = None;
+        let mut page_sizes = Vec::new();
+        loop {
+            let page = db.list_messages_paged(session.id, 20, before).unwrap();
+            if page.is_empty() {
+                break;
+            }
+            page_sizes.push(page.len());
+            before = page.last().map(|m| m.created_at);
+            seen.extend(page.into_iter().map(|m| m.content));
+        }
+
+        assert_eq!(page_sizes, vec![20, 20, 10]);
+        let expected: Vec<String> = (0..50).rev().map(|i| format!("message {}", i)).collect();
+        assert_eq!(seen, expected);
+    }
+
     #[test]
     fn test_message_crud() {
         let db = Database::in_memory().expect("Failed to create in-memory database");
@@ -790,6 +2828,200 @@ mod tests {
         assert!(value.is_none());
     }
 
+    #[test]
+    fn test_get_config_as() {
+        let db = Database::in_memory().expect("Failed to create in-memory database");
+
+        assert_eq!(db.get_config_as::<u32>("depth").unwrap(), None);
+
+        db.set_config("depth", " 50 ").unwrap();
+        assert_eq!(db.get_config_as::<u32>("depth").unwrap(), Some(50));
+
+        db.set_config("commit_session_trailer", "yes").unwrap();
+        match db.get_config_as::<bool>("commit_session_trailer") {
+            Err(DbError::ParseError { message, value, field }) => {
+                assert!(message.contains("commit_session_trailer"));
+                assert_eq!(value, "yes");
+                assert_eq!(field, "commit_session_trailer");
+            }
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_secret_config_round_trip() {
+        let db = Database::in_memory()
+            .expect("Failed to create in-memory database")
+            .with_secret("passphrase");
+
+        db.set_config("secret/anthropic_api_key", "sk-ant-123").unwrap();
+        assert_eq!(
+            db.get_config("secret/anthropic_api_key").unwrap().as_deref(),
+            Some("sk-ant-123")
+        );
+        let stored = db.list_config().unwrap();
+        assert!(stored[0].1.starts_with("enc:"));
+
+        // Without the passphrase secrets can be neither read nor written
+        let locked = Database { secrets: None, ..db.clone() };
+        let err = locked.get_config("secret/anthropic_api_key").unwrap_err();
+        assert!(matches!(&err, DbError::Secret(msg) if msg.contains(secrets::SECRET_ENV)));
+        assert!(matches!(locked.set_config("secret/other", "x"), Err(DbError::Secret(_))));
+
+        // Plain keys are unaffected
+        locked.set_config("backend", "claude").unwrap();
+        assert_eq!(locked.get_config("backend").unwrap().as_deref(), Some("claude"));
+    }
+
+    #[test]
+    fn test_repo_config_crud() {
+        let db = Database::in_memory().expect("Failed to create in-memory database");
+        let repo = db
+            .insert_repo("/path/to/repo", "my-repo")
+            .expect("Failed to insert repo");
+
+        assert_eq!(db.get_repo_config(repo.id, "denied_paths").unwrap(), None);
+
+        db.set_repo_config(repo.id, "denied_paths", r#"[".github/**"]"#)
+            .expect("Failed to set repo config");
+        assert_eq!(
+            db.get_repo_config(repo.id, "denied_paths").unwrap(),
+            Some(r#"[".github/**"]"#.to_string())
+        );
+        assert_eq!(db.list_repo_config(repo.id).unwrap().len(), 1);
+
+        // Unknown repo is rejected by the foreign key
+        assert!(matches!(
+            db.set_repo_config(Uuid::new_v4(), "denied_paths", "[]"),
+            Err(DbError::NotFound)
+        ));
+
+        // Removed along with the repo
+        db.delete_repo(repo.id).unwrap();
+        assert!(db.list_repo_config(repo.id).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_preset_upsert_and_delete() {
+        let db = Database::in_memory().expect("Failed to create in-memory database");
+
+        let created = db.upsert_preset("lint", "Lint", "", "Fix all lint warnings").unwrap();
+        assert_eq!(created.prompt, "Fix all lint warnings");
+
+        let updated = db.upsert_preset("lint", "Lint Fixer", "Tidy up", "Fix clippy").unwrap();
+        assert_eq!(updated.name, "Lint Fixer");
+        assert_eq!(updated.created_at, created.created_at);
+        assert_eq!(db.list_presets().unwrap().len(), 1);
+
+        db.delete_preset("lint").unwrap();
+        assert!(db.list_presets().unwrap().is_empty());
+        assert!(matches!(db.delete_preset("lint"), Err(DbError::NotFound)));
+    }
+
+    #[test]
+    fn test_session_template_crud() {
+        let db = Database::in_memory().expect("Failed to create in-memory database");
+
+        let mut fields = SessionTemplateFields {
+            name: "Bugfix".to_string(),
+            orchestrator: Orchestrator::Ralph,
+            backend: Some("claude".to_string()),
+            model: None,
+            preset: Some("debug".to_string()),
+            env: [("RUST_LOG".to_string(), "debug".to_string())].into(),
+            workdir: Some("backend".to_string()),
+            prompt: Some("Fix the failing test".to_string()),
+        };
+
+        let template = db
+            .insert_session_template(&fields)
+            .expect("Failed to insert template");
+        let fetched = db.get_session_template(template.id).unwrap();
+        assert_eq!(fetched.fields.name, "Bugfix");
+        assert_eq!(fetched.fields.env.get("RUST_LOG").map(String::as_str), Some("debug"));
+        assert_eq!(fetched.fields.prompt.as_deref(), Some("Fix the failing test"));
+
+        // Names are unique
+        assert!(matches!(
+            db.insert_session_template(&fields),
+            Err(DbError::ConstraintViolation(_))
+        ));
+
+        fields.prompt = None;
+        let updated = db.update_session_template(template.id, &fields).unwrap();
+        assert_eq!(updated.fields.prompt, None);
+        assert_eq!(db.list_session_templates().unwrap().len(), 1);
+
+        db.delete_session_template(template.id).unwrap();
+        assert!(matches!(
+            db.get_session_template(template.id),
+            Err(DbError::NotFound)
+        ));
+    }
+
+    #[test]
+    fn test_stats_counts_rows() {
+        let db = Database::in_memory().unwrap();
+        let repo = db.insert_repo("/path/to/repo", "repo").unwrap();
+        let session = db.insert_session(repo.id, None, Orchestrator::Ralph).unwrap();
+        db.insert_message(session.id, MessageRole::User, "hi").unwrap();
+        db.insert_message(session.id, MessageRole::Assistant, "hello").unwrap();
+
+        let stats = db.stats().unwrap();
+        assert!(stats.file_size.is_none());
+        assert!(stats.page_count > 0);
+        assert!(stats.page_size > 0);
+        assert_eq!(stats.tables["repos"], 1);
+        assert_eq!(stats.tables["sessions"], 1);
+        assert_eq!(stats.tables["messages"], 2);
+        assert_eq!(stats.tables["output_logs"], 0);
+        assert_eq!(stats.tables.len(), STATS_TABLES.len());
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let db = Database::new(temp_dir.path().join("stats.db")).unwrap();
+        db.conn()
+            .unwrap()
+            .execute_batch("PRAGMA wal_checkpoint(TRUNCATE);")
+            .unwrap();
+        let stats = db.stats().unwrap();
+        assert_eq!(stats.file_size, Some((stats.page_count * stats.page_size) as u64));
+    }
+
+    #[test]
+    fn test_repo_tags() {
+        let db = Database::in_memory().expect("Failed to create in-memory database");
+        let api = db.insert_repo("/path/to/api", "api").unwrap();
+        let web = db.insert_repo("/path/to/web", "web").unwrap();
+        db.insert_repo("/path/to/scratch", "scratch").unwrap();
+
+        db.add_repo_tag(api.id, "work").unwrap();
+        db.add_repo_tag(api.id, "work").unwrap();
+        db.add_repo_tag(api.id, "backend").unwrap();
+        db.set_repo_tags(web.id, &["work".to_string(), "frontend".to_string()])
+            .unwrap();
+
+        assert_eq!(db.list_repo_tags(api.id).unwrap(), vec!["backend", "work"]);
+        let work: Vec<_> = db
+            .list_repos_by_tag("work")
+            .unwrap()
+            .into_iter()
+            .map(|r| r.name)
+            .collect();
+        assert_eq!(work, vec!["api", "web"]);
+
+        db.remove_repo_tag(api.id, "work").unwrap();
+        assert!(matches!(db.remove_repo_tag(api.id, "work"), Err(DbError::NotFound)));
+        assert_eq!(db.list_repos_by_tag("work").unwrap().len(), 1);
+        assert!(matches!(
+            db.add_repo_tag(Uuid::new_v4(), "work"),
+            Err(DbError::NotFound)
+        ));
+
+        // Deleting a repo removes its tags
+        db.delete_repo(web.id).unwrap();
+        assert!(db.list_repos_by_tag("frontend").unwrap().is_empty());
+    }
+
     #[test]
     fn test_cascade_delete() {
         let db = Database::in_memory().expect("Failed to create in-memory database");
@@ -886,6 +3118,91 @@ mod tests {
         assert!(empty.is_empty());
     }
 
+    #[test]
+    fn test_count_sessions_by_status() {
+        let db = Database::in_memory().expect("Failed to create in-memory database");
+        let repo = db.insert_repo("/path/to/repo", "my-repo").unwrap();
+        let running = db.insert_session(repo.id, None, Orchestrator::Ralph).unwrap();
+        db.insert_session(repo.id, None, Orchestrator::Ralph).unwrap();
+        db.update_session_status(running.id, SessionStatus::Running).unwrap();
+
+        let counts = db.count_sessions_by_status().unwrap();
+        assert_eq!(counts["idle"], 1);
+        assert_eq!(counts["running"], 1);
+        assert_eq!(counts["error"], 0);
+        assert_eq!(counts.len(), 5);
+    }
+
+    #[test]
+    fn test_session_config_overrides_global() {
+        let db = Database::in_memory().expect("Failed to create in-memory database");
+        let repo = db.insert_repo("/path/to/repo", "my-repo").unwrap();
+        let first = db.insert_session(repo.id, None, Orchestrator::Ralph).unwrap();
+        let second = db.insert_session(repo.id, None, Orchestrator::Ralph).unwrap();
+
+        db.set_config("backend", "claude").unwrap();
+        db.set_session_config(first.id, "backend", "bedrock").unwrap();
+
+        assert_eq!(db.get_session_config(second.id, "backend").unwrap(), None);
+        assert_eq!(
+            db.resolve_session_config(first.id, "backend").unwrap().as_deref(),
+            Some("bedrock")
+        );
+        assert_eq!(
+            db.resolve_session_config(second.id, "backend").unwrap().as_deref(),
+            Some("claude")
+        );
+        assert_eq!(db.list_session_config(first.id).unwrap().len(), 1);
+
+        db.delete_session_config(first.id, "backend").unwrap();
+        assert_eq!(
+            db.resolve_session_config(first.id, "backend").unwrap().as_deref(),
+            Some("claude")
+        );
+
+        // Unknown session is rejected by the foreign key
+        assert!(matches!(
+            db.set_session_config(Uuid::new_v4(), "backend", "vertex"),
+            Err(DbError::NotFound)
+        ));
+
+        // Removed along with the session
+        db.set_session_config(second.id, "backend", "vertex").unwrap();
+        db.delete_session(second.id).unwrap();
+        assert!(db.list_session_config(second.id).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_list_output_logs_batch() {
+        let db = Database::in_memory().expect("Failed to create in-memory database");
+        let repo = db.insert_repo("/path/to/repo", "my-repo").unwrap();
+        let session = db.insert_session(repo.id, None, Orchestrator::Ralph).unwrap();
+
+        for i in 0..5 {
+            let stream = if i % 2 == 0 { OutputStream::Stdout } else { OutputStream::Stderr };
+            db.insert_output_log(session.id, stream, &format!("line {}", i)).unwrap();
+        }
+
+        let first = db.list_output_logs_batch(session.id, None, 0, 2).unwrap();
+        assert_eq!(first.len(), 2);
+        assert_eq!(first[0].content, "line 0");
+
+        let rest = db
+            .list_output_logs_batch(session.id, None, first[1].id, 10)
+            .unwrap();
+        let contents: Vec<&str> = rest.iter().map(|l| l.content.as_str()).collect();
+        assert_eq!(contents, ["line 2", "line 3", "line 4"]);
+
+        let stderr = db
+            .list_output_logs_batch(session.id, Some(OutputStream::Stderr), 0, 10)
+            .unwrap();
+        assert_eq!(stderr.len(), 2);
+        assert!(stderr.iter().all(|l| l.stream == OutputStream::Stderr));
+
+        assert_eq!(db.output_log_tail_start(session.id, 10).unwrap(), 0);
+        assert_eq!(db.output_log_tail_start(session.id, 3).unwrap(), first[1].id);
+    }
+
     #[test]
     fn test_output_log_cascade_delete() {
         let db = Database::in_memory().expect("Failed to create in-memory database");
@@ -911,4 +3228,227 @@ mod tests {
             .expect("Failed to list logs");
         assert!(logs.is_empty());
     }
+
+    #[test]
+    fn test_import_session() {
+        let db = Database::in_memory().expect("Failed to create in-memory database");
+        let repo = db.insert_repo("/path/to/repo", "repo").unwrap();
+        let mut session = db.insert_session(repo.id, Some("original"), Orchestrator::Ralph).unwrap();
+        session.status = SessionStatus::Running;
+        let messages = vec![db.insert_message(session.id, MessageRole::User, "Hello!").unwrap()];
+        let logs = vec![db.insert_output_log(session.id, OutputStream::Stderr, "oops").unwrap()];
+
+        let imported = db.import_session(repo.id, &session, &messages, &logs).unwrap();
+
+        assert_ne!(imported.id, session.id);
+        assert_eq!(imported.name.as_deref(), Some("original"));
+        assert_eq!(imported.status, SessionStatus::Cancelled);
+        assert_eq!(imported.created_at, session.created_at);
+
+        let imported_messages = db.list_messages(imported.id).unwrap();
+        assert_eq!(imported_messages.len(), 1);
+        assert_ne!(imported_messages[0].id, messages[0].id);
+        assert_eq!(imported_messages[0].content, "Hello!");
+        assert_eq!(imported_messages[0].created_at, messages[0].created_at);
+        let imported_logs = db.list_output_logs(imported.id, None, None, None).unwrap();
+        assert_eq!(imported_logs.len(), 1);
+        assert_eq!(imported_logs[0].stream, OutputStream::Stderr);
+        assert_eq!(imported_logs[0].content, "oops");
+
+        assert!(matches!(
+            db.import_session(Uuid::new_v4(), &session, &[], &[]),
+            Err(DbError::NotFound)
+        ));
+    }
+
+    #[test]
+    fn test_delete_sessions_by_status() {
+        let db = Database::in_memory().expect("Failed to create in-memory database");
+        let repo = db.insert_repo("/path/to/repo", "repo").unwrap();
+        let done = db.insert_session(repo.id, None, Orchestrator::Ralph).unwrap();
+        db.update_session_status(done.id, SessionStatus::Completed).unwrap();
+        db.insert_message(done.id, MessageRole::User, "Hello!").unwrap();
+        db.insert_output_log(done.id, OutputStream::Stdout, "output").unwrap();
+        let idle = db.insert_session(repo.id, None, Orchestrator::Ralph).unwrap();
+
+        assert_eq!(db.delete_sessions_by_status(SessionStatus::Completed).unwrap(), 1);
+        assert_eq!(db.delete_sessions_by_status(SessionStatus::Completed).unwrap(), 0);
+
+        assert!(matches!(db.get_session(done.id), Err(DbError::NotFound)));
+        assert!(db.list_messages(done.id).unwrap().is_empty());
+        assert!(db.list_output_logs(done.id, None, None, None).unwrap().is_empty());
+        assert_eq!(db.get_session(idle.id).unwrap().status, SessionStatus::Idle);
+    }
+
+    #[test]
+    fn test_session_error_records_stderr_tail() {
+        let db = Database::in_memory().expect("Failed to create in-memory database");
+        let repo = db.insert_repo("/path/to/repo", "repo").unwrap();
+        let session = db.insert_session(repo.id, None, Orchestrator::Ralph).unwrap();
+
+        assert!(db.get_session_error(session.id).unwrap().is_none());
+
+        for i in 0..5 {
+            db.insert_output_log(session.id, OutputStream::Stderr, &format!("err {}", i))
+                .unwrap();
+            db.insert_output_log(session.id, OutputStream::Stdout, &format!("out {}", i))
+                .unwrap();
+        }
+        let tail = db
+            .tail_output_lines(session.id, OutputStream::Stderr, 3)
+            .unwrap();
+        assert_eq!(tail, vec!["err 2", "err 3", "err 4"]);
+
+        db.upsert_session_error(session.id, Some(1), "exit status: 1", &tail)
+            .unwrap();
+        db.upsert_session_error(session.id, Some(2), "exit status: 2", &tail)
+            .unwrap();
+
+        let error = db.get_session_error(session.id).unwrap().unwrap();
+        assert_eq!(error.exit_code, Some(2));
+        assert_eq!(error.reason, "exit status: 2");
+        assert_eq!(error.stderr_tail, tail);
+    }
+
+    #[test]
+    fn test_truncate_line_respects_char_boundaries() {
+        assert_eq!(truncate_line("short", 10), None);
+        assert_eq!(
+            truncate_line("abcdef", 3).as_deref(),
+            Some("abc…[truncated]")
+        );
+        // "é" is two bytes; cutting inside it backs up to the previous boundary
+        assert_eq!(
+            truncate_line("aéb", 2).as_deref(),
+            Some("a…[truncated]")
+        );
+    }
+
+    #[test]
+    fn test_truncated_line_without_full_content() {
+        let db = Database::in_memory().unwrap();
+        let repo = db.insert_repo("/path/to/repo", "repo").unwrap();
+        let session = db.insert_session(repo.id, None, Orchestrator::Ralph).unwrap();
+        db.set_config(OUTPUT_MAX_LINE_LENGTH_KEY, "4").unwrap();
+
+        let log = db
+            .insert_output_log(session.id, OutputStream::Stderr, "0123456789")
+            .unwrap();
+        assert!(log.truncated);
+        assert_eq!(log.content, "0123…[truncated]");
+
+        // Nothing more to return when the full line was not kept
+        let fetched = db.get_full_output_log(session.id, log.id).unwrap();
+        assert_eq!(fetched.content, log.content);
+        assert!(fetched.truncated);
+    }
+
+    #[test]
+    fn test_output_log_compression_round_trip() {
+        let db = Database::in_memory().expect("Failed to create in-memory database");
+        let repo = db.insert_repo("/path/to/repo", "repo").unwrap();
+        let session = db.insert_session(repo.id, None, Orchestrator::Ralph).unwrap();
+
+        db.set_config(OUTPUT_COMPRESSION_THRESHOLD_KEY, "1024").unwrap();
+
+        let large = "compressible output line ".repeat(10_000);
+        db.insert_output_log(session.id, OutputStream::Stdout, &large)
+            .unwrap();
+        db.insert_output_log(session.id, OutputStream::Stderr, "small")
+            .unwrap();
+
+        let (compressed, stored_len): (bool, i64) = db
+            .conn()
+            .unwrap()
+            .query_row(
+                "SELECT compressed, length(content) FROM output_logs WHERE stream = 'stdout'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert!(compressed);
+        assert!((stored_len as usize) < large.len() / 10);
+
+        let logs = db.list_output_logs(session.id, None, None, None).unwrap();
+        assert_eq!(logs.len(), 2);
+        assert_eq!(logs[0].content, large);
+        assert_eq!(logs[1].content, "small");
+
+        let tail = db
+            .tail_output_lines(session.id, OutputStream::Stdout, 1)
+            .unwrap();
+        assert_eq!(tail, vec![large]);
+    }
+
+    #[test]
+    fn test_events_feed() {
+        let db = Database::in_memory().expect("Failed to create in-memory database");
+        let session_id = Uuid::new_v4();
+
+        let first = db
+            .insert_event(EventKind::SessionCreated, Some(session_id), None, serde_json::json!({}))
+            .unwrap();
+        let second = db
+            .insert_event(
+                EventKind::SessionStatusChanged,
+                Some(session_id),
+                None,
+                serde_json::json!({ "status": "running" }),
+            )
+            .unwrap();
+        assert!(second.id > first.id);
+
+        let events = db.list_events_after(0, 10).unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].kind, EventKind::SessionCreated);
+        assert_eq!(events[0].session_id, Some(session_id));
+        assert!(events[0].repo_id.is_none());
+
+        let events = db.list_events_after(first.id, 10).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].data["status"], "running");
+    }
+
+    #[test]
+    fn test_list_events_filtered() {
+        let db = Database::in_memory().expect("Failed to create in-memory database");
+        let session_id = Uuid::new_v4();
+        let before = Utc::now();
+
+        for kind in [
+            EventKind::SessionCreated,
+            EventKind::SessionStatusChanged,
+            EventKind::SessionStatusChanged,
+        ] {
+            db.insert_event(kind, Some(session_id), None, serde_json::json!({}))
+                .unwrap();
+        }
+
+        let all = db.list_events(&EventFilter::default(), 10, 0).unwrap();
+        assert_eq!(all.len(), 3);
+        assert!(all[0].id > all[2].id);
+
+        let page = db.list_events(&EventFilter::default(), 2, 2).unwrap();
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].kind, EventKind::SessionCreated);
+
+        let filter = EventFilter {
+            kind: Some(EventKind::SessionStatusChanged),
+            ..Default::default()
+        };
+        assert_eq!(db.list_events(&filter, 10, 0).unwrap().len(), 2);
+
+        let filter = EventFilter {
+            since: Some(before),
+            until: Some(before),
+            ..Default::default()
+        };
+        assert!(db.list_events(&filter, 10, 0).unwrap().is_empty());
+
+        let filter = EventFilter {
+            since: Some(before),
+            ..Default::default()
+        };
+        assert_eq!(db.list_events(&filter, 10, 0).unwrap().len(), 3);
+    }
 }
@@ -1,3 +1,5 @@
+use std::collections::{BTreeMap, HashMap};
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
@@ -44,6 +46,22 @@ impl SessionStatus {
             _ => Err(format!("invalid session status: '{}'", s)),
         }
     }
+
+    /// Whether a session may move from this status to `next`
+    ///
+    /// Staying in the same status is always allowed. Finished sessions may be
+    /// reset to idle or run again.
+    pub fn can_transition_to(&self, next: SessionStatus) -> bool {
+        use SessionStatus::*;
+
+        match (self, next) {
+            (a, b) if *a == b => true,
+            (Idle, Running | Cancelled) => true,
+            (Running, Completed | Error | Cancelled) => true,
+            (Completed | Error | Cancelled, Idle | Running) => true,
+            _ => false,
+        }
+    }
 }
 
 /// Orchestrator type for AI coding sessions
@@ -89,6 +107,53 @@ impl Default for Orchestrator {
     }
 }
 
+/// How a session's last run ended, in more detail than its status
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExitReason {
+    /// Exited successfully; whether it changed the repo is unknown
+    CompletedSuccess,
+    /// Exited successfully after committing or leaving changes
+    CompletedWithChanges,
+    /// Exited successfully without changing the repo
+    NoChanges,
+    /// Stopped by the user
+    Cancelled,
+    /// Killed for exceeding its time limit
+    TimedOut,
+    /// Exited unsuccessfully
+    Crashed,
+}
+
+impl ExitReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExitReason::CompletedSuccess => "completed_success",
+            ExitReason::CompletedWithChanges => "completed_with_changes",
+            ExitReason::NoChanges => "no_changes",
+            ExitReason::Cancelled => "cancelled",
+            ExitReason::TimedOut => "timed_out",
+            ExitReason::Crashed => "crashed",
+        }
+    }
+}
+
+impl std::str::FromStr for ExitReason {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "completed_success" => Ok(ExitReason::CompletedSuccess),
+            "completed_with_changes" => Ok(ExitReason::CompletedWithChanges),
+            "no_changes" => Ok(ExitReason::NoChanges),
+            "cancelled" => Ok(ExitReason::Cancelled),
+            "timed_out" => Ok(ExitReason::TimedOut),
+            "crashed" => Ok(ExitReason::Crashed),
+            _ => Err(format!("invalid exit reason: '{}'", s)),
+        }
+    }
+}
+
 /// Session model representing a Ralph session
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Session {
@@ -97,10 +162,29 @@ pub struct Session {
     pub name: Option<String>,
     pub orchestrator: Orchestrator,
     pub status: SessionStatus,
+    /// Archived sessions are hidden from the default session listing
+    #[serde(default)]
+    pub archived: bool,
+    /// Branch the session last ran on or checked out, if known
+    #[serde(default)]
+    pub branch: Option<String>,
+    /// How the last run ended; absent before the first run finishes and while running
+    #[serde(default)]
+    pub exit_reason: Option<ExitReason>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+/// A session with message activity, as shown in the session list
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionSummary {
+    #[serde(flatten)]
+    pub session: Session,
+    pub message_count: usize,
+    /// When the newest message was added; absent for sessions without messages
+    pub last_message_at: Option<DateTime<Utc>>,
+}
+
 /// Message role enum
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -136,6 +220,10 @@ pub struct Message {
     pub session_id: Uuid,
     pub role: MessageRole,
     pub content: String,
+    /// Set while an assistant response is still being generated, and left
+    /// set if generation was interrupted (e.g. by a server restart)
+    #[serde(default)]
+    pub incomplete: bool,
     pub created_at: DateTime<Utc>,
 }
 
@@ -171,9 +259,130 @@ pub struct OutputLog {
     pub session_id: Uuid,
     pub stream: OutputStream,
     pub content: String,
+    /// Whether `content` was cut to the configured maximum line length
+    #[serde(default)]
+    pub truncated: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Kind of server-wide lifecycle event
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    SessionCreated,
+    SessionStatusChanged,
+    SessionCompleted,
+    ConfigChanged,
+}
+
+impl EventKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventKind::SessionCreated => "session_created",
+            EventKind::SessionStatusChanged => "session_status_changed",
+            EventKind::SessionCompleted => "session_completed",
+            EventKind::ConfigChanged => "config_changed",
+        }
+    }
+}
+
+impl std::str::FromStr for EventKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "session_created" => Ok(EventKind::SessionCreated),
+            "session_status_changed" => Ok(EventKind::SessionStatusChanged),
+            "session_completed" => Ok(EventKind::SessionCompleted),
+            "config_changed" => Ok(EventKind::ConfigChanged),
+            _ => Err(format!("invalid event type: '{}'", s)),
+        }
+    }
+}
+
+/// Filter for listing recorded events
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EventFilter {
+    pub kind: Option<EventKind>,
+    /// Only events created at or after this time
+    pub since: Option<DateTime<Utc>>,
+    /// Only events created before this time
+    pub until: Option<DateTime<Utc>>,
+}
+
+/// Server-wide lifecycle event recorded in the events feed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Event {
+    pub id: i64,
+    #[serde(rename = "type")]
+    pub kind: EventKind,
+    pub session_id: Option<Uuid>,
+    pub repo_id: Option<Uuid>,
+    /// Event-specific payload (e.g. the new status)
+    pub data: serde_json::Value,
     pub created_at: DateTime<Utc>,
 }
 
+/// Failure context of a session's last failed run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionError {
+    pub session_id: Uuid,
+    /// Process exit code, absent if it was killed by a signal
+    pub exit_code: Option<i32>,
+    /// Human-readable exit reason (e.g. "exit status: 1")
+    pub reason: String,
+    /// Last stderr lines, oldest first
+    pub stderr_tail: Vec<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Configurable parts of a session template
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionTemplateFields {
+    pub name: String,
+    #[serde(default)]
+    pub orchestrator: Orchestrator,
+    /// AI backend id (see /config/backends)
+    #[serde(default)]
+    pub backend: Option<String>,
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Preset id (see /config/presets)
+    #[serde(default)]
+    pub preset: Option<String>,
+    /// Extra environment variables for the agent process
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    /// Working directory relative to the repository root
+    #[serde(default)]
+    pub workdir: Option<String>,
+    /// Initial prompt recorded when a session is created from the template
+    #[serde(default)]
+    pub prompt: Option<String>,
+}
+
+/// Saved, reusable session configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionTemplate {
+    pub id: Uuid,
+    #[serde(flatten)]
+    pub fields: SessionTemplateFields,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// User-defined workflow preset; one with a built-in preset's id overrides it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomPreset {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    /// Instructions given to the agent when the preset is selected
+    pub prompt: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
 /// Configuration entry
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConfigEntry {
@@ -181,3 +390,14 @@ pub struct ConfigEntry {
     pub value: String,
     pub updated_at: DateTime<Utc>,
 }
+
+/// Storage statistics of the database
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DbStats {
+    /// Size of the database and its WAL file on disk, absent for in-memory databases
+    pub file_size: Option<u64>,
+    pub page_count: i64,
+    pub page_size: i64,
+    /// Row count per table
+    pub tables: BTreeMap<String, i64>,
+}
@@ -4,17 +4,116 @@
 /// - repos: Git repositories being managed
 /// - sessions: Ralph sessions tied to repos
 /// - messages: Chat messages within sessions
+/// - messages_fts: Full-text index over message content
 /// - output_logs: Raw output from Ralph processes
 /// - config: Key-value configuration storage
+/// - repo_config: Per-repository key-value configuration
+/// - repo_tags: Free-form labels for grouping repositories
+/// - session_config: Per-session overrides of global configuration
+/// - session_templates: Saved session configurations
+/// - presets: User-defined workflow presets
+/// - session_errors: Failure context of the last failed run per session
+/// - events: Server-wide session lifecycle feed
+/// - secret_salt: Random salt the secret config encryption key is derived with
 
 /// Schema version for migrations
-pub const SCHEMA_VERSION: i32 = 2;
+pub const SCHEMA_VERSION: i32 = 15;
 
 /// Migration from v1 to v2: Add orchestrator column to sessions
 pub const MIGRATE_V1_TO_V2: &str = r#"
 ALTER TABLE sessions ADD COLUMN orchestrator TEXT NOT NULL DEFAULT 'ralph';
 "#;
 
+/// Migration from v2 to v3: Add resolved agent command line to sessions
+pub const MIGRATE_V2_TO_V3: &str = r#"
+ALTER TABLE sessions ADD COLUMN command TEXT;
+"#;
+
+/// Migration from v3 to v4: Flag gzip-compressed output log content
+pub const MIGRATE_V3_TO_V4: &str = r#"
+ALTER TABLE output_logs ADD COLUMN compressed INTEGER NOT NULL DEFAULT 0;
+"#;
+
+/// Migration from v4 to v5: Add run checkpoint (HEAD sha at run start) to sessions
+pub const MIGRATE_V4_TO_V5: &str = r#"
+ALTER TABLE sessions ADD COLUMN checkpoint_sha TEXT;
+"#;
+
+/// Migration from v5 to v6: Add per-repo session sequence counter
+pub const MIGRATE_V5_TO_V6: &str = r#"
+ALTER TABLE repos ADD COLUMN session_seq INTEGER NOT NULL DEFAULT 0;
+UPDATE repos SET session_seq = (SELECT COUNT(*) FROM sessions WHERE sessions.repo_id = repos.id);
+"#;
+
+/// Migration from v6 to v7: Record the AI backend a session's last run used
+pub const MIGRATE_V6_TO_V7: &str = r#"
+ALTER TABLE sessions ADD COLUMN backend TEXT;
+"#;
+
+/// Migration from v7 to v8: Flag assistant messages still being generated
+pub const MIGRATE_V7_TO_V8: &str = r#"
+ALTER TABLE messages ADD COLUMN incomplete INTEGER NOT NULL DEFAULT 0;
+"#;
+
+/// Migration from v8 to v9: Index messages written before full-text search existed
+pub const MIGRATE_V8_TO_V9: &str = r#"
+INSERT INTO messages_fts(messages_fts) VALUES ('rebuild');
+"#;
+
+/// Migration from v9 to v10: Flag sessions hidden from the default listing
+pub const MIGRATE_V9_TO_V10: &str = r#"
+ALTER TABLE sessions ADD COLUMN archived INTEGER NOT NULL DEFAULT 0;
+"#;
+
+/// Migration from v10 to v11: Record the branch a session works on
+pub const MIGRATE_V10_TO_V11: &str = r#"
+ALTER TABLE sessions ADD COLUMN branch TEXT;
+"#;
+
+/// Migration from v11 to v12: Flag truncated output lines and keep their full content
+pub const MIGRATE_V11_TO_V12: &str = r#"
+ALTER TABLE output_logs ADD COLUMN truncated INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE output_logs ADD COLUMN full_content BLOB;
+"#;
+
+/// Migration from v12 to v13: Classify how each session's last run ended
+pub const MIGRATE_V12_TO_V13: &str = r#"
+ALTER TABLE sessions ADD COLUMN exit_reason TEXT;
+"#;
+
+/// Migration from v13 to v14: Leave incomplete messages out of the full-text index
+pub const MIGRATE_V13_TO_V14: &str = r#"
+DROP TRIGGER IF EXISTS messages_fts_insert;
+DROP TRIGGER IF EXISTS messages_fts_delete;
+DROP TRIGGER IF EXISTS messages_fts_update;
+INSERT INTO messages_fts(messages_fts, rowid, content)
+    SELECT 'delete', rowid, content FROM messages WHERE incomplete = 1;
+CREATE TRIGGER IF NOT EXISTS messages_fts_insert AFTER INSERT ON messages
+WHEN new.incomplete = 0 BEGIN
+    INSERT INTO messages_fts(rowid, content) VALUES (new.rowid, new.content);
+END;
+
+CREATE TRIGGER IF NOT EXISTS messages_fts_delete AFTER DELETE ON messages
+WHEN old.incomplete = 0 BEGIN
+    INSERT INTO messages_fts(messages_fts, rowid, content) VALUES ('delete', old.rowid, old.content);
+END;
+
+CREATE TRIGGER IF NOT EXISTS messages_fts_update AFTER UPDATE OF content, incomplete ON messages
+WHEN old.incomplete = 0 OR new.incomplete = 0 BEGIN
+    INSERT INTO messages_fts(messages_fts, rowid, content)
+        SELECT 'delete', old.rowid, old.content WHERE old.incomplete = 0;
+    INSERT INTO messages_fts(rowid, content) SELECT new.rowid, new.content WHERE new.incomplete = 0;
+END;
+"#;
+
+/// Migration from v14 to v15: Record the sha a session's first run started from.
+/// Sessions that already ran start from their latest checkpoint, the best
+/// baseline still known.
+pub const MIGRATE_V14_TO_V15: &str = r#"
+ALTER TABLE sessions ADD COLUMN base_sha TEXT;
+UPDATE sessions SET base_sha = checkpoint_sha;
+"#;
+
 /// SQL to create all tables
 pub const CREATE_TABLES: &str = r#"
 -- Repositories table
@@ -22,6 +121,7 @@ CREATE TABLE IF NOT EXISTS repos (
     id TEXT PRIMARY KEY,
     path TEXT NOT NULL UNIQUE,
     name TEXT NOT NULL,
+    session_seq INTEGER NOT NULL DEFAULT 0,
     created_at TEXT NOT NULL,
     updated_at TEXT NOT NULL
 );
@@ -33,6 +133,13 @@ CREATE TABLE IF NOT EXISTS sessions (
     name TEXT,
     orchestrator TEXT NOT NULL DEFAULT 'ralph',
     status TEXT NOT NULL DEFAULT 'idle',
+    command TEXT,
+    checkpoint_sha TEXT,
+    base_sha TEXT,
+    backend TEXT,
+    archived INTEGER NOT NULL DEFAULT 0,
+    branch TEXT,
+    exit_reason TEXT,
     created_at TEXT NOT NULL,
     updated_at TEXT NOT NULL,
     FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE
@@ -44,16 +151,46 @@ CREATE TABLE IF NOT EXISTS messages (
     session_id TEXT NOT NULL,
     role TEXT NOT NULL,
     content TEXT NOT NULL,
+    incomplete INTEGER NOT NULL DEFAULT 0,
     created_at TEXT NOT NULL,
     FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE
 );
 
+-- Full-text index over message content, kept in sync with messages by triggers
+CREATE VIRTUAL TABLE IF NOT EXISTS messages_fts USING fts5(
+    content,
+    content='messages',
+    content_rowid='rowid'
+);
+
+-- Responses still being generated are indexed only once complete, so their
+-- checkpoints don't reindex the whole document each time
+CREATE TRIGGER IF NOT EXISTS messages_fts_insert AFTER INSERT ON messages
+WHEN new.incomplete = 0 BEGIN
+    INSERT INTO messages_fts(rowid, content) VALUES (new.rowid, new.content);
+END;
+
+CREATE TRIGGER IF NOT EXISTS messages_fts_delete AFTER DELETE ON messages
+WHEN old.incomplete = 0 BEGIN
+    INSERT INTO messages_fts(messages_fts, rowid, content) VALUES ('delete', old.rowid, old.content);
+END;
+
+CREATE TRIGGER IF NOT EXISTS messages_fts_update AFTER UPDATE OF content, incomplete ON messages
+WHEN old.incomplete = 0 OR new.incomplete = 0 BEGIN
+    INSERT INTO messages_fts(messages_fts, rowid, content)
+        SELECT 'delete', old.rowid, old.content WHERE old.incomplete = 0;
+    INSERT INTO messages_fts(rowid, content) SELECT new.rowid, new.content WHERE new.incomplete = 0;
+END;
+
 -- Output logs table (raw stdout/stderr from Ralph)
 CREATE TABLE IF NOT EXISTS output_logs (
     id INTEGER PRIMARY KEY AUTOINCREMENT,
     session_id TEXT NOT NULL,
     stream TEXT NOT NULL,
     content TEXT NOT NULL,
+    compressed INTEGER NOT NULL DEFAULT 0,
+    truncated INTEGER NOT NULL DEFAULT 0,
+    full_content BLOB,
     created_at TEXT NOT NULL,
     FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE
 );
@@ -65,6 +202,86 @@ CREATE TABLE IF NOT EXISTS config (
     updated_at TEXT NOT NULL
 );
 
+-- Per-repository config (key-value storage scoped to a repo)
+CREATE TABLE IF NOT EXISTS repo_config (
+    repo_id TEXT NOT NULL,
+    key TEXT NOT NULL,
+    value TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    PRIMARY KEY (repo_id, key),
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE
+);
+
+-- Free-form labels for grouping repositories
+CREATE TABLE IF NOT EXISTS repo_tags (
+    repo_id TEXT NOT NULL,
+    tag TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    PRIMARY KEY (repo_id, tag),
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE
+);
+
+-- Per-session config overriding global config keys for one session
+CREATE TABLE IF NOT EXISTS session_config (
+    session_id TEXT NOT NULL,
+    key TEXT NOT NULL,
+    value TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    PRIMARY KEY (session_id, key),
+    FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE
+);
+
+-- Session templates (reusable session configuration)
+CREATE TABLE IF NOT EXISTS session_templates (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    orchestrator TEXT NOT NULL DEFAULT 'ralph',
+    backend TEXT,
+    model TEXT,
+    preset TEXT,
+    env TEXT NOT NULL DEFAULT '{}',
+    workdir TEXT,
+    prompt TEXT,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+-- User-defined workflow presets (an id matching a built-in preset overrides it)
+CREATE TABLE IF NOT EXISTS presets (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    description TEXT NOT NULL DEFAULT '',
+    prompt TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+-- Failure context captured when a run ends in error (latest per session)
+CREATE TABLE IF NOT EXISTS session_errors (
+    session_id TEXT PRIMARY KEY,
+    exit_code INTEGER,
+    reason TEXT NOT NULL,
+    stderr_tail TEXT NOT NULL DEFAULT '[]',
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE
+);
+
+-- Server-wide lifecycle events feed
+CREATE TABLE IF NOT EXISTS events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    event_type TEXT NOT NULL,
+    session_id TEXT,
+    repo_id TEXT,
+    data TEXT NOT NULL DEFAULT '{}',
+    created_at TEXT NOT NULL
+);
+
+-- Salt for deriving the secret config key from RALPHTOWN_SECRET (a single row)
+CREATE TABLE IF NOT EXISTS secret_salt (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    salt TEXT NOT NULL
+);
+
 -- Schema version tracking
 CREATE TABLE IF NOT EXISTS schema_version (
     version INTEGER PRIMARY KEY
@@ -75,14 +292,15 @@ CREATE INDEX IF NOT EXISTS idx_sessions_repo_id ON sessions(repo_id);
 CREATE INDEX IF NOT EXISTS idx_sessions_status ON sessions(status);
 CREATE INDEX IF NOT EXISTS idx_messages_session_id ON messages(session_id);
 CREATE INDEX IF NOT EXISTS idx_output_logs_session_id ON output_logs(session_id);
+CREATE INDEX IF NOT EXISTS idx_repo_tags_tag ON repo_tags(tag);
 "#;
 
-/// SQL to insert or update schema version
-pub const UPSERT_SCHEMA_VERSION: &str = r#"
-INSERT OR REPLACE INTO schema_version (version) VALUES (?1)
+/// SQL to record that the schema reached a version
+pub const RECORD_SCHEMA_VERSION: &str = r#"
+INSERT OR IGNORE INTO schema_version (version) VALUES (?1)
 "#;
 
-/// SQL to get current schema version
+/// SQL to get current schema version (the highest one recorded)
 pub const GET_SCHEMA_VERSION: &str = r#"
-SELECT version FROM schema_version LIMIT 1
+SELECT MAX(version) FROM schema_version
 "#;
@@ -0,0 +1,179 @@
+//! Encryption at rest for secret config values
+//!
+//! Config keys under the `secret/` prefix (API keys and the like) are sealed
+//! with AES-256-GCM before they are written. The key is derived with Argon2
+//! from the `RALPHTOWN_SECRET` environment variable and a random salt stored
+//! in the database, so a copy of the database alone doesn't reveal them. The
+//! config key is bound as associated data, so a ciphertext can't be moved to
+//! another key.
+
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use argon2::Argon2;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+
+use super::{DbError, DbResult};
+
+/// Config keys with this prefix are encrypted at rest
+pub const SECRET_PREFIX: &str = "secret/";
+
+/// Environment variable holding the passphrase secret values are encrypted with
+pub const SECRET_ENV: &str = "RALPHTOWN_SECRET";
+
+/// Plain config keys that control access to the API itself: redacted like
+/// secrets, and writable only with the admin token
+pub const PROTECTED_KEYS: &[&str] = &["ws_auth_required", "ws_auth_token"];
+
+/// Placeholder returned instead of secret values in config listings
+pub const REDACTED: &str = "***";
+
+/// Marks stored values sealed by this module (and the format version)
+const SEALED_PREFIX: &str = "enc:v2:";
+
+/// Length of the random salt the key is derived with
+pub const SALT_LEN: usize = 16;
+
+/// Length of the AES-GCM nonce stored ahead of each ciphertext
+const NONCE_LEN: usize = 12;
+
+/// Whether values under `key` are encrypted at rest
+pub fn is_secret_key(key: &str) -> bool {
+    key.starts_with(SECRET_PREFIX)
+}
+
+/// Whether `key` is a protected access-control key
+pub fn is_protected_key(key: &str) -> bool {
+    PROTECTED_KEYS.contains(&key)
+}
+
+/// Whether values under `key` are hidden from config listings
+pub fn is_redacted_key(key: &str) -> bool {
+    is_secret_key(key) || is_protected_key(key)
+}
+
+/// A fresh random salt for `SecretBox::new`
+pub fn generate_salt() -> [u8; SALT_LEN] {
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    salt
+}
+
+/// The passphrase from `RALPHTOWN_SECRET`, or `None` if it is unset or empty
+pub fn passphrase_from_env() -> Option<String> {
+    std::env::var(SECRET_ENV)
+        .ok()
+        .filter(|passphrase| !passphrase.is_empty())
+}
+
+/// Cipher for secret config values, keyed from a passphrase
+#[derive(Clone)]
+pub struct SecretBox {
+    cipher: Aes256Gcm,
+}
+
+impl SecretBox {
+    pub fn new(passphrase: &str, salt: &[u8]) -> DbResult<Self> {
+        let mut key = [0u8; 32];
+        Argon2::default()
+            .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+            .map_err(|e| DbError::Secret(format!("Failed to derive secret key: {}", e)))?;
+
+        Ok(Self {
+            cipher: Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key)),
+        })
+    }
+
+    /// Encrypt `plaintext`, stored under config key `key`, with a fresh random nonce
+    pub fn seal(&self, key: &str, plaintext: &str) -> DbResult<String> {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let payload = Payload {
+            msg: plaintext.as_bytes(),
+            aad: key.as_bytes(),
+        };
+        let ciphertext = self
+            .cipher
+            .encrypt(&nonce, payload)
+            .map_err(|_| DbError::Secret("Failed to encrypt secret value".to_string()))?;
+
+        let mut sealed = nonce.to_vec();
+        sealed.extend_from_slice(&ciphertext);
+        Ok(format!("{}{}", SEALED_PREFIX, BASE64.encode(sealed)))
+    }
+
+    /// Decrypt a value `seal` produced for config key `key`
+    pub fn open(&self, key: &str, stored: &str) -> DbResult<String> {
+        let Some(encoded) = stored.strip_prefix(SEALED_PREFIX) else {
+            return Err(DbError::Secret("Stored secret value is not encrypted".to_string()));
+        };
+        let sealed = BASE64
+            .decode(encoded)
+            .map_err(|e| DbError::Secret(format!("Malformed secret value: {}", e)))?;
+        if sealed.len() < NONCE_LEN {
+            return Err(DbError::Secret("Malformed secret value: too short".to_string()));
+        }
+
+        let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+        let payload = Payload {
+            msg: ciphertext,
+            aad: key.as_bytes(),
+        };
+        let plaintext = self
+            .cipher
+            .decrypt(Nonce::from_slice(nonce), payload)
+            .map_err(|_| {
+                DbError::Secret(format!(
+                    "Failed to decrypt secret value; was {} changed?",
+                    SECRET_ENV
+                ))
+            })?;
+
+        String::from_utf8(plaintext)
+            .map_err(|e| DbError::Secret(format!("Secret value is not UTF-8: {}", e)))
+    }
+}
+
+/// Error for reading or writing a secret without `RALPHTOWN_SECRET` set
+pub fn missing_secret_error(key: &str) -> DbError {
+    DbError::Secret(format!(
+        "{} must be set to read or write secret config value '{}'",
+        SECRET_ENV, key
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: &str = "secret/anthropic_api_key";
+
+    #[test]
+    fn test_seal_open_round_trip() {
+        let secrets = SecretBox::new("correct horse battery staple", &generate_salt()).unwrap();
+
+        let sealed = secrets.seal(KEY, "sk-ant-123").unwrap();
+        assert!(sealed.starts_with(SEALED_PREFIX));
+        assert!(!sealed.contains("sk-ant-123"));
+        assert_eq!(secrets.open(KEY, &sealed).unwrap(), "sk-ant-123");
+
+        // Fresh nonce per write
+        assert_ne!(secrets.seal(KEY, "sk-ant-123").unwrap(), sealed);
+    }
+
+    #[test]
+    fn test_open_rejects_wrong_passphrase_salt_key_and_plaintext() {
+        let salt = generate_salt();
+        let one = SecretBox::new("one", &salt).unwrap();
+        let sealed = one.seal(KEY, "value").unwrap();
+
+        let two = SecretBox::new("two", &salt).unwrap();
+        assert!(matches!(two.open(KEY, &sealed), Err(DbError::Secret(_))));
+        let resalted = SecretBox::new("one", &generate_salt()).unwrap();
+        assert!(matches!(resalted.open(KEY, &sealed), Err(DbError::Secret(_))));
+        // Bound to its key: can't be swapped into another one
+        assert!(matches!(one.open("secret/other", &sealed), Err(DbError::Secret(_))));
+        assert!(matches!(one.open(KEY, "value"), Err(DbError::Secret(_))));
+    }
+
+}
@@ -1,5 +1,5 @@
 use axum::{
-    http::StatusCode,
+    http::{header, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
@@ -19,12 +19,19 @@ pub enum AppError {
     BadRequest(String),
     /// Conflict error (409) - e.g., constraint violations
     Conflict(String),
+    /// Unauthorized (401) - missing or invalid credentials
+    Unauthorized(String),
     /// Unprocessable entity (422) - e.g., parse errors
     UnprocessableEntity {
         message: String,
         field: Option<String>,
         value: Option<String>,
     },
+    /// Too many requests (429) - rate limited, retry after the given delay
+    TooManyRequests {
+        message: String,
+        retry_after_secs: u64,
+    },
     /// User action required (422) - actionable errors with help steps
     UserActionRequired {
         code: String,
@@ -55,13 +62,16 @@ impl IntoResponse for AppError {
     fn into_response(self) -> Response {
         let (status, code, message, details, help_steps) = match &self {
             AppError::Internal(msg) => {
-                // Log unexpected internal errors
+                // Log unexpected internal errors (within the request's span)
                 tracing::error!("Internal error: {}", msg);
+                // Include the request id so a bug report can be matched to the logs
+                let details = crate::request_id::current()
+                    .map(|request_id| json!({ "request_id": request_id }));
                 (
                     StatusCode::INTERNAL_SERVER_ERROR,
                     "INTERNAL_ERROR",
                     msg.clone(),
-                    None,
+                    details,
                     Vec::new(),
                 )
             }
@@ -70,6 +80,9 @@ impl IntoResponse for AppError {
                 (StatusCode::BAD_REQUEST, "BAD_REQUEST", msg.clone(), None, Vec::new())
             }
             AppError::Conflict(msg) => (StatusCode::CONFLICT, "CONFLICT", msg.clone(), None, Vec::new()),
+            AppError::Unauthorized(msg) => {
+                (StatusCode::UNAUTHORIZED, "UNAUTHORIZED", msg.clone(), None, Vec::new())
+            }
             AppError::UnprocessableEntity {
                 message,
                 field,
@@ -84,6 +97,25 @@ impl IntoResponse for AppError {
                 })),
                 Vec::new(),
             ),
+            AppError::TooManyRequests {
+                message,
+                retry_after_secs,
+            } => {
+                let body = Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: "RATE_LIMITED".to_string(),
+                        message: message.clone(),
+                        details: Some(json!({ "retry_after_secs": retry_after_secs })),
+                        help_steps: Vec::new(),
+                    },
+                });
+                return (
+                    StatusCode::TOO_MANY_REQUESTS,
+                    [(header::RETRY_AFTER, retry_after_secs.to_string())],
+                    body,
+                )
+                    .into_response();
+            }
             AppError::UserActionRequired {
                 code,
                 message,
@@ -118,9 +150,13 @@ impl std::fmt::Display for AppError {
             AppError::NotFound(msg) => write!(f, "Not found: {}", msg),
             AppError::BadRequest(msg) => write!(f, "Bad request: {}", msg),
             AppError::Conflict(msg) => write!(f, "Conflict: {}", msg),
+            AppError::Unauthorized(msg) => write!(f, "Unauthorized: {}", msg),
             AppError::UnprocessableEntity { message, .. } => {
                 write!(f, "Unprocessable entity: {}", message)
             }
+            AppError::TooManyRequests { message, .. } => {
+                write!(f, "Too many requests: {}", message)
+            }
             AppError::UserActionRequired { code, message, .. } => {
                 write!(f, "User action required [{}]: {}", code, message)
             }
@@ -200,10 +236,16 @@ impl From<crate::ralph::RalphError> for AppError {
                     help_steps,
                 }
             }
+            err @ crate::ralph::RalphError::AtCapacity { .. } => {
+                AppError::Conflict(err.to_string())
+            }
             crate::ralph::RalphError::NotRunning(session_id) => AppError::BadRequest(format!(
                 "Session {} has no running process",
                 session_id
             )),
+            crate::ralph::RalphError::InputFailed(msg) => {
+                AppError::Internal(format!("Failed to write input: {}", msg))
+            }
         }
     }
 }
@@ -0,0 +1,92 @@
+//! Server-wide session lifecycle and config change events
+//!
+//! Events are appended to the `events` table and published on the
+//! `ConnectionManager` event channel, which feeds `GET /api/events/stream`.
+
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::db::models::{EventKind, Session, SessionStatus};
+use crate::db::Database;
+use crate::ws::ConnectionManager;
+
+/// Record an event and publish it to live subscribers.
+///
+/// Failures are logged rather than returned: the feed is informational and
+/// must never fail the operation that produced the event.
+pub fn emit(
+    db: &Database,
+    connections: &ConnectionManager,
+    kind: EventKind,
+    session_id: Option<Uuid>,
+    repo_id: Option<Uuid>,
+    data: serde_json::Value,
+) {
+    match db.insert_event(kind, session_id, repo_id, data) {
+        Ok(event) => connections.publish_event(event),
+        Err(e) => tracing::warn!("Failed to record {} event: {}", kind.as_str(), e),
+    }
+}
+
+/// Record the creation of a session
+pub fn session_created(db: &Database, connections: &ConnectionManager, session: &Session) {
+    emit(
+        db,
+        connections,
+        EventKind::SessionCreated,
+        Some(session.id),
+        Some(session.repo_id),
+        json!({ "name": session.name, "orchestrator": session.orchestrator }),
+    );
+}
+
+/// Record a session status change
+pub fn status_changed(
+    db: &Database,
+    connections: &ConnectionManager,
+    session_id: Uuid,
+    repo_id: Uuid,
+    status: SessionStatus,
+) {
+    emit(
+        db,
+        connections,
+        EventKind::SessionStatusChanged,
+        Some(session_id),
+        Some(repo_id),
+        json!({ "status": status }),
+    );
+}
+
+/// Record the end of a session run with its final status
+pub fn run_finished(
+    db: &Database,
+    connections: &ConnectionManager,
+    session_id: Uuid,
+    repo_id: Uuid,
+    status: SessionStatus,
+) {
+    status_changed(db, connections, session_id, repo_id, status);
+    emit(
+        db,
+        connections,
+        EventKind::SessionCompleted,
+        Some(session_id),
+        Some(repo_id),
+        json!({ "status": status }),
+    );
+}
+
+/// Record a change to a global config value.
+///
+/// Only the key is recorded: values may hold secrets.
+pub fn config_changed(db: &Database, connections: &ConnectionManager, key: &str, deleted: bool) {
+    emit(
+        db,
+        connections,
+        EventKind::ConfigChanged,
+        None,
+        None,
+        json!({ "key": key, "action": if deleted { "deleted" } else { "set" } }),
+    );
+}
@@ -5,7 +5,7 @@
 //! - Write operations (pull, push, commit, reset, checkout) using CLI subprocess
 
 use serde::{Deserialize, Serialize};
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::rc::Rc;
@@ -26,6 +26,18 @@ pub enum GitError {
 
     #[error("Invalid branch name: {0}")]
     InvalidBranch(String),
+
+    #[error("Invalid remote: {0}")]
+    InvalidRemote(String),
+
+    #[error("Invalid path: {0}")]
+    InvalidPath(String),
+
+    #[error("Not found: {0}")]
+    NotFound(String),
+
+    #[error("Stash error: {0}")]
+    Stash(String),
 }
 
 pub type GitResult<T> = Result<T, GitError>;
@@ -186,6 +198,109 @@ pub fn validate_repo_path(path: &Path) -> Result<(), AppError> {
     Ok(())
 }
 
+/// Validate a repository-relative path supplied by a client.
+///
+/// Rejects empty, absolute and parent-traversing paths so operations stay
+/// inside the repository working tree.
+pub fn validate_relative_path(path: &str) -> GitResult<()> {
+    if path.trim().is_empty() || path.contains('\0') {
+        return Err(GitError::InvalidPath(format!("'{}'", path)));
+    }
+
+    let path = Path::new(path);
+    let escapes = path.components().any(|c| {
+        !matches!(c, std::path::Component::Normal(_) | std::path::Component::CurDir)
+    });
+    if escapes {
+        return Err(GitError::InvalidPath(format!(
+            "'{}' must be relative to the repository root",
+            path.display()
+        )));
+    }
+
+    Ok(())
+}
+
+/// Validate a branch name against git's ref naming rules.
+///
+/// libgit2 accepts a few names git itself refuses ("@", "HEAD"), so those are
+/// checked here too, as are names starting with '-' that could be mistaken
+/// for command-line options.
+pub fn validate_branch_name(name: &str) -> GitResult<()> {
+    let valid = !name.starts_with('-')
+        && name != "@"
+        && name != "HEAD"
+        && git2::Branch::name_is_valid(name)
+            .map_err(|e| GitError::OperationFailed(e.message().to_string()))?;
+    if !valid {
+        return Err(GitError::InvalidBranch(format!("'{}'", name)));
+    }
+    Ok(())
+}
+
+/// Git trailer identifying the session a commit was made through
+pub const SESSION_TRAILER: &str = "Ralphtown-Session";
+
+/// Whether a line has the `Token: value` shape of a git trailer
+fn is_trailer_line(line: &str) -> bool {
+    line.split_once(':').is_some_and(|(token, _)| {
+        !token.is_empty() && token.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+    })
+}
+
+/// The trailing paragraph of a commit message, if it consists only of trailers
+fn trailer_block(message: &str) -> Option<&str> {
+    let message = message.trim_end();
+    let block = message.rsplit_once("\n\n").map(|(_, last)| last)?;
+    block.lines().all(is_trailer_line).then_some(block)
+}
+
+/// Append a `key: value` trailer to a commit message, joining an existing
+/// trailer block or starting a new paragraph
+pub fn append_trailer(message: &str, key: &str, value: &str) -> String {
+    let message = message.trim_end();
+    let separator = if trailer_block(message).is_some() { "\n" } else { "\n\n" };
+    format!("{}{}{}: {}", message, separator, key, value)
+}
+
+/// Read a trailer's value from a commit message (keys compare case-insensitively, as in git)
+pub fn parse_trailer(message: &str, key: &str) -> Option<String> {
+    trailer_block(message)?.lines().find_map(|line| {
+        let (token, value) = line.split_once(':')?;
+        token
+            .eq_ignore_ascii_case(key)
+            .then(|| value.trim().to_string())
+    })
+}
+
+/// Parse a list of glob patterns stored as a JSON array of strings
+pub fn parse_pattern_list(raw: &str) -> Result<Vec<String>, String> {
+    let patterns: Vec<String> = serde_json::from_str(raw)
+        .map_err(|e| format!("expected a JSON array of glob patterns: {}", e))?;
+
+    if let Some(bad) = patterns.iter().find(|p| p.trim().is_empty()) {
+        return Err(format!("invalid empty pattern '{}'", bad));
+    }
+
+    Ok(patterns)
+}
+
+/// Return the subset of `paths` matched by any of the glob `patterns`
+pub fn match_patterns(patterns: &[String], paths: &[String]) -> GitResult<Vec<String>> {
+    if patterns.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let pathspec = git2::Pathspec::new(patterns.iter())
+        .map_err(|e| GitError::OperationFailed(e.message().to_string()))?;
+
+    Ok(paths
+        .iter()
+        .filter(|path| pathspec.matches_path(Path::new(path.as_str()), git2::PathspecFlags::DEFAULT))
+        .cloned()
+        .collect())
+}
+
 /// File status in git working tree
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
@@ -207,11 +322,19 @@ pub struct FileStatus {
     pub old_path: Option<String>,
 }
 
+/// Uncommitted changes at a point in time: each changed path with a hash of
+/// its working-tree content (`None` if it doesn't exist), sorted by path
+pub type WorktreeSnapshot = Vec<(String, Option<String>)>;
+
 /// Git repository status
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GitStatus {
     pub branch: String,
+    /// Upstream tracking branch (e.g. "origin/main"), if configured
+    pub upstream: Option<String>,
+    /// Commits on the branch not on its upstream (0 without an upstream)
     pub ahead: usize,
+    /// Commits on the upstream not on the branch (0 without an upstream)
     pub behind: usize,
     pub staged: Vec<FileStatus>,
     pub unstaged: Vec<FileStatus>,
@@ -227,6 +350,45 @@ pub struct Commit {
     pub author: String,
     pub email: String,
     pub timestamp: String,
+    /// Session recorded in the `Ralphtown-Session` trailer, for commits made
+    /// through a session
+    pub session_id: Option<String>,
+}
+
+/// Result of searching commit messages
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitSearch {
+    /// Matching commits, newest first
+    pub commits: Vec<Commit>,
+    /// Number of commits examined
+    pub scanned: usize,
+    /// Whether the search stopped before reaching the start of history
+    pub truncated: bool,
+}
+
+/// Identity and time of a commit's author or committer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitSignature {
+    pub name: String,
+    pub email: String,
+    pub timestamp: String,
+}
+
+/// Full detail of a single commit, including its patch against the first parent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitDetail {
+    pub id: String,
+    pub short_id: String,
+    /// Full commit message, including the body and trailers
+    pub message: String,
+    pub author: CommitSignature,
+    pub committer: CommitSignature,
+    pub parents: Vec<String>,
+    /// Session recorded in the `Ralphtown-Session` trailer, if any
+    pub session_id: Option<String>,
+    pub files: Vec<FileDelta>,
+    /// Unified diff of the commit's changes
+    pub diff: String,
 }
 
 /// A git branch
@@ -238,6 +400,156 @@ pub struct Branch {
     pub upstream: Option<String>,
 }
 
+/// A configured git remote
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Remote {
+    pub name: String,
+    pub fetch_url: Option<String>,
+    /// Where pushes go: the remote's push URL if one is set, otherwise its fetch URL
+    pub push_url: Option<String>,
+}
+
+/// Summary of the commit a branch points at
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BranchTip {
+    pub id: String,
+    pub short_id: String,
+    /// First line of the commit message
+    pub summary: String,
+    pub author: String,
+    pub timestamp: String,
+}
+
+/// A git branch with its tip commit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BranchDetail {
+    #[serde(flatten)]
+    pub branch: Branch,
+    /// Tip commit, absent if the branch doesn't point at a commit
+    pub tip: Option<BranchTip>,
+}
+
+/// Unified diff of a single file between two commits
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileDiffBetween {
+    pub path: String,
+    /// Resolved commit id for `from`
+    pub from: String,
+    /// Resolved commit id for `to`
+    pub to: String,
+    pub binary: bool,
+    /// Unified diff text (empty if the file is unchanged or binary)
+    pub diff: String,
+}
+
+/// One side of a conflicted file, read from an index stage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConflictSide {
+    /// Path recorded in this stage (may differ between sides after a rename)
+    pub path: String,
+    pub binary: bool,
+    /// File content, absent for binary files
+    pub content: Option<String>,
+}
+
+/// An entry in the stash list
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StashEntry {
+    /// Position in the stash list (0 is the most recent, `stash@{0}`)
+    pub index: usize,
+    pub message: String,
+    pub created_at: String,
+}
+
+/// A file with unresolved merge conflicts and its three-way versions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConflictFile {
+    pub path: String,
+    /// Common ancestor version (stage 1), absent if both sides added the file
+    pub base: Option<ConflictSide>,
+    /// Our version (stage 2), absent if we deleted the file
+    pub ours: Option<ConflictSide>,
+    /// Their version (stage 3), absent if they deleted the file
+    pub theirs: Option<ConflictSide>,
+}
+
+/// Unified diff of a single file's pending changes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileDiff {
+    pub path: String,
+    /// True for the staged diff (HEAD to index), false for the working tree
+    /// diff (index to working tree, including untracked files)
+    pub staged: bool,
+    pub binary: bool,
+    /// Unified diff text (empty if the file is unchanged or binary)
+    pub diff: String,
+}
+
+/// Kind of change for one line of a diff hunk
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffLineKind {
+    Context,
+    Added,
+    Removed,
+}
+
+/// A single line of a diff hunk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiffLine {
+    pub kind: DiffLineKind,
+    /// Line number in the old file, absent for added lines
+    pub old_lineno: Option<u32>,
+    /// Line number in the new file, absent for removed lines
+    pub new_lineno: Option<u32>,
+    /// Line content without the trailing newline
+    pub content: String,
+}
+
+/// A contiguous block of changes with its line ranges
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiffHunk {
+    /// Raw `@@ -a,b +c,d @@` header
+    pub header: String,
+    pub old_start: u32,
+    pub old_lines: u32,
+    pub new_start: u32,
+    pub new_lines: u32,
+    pub lines: Vec<DiffLine>,
+}
+
+/// Structured working-tree diff of one file against HEAD
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileHunks {
+    pub path: String,
+    pub binary: bool,
+    /// Hunks in file order (empty if unchanged or binary)
+    pub hunks: Vec<DiffHunk>,
+}
+
+/// Number of commits made on a single day
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivityDay {
+    /// Day in YYYY-MM-DD format (UTC)
+    pub date: String,
+    pub count: usize,
+}
+
+/// Commit activity histogram over a window of days
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitActivity {
+    /// One bucket per day, oldest first, including days without commits
+    pub days: Vec<ActivityDay>,
+    /// True if the walk stopped at the commit cap before covering the window
+    pub truncated: bool,
+}
+
+/// Maximum number of days an activity histogram may cover
+pub const MAX_ACTIVITY_DAYS: u32 = 365;
+
+/// Maximum number of commits walked when building an activity histogram
+const MAX_ACTIVITY_WALK: usize = 10_000;
+
 /// File change statistics
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FileDelta {
@@ -246,6 +558,14 @@ pub struct FileDelta {
     pub removed: usize,
 }
 
+/// Differences between the current contents of two worktrees of one repository
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorktreeDiff {
+    pub files: Vec<FileDelta>,
+    /// Unified diff text
+    pub patch: String,
+}
+
 /// Result of a git command execution
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CommandOutput {
@@ -254,6 +574,21 @@ pub struct CommandOutput {
     pub stderr: String,
 }
 
+/// Options for `git push`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PushOptions {
+    /// Record the pushed branch as the local branch's upstream
+    pub set_upstream: bool,
+    /// Overwrite the remote branch, via `--force-with-lease` so commits pushed
+    /// by others since the last fetch are never clobbered
+    pub force: bool,
+    /// Remote to push to (default: the branch's upstream remote, else `origin`)
+    pub remote: Option<String>,
+    /// Branch to push (default: the current branch)
+    pub branch: Option<String>,
+}
+
 /// Clone progress information from git2 transfer_progress callback
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CloneProgress {
@@ -271,32 +606,56 @@ pub struct CloneProgress {
     pub indexed_deltas: usize,
 }
 
+/// Result of probing a remote URL without cloning it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteCheck {
+    /// The remote answered and listed its refs
+    pub reachable: bool,
+    /// The remote asked for credentials
+    pub auth_required: bool,
+    /// Branch the remote's HEAD points to, when reachable
+    pub default_branch: Option<String>,
+    /// Why the remote could not be reached
+    pub error: Option<String>,
+}
+
 /// Git operations manager
 pub struct GitManager;
 
 impl GitManager {
     /// Get repository status using git2
     pub fn status(repo_path: &Path) -> GitResult<GitStatus> {
+        Self::read_status(repo_path, None)
+    }
+
+    /// Get repository status with changes limited to files under `dir`
+    pub fn status_in(repo_path: &Path, dir: &str) -> GitResult<GitStatus> {
+        validate_relative_path(dir)?;
+        Self::read_status(repo_path, Some(dir.trim_end_matches('/')))
+    }
+
+    fn read_status(repo_path: &Path, pathspec: Option<&str>) -> GitResult<GitStatus> {
         let repo = git2::Repository::open(repo_path)
             .map_err(|e| GitError::NotARepo(e.message().to_string()))?;
 
         // Get current branch
         let branch = Self::get_current_branch(&repo)?;
 
-        // Get ahead/behind counts
-        let (ahead, behind) = Self::get_ahead_behind(&repo).unwrap_or((0, 0));
+        // Get upstream and ahead/behind counts
+        let (upstream, ahead, behind) = Self::get_upstream_tracking(&repo);
 
         // Get status
         let mut staged = Vec::new();
         let mut unstaged = Vec::new();
         let mut untracked = Vec::new();
 
+        let mut options = git2::StatusOptions::new();
+        options.include_untracked(true).recurse_untracked_dirs(true);
+        if let Some(pathspec) = pathspec {
+            options.pathspec(pathspec);
+        }
         let statuses = repo
-            .statuses(Some(
-                git2::StatusOptions::new()
-                    .include_untracked(true)
-                    .recurse_untracked_dirs(true),
-            ))
+            .statuses(Some(&mut options))
             .map_err(|e| GitError::OperationFailed(e.message().to_string()))?;
 
         for entry in statuses.iter() {
@@ -356,6 +715,7 @@ impl GitManager {
 
         Ok(GitStatus {
             branch,
+            upstream,
             ahead,
             behind,
             staged,
@@ -378,30 +738,459 @@ impl GitManager {
             .push_head()
             .map_err(|e| GitError::OperationFailed(e.message().to_string()))?;
 
+        Self::collect_commits(&repo, revwalk.take(limit))
+    }
+
+    /// Get the commits reachable from HEAD but not from `base`, newest first
+    pub fn log_range(repo_path: &Path, base: &str) -> GitResult<Vec<Commit>> {
+        let repo = git2::Repository::open(repo_path)
+            .map_err(|e| GitError::NotARepo(e.message().to_string()))?;
+
+        let base_oid = repo
+            .revparse_single(base)
+            .and_then(|object| object.peel_to_commit())
+            .map_err(|_| GitError::InvalidBranch(format!("'{}' does not resolve to a commit", base)))?
+            .id();
+
+        let mut revwalk = repo
+            .revwalk()
+            .map_err(|e| GitError::OperationFailed(e.message().to_string()))?;
+        revwalk
+            .push_head()
+            .and_then(|_| revwalk.hide(base_oid))
+            .map_err(|e| GitError::OperationFailed(e.message().to_string()))?;
+
+        Self::collect_commits(&repo, revwalk)
+    }
+
+    /// Summarize the commits produced by a revwalk
+    fn collect_commits(
+        repo: &git2::Repository,
+        oids: impl Iterator<Item = Result<git2::Oid, git2::Error>>,
+    ) -> GitResult<Vec<Commit>> {
         let mut commits = Vec::new();
-        for oid in revwalk.take(limit) {
+        for oid in oids {
             let oid = oid.map_err(|e| GitError::OperationFailed(e.message().to_string()))?;
             let commit = repo
                 .find_commit(oid)
                 .map_err(|e| GitError::OperationFailed(e.message().to_string()))?;
+            commits.push(Self::commit_summary(&commit));
+        }
+
+        Ok(commits)
+    }
+
+    /// Log entry for a commit
+    fn commit_summary(commit: &git2::Commit<'_>) -> Commit {
+        let oid = commit.id();
+        let author = commit.author();
+        let time = commit.time();
+        let timestamp = chrono::DateTime::from_timestamp(time.seconds(), 0)
+            .map(|dt| dt.to_rfc3339())
+            .unwrap_or_default();
+
+        let message = commit.message().unwrap_or("").trim().to_string();
+        Commit {
+            id: oid.to_string(),
+            short_id: oid.to_string()[..7.min(oid.to_string().len())].to_string(),
+            session_id: parse_trailer(&message, SESSION_TRAILER),
+            message,
+            author: author.name().unwrap_or("").to_string(),
+            email: author.email().unwrap_or("").to_string(),
+            timestamp,
+        }
+    }
+
+    /// Find commits reachable from HEAD whose message contains `query`
+    /// (case-insensitive), newest first
+    ///
+    /// Stops after `limit` matches or `max_scan` commits, whichever comes first.
+    pub fn search_commits(
+        repo_path: &Path,
+        query: &str,
+        limit: usize,
+        max_scan: usize,
+    ) -> GitResult<CommitSearch> {
+        let repo = git2::Repository::open(repo_path)
+            .map_err(|e| GitError::NotARepo(e.message().to_string()))?;
+
+        let mut revwalk = repo
+            .revwalk()
+            .map_err(|e| GitError::OperationFailed(e.message().to_string()))?;
+        revwalk
+            .push_head()
+            .map_err(|e| GitError::OperationFailed(e.message().to_string()))?;
 
-            let author = commit.author();
-            let time = commit.time();
-            let timestamp = chrono::DateTime::from_timestamp(time.seconds(), 0)
+        let needle = query.to_lowercase();
+        let mut commits = Vec::new();
+        let mut scanned = 0;
+        for oid in revwalk {
+            if commits.len() >= limit || scanned >= max_scan {
+                return Ok(CommitSearch {
+                    commits,
+                    scanned,
+                    truncated: true,
+                });
+            }
+            scanned += 1;
+
+            let oid = oid.map_err(|e| GitError::OperationFailed(e.message().to_string()))?;
+            let commit = repo
+                .find_commit(oid)
+                .map_err(|e| GitError::OperationFailed(e.message().to_string()))?;
+            let message = String::from_utf8_lossy(commit.message_bytes()).to_lowercase();
+            if message.contains(&needle) {
+                commits.push(Self::commit_summary(&commit));
+            }
+        }
+
+        Ok(CommitSearch {
+            commits,
+            scanned,
+            truncated: false,
+        })
+    }
+
+    /// Show one commit (sha, short sha, or any revision like `HEAD~2`) with its diff
+    pub fn show(repo_path: &Path, rev: &str) -> GitResult<CommitDetail> {
+        let repo = git2::Repository::open(repo_path)
+            .map_err(|e| GitError::NotARepo(e.message().to_string()))?;
+
+        let commit = repo
+            .revparse_single(rev)
+            .map_err(|_| GitError::NotFound(format!("Revision not found: {}", rev)))?
+            .peel_to_commit()
+            .map_err(|_| {
+                GitError::InvalidBranch(format!("'{}' does not point to a commit", rev))
+            })?;
+
+        let tree = commit
+            .tree()
+            .map_err(|e| GitError::OperationFailed(e.message().to_string()))?;
+        let parent_tree = match commit.parent(0) {
+            Ok(parent) => Some(
+                parent
+                    .tree()
+                    .map_err(|e| GitError::OperationFailed(e.message().to_string()))?,
+            ),
+            Err(_) => None,
+        };
+        let diff = repo
+            .diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)
+            .map_err(|e| GitError::OperationFailed(e.message().to_string()))?;
+
+        let signature = |sig: git2::Signature<'_>| CommitSignature {
+            name: sig.name().unwrap_or("").to_string(),
+            email: sig.email().unwrap_or("").to_string(),
+            timestamp: chrono::DateTime::from_timestamp(sig.when().seconds(), 0)
                 .map(|dt| dt.to_rfc3339())
-                .unwrap_or_default();
+                .unwrap_or_default(),
+        };
+
+        let id = commit.id().to_string();
+        let message = commit.message().unwrap_or("").trim().to_string();
+        Ok(CommitDetail {
+            short_id: id[..7.min(id.len())].to_string(),
+            id,
+            session_id: parse_trailer(&message, SESSION_TRAILER),
+            message,
+            author: signature(commit.author()),
+            committer: signature(commit.committer()),
+            parents: commit.parent_ids().map(|oid| oid.to_string()).collect(),
+            files: Self::file_deltas(&diff)?,
+            diff: Self::diff_to_text(&diff)?,
+        })
+    }
+
+    /// Unified diff of one file between two revisions (commits, branches, tags...)
+    pub fn file_diff_between(
+        repo_path: &Path,
+        path: &str,
+        from: &str,
+        to: &str,
+    ) -> GitResult<FileDiffBetween> {
+        validate_relative_path(path)?;
+
+        let repo = git2::Repository::open(repo_path)
+            .map_err(|e| GitError::NotARepo(e.message().to_string()))?;
+
+        let resolve = |rev: &str| {
+            repo.revparse_single(rev)
+                .and_then(|obj| obj.peel_to_commit())
+                .map_err(|_| GitError::NotFound(format!("Revision not found: {}", rev)))
+        };
+        let from_commit = resolve(from)?;
+        let to_commit = resolve(to)?;
+
+        let from_tree = from_commit
+            .tree()
+            .map_err(|e| GitError::OperationFailed(e.message().to_string()))?;
+        let to_tree = to_commit
+            .tree()
+            .map_err(|e| GitError::OperationFailed(e.message().to_string()))?;
+
+        let file = Path::new(path);
+        if from_tree.get_path(file).is_err() && to_tree.get_path(file).is_err() {
+            return Err(GitError::NotFound(format!(
+                "'{}' does not exist in {} or {}",
+                path, from, to
+            )));
+        }
+
+        let mut opts = git2::DiffOptions::new();
+        opts.pathspec(path).disable_pathspec_match(true);
+        let diff = repo
+            .diff_tree_to_tree(Some(&from_tree), Some(&to_tree), Some(&mut opts))
+            .map_err(|e| GitError::OperationFailed(e.message().to_string()))?;
+
+        let binary = diff.deltas().any(|d| d.flags().is_binary());
+        let diff_text = Self::diff_to_text(&diff)?;
+
+        Ok(FileDiffBetween {
+            path: path.to_string(),
+            from: from_commit.id().to_string(),
+            to: to_commit.id().to_string(),
+            binary,
+            diff: if binary { String::new() } else { diff_text },
+        })
+    }
+
+    /// Stash uncommitted changes, including untracked files
+    pub fn stash_save(repo_path: &Path, message: Option<&str>) -> GitResult<()> {
+        let mut repo = git2::Repository::open(repo_path)
+            .map_err(|e| GitError::NotARepo(e.message().to_string()))?;
+
+        let signature = repo
+            .signature()
+            .map_err(|e| GitError::OperationFailed(e.message().to_string()))?;
+
+        let flags = git2::StashFlags::INCLUDE_UNTRACKED;
+        repo.stash_save(&signature, message.unwrap_or(""), Some(flags))
+            .map_err(|e| {
+                if e.code() == git2::ErrorCode::NotFound {
+                    GitError::Stash("No local changes to stash".to_string())
+                } else {
+                    GitError::OperationFailed(e.message().to_string())
+                }
+            })?;
+
+        Ok(())
+    }
+
+    /// List stash entries, most recent first
+    pub fn stash_list(repo_path: &Path) -> GitResult<Vec<StashEntry>> {
+        let mut repo = git2::Repository::open(repo_path)
+            .map_err(|e| GitError::NotARepo(e.message().to_string()))?;
+
+        let mut stashes = Vec::new();
+        repo.stash_foreach(|index, message, oid| {
+            stashes.push((index, message.to_string(), *oid));
+            true
+        })
+        .map_err(|e| GitError::OperationFailed(e.message().to_string()))?;
+
+        stashes
+            .into_iter()
+            .map(|(index, message, oid)| {
+                let commit = repo
+                    .find_commit(oid)
+                    .map_err(|e| GitError::OperationFailed(e.message().to_string()))?;
+                let created_at = chrono::DateTime::from_timestamp(commit.time().seconds(), 0)
+                    .map(|dt| dt.to_rfc3339())
+                    .unwrap_or_default();
+                Ok(StashEntry {
+                    index,
+                    message,
+                    created_at,
+                })
+            })
+            .collect()
+    }
+
+    /// Apply a stash entry and remove it from the stash list
+    pub fn stash_pop(repo_path: &Path, index: usize) -> GitResult<()> {
+        let mut repo = Self::open_stash_entry(repo_path, index)?;
+
+        repo.stash_pop(index, None).map_err(|e| match e.code() {
+            git2::ErrorCode::Conflict | git2::ErrorCode::MergeConflict => GitError::Stash(format!(
+                "stash@{{{}}} conflicts with local changes: {}",
+                index,
+                e.message()
+            )),
+            _ => GitError::OperationFailed(e.message().to_string()),
+        })
+    }
+
+    /// Remove a stash entry without applying it
+    pub fn stash_drop(repo_path: &Path, index: usize) -> GitResult<()> {
+        let mut repo = Self::open_stash_entry(repo_path, index)?;
+
+        repo.stash_drop(index)
+            .map_err(|e| GitError::OperationFailed(e.message().to_string()))
+    }
+
+    /// Open a repository, checking that the stash entry at `index` exists
+    fn open_stash_entry(repo_path: &Path, index: usize) -> GitResult<git2::Repository> {
+        let count = Self::stash_list(repo_path)?.len();
+        if count == 0 {
+            return Err(GitError::Stash("The stash is empty".to_string()));
+        }
+        if index >= count {
+            return Err(GitError::NotFound(format!("Stash entry not found: stash@{{{}}}", index)));
+        }
+
+        git2::Repository::open(repo_path).map_err(|e| GitError::NotARepo(e.message().to_string()))
+    }
+
+    /// List conflicted files with their base/ours/theirs versions from the index
+    pub fn conflicts(repo_path: &Path) -> GitResult<Vec<ConflictFile>> {
+        let repo = git2::Repository::open(repo_path)
+            .map_err(|e| GitError::NotARepo(e.message().to_string()))?;
+        let index = repo
+            .index()
+            .map_err(|e| GitError::OperationFailed(e.message().to_string()))?;
+        if !index.has_conflicts() {
+            return Ok(Vec::new());
+        }
+
+        let read_side = |entry: Option<git2::IndexEntry>| -> GitResult<Option<ConflictSide>> {
+            let Some(entry) = entry else {
+                return Ok(None);
+            };
+            let blob = repo
+                .find_blob(entry.id)
+                .map_err(|e| GitError::OperationFailed(e.message().to_string()))?;
+            let content = if blob.is_binary() {
+                None
+            } else {
+                String::from_utf8(blob.content().to_vec()).ok()
+            };
+            Ok(Some(ConflictSide {
+                path: String::from_utf8_lossy(&entry.path).to_string(),
+                binary: content.is_none(),
+                content,
+            }))
+        };
+
+        let conflicts = index
+            .conflicts()
+            .map_err(|e| GitError::OperationFailed(e.message().to_string()))?;
 
-            commits.push(Commit {
-                id: oid.to_string(),
-                short_id: oid.to_string()[..7.min(oid.to_string().len())].to_string(),
-                message: commit.message().unwrap_or("").trim().to_string(),
-                author: author.name().unwrap_or("").to_string(),
-                email: author.email().unwrap_or("").to_string(),
-                timestamp,
+        let mut files = Vec::new();
+        for conflict in conflicts {
+            let conflict = conflict.map_err(|e| GitError::OperationFailed(e.message().to_string()))?;
+            let base = read_side(conflict.ancestor)?;
+            let ours = read_side(conflict.our)?;
+            let theirs = read_side(conflict.their)?;
+            let path = [&ours, &theirs, &base]
+                .into_iter()
+                .flatten()
+                .map(|side| side.path.clone())
+                .next()
+                .unwrap_or_default();
+            files.push(ConflictFile {
+                path,
+                base,
+                ours,
+                theirs,
             });
         }
 
-        Ok(commits)
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(files)
+    }
+
+    /// List the paths left conflicted in the index, without reading their contents
+    pub fn conflicted_paths(repo_path: &Path) -> GitResult<Vec<String>> {
+        let repo = git2::Repository::open(repo_path)
+            .map_err(|e| GitError::NotARepo(e.message().to_string()))?;
+        let index = repo
+            .index()
+            .map_err(|e| GitError::OperationFailed(e.message().to_string()))?;
+        if !index.has_conflicts() {
+            return Ok(Vec::new());
+        }
+
+        let conflicts = index
+            .conflicts()
+            .map_err(|e| GitError::OperationFailed(e.message().to_string()))?;
+
+        let mut paths = Vec::new();
+        for conflict in conflicts {
+            let conflict = conflict.map_err(|e| GitError::OperationFailed(e.message().to_string()))?;
+            if let Some(entry) = conflict.our.or(conflict.their).or(conflict.ancestor) {
+                paths.push(String::from_utf8_lossy(&entry.path).to_string());
+            }
+        }
+
+        paths.sort();
+        Ok(paths)
+    }
+
+    /// Count commits per day over the last `days` days ending at `today` (UTC)
+    pub fn activity(
+        repo_path: &Path,
+        days: u32,
+        today: chrono::NaiveDate,
+    ) -> GitResult<CommitActivity> {
+        let days = days.clamp(1, MAX_ACTIVITY_DAYS);
+        let repo = git2::Repository::open(repo_path)
+            .map_err(|e| GitError::NotARepo(e.message().to_string()))?;
+
+        let start = today - chrono::Days::new(u64::from(days - 1));
+        let start_ts = start
+            .and_hms_opt(0, 0, 0)
+            .map(|dt| dt.and_utc().timestamp())
+            .unwrap_or_default();
+
+        let mut revwalk = repo
+            .revwalk()
+            .map_err(|e| GitError::OperationFailed(e.message().to_string()))?;
+        revwalk
+            .set_sorting(git2::Sort::TIME)
+            .map_err(|e| GitError::OperationFailed(e.message().to_string()))?;
+        revwalk
+            .push_head()
+            .map_err(|e| GitError::OperationFailed(e.message().to_string()))?;
+
+        let mut counts = vec![0usize; days as usize];
+        let mut truncated = false;
+
+        for (walked, oid) in revwalk.enumerate() {
+            if walked >= MAX_ACTIVITY_WALK {
+                truncated = true;
+                break;
+            }
+
+            let oid = oid.map_err(|e| GitError::OperationFailed(e.message().to_string()))?;
+            let commit = repo
+                .find_commit(oid)
+                .map_err(|e| GitError::OperationFailed(e.message().to_string()))?;
+            let seconds = commit.time().seconds();
+
+            // Commits come newest first, so the first one before the window ends the walk
+            if seconds < start_ts {
+                break;
+            }
+
+            if let Some(date) = chrono::DateTime::from_timestamp(seconds, 0).map(|dt| dt.date_naive()) {
+                let offset = (date - start).num_days();
+                if let Some(count) = usize::try_from(offset).ok().and_then(|i| counts.get_mut(i)) {
+                    *count += 1;
+                }
+            }
+        }
+
+        let days = counts
+            .into_iter()
+            .enumerate()
+            .map(|(i, count)| ActivityDay {
+                date: (start + chrono::Days::new(i as u64)).format("%Y-%m-%d").to_string(),
+                count,
+            })
+            .collect();
+
+        Ok(CommitActivity { days, truncated })
     }
 
     /// List branches using git2
@@ -409,7 +1198,40 @@ impl GitManager {
         let repo = git2::Repository::open(repo_path)
             .map_err(|e| GitError::NotARepo(e.message().to_string()))?;
 
-        let current_branch = Self::get_current_branch(&repo).unwrap_or_default();
+        Ok(Self::branch_entries(&repo)?
+            .into_iter()
+            .map(|(branch, _)| branch)
+            .collect())
+    }
+
+    /// List branches along with each branch's tip commit
+    pub fn branches_detailed(repo_path: &Path) -> GitResult<Vec<BranchDetail>> {
+        let repo = git2::Repository::open(repo_path)
+            .map_err(|e| GitError::NotARepo(e.message().to_string()))?;
+
+        Ok(Self::branch_entries(&repo)?
+            .into_iter()
+            .map(|(branch, target)| {
+                let tip = target
+                    .and_then(|oid| repo.find_commit(oid).ok())
+                    .map(|commit| {
+                        let summary = Self::commit_summary(&commit);
+                        BranchTip {
+                            id: summary.id,
+                            short_id: summary.short_id,
+                            summary: commit.summary().unwrap_or("").to_string(),
+                            author: summary.author,
+                            timestamp: summary.timestamp,
+                        }
+                    });
+                BranchDetail { branch, tip }
+            })
+            .collect())
+    }
+
+    /// Local then remote branches, each with the commit id it points at
+    fn branch_entries(repo: &git2::Repository) -> GitResult<Vec<(Branch, Option<git2::Oid>)>> {
+        let current_branch = Self::get_current_branch(repo).unwrap_or_default();
 
         let mut branches = Vec::new();
 
@@ -427,12 +1249,16 @@ impl GitManager {
                 .ok()
                 .and_then(|u| u.name().ok().flatten().map(|s| s.to_string()));
 
-            branches.push(Branch {
-                name: name.clone(),
-                is_current: name == current_branch,
-                is_remote: false,
-                upstream,
-            });
+            let target = branch.get().target();
+            branches.push((
+                Branch {
+                    name: name.clone(),
+                    is_current: name == current_branch,
+                    is_remote: false,
+                    upstream,
+                },
+                target,
+            ));
         }
 
         // Remote branches
@@ -449,24 +1275,166 @@ impl GitManager {
                 continue;
             }
 
-            branches.push(Branch {
-                name,
-                is_current: false,
-                is_remote: true,
-                upstream: None,
-            });
+            let target = branch.get().target();
+            branches.push((
+                Branch {
+                    name,
+                    is_current: false,
+                    is_remote: true,
+                    upstream: None,
+                },
+                target,
+            ));
         }
 
         Ok(branches)
     }
 
+    /// Create a local branch at `from` (any revision, default HEAD) without checking it out
+    pub fn create_branch(repo_path: &Path, name: &str, from: Option<&str>) -> GitResult<Branch> {
+        validate_branch_name(name)?;
+
+        let repo = git2::Repository::open(repo_path)
+            .map_err(|e| GitError::NotARepo(e.message().to_string()))?;
+
+        let from = from.unwrap_or("HEAD");
+        let target = repo
+            .revparse_single(from)
+            .and_then(|object| object.peel_to_commit())
+            .map_err(|_| GitError::NotFound(format!("Revision not found: {}", from)))?;
+
+        repo.branch(name, &target, false).map_err(|e| {
+            if e.code() == git2::ErrorCode::Exists {
+                GitError::InvalidBranch(format!("'{}' already exists", name))
+            } else {
+                GitError::OperationFailed(e.message().to_string())
+            }
+        })?;
+
+        Ok(Branch {
+            name: name.to_string(),
+            is_current: false,
+            is_remote: false,
+            upstream: None,
+        })
+    }
+
+    /// Delete a local branch
+    ///
+    /// The current branch can never be deleted. Without `force`, the branch
+    /// must already be merged into HEAD.
+    pub fn delete_branch(repo_path: &Path, name: &str, force: bool) -> GitResult<()> {
+        validate_branch_name(name)?;
+
+        let repo = git2::Repository::open(repo_path)
+            .map_err(|e| GitError::NotARepo(e.message().to_string()))?;
+
+        let mut branch = repo
+            .find_branch(name, git2::BranchType::Local)
+            .map_err(|_| GitError::NotFound(format!("Branch not found: {}", name)))?;
+
+        if branch.is_head() {
+            return Err(GitError::InvalidBranch(format!(
+                "cannot delete the current branch '{}'",
+                name
+            )));
+        }
+
+        if !force {
+            let head = repo.head().ok().and_then(|h| h.target());
+            let merged = match (head, branch.get().target()) {
+                (Some(head), Some(tip)) => {
+                    head == tip
+                        || repo
+                            .graph_descendant_of(head, tip)
+                            .map_err(|e| GitError::OperationFailed(e.message().to_string()))?
+                }
+                _ => false,
+            };
+            if !merged {
+                return Err(GitError::InvalidBranch(format!(
+                    "'{}' is not fully merged; use force to delete it",
+                    name
+                )));
+            }
+        }
+
+        branch
+            .delete()
+            .map_err(|e| GitError::OperationFailed(e.message().to_string()))
+    }
+
+    /// List the repository's remotes with their fetch and push URLs
+    pub fn list_remotes(repo_path: &Path) -> GitResult<Vec<Remote>> {
+        let repo = git2::Repository::open(repo_path)
+            .map_err(|e| GitError::NotARepo(e.message().to_string()))?;
+
+        let names = repo
+            .remotes()
+            .map_err(|e| GitError::OperationFailed(e.message().to_string()))?;
+
+        let mut remotes = Vec::new();
+        for name in names.iter().flatten() {
+            let remote = repo
+                .find_remote(name)
+                .map_err(|e| GitError::OperationFailed(e.message().to_string()))?;
+            let fetch_url = remote.url().map(str::to_string);
+            remotes.push(Remote {
+                name: name.to_string(),
+                push_url: remote.pushurl().map(str::to_string).or_else(|| fetch_url.clone()),
+                fetch_url,
+            });
+        }
+
+        Ok(remotes)
+    }
+
+    /// Add a remote
+    pub fn add_remote(repo_path: &Path, name: &str, url: &str) -> GitResult<Remote> {
+        if !git2::Remote::is_valid_name(name) {
+            return Err(GitError::InvalidRemote(format!("'{}' is not a valid remote name", name)));
+        }
+        let url = url.trim();
+        if url.is_empty() || url.starts_with('-') || url.contains(char::is_whitespace) {
+            return Err(GitError::InvalidRemote(format!("'{}' is not a valid URL", url)));
+        }
+
+        let repo = git2::Repository::open(repo_path)
+            .map_err(|e| GitError::NotARepo(e.message().to_string()))?;
+
+        repo.remote(name, url).map_err(|e| {
+            if e.code() == git2::ErrorCode::Exists {
+                GitError::InvalidRemote(format!("'{}' already exists", name))
+            } else {
+                GitError::OperationFailed(e.message().to_string())
+            }
+        })?;
+
+        Ok(Remote {
+            name: name.to_string(),
+            fetch_url: Some(url.to_string()),
+            push_url: Some(url.to_string()),
+        })
+    }
+
+    /// Remove a remote along with its remote-tracking branches
+    pub fn remove_remote(repo_path: &Path, name: &str) -> GitResult<()> {
+        let repo = git2::Repository::open(repo_path)
+            .map_err(|e| GitError::NotARepo(e.message().to_string()))?;
+
+        if repo.find_remote(name).is_err() {
+            return Err(GitError::NotFound(format!("Remote not found: {}", name)));
+        }
+
+        repo.remote_delete(name)
+            .map_err(|e| GitError::OperationFailed(e.message().to_string()))
+    }
+
     /// Get diff statistics for uncommitted changes
     pub fn diff_stats(repo_path: &Path) -> GitResult<Vec<FileDelta>> {
         let repo = git2::Repository::open(repo_path)
             .map_err(|e| GitError::NotARepo(e.message().to_string()))?;
 
-        let mut deltas = Vec::new();
-
         // Get HEAD tree
         let head = repo.head().ok();
         let head_tree = head.as_ref().and_then(|h| h.peel_to_tree().ok());
@@ -476,6 +1444,82 @@ impl GitManager {
             .diff_tree_to_workdir_with_index(head_tree.as_ref(), None)
             .map_err(|e| GitError::OperationFailed(e.message().to_string()))?;
 
+        Self::file_deltas(&diff)
+    }
+
+    /// Get per-file diff statistics between two revisions (branches, tags, shas...)
+    pub fn diff_refs(repo_path: &Path, from: &str, to: &str) -> GitResult<Vec<FileDelta>> {
+        let repo = git2::Repository::open(repo_path)
+            .map_err(|e| GitError::NotARepo(e.message().to_string()))?;
+
+        let resolve = |rev: &str| {
+            repo.revparse_single(rev)
+                .and_then(|object| object.peel_to_tree())
+                .map_err(|_| {
+                    GitError::InvalidBranch(format!("'{}' does not resolve to a commit", rev))
+                })
+        };
+        let from_tree = resolve(from)?;
+        let to_tree = resolve(to)?;
+
+        let diff = repo
+            .diff_tree_to_tree(Some(&from_tree), Some(&to_tree), None)
+            .map_err(|e| GitError::OperationFailed(e.message().to_string()))?;
+
+        Self::file_deltas(&diff)
+    }
+
+    /// Git directory shared by every worktree of the repository at `repo_path`
+    pub fn common_dir(repo_path: &Path) -> GitResult<PathBuf> {
+        let repo = git2::Repository::open(repo_path)
+            .map_err(|e| GitError::NotARepo(e.message().to_string()))?;
+        let dir = repo.commondir();
+        Ok(dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf()))
+    }
+
+    /// Diff the current contents of two worktrees of the same repository,
+    /// from `a` to `b`, including uncommitted and untracked files
+    pub fn diff_worktrees(a: &Path, b: &Path) -> GitResult<WorktreeDiff> {
+        let open = |path: &Path| {
+            git2::Repository::open(path).map_err(|e| GitError::NotARepo(e.message().to_string()))
+        };
+        let repo_a = open(a)?;
+        let repo_b = open(b)?;
+        let tree_a = Self::snapshot_worktree(&repo_a)?;
+        let tree_b = Self::snapshot_worktree(&repo_b)?;
+
+        // Worktrees share one object database, so both trees resolve from either
+        let find = |oid| {
+            repo_a
+                .find_tree(oid)
+                .map_err(|e| GitError::OperationFailed(e.message().to_string()))
+        };
+        let diff = repo_a
+            .diff_tree_to_tree(Some(&find(tree_a)?), Some(&find(tree_b)?), None)
+            .map_err(|e| GitError::OperationFailed(e.message().to_string()))?;
+
+        Ok(WorktreeDiff {
+            files: Self::file_deltas(&diff)?,
+            patch: Self::diff_to_text(&diff)?,
+        })
+    }
+
+    /// Write a tree of a worktree's files as `git add -A` would stage them,
+    /// leaving its index on disk untouched
+    fn snapshot_worktree(repo: &git2::Repository) -> GitResult<git2::Oid> {
+        let snapshot = || -> Result<git2::Oid, git2::Error> {
+            let mut index = repo.index()?;
+            index.add_all(["*"], git2::IndexAddOption::DEFAULT, None)?;
+            index.update_all(["*"], None)?;
+            index.write_tree()
+        };
+        snapshot().map_err(|e| GitError::OperationFailed(e.message().to_string()))
+    }
+
+    /// Per-file added/removed line counts for a diff
+    fn file_deltas(diff: &git2::Diff<'_>) -> GitResult<Vec<FileDelta>> {
+        let mut deltas = Vec::new();
+
         let stats = diff
             .stats()
             .map_err(|e| GitError::OperationFailed(e.message().to_string()))?;
@@ -491,7 +1535,7 @@ impl GitManager {
                     .unwrap_or_default();
 
                 // Get patch for line counts
-                if let Ok(patch) = git2::Patch::from_diff(&diff, i) {
+                if let Ok(patch) = git2::Patch::from_diff(diff, i) {
                     if let Some(patch) = patch {
                         let (_, additions, deletions) = patch.line_stats().unwrap_or((0, 0, 0));
                         deltas.push(FileDelta {
@@ -514,7 +1558,146 @@ impl GitManager {
             });
         }
 
-        Ok(deltas)
+        Ok(deltas)
+    }
+
+    /// Unified diff of one file's staged or unstaged changes
+    pub fn file_diff(repo_path: &Path, path: &str, staged: bool) -> GitResult<FileDiff> {
+        validate_relative_path(path)?;
+
+        let repo = git2::Repository::open(repo_path)
+            .map_err(|e| GitError::NotARepo(e.message().to_string()))?;
+
+        let mut opts = git2::DiffOptions::new();
+        opts.pathspec(path).disable_pathspec_match(true);
+
+        let diff = if staged {
+            let head = repo.head().ok();
+            let head_tree = head.as_ref().and_then(|h| h.peel_to_tree().ok());
+            repo.diff_tree_to_index(head_tree.as_ref(), None, Some(&mut opts))
+        } else {
+            opts.include_untracked(true)
+                .recurse_untracked_dirs(true)
+                .show_untracked_content(true);
+            repo.diff_index_to_workdir(None, Some(&mut opts))
+        }
+        .map_err(|e| GitError::OperationFailed(e.message().to_string()))?;
+
+        // Binary detection happens as file content is loaded for the patch
+        let diff_text = Self::diff_to_text(&diff)?;
+        let binary = diff.deltas().any(|d| d.flags().is_binary());
+
+        Ok(FileDiff {
+            path: path.to_string(),
+            staged,
+            binary,
+            diff: if binary { String::new() } else { diff_text },
+        })
+    }
+
+    /// Unified patch of all working-tree changes (staged, unstaged and
+    /// untracked) against `base`, or HEAD when not given
+    pub fn patch(repo_path: &Path, base: Option<&str>) -> GitResult<String> {
+        let repo = git2::Repository::open(repo_path)
+            .map_err(|e| GitError::NotARepo(e.message().to_string()))?;
+
+        let base_tree = match base {
+            Some(rev) => Some(
+                repo.revparse_single(rev)
+                    .and_then(|object| object.peel_to_tree())
+                    .map_err(|_| GitError::NotFound(format!("Revision not found: {}", rev)))?,
+            ),
+            None => repo.head().ok().and_then(|h| h.peel_to_tree().ok()),
+        };
+
+        let mut opts = git2::DiffOptions::new();
+        opts.include_untracked(true)
+            .recurse_untracked_dirs(true)
+            .show_untracked_content(true)
+            .show_binary(true);
+        let diff = repo
+            .diff_tree_to_workdir_with_index(base_tree.as_ref(), Some(&mut opts))
+            .map_err(|e| GitError::OperationFailed(e.message().to_string()))?;
+
+        Self::diff_to_text(&diff)
+    }
+
+    /// Structured hunks of one file's working-tree changes against HEAD
+    /// (staged and unstaged, including untracked files)
+    pub fn diff_hunks(repo_path: &Path, path: &str) -> GitResult<FileHunks> {
+        validate_relative_path(path)?;
+
+        let repo = git2::Repository::open(repo_path)
+            .map_err(|e| GitError::NotARepo(e.message().to_string()))?;
+
+        let head = repo.head().ok();
+        let head_tree = head.as_ref().and_then(|h| h.peel_to_tree().ok());
+
+        let mut opts = git2::DiffOptions::new();
+        opts.pathspec(path)
+            .disable_pathspec_match(true)
+            .include_untracked(true)
+            .recurse_untracked_dirs(true)
+            .show_untracked_content(true);
+        let diff = repo
+            .diff_tree_to_workdir_with_index(head_tree.as_ref(), Some(&mut opts))
+            .map_err(|e| GitError::OperationFailed(e.message().to_string()))?;
+
+        let mut result = FileHunks {
+            path: path.to_string(),
+            binary: false,
+            hunks: Vec::new(),
+        };
+
+        for i in 0..diff.deltas().len() {
+            let Some(patch) = git2::Patch::from_diff(&diff, i)
+                .map_err(|e| GitError::OperationFailed(e.message().to_string()))?
+            else {
+                continue;
+            };
+            if patch.delta().flags().is_binary() {
+                result.binary = true;
+                continue;
+            }
+
+            for h in 0..patch.num_hunks() {
+                let (hunk, line_count) = patch
+                    .hunk(h)
+                    .map_err(|e| GitError::OperationFailed(e.message().to_string()))?;
+
+                let mut lines = Vec::with_capacity(line_count);
+                for l in 0..line_count {
+                    let line = patch
+                        .line_in_hunk(h, l)
+                        .map_err(|e| GitError::OperationFailed(e.message().to_string()))?;
+                    let kind = match line.origin() {
+                        ' ' => DiffLineKind::Context,
+                        '+' => DiffLineKind::Added,
+                        '-' => DiffLineKind::Removed,
+                        // End-of-file newline markers
+                        _ => continue,
+                    };
+                    let content = String::from_utf8_lossy(line.content());
+                    lines.push(DiffLine {
+                        kind,
+                        old_lineno: line.old_lineno(),
+                        new_lineno: line.new_lineno(),
+                        content: content.trim_end_matches(['\n', '\r']).to_string(),
+                    });
+                }
+
+                result.hunks.push(DiffHunk {
+                    header: String::from_utf8_lossy(hunk.header()).trim_end().to_string(),
+                    old_start: hunk.old_start(),
+                    old_lines: hunk.old_lines(),
+                    new_start: hunk.new_start(),
+                    new_lines: hunk.new_lines(),
+                    lines,
+                });
+            }
+        }
+
+        Ok(result)
     }
 
     // --- Clone operation ---
@@ -668,6 +1851,57 @@ impl GitManager {
             .map_err(|e| classify_clone_error(e, url))
     }
 
+    /// Probe a remote URL like `git ls-remote`, without downloading objects
+    ///
+    /// Only the ssh-agent is offered when the remote asks for credentials, so
+    /// the probe never blocks on a prompt. This is a synchronous network
+    /// operation: callers should run it with `spawn_blocking` and a timeout.
+    pub fn check_remote(url: &str) -> RemoteCheck {
+        let auth_requested = Rc::new(Cell::new(false));
+        let requested = Rc::clone(&auth_requested);
+        let tried_agent = Cell::new(false);
+
+        let mut callbacks = git2::RemoteCallbacks::new();
+        callbacks.credentials(move |_url, username_from_url, allowed| {
+            requested.set(true);
+            if allowed.contains(git2::CredentialType::SSH_KEY) && !tried_agent.replace(true) {
+                return git2::Cred::ssh_key_from_agent(username_from_url.unwrap_or("git"));
+            }
+            Err(git2::Error::from_str("credentials required"))
+        });
+
+        let probe = git2::Remote::create_detached(url).and_then(|mut remote| {
+            let connection = remote.connect_auth(git2::Direction::Fetch, Some(callbacks), None)?;
+            let default_branch = connection.default_branch().ok().and_then(|name| {
+                name.as_str()
+                    .map(|name| name.trim_start_matches("refs/heads/").to_string())
+            });
+            Ok(default_branch)
+        });
+
+        match probe {
+            Ok(default_branch) => RemoteCheck {
+                reachable: true,
+                auth_required: auth_requested.get(),
+                default_branch,
+                error: None,
+            },
+            Err(e) => {
+                let error = classify_clone_error(e, url);
+                RemoteCheck {
+                    reachable: false,
+                    auth_required: auth_requested.get()
+                        || matches!(
+                            error,
+                            CloneError::SshAuthFailed { .. } | CloneError::HttpsAuthFailed { .. }
+                        ),
+                    default_branch: None,
+                    error: Some(error.to_string()),
+                }
+            }
+        }
+    }
+
     // --- Write operations using CLI subprocess ---
 
     /// Execute git pull
@@ -676,8 +1910,50 @@ impl GitManager {
     }
 
     /// Execute git push
-    pub fn push(repo_path: &Path) -> GitResult<CommandOutput> {
-        Self::run_git_command(repo_path, &["push"])
+    ///
+    /// With no remote, branch or upstream option this is a bare `git push`.
+    /// Otherwise the remote and branch are resolved up front and the remote
+    /// must exist.
+    pub fn push(repo_path: &Path, options: &PushOptions) -> GitResult<CommandOutput> {
+        let mut args = vec!["push".to_string()];
+        if options.force {
+            args.push("--force-with-lease".to_string());
+        }
+
+        if options.set_upstream || options.remote.is_some() || options.branch.is_some() {
+            let repo = git2::Repository::open(repo_path)
+                .map_err(|e| GitError::NotARepo(e.message().to_string()))?;
+
+            let branch = match &options.branch {
+                Some(branch) => branch.clone(),
+                None => Self::current_branch(repo_path)?.ok_or_else(|| {
+                    GitError::InvalidBranch("HEAD is detached; name a branch to push".to_string())
+                })?,
+            };
+            if branch.starts_with('-') || !git2::Branch::name_is_valid(&branch).unwrap_or(false) {
+                return Err(GitError::InvalidBranch(branch));
+            }
+
+            let remote = match &options.remote {
+                Some(remote) => remote.clone(),
+                None => repo
+                    .config()
+                    .and_then(|config| config.get_string(&format!("branch.{}.remote", branch)))
+                    .unwrap_or_else(|_| "origin".to_string()),
+            };
+            if remote.starts_with('-') || repo.find_remote(&remote).is_err() {
+                return Err(GitError::NotFound(format!("Remote not found: {}", remote)));
+            }
+
+            if options.set_upstream {
+                args.push("--set-upstream".to_string());
+            }
+            args.push(remote);
+            args.push(branch);
+        }
+
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        Self::run_git_command(repo_path, &args)
     }
 
     /// Execute git commit with message
@@ -685,11 +1961,60 @@ impl GitManager {
         Self::run_git_command(repo_path, &["commit", "-m", message])
     }
 
+    /// Amend the last commit with the staged changes, keeping its message
+    /// unless a new one is given
+    pub fn commit_amend(repo_path: &Path, message: Option<&str>) -> GitResult<CommandOutput> {
+        if Self::head_sha(repo_path)?.is_none() {
+            return Err(GitError::OperationFailed(
+                "Cannot amend: the repository has no commits yet".to_string(),
+            ));
+        }
+
+        match message {
+            Some(message) => Self::run_git_command(repo_path, &["commit", "--amend", "-m", message]),
+            None => Self::run_git_command(repo_path, &["commit", "--amend", "--no-edit"]),
+        }
+    }
+
     /// Execute git reset --hard
     pub fn reset_hard(repo_path: &Path) -> GitResult<CommandOutput> {
         Self::run_git_command(repo_path, &["reset", "--hard"])
     }
 
+    /// Execute git reset --hard to a specific commit
+    pub fn reset_hard_to(repo_path: &Path, sha: &str) -> GitResult<CommandOutput> {
+        git2::Oid::from_str(sha)
+            .map_err(|_| GitError::OperationFailed(format!("Invalid commit sha: {}", sha)))?;
+        Self::run_git_command(repo_path, &["reset", "--hard", sha])
+    }
+
+    /// Get the sha HEAD points to (None for a repository without commits)
+    pub fn head_sha(repo_path: &Path) -> GitResult<Option<String>> {
+        let repo = git2::Repository::open(repo_path)
+            .map_err(|e| GitError::NotARepo(e.message().to_string()))?;
+
+        match repo.head() {
+            Ok(head) => Ok(head.target().map(|oid| oid.to_string())),
+            Err(e) if e.code() == git2::ErrorCode::UnbornBranch => Ok(None),
+            Err(e) => Err(GitError::OperationFailed(e.message().to_string())),
+        }
+    }
+
+    /// Name of the checked-out branch, or `None` when HEAD is detached
+    pub fn current_branch(repo_path: &Path) -> GitResult<Option<String>> {
+        let repo = git2::Repository::open(repo_path)
+            .map_err(|e| GitError::NotARepo(e.message().to_string()))?;
+
+        // Read HEAD itself so an unborn branch still reports its name
+        let head = repo
+            .find_reference("HEAD")
+            .map_err(|e| GitError::OperationFailed(e.message().to_string()))?;
+        Ok(head
+            .symbolic_target()
+            .and_then(|target| target.strip_prefix("refs/heads/"))
+            .map(String::from))
+    }
+
     /// Execute git checkout to switch branch
     pub fn checkout(repo_path: &Path, branch: &str) -> GitResult<CommandOutput> {
         // Validate branch name (basic sanity check)
@@ -699,11 +2024,147 @@ impl GitManager {
         Self::run_git_command(repo_path, &["checkout", branch])
     }
 
+    /// Discard working tree and index changes to a single file, restoring it from HEAD
+    pub fn checkout_file(repo_path: &Path, path: &str) -> GitResult<()> {
+        validate_relative_path(path)?;
+
+        let repo = git2::Repository::open(repo_path)
+            .map_err(|e| GitError::NotARepo(e.message().to_string()))?;
+
+        let head_tree = repo
+            .head()
+            .and_then(|h| h.peel_to_tree())
+            .map_err(|e| GitError::OperationFailed(e.message().to_string()))?;
+        if head_tree.get_path(Path::new(path)).is_err() {
+            return Err(GitError::InvalidPath(format!("'{}' is not tracked in HEAD", path)));
+        }
+
+        let mut checkout = git2::build::CheckoutBuilder::new();
+        checkout.force().disable_pathspec_match(true).path(path);
+
+        repo.checkout_head(Some(&mut checkout))
+            .map_err(|e| GitError::OperationFailed(e.message().to_string()))
+    }
+
+    /// List every path with uncommitted changes (staged, unstaged or untracked)
+    pub fn changed_paths(repo_path: &Path) -> GitResult<Vec<String>> {
+        let status = Self::status(repo_path)?;
+
+        let mut paths: Vec<String> = status
+            .staged
+            .iter()
+            .chain(status.unstaged.iter())
+            .flat_map(|f| std::iter::once(f.path.clone()).chain(f.old_path.clone()))
+            .chain(status.untracked)
+            .collect();
+        paths.sort();
+        paths.dedup();
+
+        Ok(paths)
+    }
+
+    /// Snapshot the uncommitted changes, so later edits (even to files that
+    /// were already dirty) can be told apart from them
+    pub fn worktree_snapshot(repo_path: &Path) -> GitResult<WorktreeSnapshot> {
+        let paths = Self::changed_paths(repo_path)?;
+
+        Ok(paths
+            .into_iter()
+            .map(|path| {
+                let hash = git2::Oid::hash_file(git2::ObjectType::Blob, repo_path.join(&path))
+                    .ok()
+                    .map(|oid| oid.to_string());
+                (path, hash)
+            })
+            .collect())
+    }
+
+    /// Discard changes to the given paths: tracked files are restored from
+    /// HEAD and files that don't exist in HEAD are removed
+    pub fn revert_paths(repo_path: &Path, paths: &[String]) -> GitResult<()> {
+        if paths.is_empty() {
+            return Ok(());
+        }
+
+        let repo = git2::Repository::open(repo_path)
+            .map_err(|e| GitError::NotARepo(e.message().to_string()))?;
+        let head_tree = repo.head().and_then(|h| h.peel_to_tree()).ok();
+
+        let (tracked, new): (Vec<&String>, Vec<&String>) = paths.iter().partition(|path| {
+            head_tree
+                .as_ref()
+                .is_some_and(|tree| tree.get_path(Path::new(path.as_str())).is_ok())
+        });
+
+        if !tracked.is_empty() {
+            let mut checkout = git2::build::CheckoutBuilder::new();
+            checkout.force().disable_pathspec_match(true);
+            for path in &tracked {
+                checkout.path(path.as_str());
+            }
+            repo.checkout_head(Some(&mut checkout))
+                .map_err(|e| GitError::OperationFailed(e.message().to_string()))?;
+        }
+
+        if !new.is_empty() {
+            let mut index = repo
+                .index()
+                .map_err(|e| GitError::OperationFailed(e.message().to_string()))?;
+            for path in &new {
+                let _ = index.remove_path(Path::new(path.as_str()));
+                let full_path = repo_path.join(path.as_str());
+                if full_path.is_file() {
+                    std::fs::remove_file(&full_path).map_err(|e| {
+                        GitError::OperationFailed(format!("Failed to remove {}: {}", path, e))
+                    })?;
+                }
+            }
+            index
+                .write()
+                .map_err(|e| GitError::OperationFailed(e.message().to_string()))?;
+        }
+
+        Ok(())
+    }
+
     /// Stage all changes (git add -A)
     pub fn add_all(repo_path: &Path) -> GitResult<CommandOutput> {
         Self::run_git_command(repo_path, &["add", "-A"])
     }
 
+    /// Stage only changed files matching any of the glob `patterns`,
+    /// including deletions. Returns the staged paths.
+    pub fn add_paths(repo_path: &Path, patterns: &[String]) -> GitResult<Vec<String>> {
+        let changed = Self::changed_paths(repo_path)?;
+        let matched = match_patterns(patterns, &changed)?;
+        if matched.is_empty() {
+            return Ok(matched);
+        }
+
+        let repo = git2::Repository::open(repo_path)
+            .map_err(|e| GitError::NotARepo(e.message().to_string()))?;
+        let mut index = repo
+            .index()
+            .map_err(|e| GitError::OperationFailed(e.message().to_string()))?;
+
+        for path in &matched {
+            let result = if repo_path.join(path).exists() {
+                index.add_path(Path::new(path))
+            } else {
+                index.remove_path(Path::new(path))
+            };
+            result.map_err(|e| {
+                GitError::OperationFailed(format!("Failed to stage {}: {}", path, e.message()))
+            })?;
+        }
+
+        index
+            .write()
+            .map_err(|e| GitError::OperationFailed(e.message().to_string()))?;
+
+        Ok(matched)
+    }
+
     // --- Helper methods ---
 
     fn get_current_branch(repo: &git2::Repository) -> GitResult<String> {
@@ -725,27 +2186,44 @@ impl GitManager {
         }
     }
 
-    fn get_ahead_behind(repo: &git2::Repository) -> GitResult<(usize, usize)> {
+    /// Upstream name and ahead/behind counts of the current branch
+    fn get_upstream_tracking(repo: &git2::Repository) -> (Option<String>, usize, usize) {
         let head = repo.head().ok();
-        let head_ref = head.as_ref().and_then(|h| h.shorthand());
-
-        if let Some(branch_name) = head_ref {
-            // Try to find upstream
-            if let Ok(branch) = repo.find_branch(branch_name, git2::BranchType::Local) {
-                if let Ok(upstream) = branch.upstream() {
-                    let local_oid = repo.head().ok().and_then(|h| h.target());
-                    let upstream_oid = upstream.get().target();
-
-                    if let (Some(local), Some(upstream)) = (local_oid, upstream_oid) {
-                        if let Ok((ahead, behind)) = repo.graph_ahead_behind(local, upstream) {
-                            return Ok((ahead, behind));
-                        }
-                    }
-                }
-            }
-        }
+        let Some(branch_name) = head.as_ref().filter(|h| h.is_branch()).and_then(|h| h.shorthand())
+        else {
+            return (None, 0, 0);
+        };
+        let Ok(branch) = repo.find_branch(branch_name, git2::BranchType::Local) else {
+            return (None, 0, 0);
+        };
+        let Ok(upstream) = branch.upstream() else {
+            return (None, 0, 0);
+        };
+
+        let upstream_name = upstream.name().ok().flatten().map(String::from);
+        let local_oid = head.as_ref().and_then(|h| h.target());
+        let upstream_oid = upstream.get().target();
+
+        let (ahead, behind) = match (local_oid, upstream_oid) {
+            (Some(local), Some(upstream)) => repo.graph_ahead_behind(local, upstream).unwrap_or((0, 0)),
+            _ => (0, 0),
+        };
+
+        (upstream_name, ahead, behind)
+    }
 
-        Ok((0, 0))
+    /// Render a diff in unified patch format
+    fn diff_to_text(diff: &git2::Diff) -> GitResult<String> {
+        let mut text = String::new();
+        diff.print(git2::DiffFormat::Patch, |_, _, line| {
+            if matches!(line.origin(), '+' | '-' | ' ') {
+                text.push(line.origin());
+            }
+            text.push_str(&String::from_utf8_lossy(line.content()));
+            true
+        })
+        .map_err(|e| GitError::OperationFailed(e.message().to_string()))?;
+        Ok(text)
     }
 
     fn run_git_command(repo_path: &Path, args: &[&str]) -> GitResult<CommandOutput> {
@@ -792,6 +2270,290 @@ mod tests {
         (temp_dir, repo)
     }
 
+    #[test]
+    fn test_validate_branch_name() {
+        assert!(validate_branch_name("feature/login").is_ok());
+        for name in ["", "-f", "a..b", "a b", "a~1", "ends.lock", "trailing/", "x:y", "@", "HEAD"] {
+            assert!(
+                matches!(validate_branch_name(name), Err(GitError::InvalidBranch(_))),
+                "{:?} should be rejected",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_push_with_options() {
+        let (temp_dir, repo) = create_test_repo();
+        let path = temp_dir.path();
+        let remote_dir = TempDir::new().unwrap();
+        git2::Repository::init_bare(remote_dir.path()).unwrap();
+        repo.remote("origin", &remote_dir.path().to_string_lossy()).unwrap();
+        fs::write(path.join("file.txt"), "one\n").unwrap();
+        GitManager::add_all(path).unwrap();
+        GitManager::commit(path, "First").unwrap();
+        let branch = GitManager::current_branch(path).unwrap().unwrap();
+
+        // A new branch has no upstream, so a bare push fails
+        assert!(!GitManager::push(path, &PushOptions::default()).unwrap().success);
+
+        let output = GitManager::push(
+            path,
+            &PushOptions {
+                set_upstream: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert!(output.success, "{}", output.stderr);
+        let config = repo.config().unwrap().snapshot().unwrap();
+        assert_eq!(config.get_str(&format!("branch.{}.remote", branch)).unwrap(), "origin");
+
+        // Rewriting the pushed commit needs force
+        GitManager::commit_amend(path, Some("First, reworded")).unwrap();
+        assert!(!GitManager::push(path, &PushOptions::default()).unwrap().success);
+        let force = PushOptions {
+            force: true,
+            ..Default::default()
+        };
+        assert!(GitManager::push(path, &force).unwrap().success);
+
+        let unknown = PushOptions {
+            remote: Some("fork".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(GitManager::push(path, &unknown), Err(GitError::NotFound(_))));
+        let bad_branch = PushOptions {
+            branch: Some("--all".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            GitManager::push(path, &bad_branch),
+            Err(GitError::InvalidBranch(_))
+        ));
+    }
+
+    #[test]
+    fn test_add_list_and_remove_remotes() {
+        let (temp_dir, repo) = create_test_repo();
+        let path = temp_dir.path();
+        assert!(GitManager::list_remotes(path).unwrap().is_empty());
+
+        GitManager::add_remote(path, "origin", "https://example.com/upstream.git").unwrap();
+        GitManager::add_remote(path, "fork", "git@example.com:me/fork.git").unwrap();
+        repo.remote_set_pushurl("origin", Some("git@example.com:upstream.git"))
+            .unwrap();
+        assert!(matches!(
+            GitManager::add_remote(path, "fork", "https://example.com/other.git"),
+            Err(GitError::InvalidRemote(_))
+        ));
+        for (name, url) in [("bad name", "https://example.com/x.git"), ("x", "--upload-pack=evil")] {
+            assert!(matches!(
+                GitManager::add_remote(path, name, url),
+                Err(GitError::InvalidRemote(_))
+            ));
+        }
+
+        let remotes = GitManager::list_remotes(path).unwrap();
+        let origin = remotes.iter().find(|r| r.name == "origin").unwrap();
+        assert_eq!(origin.fetch_url.as_deref(), Some("https://example.com/upstream.git"));
+        assert_eq!(origin.push_url.as_deref(), Some("git@example.com:upstream.git"));
+        let fork = remotes.iter().find(|r| r.name == "fork").unwrap();
+        assert_eq!(fork.push_url, fork.fetch_url);
+
+        GitManager::remove_remote(path, "fork").unwrap();
+        assert!(matches!(
+            GitManager::remove_remote(path, "fork"),
+            Err(GitError::NotFound(_))
+        ));
+        assert_eq!(GitManager::list_remotes(path).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_create_and_delete_branch() {
+        let (temp_dir, repo) = create_test_repo();
+        let path = temp_dir.path();
+        let current = GitManager::status(path).unwrap().branch;
+
+        let branch = GitManager::create_branch(path, "feature/x", None).unwrap();
+        assert_eq!(branch.name, "feature/x");
+        assert!(matches!(
+            GitManager::create_branch(path, "feature/x", None),
+            Err(GitError::InvalidBranch(_))
+        ));
+        assert!(matches!(
+            GitManager::create_branch(path, "other", Some("no-such-rev")),
+            Err(GitError::NotFound(_))
+        ));
+        // Creating a branch does not switch to it
+        assert_eq!(GitManager::status(path).unwrap().branch, current);
+
+        // A branch with commits not in HEAD needs force
+        {
+            let sig = repo.signature().unwrap();
+            let parent = repo.head().unwrap().peel_to_commit().unwrap();
+            let tree = parent.tree().unwrap();
+            repo.commit(
+                Some("refs/heads/feature/x"),
+                &sig,
+                &sig,
+                "Unmerged work",
+                &tree,
+                &[&parent],
+            )
+            .unwrap();
+        }
+        assert!(matches!(
+            GitManager::delete_branch(path, "feature/x", false),
+            Err(GitError::InvalidBranch(_))
+        ));
+        GitManager::delete_branch(path, "feature/x", true).unwrap();
+        assert!(repo.find_branch("feature/x", git2::BranchType::Local).is_err());
+
+        assert!(matches!(
+            GitManager::delete_branch(path, "feature/x", true),
+            Err(GitError::NotFound(_))
+        ));
+        assert!(matches!(
+            GitManager::delete_branch(path, &current, true),
+            Err(GitError::InvalidBranch(_))
+        ));
+
+        // Merged branches delete without force
+        GitManager::create_branch(path, "merged", Some("HEAD")).unwrap();
+        GitManager::delete_branch(path, "merged", false).unwrap();
+    }
+
+    #[test]
+    fn test_stash_save_list_pop_drop() {
+        let (temp_dir, _repo) = create_test_repo();
+        let path = temp_dir.path();
+
+        assert!(GitManager::stash_list(path).unwrap().is_empty());
+        assert!(matches!(GitManager::stash_pop(path, 0), Err(GitError::Stash(_))));
+        assert!(matches!(GitManager::stash_drop(path, 0), Err(GitError::Stash(_))));
+        assert!(matches!(GitManager::stash_save(path, None), Err(GitError::Stash(_))));
+
+        fs::write(path.join("first.txt"), "first").unwrap();
+        GitManager::stash_save(path, Some("first change")).unwrap();
+        fs::write(path.join("second.txt"), "second").unwrap();
+        GitManager::stash_save(path, Some("second change")).unwrap();
+
+        // Untracked files are stashed too
+        assert!(GitManager::status(path).unwrap().untracked.is_empty());
+
+        let entries = GitManager::stash_list(path).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].index, 0);
+        assert!(entries[0].message.contains("second change"));
+        assert!(entries[1].message.contains("first change"));
+        assert!(!entries[0].created_at.is_empty());
+
+        assert!(matches!(GitManager::stash_pop(path, 2), Err(GitError::NotFound(_))));
+
+        GitManager::stash_drop(path, 0).unwrap();
+        GitManager::stash_pop(path, 0).unwrap();
+        assert!(GitManager::stash_list(path).unwrap().is_empty());
+        assert_eq!(
+            GitManager::status(path).unwrap().untracked,
+            vec!["first.txt".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_status_in_subdirectory() {
+        let (temp_dir, _repo) = create_test_repo();
+        let path = temp_dir.path();
+        fs::create_dir_all(path.join("packages/app/src")).unwrap();
+        fs::create_dir_all(path.join("packages/lib")).unwrap();
+        fs::write(path.join("packages/app/src/main.rs"), "fn main() {}").unwrap();
+        fs::write(path.join("packages/lib/lib.rs"), "").unwrap();
+        fs::write(path.join("README.md"), "readme").unwrap();
+
+        let status = GitManager::status_in(path, "packages/app/").unwrap();
+        assert_eq!(status.untracked, vec!["packages/app/src/main.rs".to_string()]);
+        assert!(!status.branch.is_empty());
+
+        // Unscoped status still sees everything
+        assert_eq!(GitManager::status(path).unwrap().untracked.len(), 3);
+
+        assert!(matches!(
+            GitManager::status_in(path, "../outside"),
+            Err(GitError::InvalidPath(_))
+        ));
+        assert!(matches!(GitManager::status_in(path, "/etc"), Err(GitError::InvalidPath(_))));
+    }
+
+    #[test]
+    fn test_commit_amend() {
+        let (temp_dir, _repo) = create_test_repo();
+        let path = temp_dir.path();
+
+        fs::write(path.join("a.txt"), "a").unwrap();
+        GitManager::add_all(path).unwrap();
+        assert!(GitManager::commit(path, "Add a").unwrap().success);
+
+        // Keep the message, fold in newly staged changes
+        fs::write(path.join("b.txt"), "b").unwrap();
+        GitManager::add_all(path).unwrap();
+        assert!(GitManager::commit_amend(path, None).unwrap().success);
+        let commits = GitManager::log(path, 10).unwrap();
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[0].message, "Add a");
+        assert!(GitManager::status(path).unwrap().staged.is_empty());
+
+        assert!(GitManager::commit_amend(path, Some("Add a and b")).unwrap().success);
+        let commits = GitManager::log(path, 10).unwrap();
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[0].message, "Add a and b");
+
+        let empty = TempDir::new().unwrap();
+        git2::Repository::init(empty.path()).unwrap();
+        assert!(matches!(
+            GitManager::commit_amend(empty.path(), None),
+            Err(GitError::OperationFailed(_))
+        ));
+    }
+
+    #[test]
+    fn test_patch_applies_cleanly() {
+        let (temp_dir, _repo) = create_test_repo();
+        let path = temp_dir.path();
+        fs::write(path.join("tracked.txt"), "one\n").unwrap();
+        GitManager::add_all(path).unwrap();
+        GitManager::commit(path, "Add tracked").unwrap();
+        let base = GitManager::head_sha(path).unwrap().unwrap();
+
+        fs::write(path.join("tracked.txt"), "one\ntwo\n").unwrap();
+        GitManager::add_all(path).unwrap();
+        GitManager::commit(path, "Extend tracked").unwrap();
+        fs::create_dir(path.join("src")).unwrap();
+        fs::write(path.join("src/new.txt"), "new\n").unwrap();
+
+        let working = GitManager::patch(path, None).unwrap();
+        assert!(working.contains("+++ b/src/new.txt"));
+        assert!(!working.contains("tracked.txt"));
+
+        let since_base = GitManager::patch(path, Some(&base)).unwrap();
+        assert!(since_base.contains("+two"));
+        assert!(since_base.contains("+++ b/src/new.txt"));
+
+        // The patch recreates the changes on a checkout of the base commit
+        let other = TempDir::new().unwrap();
+        let clone = git2::Repository::clone(path.to_str().unwrap(), other.path()).unwrap();
+        let commit = clone.revparse_single(&base).unwrap();
+        clone.reset(&commit, git2::ResetType::Hard, None).unwrap();
+        let diff = git2::Diff::from_buffer(since_base.as_bytes()).unwrap();
+        clone.apply(&diff, git2::ApplyLocation::WorkDir, None).unwrap();
+        assert_eq!(fs::read_to_string(other.path().join("tracked.txt")).unwrap(), "one\ntwo\n");
+        assert_eq!(fs::read_to_string(other.path().join("src/new.txt")).unwrap(), "new\n");
+
+        assert!(matches!(
+            GitManager::patch(path, Some("no-such-rev")),
+            Err(GitError::NotFound(_))
+        ));
+    }
+
     #[test]
     fn test_status_clean_repo() {
         let (temp_dir, _repo) = create_test_repo();
@@ -845,6 +2607,33 @@ mod tests {
         assert_eq!(status.unstaged[0].status, FileStatusType::Modified);
     }
 
+    #[test]
+    fn test_status_tracks_upstream() {
+        let (temp_dir, repo) = create_test_repo();
+
+        let status = GitManager::status(temp_dir.path()).unwrap();
+        assert!(status.upstream.is_none());
+        assert_eq!((status.ahead, status.behind), (0, 0));
+
+        // Track a local branch at the initial commit, then commit ahead of it
+        let head_commit = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.branch("base", &head_commit, false).unwrap();
+        let branch_name = repo.head().unwrap().shorthand().unwrap().to_string();
+        repo.find_branch(&branch_name, git2::BranchType::Local)
+            .unwrap()
+            .set_upstream(Some("base"))
+            .unwrap();
+
+        let sig = repo.signature().unwrap();
+        let tree = head_commit.tree().unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "Ahead", &tree, &[&head_commit])
+            .unwrap();
+
+        let status = GitManager::status(temp_dir.path()).unwrap();
+        assert_eq!(status.upstream.as_deref(), Some("base"));
+        assert_eq!((status.ahead, status.behind), (1, 0));
+    }
+
     #[test]
     fn test_log() {
         let (temp_dir, _repo) = create_test_repo();
@@ -853,6 +2642,45 @@ mod tests {
 
         assert_eq!(commits.len(), 1);
         assert_eq!(commits[0].message, "Initial commit");
+        assert!(commits[0].session_id.is_none());
+    }
+
+    #[test]
+    fn test_session_trailer_round_trip() {
+        let stamped = append_trailer("Fix parser\n", SESSION_TRAILER, "abc");
+        assert_eq!(stamped, "Fix parser\n\nRalphtown-Session: abc");
+        assert_eq!(parse_trailer(&stamped, SESSION_TRAILER).as_deref(), Some("abc"));
+
+        // Joins an existing trailer block
+        let stamped = append_trailer(
+            "Fix parser\n\nDetails here.\n\nSigned-off-by: Dev <dev@example.com>",
+            SESSION_TRAILER,
+            "abc",
+        );
+        assert!(stamped.ends_with("Signed-off-by: Dev <dev@example.com>\nRalphtown-Session: abc"));
+        assert_eq!(
+            parse_trailer(&stamped, "ralphtown-session").as_deref(),
+            Some("abc")
+        );
+
+        // Only the final paragraph counts, and only if it is all trailers
+        assert!(parse_trailer("Ralphtown-Session: abc", SESSION_TRAILER).is_none());
+        assert!(parse_trailer("Subject\n\nRalphtown-Session: abc\nnot a trailer", SESSION_TRAILER).is_none());
+    }
+
+    #[test]
+    fn test_log_surfaces_session_trailer() {
+        let (temp_dir, repo) = create_test_repo();
+        let sig = repo.signature().unwrap();
+        let parent = repo.head().unwrap().peel_to_commit().unwrap();
+        let tree = parent.tree().unwrap();
+        let message = append_trailer("Agent change", SESSION_TRAILER, "session-1");
+        repo.commit(Some("HEAD"), &sig, &sig, &message, &tree, &[&parent])
+            .unwrap();
+
+        let commits = GitManager::log(temp_dir.path(), 10).unwrap();
+        assert_eq!(commits[0].session_id.as_deref(), Some("session-1"));
+        assert!(commits[1].session_id.is_none());
     }
 
     #[test]
@@ -917,6 +2745,57 @@ mod tests {
         assert!(matches!(result, Err(GitError::NotARepo(_))));
     }
 
+    #[test]
+    fn test_diff_worktrees() {
+        let (temp_dir, repo) = create_test_repo();
+        fs::write(temp_dir.path().join("shared.txt"), "one\n").unwrap();
+        let wt_parent = TempDir::new().unwrap();
+        let wt_path = wt_parent.path().join("wt");
+        repo.worktree("wt", &wt_path, None).unwrap();
+
+        assert_eq!(
+            GitManager::common_dir(temp_dir.path()).unwrap(),
+            GitManager::common_dir(&wt_path).unwrap()
+        );
+
+        fs::write(wt_path.join("shared.txt"), "one\ntwo\n").unwrap();
+        fs::write(wt_path.join("new.txt"), "hello\n").unwrap();
+
+        let diff = GitManager::diff_worktrees(temp_dir.path(), &wt_path).unwrap();
+        let mut paths: Vec<&str> = diff.files.iter().map(|f| f.path.as_str()).collect();
+        paths.sort();
+        assert_eq!(paths, ["new.txt", "shared.txt"]);
+        let shared = diff.files.iter().find(|f| f.path == "shared.txt").unwrap();
+        assert_eq!((shared.added, shared.removed), (1, 0));
+        assert!(diff.patch.contains("+hello"));
+
+        // Snapshotting leaves both indexes as they were
+        assert_eq!(GitManager::status(temp_dir.path()).unwrap().untracked, ["shared.txt"]);
+        assert!(GitManager::status(&wt_path).unwrap().staged.is_empty());
+    }
+
+    #[test]
+    fn test_current_branch() {
+        // An unborn branch still has a name
+        let unborn_dir = TempDir::new().unwrap();
+        git2::Repository::init(unborn_dir.path()).unwrap();
+        assert!(GitManager::current_branch(unborn_dir.path()).unwrap().is_some());
+
+        let (temp_dir, repo) = create_test_repo();
+        let oid = repo.head().unwrap().target().unwrap();
+        let commit = repo.find_commit(oid).unwrap();
+
+        repo.branch("feature/foo", &commit, false).unwrap();
+        repo.set_head("refs/heads/feature/foo").unwrap();
+        assert_eq!(
+            GitManager::current_branch(temp_dir.path()).unwrap().as_deref(),
+            Some("feature/foo")
+        );
+
+        repo.set_head_detached(oid).unwrap();
+        assert_eq!(GitManager::current_branch(temp_dir.path()).unwrap(), None);
+    }
+
     #[test]
     fn test_checkout_invalid_branch() {
         let result = GitManager::checkout(Path::new("/tmp"), "--invalid");
@@ -973,6 +2852,23 @@ mod tests {
         drop(cloned_repo);
     }
 
+    #[test]
+    fn test_check_remote() {
+        let (temp_dir, _repo) = create_test_repo();
+        let url = temp_dir.path().to_string_lossy().to_string();
+
+        let check = GitManager::check_remote(&url);
+        assert!(check.reachable, "{:?}", check.error);
+        assert!(!check.auth_required);
+        assert!(check.default_branch.is_some());
+        assert!(check.error.is_none());
+
+        let check = GitManager::check_remote(&temp_dir.path().join("missing").to_string_lossy());
+        assert!(!check.reachable);
+        assert!(!check.auth_required);
+        assert!(check.error.is_some());
+    }
+
     #[test]
     fn test_clone_invalid_url() {
         let dest_dir = TempDir::new().expect("Failed to create dest temp dir");
@@ -1021,4 +2917,468 @@ mod tests {
         let result = validate_repo_path(temp_dir.path());
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_checkout_file_restores_from_head() {
+        let (temp_dir, repo) = create_test_repo();
+
+        // Commit a tracked file
+        fs::write(temp_dir.path().join("tracked.txt"), "original").unwrap();
+        fs::write(temp_dir.path().join("other.txt"), "original").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("tracked.txt")).unwrap();
+        index.add_path(Path::new("other.txt")).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = repo.signature().unwrap();
+        let parent = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "Add files", &tree, &[&parent])
+            .unwrap();
+
+        fs::write(temp_dir.path().join("tracked.txt"), "changed").unwrap();
+        fs::write(temp_dir.path().join("other.txt"), "changed").unwrap();
+
+        GitManager::checkout_file(temp_dir.path(), "tracked.txt").expect("Failed to checkout file");
+
+        assert_eq!(fs::read_to_string(temp_dir.path().join("tracked.txt")).unwrap(), "original");
+        // Other files are left alone
+        assert_eq!(fs::read_to_string(temp_dir.path().join("other.txt")).unwrap(), "changed");
+    }
+
+    #[test]
+    fn test_checkout_file_rejects_bad_paths() {
+        let (temp_dir, _repo) = create_test_repo();
+
+        for path in ["", "../outside.txt", "/etc/passwd", "untracked.txt"] {
+            let result = GitManager::checkout_file(temp_dir.path(), path);
+            assert!(matches!(result, Err(GitError::InvalidPath(_))), "path {:?}", path);
+        }
+    }
+
+    #[test]
+    fn test_activity_buckets_commits_by_day() {
+        let (temp_dir, repo) = create_test_repo();
+        let today = chrono::Utc::now().date_naive();
+
+        // Add a commit dated two days ago
+        let two_days_ago = chrono::Utc::now() - chrono::Duration::days(2);
+        let sig = git2::Signature::new(
+            "Test User",
+            "test@example.com",
+            &git2::Time::new(two_days_ago.timestamp(), 0),
+        )
+        .unwrap();
+        let tree = repo.find_tree(repo.index().unwrap().write_tree().unwrap()).unwrap();
+        let parent = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "Backdated commit", &tree, &[&parent])
+            .unwrap();
+
+        let activity = GitManager::activity(temp_dir.path(), 7, today).expect("Failed to get activity");
+        assert_eq!(activity.days.len(), 7);
+        assert!(!activity.truncated);
+        assert_eq!(activity.days.last().unwrap().date, today.format("%Y-%m-%d").to_string());
+        assert_eq!(activity.days[6].count, 1);
+        assert_eq!(activity.days[4].count, 1);
+        assert_eq!(activity.days.iter().map(|d| d.count).sum::<usize>(), 2);
+
+        // The window is capped
+        let activity = GitManager::activity(temp_dir.path(), 10_000, today).unwrap();
+        assert_eq!(activity.days.len(), MAX_ACTIVITY_DAYS as usize);
+    }
+
+    #[test]
+    fn test_parse_and_match_patterns() {
+        let patterns = parse_pattern_list(r#"[".github/**", "infra/*"]"#).unwrap();
+        let paths = vec![
+            ".github/workflows/ci.yml".to_string(),
+            "infra/main.tf".to_string(),
+            "src/main.rs".to_string(),
+        ];
+
+        let matched = match_patterns(&patterns, &paths).unwrap();
+        assert_eq!(matched, vec![".github/workflows/ci.yml", "infra/main.tf"]);
+
+        assert!(parse_pattern_list("not json").is_err());
+        assert!(parse_pattern_list(r#"[""]"#).is_err());
+    }
+
+    #[test]
+    fn test_revert_paths() {
+        let (temp_dir, repo) = create_test_repo();
+
+        fs::write(temp_dir.path().join("tracked.txt"), "original").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("tracked.txt")).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = repo.signature().unwrap();
+        let parent = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "Add file", &tree, &[&parent])
+            .unwrap();
+
+        fs::write(temp_dir.path().join("tracked.txt"), "changed").unwrap();
+        fs::write(temp_dir.path().join("new.txt"), "new").unwrap();
+        fs::write(temp_dir.path().join("keep.txt"), "keep").unwrap();
+
+        let changed = GitManager::changed_paths(temp_dir.path()).unwrap();
+        assert_eq!(changed, vec!["keep.txt", "new.txt", "tracked.txt"]);
+
+        GitManager::revert_paths(
+            temp_dir.path(),
+            &["tracked.txt".to_string(), "new.txt".to_string()],
+        )
+        .expect("Failed to revert paths");
+
+        assert_eq!(fs::read_to_string(temp_dir.path().join("tracked.txt")).unwrap(), "original");
+        assert!(!temp_dir.path().join("new.txt").exists());
+        assert!(temp_dir.path().join("keep.txt").exists());
+    }
+
+    #[test]
+    fn test_add_paths_stages_matching_files() {
+        let (temp_dir, repo) = create_test_repo();
+
+        fs::create_dir(temp_dir.path().join("src")).unwrap();
+        fs::write(temp_dir.path().join("src/lib.rs"), "fn main() {}").unwrap();
+        fs::write(temp_dir.path().join("notes.txt"), "scratch").unwrap();
+
+        let staged = GitManager::add_paths(temp_dir.path(), &["src/*.rs".to_string()])
+            .expect("Failed to stage paths");
+        assert_eq!(staged, vec!["src/lib.rs"]);
+
+        let mut index = repo.index().unwrap();
+        index.read(true).unwrap();
+        assert!(index.get_path(Path::new("src/lib.rs"), 0).is_some());
+        assert!(index.get_path(Path::new("notes.txt"), 0).is_none());
+
+        let staged = GitManager::add_paths(temp_dir.path(), &["*.md".to_string()]).unwrap();
+        assert!(staged.is_empty());
+    }
+
+    #[test]
+    fn test_file_diff_between_commits() {
+        let (temp_dir, repo) = create_test_repo();
+        let sig = repo.signature().unwrap();
+
+        let commit_file = |content: &str, message: &str| {
+            fs::write(temp_dir.path().join("file.txt"), content).unwrap();
+            let mut index = repo.index().unwrap();
+            index.add_path(Path::new("file.txt")).unwrap();
+            index.write().unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let parent = repo.head().unwrap().peel_to_commit().unwrap();
+            repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &[&parent])
+                .unwrap()
+        };
+        let first = commit_file("one\n", "First");
+        let second = commit_file("one\ntwo\n", "Second");
+
+        let diff = GitManager::file_diff_between(
+            temp_dir.path(),
+            "file.txt",
+            &first.to_string(),
+            "HEAD",
+        )
+        .expect("Failed to diff file");
+        assert_eq!(diff.to, second.to_string());
+        assert!(!diff.binary);
+        assert!(diff.diff.contains("+two"));
+        assert!(diff.diff.contains("--- a/file.txt"));
+
+        assert!(matches!(
+            GitManager::file_diff_between(temp_dir.path(), "file.txt", "nope", "HEAD"),
+            Err(GitError::NotFound(_))
+        ));
+        assert!(matches!(
+            GitManager::file_diff_between(temp_dir.path(), "missing.txt", "HEAD~1", "HEAD"),
+            Err(GitError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_show_commit_detail() {
+        let (temp_dir, repo) = create_test_repo();
+        let sig = repo.signature().unwrap();
+        let root = repo.head().unwrap().peel_to_commit().unwrap().id();
+
+        fs::write(temp_dir.path().join("notes.txt"), "first\nsecond\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("notes.txt")).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let parent = repo.find_commit(root).unwrap();
+        let message = append_trailer("Add notes\n\nLonger body", SESSION_TRAILER, "abc");
+        let oid = repo
+            .commit(Some("HEAD"), &sig, &sig, &message, &tree, &[&parent])
+            .unwrap();
+
+        let short = &oid.to_string()[..7];
+        let detail = GitManager::show(temp_dir.path(), short).unwrap();
+        assert_eq!(detail.id, oid.to_string());
+        assert_eq!(detail.parents, vec![root.to_string()]);
+        assert!(detail.message.contains("Longer body"));
+        assert_eq!(detail.session_id.as_deref(), Some("abc"));
+        assert_eq!(detail.author.name, "Test User");
+        assert_eq!(detail.committer.email, "test@example.com");
+        assert_eq!(detail.files.len(), 1);
+        assert_eq!(detail.files[0].added, 2);
+        assert!(detail.diff.contains("+second"));
+
+        let root_detail = GitManager::show(temp_dir.path(), "HEAD~1").unwrap();
+        assert_eq!(root_detail.id, root.to_string());
+        assert!(root_detail.parents.is_empty());
+
+        assert!(matches!(
+            GitManager::show(temp_dir.path(), "HEAD~5"),
+            Err(GitError::NotFound(_))
+        ));
+        assert!(matches!(
+            GitManager::show(temp_dir.path(), "HEAD:notes.txt"),
+            Err(GitError::InvalidBranch(_))
+        ));
+    }
+
+    #[test]
+    fn test_diff_refs_between_branches() {
+        let (temp_dir, repo) = create_test_repo();
+        let sig = repo.signature().unwrap();
+
+        let main_head = repo.head().unwrap().name().unwrap().to_string();
+        let base = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.branch("feature", &base, false).unwrap();
+        repo.set_head("refs/heads/feature").unwrap();
+
+        // Two commits on the feature branch touching different files
+        for (name, content) in [("a.txt", "one\ntwo\n"), ("b.txt", "three\n")] {
+            fs::write(temp_dir.path().join(name), content).unwrap();
+            let mut index = repo.index().unwrap();
+            index.add_path(Path::new(name)).unwrap();
+            index.write().unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let parent = repo.head().unwrap().peel_to_commit().unwrap();
+            repo.commit(Some("HEAD"), &sig, &sig, name, &tree, &[&parent])
+                .unwrap();
+        }
+
+        let deltas = GitManager::diff_refs(temp_dir.path(), &main_head, "feature").unwrap();
+        let summary: Vec<_> = deltas
+            .iter()
+            .map(|d| (d.path.as_str(), d.added, d.removed))
+            .collect();
+        assert_eq!(summary, vec![("a.txt", 2, 0), ("b.txt", 1, 0)]);
+
+        assert!(GitManager::diff_refs(temp_dir.path(), "feature", "feature")
+            .unwrap()
+            .is_empty());
+        assert!(matches!(
+            GitManager::diff_refs(temp_dir.path(), "feature", "no-such-branch"),
+            Err(GitError::InvalidBranch(_))
+        ));
+    }
+
+    #[test]
+    fn test_search_commits_by_message() {
+        let (temp_dir, repo) = create_test_repo();
+        let sig = repo.signature().unwrap();
+
+        for message in ["Fix login bug", "Add docs", "fix LOGIN again"] {
+            let tree = repo.head().unwrap().peel_to_tree().unwrap();
+            let parent = repo.head().unwrap().peel_to_commit().unwrap();
+            repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &[&parent])
+                .unwrap();
+        }
+
+        let found = GitManager::search_commits(temp_dir.path(), "Login", 10, 100).unwrap();
+        let messages: Vec<_> = found.commits.iter().map(|c| c.message.as_str()).collect();
+        assert_eq!(messages, vec!["fix LOGIN again", "Fix login bug"]);
+        assert!(!found.truncated);
+        assert_eq!(found.scanned, 4);
+
+        // Capped by matches or by commits walked
+        let found = GitManager::search_commits(temp_dir.path(), "login", 1, 100).unwrap();
+        assert_eq!(found.commits.len(), 1);
+        assert!(found.truncated);
+        let found = GitManager::search_commits(temp_dir.path(), "login", 10, 2).unwrap();
+        assert_eq!(found.commits.len(), 1);
+        assert_eq!(found.scanned, 2);
+        assert!(found.truncated);
+    }
+
+    #[test]
+    fn test_log_range_excludes_base_history() {
+        let (temp_dir, repo) = create_test_repo();
+        let sig = repo.signature().unwrap();
+        let base = repo.head().unwrap().peel_to_commit().unwrap().id().to_string();
+
+        for name in ["a.txt", "b.txt"] {
+            fs::write(temp_dir.path().join(name), name).unwrap();
+            let mut index = repo.index().unwrap();
+            index.add_path(Path::new(name)).unwrap();
+            index.write().unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let parent = repo.head().unwrap().peel_to_commit().unwrap();
+            repo.commit(Some("HEAD"), &sig, &sig, &format!("Add {}", name), &tree, &[&parent])
+                .unwrap();
+        }
+
+        let commits = GitManager::log_range(temp_dir.path(), &base).unwrap();
+        let messages: Vec<_> = commits.iter().map(|c| c.message.as_str()).collect();
+        assert_eq!(messages, vec!["Add b.txt", "Add a.txt"]);
+
+        assert!(GitManager::log_range(temp_dir.path(), "HEAD").unwrap().is_empty());
+        assert!(matches!(
+            GitManager::log_range(temp_dir.path(), "no-such-rev"),
+            Err(GitError::InvalidBranch(_))
+        ));
+    }
+
+    #[test]
+    fn test_conflicts_report_three_way_versions() {
+        let (temp_dir, repo) = create_test_repo();
+        let sig = repo.signature().unwrap();
+
+        let commit_files = |files: &[(&str, &[u8])], message: &str| {
+            for (name, content) in files {
+                fs::write(temp_dir.path().join(name), content).unwrap();
+            }
+            let mut index = repo.index().unwrap();
+            for (name, _) in files {
+                index.add_path(Path::new(name)).unwrap();
+            }
+            index.write().unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let parent = repo.head().unwrap().peel_to_commit().unwrap();
+            repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &[&parent])
+                .unwrap()
+        };
+
+        let base = commit_files(
+            &[("text.txt", b"base\n"), ("image.bin", b"\0base")],
+            "Base",
+        );
+        let base_commit = repo.find_commit(base).unwrap();
+        repo.branch("other", &base_commit, false).unwrap();
+
+        commit_files(&[("text.txt", b"ours\n"), ("image.bin", b"\0ours")], "Ours");
+        let main_head = repo.head().unwrap().name().unwrap().to_string();
+
+        repo.set_head("refs/heads/other").unwrap();
+        repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force()))
+            .unwrap();
+        let theirs = commit_files(
+            &[("text.txt", b"theirs\n"), ("image.bin", b"\0theirs")],
+            "Theirs",
+        );
+        repo.set_head(&main_head).unwrap();
+        repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force()))
+            .unwrap();
+
+        assert!(GitManager::conflicts(temp_dir.path()).unwrap().is_empty());
+        assert!(GitManager::conflicted_paths(temp_dir.path()).unwrap().is_empty());
+
+        let annotated = repo.find_annotated_commit(theirs).unwrap();
+        repo.merge(&[&annotated], None, None).unwrap();
+
+        let conflicts = GitManager::conflicts(temp_dir.path()).unwrap();
+        assert_eq!(conflicts.len(), 2);
+        assert_eq!(
+            GitManager::conflicted_paths(temp_dir.path()).unwrap(),
+            vec!["image.bin", "text.txt"]
+        );
+
+        let image = &conflicts[0];
+        assert_eq!(image.path, "image.bin");
+        let ours = image.ours.as_ref().unwrap();
+        assert!(ours.binary);
+        assert!(ours.content.is_none());
+
+        let text = &conflicts[1];
+        assert_eq!(text.path, "text.txt");
+        assert_eq!(text.base.as_ref().unwrap().content.as_deref(), Some("base\n"));
+        assert_eq!(text.ours.as_ref().unwrap().content.as_deref(), Some("ours\n"));
+        assert_eq!(text.theirs.as_ref().unwrap().content.as_deref(), Some("theirs\n"));
+        assert!(!text.theirs.as_ref().unwrap().binary);
+    }
+
+    #[test]
+    fn test_file_diff_staged_and_unstaged() {
+        let (temp_dir, repo) = create_test_repo();
+
+        fs::write(temp_dir.path().join("notes.txt"), "staged\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("notes.txt")).unwrap();
+        index.write().unwrap();
+        fs::write(temp_dir.path().join("notes.txt"), "staged\nunstaged\n").unwrap();
+        fs::write(temp_dir.path().join("blob.bin"), b"\0\x01binary").unwrap();
+
+        let staged = GitManager::file_diff(temp_dir.path(), "notes.txt", true).unwrap();
+        assert!(staged.staged);
+        assert!(staged.diff.contains("+staged"));
+        assert!(!staged.diff.contains("+unstaged"));
+
+        let unstaged = GitManager::file_diff(temp_dir.path(), "notes.txt", false).unwrap();
+        assert!(!unstaged.staged);
+        assert!(unstaged.diff.contains("+unstaged"));
+        assert!(!unstaged.diff.contains("+staged"));
+
+        let binary = GitManager::file_diff(temp_dir.path(), "blob.bin", false).unwrap();
+        assert!(binary.binary);
+        assert!(binary.diff.is_empty());
+    }
+
+    #[test]
+    fn test_diff_hunks_structure() {
+        let (temp_dir, repo) = create_test_repo();
+
+        let file_path = temp_dir.path().join("lines.txt");
+        fs::write(&file_path, "one\ntwo\nthree\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("lines.txt")).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = repo.signature().unwrap();
+        let parent = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "Add lines", &tree, &[&parent])
+            .unwrap();
+
+        fs::write(&file_path, "one\n2\nthree\n").unwrap();
+
+        let diff = GitManager::diff_hunks(temp_dir.path(), "lines.txt").unwrap();
+        assert_eq!(diff.path, "lines.txt");
+        assert!(!diff.binary);
+        assert_eq!(diff.hunks.len(), 1);
+
+        let hunk = &diff.hunks[0];
+        assert_eq!(hunk.header, "@@ -1,3 +1,3 @@");
+        assert_eq!((hunk.old_start, hunk.old_lines), (1, 3));
+        assert_eq!((hunk.new_start, hunk.new_lines), (1, 3));
+
+        let summary: Vec<_> = hunk
+            .lines
+            .iter()
+            .map(|l| (l.kind, l.old_lineno, l.new_lineno, l.content.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (DiffLineKind::Context, Some(1), Some(1), "one"),
+                (DiffLineKind::Removed, Some(2), None, "two"),
+                (DiffLineKind::Added, None, Some(2), "2"),
+                (DiffLineKind::Context, Some(3), Some(3), "three"),
+            ]
+        );
+
+        // Untracked files show up as a single all-added hunk
+        fs::write(temp_dir.path().join("new.txt"), "hello\n").unwrap();
+        let diff = GitManager::diff_hunks(temp_dir.path(), "new.txt").unwrap();
+        assert_eq!(diff.hunks.len(), 1);
+        assert_eq!(diff.hunks[0].lines[0].kind, DiffLineKind::Added);
+
+        // Unchanged files have no hunks
+        fs::write(temp_dir.path().join("lines.txt"), "one\ntwo\nthree\n").unwrap();
+        let diff = GitManager::diff_hunks(temp_dir.path(), "lines.txt").unwrap();
+        assert!(diff.hunks.is_empty());
+
+        assert!(GitManager::diff_hunks(temp_dir.path(), "../outside").is_err());
+    }
 }
@@ -2,19 +2,27 @@ mod assets;
 pub mod api;
 pub mod db;
 mod error;
+pub mod events;
 pub mod git;
 pub mod ralph;
+pub mod request_id;
 pub mod service;
 pub mod ws;
 
-use axum::{routing::get, Json, Router};
+use std::future::IntoFuture;
+
+use axum::{extract::State, http::StatusCode, routing::get, Json, Router};
 use clap::{Parser, Subcommand};
 use serde::{Deserialize, Serialize};
+use tower_http::compression::predicate::{DefaultPredicate, Predicate};
+use tower_http::compression::CompressionLayer;
 use tower_http::cors::{Any, CorsLayer};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 use api::AppState;
 use db::Database;
+use ralph::backend_health::{BackendStatus, BACKEND_HEALTH_CHECKS_KEY};
+use ralph::ProcessHealth;
 use service::ServiceController;
 
 pub use error::{AppError, AppResult};
@@ -52,6 +60,16 @@ enum Commands {
     Status,
 }
 
+/// Environment variable that makes `serve` print the pending schema
+/// migrations and exit without applying them
+const MIGRATE_DRY_RUN_ENV: &str = "RALPHTOWN_MIGRATE_DRY_RUN";
+
+/// Exit code of a migration dry run, distinct from success and failure
+const MIGRATE_DRY_RUN_EXIT_CODE: i32 = 3;
+
+/// How long in-flight requests and session output get to finish on shutdown
+const SHUTDOWN_GRACE_PERIOD: std::time::Duration = std::time::Duration::from_secs(10);
+
 #[derive(Serialize, Deserialize)]
 pub struct HealthResponse {
     pub status: String,
@@ -63,22 +81,122 @@ async fn health_check() -> Json<HealthResponse> {
     })
 }
 
+/// Readiness of a single dependency
+#[derive(Serialize, Deserialize)]
+pub struct ComponentHealth {
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ReadinessResponse {
+    pub status: String,
+    pub database: ComponentHealth,
+    pub processes: ProcessHealth,
+    /// Per-backend API availability, when `backend_health_checks` is enabled.
+    /// Informational only: an unavailable backend doesn't fail readiness.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backends: Option<Vec<BackendStatus>>,
+}
+
+/// Readiness check covering the database and the process subsystem, plus the
+/// backend APIs when enabled
+async fn readiness_check(
+    State(state): State<AppState>,
+) -> (StatusCode, Json<ReadinessResponse>) {
+    let database = match state.db.ping() {
+        Ok(()) => ComponentHealth {
+            ok: true,
+            error: None,
+        },
+        Err(e) => ComponentHealth {
+            ok: false,
+            error: Some(e.to_string()),
+        },
+    };
+    let processes = state.ralph_manager.health().await;
+    let backends = if backend_checks_enabled(&state) {
+        let ids: Vec<String> = api::config::builtin_backends()
+            .into_iter()
+            .map(|b| b.id)
+            .collect();
+        Some(state.backend_health.check(&ids).await)
+    } else {
+        None
+    };
+
+    let ready = database.ok && processes.spawn_ok;
+    let (code, status) = if ready {
+        (StatusCode::OK, "ready")
+    } else {
+        (StatusCode::SERVICE_UNAVAILABLE, "not_ready")
+    };
+
+    (
+        code,
+        Json(ReadinessResponse {
+            status: status.to_string(),
+            database,
+            processes,
+            backends,
+        }),
+    )
+}
+
+/// Whether `backend_health_checks` is enabled; a malformed value disables them
+fn backend_checks_enabled(state: &AppState) -> bool {
+    match state.config.get_as::<bool>(BACKEND_HEALTH_CHECKS_KEY) {
+        Ok(enabled) => enabled.unwrap_or(false),
+        Err(e) => {
+            tracing::warn!("Ignoring {}: {}", BACKEND_HEALTH_CHECKS_KEY, e);
+            false
+        }
+    }
+}
+
+/// Compression predicate that leaves protocol upgrade responses alone
+fn is_not_upgrade(
+    status: StatusCode,
+    _: axum::http::Version,
+    _: &axum::http::HeaderMap,
+    _: &axum::http::Extensions,
+) -> bool {
+    status != StatusCode::SWITCHING_PROTOCOLS
+}
+
 pub fn create_app(state: AppState) -> Router {
     let cors = CorsLayer::new()
         .allow_origin(Any)
         .allow_methods(Any)
         .allow_headers(Any);
 
-    Router::new()
+    // Compress responses when the client accepts gzip or brotli. The default
+    // predicate already skips small bodies and event streams; WebSocket
+    // upgrades are excluded explicitly so the handshake is never touched.
+    let compression =
+        CompressionLayer::new().compress_when(DefaultPredicate::new().and(is_not_upgrade));
+
+    let api = Router::new()
         .route("/api/health", get(health_check))
+        .route("/api/health/ready", get(readiness_check))
         .nest("/api", api::repos::router())
         .nest("/api", api::sessions::router())
+        .nest("/api", api::export::router())
+        .nest("/api", api::templates::router())
         .nest("/api", api::git::router())
         .nest("/api", api::config::router())
+        .nest("/api", api::events::router())
+        .nest("/api", api::maintenance::router())
+        .nest("/api", api::metrics::router())
         .nest("/api", api::service::router())
         .nest("/api", ws::router())
-        .with_state(state)
-        .fallback(assets::serve_frontend)
+        .with_state(state.clone());
+
+    assets::with_frontend(api, assets::static_dir_from_env())
+        .layer(axum::middleware::from_fn_with_state(state, api::rate_limit::middleware))
+        .layer(axum::middleware::from_fn(request_id::middleware))
+        .layer(compression)
         .layer(cors)
 }
 
@@ -112,10 +230,24 @@ async fn run_server() {
     let db_path = Database::default_path().expect("Failed to determine database path");
     tracing::info!("Using database at: {:?}", db_path);
 
+    if is_enabled(std::env::var(MIGRATE_DRY_RUN_ENV).ok().as_deref()) {
+        match Database::plan_migrations(&db_path) {
+            Ok(plan) => {
+                println!("Database: {}", db_path.display());
+                println!("{}", plan);
+                std::process::exit(MIGRATE_DRY_RUN_EXIT_CODE);
+            }
+            Err(e) => {
+                eprintln!("Failed to read database schema version: {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+
     let db = Database::new(db_path).expect("Failed to initialize database");
-    let state = AppState::new(db);
+    let state = AppState::new(db).with_admin_token(api::admin_token_from_env().as_deref());
 
-    let app = create_app(state);
+    let app = create_app(state.clone());
 
     let listener = tokio::net::TcpListener::bind("127.0.0.1:3000")
         .await
@@ -123,7 +255,69 @@ async fn run_server() {
 
     tracing::info!("Ralphtown server listening on http://127.0.0.1:3000");
 
-    axum::serve(listener, app).await.unwrap();
+    let (stop_tx, stop_rx) = tokio::sync::oneshot::channel::<()>();
+    let server = tokio::spawn(
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+            .with_graceful_shutdown(async {
+                let _ = stop_rx.await;
+            })
+            .into_future(),
+    );
+
+    shutdown_signal().await;
+    tracing::info!("Shutdown signal received");
+
+    // Stop accepting connections, then stop agents while in-flight requests finish
+    let _ = stop_tx.send(());
+    state
+        .ralph_manager
+        .shutdown(state.db.clone(), state.connections.clone(), SHUTDOWN_GRACE_PERIOD)
+        .await;
+
+    match tokio::time::timeout(SHUTDOWN_GRACE_PERIOD, server).await {
+        Ok(Ok(Ok(()))) => tracing::info!("Ralphtown server stopped"),
+        Ok(Ok(Err(e))) => tracing::error!("Server error during shutdown: {}", e),
+        Ok(Err(e)) => tracing::error!("Server task failed: {}", e),
+        Err(_) => tracing::warn!("In-flight requests did not finish in time; exiting anyway"),
+    }
+}
+
+/// Whether an on/off environment variable is switched on
+fn is_enabled(value: Option<&str>) -> bool {
+    value.is_some_and(|v| {
+        !matches!(
+            v.trim().to_ascii_lowercase().as_str(),
+            "" | "0" | "false" | "no" | "off"
+        )
+    })
+}
+
+/// Wait for Ctrl-C or, on Unix, SIGTERM
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("Failed to install Ctrl-C handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("Failed to install SIGTERM handler")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
 }
 
 fn handle_install() {
@@ -220,4 +414,105 @@ mod tests {
         let body: HealthResponse = response.json();
         assert_eq!(body.status, "ok");
     }
+
+    #[test]
+    fn test_is_enabled() {
+        assert!(is_enabled(Some("1")));
+        assert!(is_enabled(Some("true")));
+        assert!(!is_enabled(Some("0")));
+        assert!(!is_enabled(Some("False")));
+        assert!(!is_enabled(Some("")));
+        assert!(!is_enabled(None));
+    }
+
+    #[tokio::test]
+    async fn test_request_id_is_generated_or_propagated() {
+        let server = TestServer::new(create_test_app()).unwrap();
+
+        let response = server.get("/api/health").await;
+        let generated = response.header(request_id::REQUEST_ID_HEADER);
+        assert!(uuid::Uuid::parse_str(generated.to_str().unwrap()).is_ok());
+
+        let response = server
+            .get("/api/health")
+            .add_header(request_id::REQUEST_ID_HEADER, "client-abc-123")
+            .await;
+        assert_eq!(response.header(request_id::REQUEST_ID_HEADER), "client-abc-123");
+    }
+
+    #[tokio::test]
+    async fn test_internal_errors_include_request_id() {
+        async fn fail() -> AppResult<()> {
+            Err(AppError::Internal("boom".to_string()))
+        }
+        let app = Router::new()
+            .route("/fail", get(fail))
+            .layer(axum::middleware::from_fn(request_id::middleware));
+        let server = TestServer::new(app).unwrap();
+
+        let response = server
+            .get("/fail")
+            .add_header(request_id::REQUEST_ID_HEADER, "req-42")
+            .await;
+
+        response.assert_status(StatusCode::INTERNAL_SERVER_ERROR);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["error"]["details"]["request_id"], "req-42");
+    }
+
+    #[tokio::test]
+    async fn test_responses_are_compressed_when_accepted() {
+        let server = TestServer::new(create_test_app()).unwrap();
+
+        let response = server
+            .get("/api/health/ready")
+            .add_header("accept-encoding", "gzip")
+            .await;
+        response.assert_status_ok();
+        assert_eq!(response.header("content-encoding"), "gzip");
+
+        let response = server.get("/api/health/ready").await;
+        assert!(response.maybe_header("content-encoding").is_none());
+        let body: ReadinessResponse = response.json();
+        assert_eq!(body.status, "ready");
+    }
+
+    #[tokio::test]
+    async fn test_readiness_includes_process_health() {
+        let app = create_test_app();
+        let server = TestServer::new(app).unwrap();
+
+        let response = server.get("/api/health/ready").await;
+
+        response.assert_status_ok();
+        let body: ReadinessResponse = response.json();
+        assert_eq!(body.status, "ready");
+        assert!(body.database.ok);
+        assert!(body.processes.spawn_ok);
+        assert_eq!(body.processes.running, 0);
+        assert!(body.processes.recent_abnormal_exits.is_empty());
+        assert!(body.backends.is_none());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_readiness_reports_backends_when_enabled() {
+        let db = Database::in_memory().expect("Failed to create test database");
+        let mut state = AppState::new(db);
+        state.backend_health =
+            ralph::backend_health::BackendHealthChecker::with_programs("false", "true", "true");
+        state.config.set(BACKEND_HEALTH_CHECKS_KEY, "true").unwrap();
+        let server = TestServer::new(create_app(state)).unwrap();
+
+        let response = server.get("/api/health/ready").await;
+
+        // Backend failures are reported without failing readiness
+        response.assert_status_ok();
+        let body: ReadinessResponse = response.json();
+        let backends = body.backends.unwrap();
+        let ids: Vec<&str> = backends.iter().map(|b| b.backend.as_str()).collect();
+        assert_eq!(ids, ["claude", "bedrock", "vertex"]);
+        assert!(backends[1].ok);
+        assert!(backends[2].ok);
+    }
 }
@@ -0,0 +1,318 @@
+//! Readiness probes for the remote APIs behind each AI backend
+//!
+//! Each probe makes one minimal authenticated request with the credentials the
+//! agent would use: `curl` against the Anthropic API, the `aws` CLI for Bedrock
+//! and `gcloud` for Vertex AI. This surfaces expired keys or exhausted quotas
+//! before a run fails on them. Results are cached briefly so that readiness
+//! polling doesn't hammer the APIs.
+
+use std::collections::HashMap;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tokio::sync::Mutex;
+
+/// Config key: when "true", readiness includes a per-backend API check
+pub const BACKEND_HEALTH_CHECKS_KEY: &str = "backend_health_checks";
+
+/// Environment variable the agent reads the Anthropic API key from
+const ANTHROPIC_API_KEY_ENV: &str = "ANTHROPIC_API_KEY";
+
+/// Environment variable overriding the Anthropic API URL
+const ANTHROPIC_BASE_URL_ENV: &str = "ANTHROPIC_BASE_URL";
+
+const ANTHROPIC_DEFAULT_BASE_URL: &str = "https://api.anthropic.com";
+
+/// `anthropic-version` header sent with the probe request
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+
+/// How long a probe result is reused before the backend is checked again
+const CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// Upper bound on a single probe, so a hung CLI can't stall readiness
+const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Availability of one backend's API
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackendStatus {
+    pub backend: String,
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub checked_at: DateTime<Utc>,
+}
+
+/// Last probe result for one backend, locked for the duration of a probe
+type CacheSlot = Arc<Mutex<Option<(Instant, BackendStatus)>>>;
+
+/// Runs and caches backend API probes
+#[derive(Clone)]
+pub struct BackendHealthChecker {
+    curl: String,
+    aws: String,
+    gcloud: String,
+    cache: Arc<std::sync::Mutex<HashMap<String, CacheSlot>>>,
+}
+
+impl BackendHealthChecker {
+    pub fn new() -> Self {
+        Self::with_programs("curl", "aws", "gcloud")
+    }
+
+    /// Use the given programs in place of `curl`, `aws` and `gcloud`
+    pub(crate) fn with_programs(curl: &str, aws: &str, gcloud: &str) -> Self {
+        Self {
+            curl: curl.to_string(),
+            aws: aws.to_string(),
+            gcloud: gcloud.to_string(),
+            cache: Arc::new(std::sync::Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Check each backend, reusing results younger than the cache TTL
+    pub async fn check(&self, backends: &[String]) -> Vec<BackendStatus> {
+        let checks = backends.iter().map(|backend| self.check_one(backend));
+        futures::future::join_all(checks).await
+    }
+
+    /// Check one backend; concurrent callers wait for a running probe and
+    /// share its result instead of starting their own
+    async fn check_one(&self, backend: &str) -> BackendStatus {
+        let slot = self
+            .cache
+            .lock()
+            .unwrap()
+            .entry(backend.to_string())
+            .or_default()
+            .clone();
+        let mut cached = slot.lock().await;
+        if let Some((checked, status)) = cached.as_ref()
+            && checked.elapsed() < CACHE_TTL
+        {
+            return status.clone();
+        }
+
+        let result = match backend {
+            "claude" => self.probe_anthropic().await,
+            "bedrock" => self.probe_bedrock().await,
+            "vertex" => self.probe_vertex().await,
+            other => Err(format!("No health check for backend '{}'", other)),
+        };
+
+        let status = BackendStatus {
+            backend: backend.to_string(),
+            ok: result.is_ok(),
+            error: result.err(),
+            checked_at: Utc::now(),
+        };
+        *cached = Some((Instant::now(), status.clone()));
+        status
+    }
+
+    /// Check the API key the agent inherits from the environment
+    async fn probe_anthropic(&self) -> Result<(), String> {
+        let api_key = std::env::var(ANTHROPIC_API_KEY_ENV)
+            .ok()
+            .filter(|key| !key.is_empty())
+            .ok_or_else(|| format!("No API key configured (set {})", ANTHROPIC_API_KEY_ENV))?;
+        self.probe_anthropic_key(&api_key).await
+    }
+
+    /// List one model, which needs a valid key with remaining quota
+    async fn probe_anthropic_key(&self, api_key: &str) -> Result<(), String> {
+        // The key is quoted in a curl config, so anything that could end the
+        // quoted string or the line is refused rather than escaped
+        if api_key.chars().any(|c| c == '"' || c == '\\' || c.is_control()) {
+            return Err(format!("{} contains invalid characters", ANTHROPIC_API_KEY_ENV));
+        }
+
+        let base_url = std::env::var(ANTHROPIC_BASE_URL_ENV)
+            .ok()
+            .filter(|url| !url.is_empty())
+            .unwrap_or_else(|| ANTHROPIC_DEFAULT_BASE_URL.to_string());
+        let url = format!("{}/v1/models?limit=1", base_url.trim_end_matches('/'));
+
+        // Headers go through a curl config on stdin so the key never shows up
+        // in the process list
+        let headers = format!(
+            "header = \"x-api-key: {}\"\nheader = \"anthropic-version: {}\"\n",
+            api_key, ANTHROPIC_VERSION
+        );
+        let mut cmd = Command::new(&self.curl);
+        cmd.args(["--silent", "--show-error", "--config", "-"])
+            .args(["--write-out", "\n%{http_code}", &url]);
+        let output = run_probe(cmd, Some(headers)).await?;
+
+        let code = output.lines().last().unwrap_or_default().trim();
+        match code {
+            c if c.starts_with('2') => Ok(()),
+            "401" | "403" => Err(format!("API key rejected (HTTP {})", code)),
+            "429" => Err("Rate limited or out of quota (HTTP 429)".to_string()),
+            _ => Err(format!("Unexpected response (HTTP {})", code)),
+        }
+    }
+
+    /// List Anthropic foundation models with the ambient AWS credentials
+    async fn probe_bedrock(&self) -> Result<(), String> {
+        let mut cmd = Command::new(&self.aws);
+        cmd.args(["bedrock", "list-foundation-models", "--by-provider", "anthropic"])
+            .args(["--query", "modelSummaries[0].modelId", "--output", "text"]);
+        run_probe(cmd, None).await.map(|_| ())
+    }
+
+    /// Mint an access token with the ambient Google Cloud credentials
+    async fn probe_vertex(&self) -> Result<(), String> {
+        let mut cmd = Command::new(&self.gcloud);
+        cmd.args(["auth", "print-access-token", "--quiet"]);
+        // Only success matters; the token itself is never used or logged
+        run_probe(cmd, None).await.map(|_| ())
+    }
+}
+
+impl Default for BackendHealthChecker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Run a probe command to completion, returning its stdout on success and the
+/// last line of its stderr otherwise
+async fn run_probe(mut cmd: Command, stdin: Option<String>) -> Result<String, String> {
+    let program = cmd.as_std().get_program().to_string_lossy().into_owned();
+    cmd.stdin(if stdin.is_some() { Stdio::piped() } else { Stdio::null() })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+
+    let mut child = cmd.spawn().map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => format!("'{}' not found on PATH", program),
+        _ => format!("Failed to run '{}': {}", program, e),
+    })?;
+
+    if let (Some(input), Some(mut pipe)) = (stdin, child.stdin.take()) {
+        pipe.write_all(input.as_bytes())
+            .await
+            .map_err(|e| format!("Failed to write to '{}': {}", program, e))?;
+    }
+
+    let output = tokio::time::timeout(PROBE_TIMEOUT, child.wait_with_output())
+        .await
+        .map_err(|_| format!("'{}' timed out after {}s", program, PROBE_TIMEOUT.as_secs()))?
+        .map_err(|e| format!("Failed to run '{}': {}", program, e))?;
+
+    if output.status.success() {
+        return Ok(String::from_utf8_lossy(&output.stdout).into_owned());
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    Err(stderr
+        .lines()
+        .rev()
+        .find(|line| !line.trim().is_empty())
+        .map(|line| line.trim().to_string())
+        .unwrap_or_else(|| format!("'{}' exited with {}", program, output.status)))
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_check_reports_probe_results() {
+        let checker = BackendHealthChecker::with_programs("true", "true", "false");
+
+        let backends = ["bedrock", "vertex", "openai"].map(String::from);
+        let statuses = checker.check(&backends).await;
+
+        assert!(statuses[0].ok);
+        assert!(statuses[0].error.is_none());
+        assert!(!statuses[1].ok);
+        assert!(statuses[1].error.as_deref().unwrap().contains("exited with"));
+        assert_eq!(
+            statuses[2].error.as_deref(),
+            Some("No health check for backend 'openai'")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_check_caches_results() {
+        let checker = BackendHealthChecker::with_programs("true", "true", "true");
+        let backends = vec!["bedrock".to_string()];
+
+        let first = checker.check(&backends).await;
+        let second = checker.check(&backends).await;
+
+        assert_eq!(first[0].checked_at, second[0].checked_at);
+    }
+
+    #[tokio::test]
+    async fn test_missing_program_is_reported() {
+        let checker = BackendHealthChecker::with_programs("curl", "no-such-aws-cli", "gcloud");
+
+        let statuses = checker.check(&["bedrock".to_string()]).await;
+
+        assert_eq!(
+            statuses[0].error.as_deref(),
+            Some("'no-such-aws-cli' not found on PATH")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_anthropic_probe_reads_status_code() {
+        use std::os::unix::fs::PermissionsExt;
+
+        // Stand-in for curl that swallows the header config and answers 429
+        let dir = tempfile::tempdir().unwrap();
+        let curl = dir.path().join("curl");
+        std::fs::write(&curl, "#!/bin/sh\ncat > /dev/null\nprintf '{}\\n429'\n").unwrap();
+        std::fs::set_permissions(&curl, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let checker = BackendHealthChecker::with_programs(curl.to_str().unwrap(), "aws", "gcloud");
+
+        assert_eq!(
+            checker.probe_anthropic_key("sk-ant-123").await,
+            Err("Rate limited or out of quota (HTTP 429)".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_anthropic_probe_rejects_unsafe_keys() {
+        // curl would fail the test if it were ever run
+        let checker = BackendHealthChecker::with_programs("false", "aws", "gcloud");
+
+        for key in ["sk\"\nurl = \"http://evil", "sk\\x", "sk\tx"] {
+            assert_eq!(
+                checker.probe_anthropic_key(key).await,
+                Err("ANTHROPIC_API_KEY contains invalid characters".to_string())
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_checks_share_one_probe() {
+        use std::os::unix::fs::PermissionsExt;
+
+        // Stand-in for aws that counts its invocations
+        let dir = tempfile::tempdir().unwrap();
+        let aws = dir.path().join("aws");
+        let calls = dir.path().join("calls");
+        std::fs::write(
+            &aws,
+            format!("#!/bin/sh\nsleep 0.2\necho x >> '{}'\n", calls.display()),
+        )
+        .unwrap();
+        std::fs::set_permissions(&aws, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let checker = BackendHealthChecker::with_programs("curl", aws.to_str().unwrap(), "gcloud");
+        let backends = vec!["bedrock".to_string()];
+        let (first, second) = tokio::join!(checker.check(&backends), checker.check(&backends));
+
+        assert!(first[0].ok && second[0].ok);
+        assert_eq!(std::fs::read_to_string(&calls).unwrap().lines().count(), 1);
+    }
+}
@@ -1,23 +1,544 @@
 //! Ralph process manager - spawns and tracks ralph CLI processes
 
-use std::collections::HashMap;
+pub mod backend_health;
+
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::process::Stdio;
 use std::sync::Arc;
 
-use tokio::io::{AsyncBufReadExt, BufReader};
-use tokio::process::{Child, Command};
-use tokio::sync::RwLock;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{Child, ChildStderr, ChildStdin, ChildStdout, Command};
+use tokio::sync::{Mutex, RwLock};
 use uuid::Uuid;
 
-use crate::db::models::{OutputStream as DbOutputStream, SessionStatus as DbSessionStatus};
+use crate::db::models::{
+    ExitReason, MessageRole, OutputStream as DbOutputStream, SessionStatus as DbSessionStatus,
+};
 use crate::db::Database;
+use crate::events;
+use crate::git::{match_patterns, parse_pattern_list, GitManager, WorktreeSnapshot};
 use crate::ws::messages::{OutputStream, ServerMessage, SessionStatus as WsSessionStatus};
 use crate::ws::ConnectionManager;
 
+/// Repo config key: JSON array of glob patterns the agent must not modify
+pub const DENIED_PATHS_KEY: &str = "denied_paths";
+
+/// Repo config key: when "true", changes to denied paths are reverted after a run
+pub const REVERT_DENIED_PATHS_KEY: &str = "revert_denied_paths";
+
+/// Config key: JSON array of shell commands the agent may execute
+pub const COMMAND_ALLOWLIST_KEY: &str = "command_allowlist";
+
+/// Environment variable through which the allowlist is passed to the agent
+pub const ALLOWED_COMMANDS_ENV: &str = "RALPH_ALLOWED_COMMANDS";
+
+/// Config key: AI backend passed to the agent (see /config/backends)
+pub const BACKEND_KEY: &str = "backend";
+
+/// Config key: backend to fall back to when the primary one fails to start
+pub const FALLBACK_BACKEND_KEY: &str = "fallback_backend";
+
+/// Config key: model passed to the agent with `--model`, or the backend's default
+pub const MODEL_KEY: &str = "model";
+
+/// Config key: JSON object of extra environment variables for the agent
+pub const AGENT_ENV_KEY: &str = "agent_env";
+
+/// Config key: directory the agent runs in, relative to the repository root
+pub const AGENT_WORKDIR_KEY: &str = "agent_workdir";
+
+/// A failed run that exits within this long of starting is treated as the
+/// backend failing to start (e.g. a missing or rejected API key)
+const BACKEND_STARTUP_WINDOW: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Config key: how many times a crashed agent is restarted before the session
+/// is marked as errored (default 0)
+pub const MAX_CRASH_RESTARTS_KEY: &str = "max_crash_restarts";
+
+/// Config key: how many sessions may run at once across all repos (0 or
+/// unset for no limit)
+pub const MAX_CONCURRENT_SESSIONS_KEY: &str = "max_concurrent_sessions";
+
+/// Config key: when "true", the agent's stdin stays open for input sent over
+/// WebSocket; otherwise it reads EOF, as agents that read stdin expect
+/// (default false)
+pub const INTERACTIVE_INPUT_KEY: &str = "agent_interactive_input";
+
+/// Config key: address-space limit for agent processes, in MiB (Unix only)
+pub const MEMORY_LIMIT_MB_KEY: &str = "agent_memory_limit_mb";
+
+/// Config key: CPU time limit for agent processes, in seconds (Unix only)
+pub const CPU_TIME_LIMIT_SECS_KEY: &str = "agent_cpu_time_limit_secs";
+
+/// Extra CPU seconds between the SIGXCPU warning and the SIGKILL hard limit
+const CPU_TIME_LIMIT_GRACE_SECS: u64 = 5;
+
+/// Program spawned for ralph sessions
+const RALPH_PROGRAM: &str = "ralph";
+
+/// Placeholder shown in recorded command lines instead of secret values
+const MASKED_ARG: &str = "****";
+
+/// Whether a config key holds a secret whose value must never be recorded
+fn is_secret_config_key(key: &str) -> bool {
+    let key = key.to_lowercase();
+    ["secret", "token", "password", "api_key", "apikey"]
+        .iter()
+        .any(|marker| key.contains(marker))
+}
+
+/// Quote an argument for display as a POSIX shell word
+fn shell_quote(arg: &str) -> String {
+    let is_plain = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./=:,@%+".contains(c));
+    if is_plain {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    }
+}
+
+/// Parse the command allowlist: a JSON array of bare command names
+pub fn parse_command_allowlist(raw: &str) -> Result<Vec<String>, String> {
+    let commands: Vec<String> = serde_json::from_str(raw)
+        .map_err(|e| format!("expected a JSON array of command names: {}", e))?;
+
+    if let Some(bad) = commands
+        .iter()
+        .find(|c| c.is_empty() || c.contains(|ch: char| ch.is_whitespace() || ch == ','))
+    {
+        return Err(format!("invalid command name '{}'", bad));
+    }
+
+    Ok(commands)
+}
+
+/// Parse the agent environment: a JSON object of variable names to values
+pub fn parse_agent_env(raw: &str) -> Result<Vec<(String, String)>, String> {
+    let env: std::collections::BTreeMap<String, String> = serde_json::from_str(raw)
+        .map_err(|e| format!("expected a JSON object of variable names to values: {}", e))?;
+
+    if let Some(bad) = env.keys().find(|k| k.is_empty() || k.contains(['=', '\0'])) {
+        return Err(format!("invalid variable name '{}'", bad));
+    }
+
+    Ok(env.into_iter().collect())
+}
+
+/// Parse the crash restart limit: a non-negative integer
+pub fn parse_max_crash_restarts(raw: &str) -> Result<u32, String> {
+    raw.trim()
+        .parse::<u32>()
+        .map_err(|_| format!("expected a non-negative integer, got '{}'", raw))
+}
+
+/// Parse the concurrent session limit: a non-negative integer (0 for no limit)
+pub fn parse_max_concurrent_sessions(raw: &str) -> Result<usize, String> {
+    raw.trim()
+        .parse::<usize>()
+        .map_err(|_| format!("expected a non-negative integer, got '{}'", raw))
+}
+
+/// Parse a resource limit: a positive integer
+pub fn parse_resource_limit(raw: &str) -> Result<u64, String> {
+    match raw.trim().parse::<u64>() {
+        Ok(limit) if limit > 0 => Ok(limit),
+        _ => Err(format!("expected a positive integer, got '{}'", raw)),
+    }
+}
+
+/// Resource limits applied to each agent process with `setrlimit`
+///
+/// Limits are inherited by everything the agent spawns. They are a no-op on
+/// non-Unix platforms.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct ResourceLimits {
+    /// Maximum address space (`RLIMIT_AS`)
+    memory_bytes: Option<u64>,
+    /// Maximum CPU time (`RLIMIT_CPU`)
+    cpu_seconds: Option<u64>,
+}
+
+impl ResourceLimits {
+    /// Read the configured limits, failing on malformed values so a typo
+    /// never runs the agent unrestricted
+    fn from_config(db: &Database) -> Result<Self, RalphError> {
+        let read = |key: &str| -> Result<Option<u64>, RalphError> {
+            match db.get_config(key) {
+                Ok(Some(raw)) if !raw.trim().is_empty() => parse_resource_limit(&raw)
+                    .map(Some)
+                    .map_err(|e| RalphError::SpawnFailed(format!("Invalid {}: {}", key, e))),
+                Ok(_) => Ok(None),
+                Err(e) => Err(RalphError::SpawnFailed(format!("Failed to read {}: {}", key, e))),
+            }
+        };
+
+        Ok(Self {
+            memory_bytes: read(MEMORY_LIMIT_MB_KEY)?.map(|mb| mb.saturating_mul(1024 * 1024)),
+            cpu_seconds: read(CPU_TIME_LIMIT_SECS_KEY)?,
+        })
+    }
+
+    /// Apply the limits to the current process
+    ///
+    /// Runs in the forked child before exec, so it only calls async-signal-safe
+    /// functions.
+    #[cfg(unix)]
+    fn apply(&self) -> std::io::Result<()> {
+        use nix::sys::resource::{setrlimit, Resource};
+
+        if let Some(bytes) = self.memory_bytes {
+            setrlimit(Resource::RLIMIT_AS, bytes, bytes)?;
+        }
+        if let Some(seconds) = self.cpu_seconds {
+            // The soft limit sends SIGXCPU so the agent can exit cleanly
+            let hard = seconds.saturating_add(CPU_TIME_LIMIT_GRACE_SECS);
+            setrlimit(Resource::RLIMIT_CPU, seconds, hard)?;
+        }
+        Ok(())
+    }
+}
+
+/// Render a copy-pasteable command line, masking any argument containing a secret
+fn format_command_line(
+    env: &[(String, String)],
+    program: &str,
+    args: &[String],
+    secrets: &[String],
+) -> String {
+    env.iter()
+        .map(|(key, value)| {
+            if is_secret_config_key(key) {
+                format!("{}={}", key, MASKED_ARG)
+            } else {
+                format!("{}={}", key, shell_quote(value))
+            }
+        })
+        .chain(std::iter::once(shell_quote(program)))
+        .chain(args.iter().map(|arg| {
+            if secrets.iter().any(|secret| arg.contains(secret.as_str())) {
+                MASKED_ARG.to_string()
+            } else {
+                shell_quote(arg)
+            }
+        }))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Number of trailing stderr lines recorded when a run fails
+const ERROR_STDERR_TAIL_LINES: i64 = 20;
+
+/// Number of abnormal exits remembered for health reporting
+const MAX_RECENT_ABNORMAL_EXITS: usize = 20;
+
+/// How long a cancelled process gets to exit after SIGTERM before SIGKILL
+const CANCEL_GRACE_PERIOD: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Exit code `timeout(1)` and similar wrappers use when the time limit expires
+const TIMEOUT_EXIT_CODE: i32 = 124;
+
+/// How often a cancelled process is polled for exit during the grace period
+const CANCEL_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(100);
+
+/// How often an in-progress assistant response is checkpointed to the database
+const MESSAGE_CHECKPOINT_INTERVAL: std::time::Duration = std::time::Duration::from_secs(3);
+
+/// Unsaved response bytes that force a checkpoint before the interval elapses
+const MESSAGE_CHECKPOINT_BYTES: usize = 4096;
+
+/// Saved response size per extra `MESSAGE_CHECKPOINT_INTERVAL` between checkpoints
+const MESSAGE_CHECKPOINT_GROWTH_BYTES: usize = 64 * 1024;
+
+/// Longest wait between checkpoints of a long response
+const MESSAGE_CHECKPOINT_MAX_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Assistant response assembled from a run's stdout
+///
+/// The message is upserted as `incomplete` every few seconds or kilobytes so
+/// a long response survives a server restart, and marked complete when the
+/// output ends. Each checkpoint rewrites the whole response, so they are
+/// spaced out as it grows, keeping the total written roughly linear in its size.
+struct ResponseCheckpoint {
+    id: Uuid,
+    session_id: Uuid,
+    content: String,
+    unsaved: usize,
+    last_saved: std::time::Instant,
+}
+
+impl ResponseCheckpoint {
+    fn new(session_id: Uuid) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            session_id,
+            content: String::new(),
+            unsaved: 0,
+            last_saved: std::time::Instant::now(),
+        }
+    }
+
+    /// Append an output line, checkpointing if enough time or output has passed
+    fn push_line(&mut self, line: &str, db: &Database) {
+        if !self.content.is_empty() {
+            self.content.push('\n');
+        }
+        self.content.push_str(line);
+        self.unsaved += line.len() + 1;
+
+        if self.unsaved >= self.checkpoint_bytes()
+            || self.last_saved.elapsed() >= self.checkpoint_interval()
+        {
+            self.save(db, true);
+        }
+    }
+
+    /// Unsaved bytes that force a checkpoint: a quarter of the response, at least
+    fn checkpoint_bytes(&self) -> usize {
+        MESSAGE_CHECKPOINT_BYTES.max(self.content.len() / 4)
+    }
+
+    /// Time between checkpoints, one more interval per `MESSAGE_CHECKPOINT_GROWTH_BYTES`
+    fn checkpoint_interval(&self) -> std::time::Duration {
+        let steps = 1 + self.content.len() / MESSAGE_CHECKPOINT_GROWTH_BYTES;
+        MESSAGE_CHECKPOINT_INTERVAL
+            .saturating_mul(u32::try_from(steps).unwrap_or(u32::MAX))
+            .min(MESSAGE_CHECKPOINT_MAX_INTERVAL)
+    }
+
+    /// Persist the full response and clear the incomplete flag
+    fn finish(mut self, db: &Database) {
+        if !self.content.is_empty() {
+            self.save(db, false);
+        }
+    }
+
+    fn save(&mut self, db: &Database, incomplete: bool) {
+        if let Err(e) = db.upsert_message(
+            self.id,
+            self.session_id,
+            MessageRole::Assistant,
+            &self.content,
+            incomplete,
+        ) {
+            tracing::warn!("Failed to checkpoint response for session {}: {}", self.session_id, e);
+        }
+        self.unsaved = 0;
+        self.last_saved = std::time::Instant::now();
+    }
+}
+
+/// A ralph process that exited unsuccessfully
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AbnormalExit {
+    pub session_id: Uuid,
+    pub repo_id: Uuid,
+    /// Exit code, if the process exited normally rather than by signal
+    pub exit_code: Option<i32>,
+    pub exited_at: DateTime<Utc>,
+}
+
+/// A session with a running ralph process
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunningProcess {
+    pub session_id: Uuid,
+    pub repo_id: Uuid,
+    /// When the run started (restarts after a crash keep the original time)
+    pub started_at: DateTime<Utc>,
+    /// Number of times the agent has been restarted after crashing
+    pub restarts: u32,
+}
+
+/// Health summary of the process subsystem
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessHealth {
+    /// Number of ralph processes currently running
+    pub running: usize,
+    /// Most recent abnormal exits, newest first
+    pub recent_abnormal_exits: Vec<AbnormalExit>,
+    /// Whether a trivial command could be spawned
+    pub spawn_ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spawn_error: Option<String>,
+}
+
+/// How a ralph process run ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RunOutcome {
+    /// Exited successfully
+    Completed,
+    /// Stopped by `cancel`
+    Cancelled,
+    /// Exited unsuccessfully (or its status was unavailable) without being cancelled
+    Crashed,
+}
+
+impl RunOutcome {
+    fn classify(cancelled: bool, status: Option<std::process::ExitStatus>) -> Self {
+        if cancelled {
+            RunOutcome::Cancelled
+        } else if status.is_some_and(|s| s.success()) {
+            RunOutcome::Completed
+        } else {
+            RunOutcome::Crashed
+        }
+    }
+
+    /// Detailed classification recorded on the session once the run is over
+    ///
+    /// `changed` is whether the run left the repo different from its
+    /// checkpoint, if that could be determined.
+    fn exit_reason(
+        self,
+        status: Option<std::process::ExitStatus>,
+        changed: Option<bool>,
+    ) -> ExitReason {
+        match self {
+            RunOutcome::Cancelled => ExitReason::Cancelled,
+            RunOutcome::Completed => match changed {
+                Some(true) => ExitReason::CompletedWithChanges,
+                Some(false) => ExitReason::NoChanges,
+                None => ExitReason::CompletedSuccess,
+            },
+            RunOutcome::Crashed if status.is_some_and(is_timeout) => ExitReason::TimedOut,
+            RunOutcome::Crashed => ExitReason::Crashed,
+        }
+    }
+}
+
+/// Whether the agent was stopped for running out of time: killed by the CPU
+/// time limit's SIGXCPU, or exited with the conventional timeout code
+fn is_timeout(status: std::process::ExitStatus) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if status.signal() == Some(libc::SIGXCPU) {
+            return true;
+        }
+    }
+    status.code() == Some(TIMEOUT_EXIT_CODE)
+}
+
+/// What the supervisor does after an agent process exits
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ExitAction {
+    /// The run is over and its final status has been recorded
+    Finish,
+    /// Restart the agent after a crash
+    Restart,
+    /// The backend failed to start; restart the agent with this one instead
+    FallBack(String),
+}
+
+/// Everything needed to spawn the agent, kept so a crashed run can be restarted
+#[derive(Debug, Clone)]
+struct AgentCommand {
+    program: String,
+    args: Vec<String>,
+    /// Backend selected with `--backend`, or the CLI's default
+    backend: Option<String>,
+    /// Model selected with `--model`, or the backend's default
+    model: Option<String>,
+    env: Vec<(String, String)>,
+    current_dir: String,
+    limits: ResourceLimits,
+    /// Pipe stdin for `send_input` instead of connecting it to /dev/null
+    interactive: bool,
+}
+
+impl AgentCommand {
+    /// Arguments including the backend selection
+    fn full_args(&self) -> Vec<String> {
+        let mut args = self.args.clone();
+        if let Some(backend) = &self.backend {
+            args.extend(["--backend".to_string(), backend.clone()]);
+        }
+        if let Some(model) = &self.model {
+            args.extend(["--model".to_string(), model.clone()]);
+        }
+        args
+    }
+
+    /// Spawn the agent in its own process group with piped output
+    fn spawn(&self) -> Result<Child, RalphError> {
+        let stdin = if self.interactive {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        };
+        let mut cmd = Command::new(&self.program);
+        cmd.args(self.full_args())
+            .envs(self.env.iter().map(|(key, value)| (key, value)))
+            .current_dir(&self.current_dir)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .stdin(stdin);
+
+        // On Unix, set up process group for signal handling
+        #[cfg(unix)]
+        {
+            #[allow(unused_imports)]
+            use std::os::unix::process::CommandExt;
+            let limits = self.limits;
+            // SAFETY: setpgid and setrlimit are async-signal-safe POSIX
+            // functions, so they are safe to call between fork and exec
+            unsafe {
+                cmd.pre_exec(move || {
+                    // Set this process as the process group leader
+                    // This allows us to send signals to the entire process group
+                    libc::setpgid(0, 0);
+                    limits.apply()
+                });
+            }
+        }
+
+        cmd.spawn().map_err(|e| {
+            if e.kind() == std::io::ErrorKind::NotFound {
+                RalphError::NotFound {
+                    message: "ralph CLI not found in PATH".to_string(),
+                    help_steps: vec![
+                        "Install ralph: cargo install ralph".to_string(),
+                        "Or download from release page".to_string(),
+                        "Ensure ~/.cargo/bin is in your PATH".to_string(),
+                        "Restart your terminal after installation".to_string(),
+                    ],
+                }
+            } else {
+                RalphError::SpawnFailed(e.to_string())
+            }
+        })
+    }
+}
+
 /// Active process handle with metadata
 struct ProcessHandle {
     child: Child,
+    /// Agent's stdin, shared so input can be written without holding the manager lock
+    stdin: Option<Arc<Mutex<ChildStdin>>>,
     repo_id: Uuid,
+    /// Set by `cancel`, which then owns the session's final status
+    cancelled: bool,
+    /// Number of times the agent has been restarted after crashing
+    restarts: u32,
+    /// Restart limit read from config when the run started
+    max_restarts: u32,
+    /// When the current process was spawned
+    started_at: tokio::time::Instant,
+    /// Wall-clock time the run started, for reporting
+    run_started_at: DateTime<Utc>,
+    /// Backend to switch to if the current one fails to start; used at most once
+    fallback_backend: Option<String>,
+    /// Uncommitted changes already present when the run started
+    worktree: WorktreeSnapshot,
+}
+
+/// Detach a child's stdin for sharing through its `ProcessHandle`
+fn take_stdin(child: &mut Child) -> Option<Arc<Mutex<ChildStdin>>> {
+    child.stdin.take().map(|stdin| Arc::new(Mutex::new(stdin)))
 }
 
 /// Inner state for RalphManager
@@ -26,12 +547,20 @@ struct RalphManagerInner {
     processes: HashMap<Uuid, ProcessHandle>,
     /// Set of repo_ids with running processes (for 1-instance-per-repo constraint)
     active_repos: HashMap<Uuid, Uuid>, // repo_id -> session_id
+    /// Sessions that have claimed their repo and a slot but are still spawning
+    starting: HashSet<Uuid>,
+    /// Recent abnormal exits, newest first
+    abnormal_exits: VecDeque<AbnormalExit>,
+    /// Tasks streaming each run's output; awaited on shutdown so output is persisted
+    supervisors: Vec<tokio::task::JoinHandle<()>>,
 }
 
 /// Manages spawning and tracking of ralph CLI processes
 #[derive(Clone)]
 pub struct RalphManager {
     inner: Arc<RwLock<RalphManagerInner>>,
+    /// Program spawned for each run
+    program: String,
 }
 
 impl RalphManager {
@@ -40,7 +569,20 @@ impl RalphManager {
             inner: Arc::new(RwLock::new(RalphManagerInner {
                 processes: HashMap::new(),
                 active_repos: HashMap::new(),
+                starting: HashSet::new(),
+                abnormal_exits: VecDeque::new(),
+                supervisors: Vec::new(),
             })),
+            program: RALPH_PROGRAM.to_string(),
+        }
+    }
+
+    /// Create a manager that spawns `program` instead of the ralph CLI
+    #[cfg(test)]
+    pub(crate) fn with_program(program: &str) -> Self {
+        Self {
+            program: program.to_string(),
+            ..Self::new()
         }
     }
 
@@ -62,6 +604,72 @@ impl RalphManager {
         inner.processes.contains_key(&session_id)
     }
 
+    /// Number of sessions with a running (or starting) process
+    pub async fn running_count(&self) -> usize {
+        let inner = self.inner.read().await;
+        inner.processes.len() + inner.starting.len()
+    }
+
+    /// Claim the repo and a concurrency slot for a session about to be spawned
+    ///
+    /// Checking and claiming under one lock keeps concurrent starts from both
+    /// passing the checks. The claim is released by `release` if the spawn
+    /// fails, or turned into a `ProcessHandle` once it succeeds.
+    async fn reserve(
+        &self,
+        session_id: Uuid,
+        repo_id: Uuid,
+        limit: Option<usize>,
+    ) -> Result<(), RalphError> {
+        let mut inner = self.inner.write().await;
+        if inner.active_repos.contains_key(&repo_id) {
+            return Err(RalphError::RepoBusy(repo_id));
+        }
+        if inner.processes.contains_key(&session_id) || inner.starting.contains(&session_id) {
+            return Err(RalphError::SessionAlreadyRunning(session_id));
+        }
+        if let Some(limit) = limit {
+            let running = inner.processes.len() + inner.starting.len();
+            if running >= limit {
+                return Err(RalphError::AtCapacity { running, limit });
+            }
+        }
+
+        inner.starting.insert(session_id);
+        inner.active_repos.insert(repo_id, session_id);
+        Ok(())
+    }
+
+    /// Give up a claim made by `reserve` for a session that failed to spawn
+    async fn release(&self, session_id: Uuid, repo_id: Uuid) {
+        let mut inner = self.inner.write().await;
+        inner.starting.remove(&session_id);
+        if inner.active_repos.get(&repo_id) == Some(&session_id) {
+            inner.active_repos.remove(&repo_id);
+        }
+    }
+
+    /// Configured limit on concurrently running sessions, if any
+    ///
+    /// A malformed value is ignored rather than blocking every run.
+    pub fn max_concurrent_sessions(db: &Database) -> Option<usize> {
+        match db.get_config(MAX_CONCURRENT_SESSIONS_KEY) {
+            Ok(Some(raw)) => match parse_max_concurrent_sessions(&raw) {
+                Ok(0) => None,
+                Ok(limit) => Some(limit),
+                Err(e) => {
+                    tracing::warn!("Ignoring invalid {}: {}", MAX_CONCURRENT_SESSIONS_KEY, e);
+                    None
+                }
+            },
+            Ok(None) => None,
+            Err(e) => {
+                tracing::warn!("Failed to read {}: {}", MAX_CONCURRENT_SESSIONS_KEY, e);
+                None
+            }
+        }
+    }
+
     /// Spawn a ralph process for a session
     ///
     /// # Arguments
@@ -83,60 +691,145 @@ impl RalphManager {
         db: Arc<Database>,
         connections: ConnectionManager,
     ) -> Result<(), RalphError> {
-        // Check if repo already has a running process
-        if self.is_repo_busy(repo_id).await {
-            return Err(RalphError::RepoBusy(repo_id));
-        }
+        self.run_with_backend(session_id, repo_id, repo_path, prompt, None, db, connections)
+            .await
+    }
 
-        // Check if session already has a running process
-        if self.is_session_running(session_id).await {
-            return Err(RalphError::SessionAlreadyRunning(session_id));
-        }
+    /// Spawn a ralph process like `run`, using `backend` instead of the
+    /// configured backend when given
+    #[allow(clippy::too_many_arguments)]
+    pub async fn run_with_backend(
+        &self,
+        session_id: Uuid,
+        repo_id: Uuid,
+        repo_path: &str,
+        prompt: &str,
+        backend: Option<String>,
+        db: Arc<Database>,
+        connections: ConnectionManager,
+    ) -> Result<(), RalphError> {
+        self.reserve(session_id, repo_id, Self::max_concurrent_sessions(&db))
+            .await?;
 
-        // Build the command
-        let mut cmd = Command::new("ralph");
-        cmd.arg("run")
-            .arg("--autonomous")
-            .arg("--prompt")
-            .arg(prompt)
-            .current_dir(repo_path)
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .stdin(Stdio::null());
+        let spawned: Result<_, RalphError> = async {
+            // Extra variables come first so the entries below take precedence
+            let mut env = match Self::read_session_config(&db, session_id, AGENT_ENV_KEY) {
+                Some(raw) => parse_agent_env(&raw).map_err(|e| {
+                    RalphError::SpawnFailed(format!("Invalid {}: {}", AGENT_ENV_KEY, e))
+                })?,
+                None => Vec::new(),
+            };
 
-        // On Unix, set up process group for signal handling
-        #[cfg(unix)]
-        {
-            #[allow(unused_imports)]
-            use std::os::unix::process::CommandExt;
-            // SAFETY: setpgid is safe to call in pre_exec, it's a standard
-            // POSIX function that sets the process group for signal handling
-            unsafe {
-                cmd.pre_exec(|| {
-                    // Set this process as the process group leader
-                    // This allows us to send signals to the entire process group
-                    libc::setpgid(0, 0);
-                    Ok(())
-                });
+            // Restrict the agent's shell commands when an allowlist is configured.
+            // A malformed allowlist refuses to run rather than running unrestricted.
+            match db.get_config(COMMAND_ALLOWLIST_KEY) {
+                Ok(Some(raw)) => {
+                    let commands = parse_command_allowlist(&raw).map_err(|e| {
+                        RalphError::SpawnFailed(format!("Invalid {}: {}", COMMAND_ALLOWLIST_KEY, e))
+                    })?;
+                    env.push((ALLOWED_COMMANDS_ENV.to_string(), commands.join(",")));
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    return Err(RalphError::SpawnFailed(format!(
+                        "Failed to read {}: {}",
+                        COMMAND_ALLOWLIST_KEY, e
+                    )));
+                }
             }
-        }
 
-        // Spawn the process
-        let mut child = cmd.spawn().map_err(|e| {
-            if e.kind() == std::io::ErrorKind::NotFound {
-                RalphError::NotFound {
-                    message: "ralph CLI not found in PATH".to_string(),
-                    help_steps: vec![
-                        "Install ralph: cargo install ralph".to_string(),
-                        "Or download from release page".to_string(),
-                        "Ensure ~/.cargo/bin is in your PATH".to_string(),
-                        "Restart your terminal after installation".to_string(),
-                    ],
+            // A malformed restart limit disables restarts rather than refusing to run
+            let max_restarts = match db.get_config(MAX_CRASH_RESTARTS_KEY) {
+                Ok(Some(raw)) => parse_max_crash_restarts(&raw).unwrap_or_else(|e| {
+                    tracing::warn!("Ignoring invalid {}: {}", MAX_CRASH_RESTARTS_KEY, e);
+                    0
+                }),
+                Ok(None) => 0,
+                Err(e) => {
+                    tracing::warn!("Failed to read {}: {}", MAX_CRASH_RESTARTS_KEY, e);
+                    0
                 }
-            } else {
-                RalphError::SpawnFailed(e.to_string())
+            };
+
+            let limits = ResourceLimits::from_config(&db)?;
+
+            let backend =
+                backend.or_else(|| Self::read_session_config(&db, session_id, BACKEND_KEY));
+            let mut fallback_backend = Self::read_session_config(&db, session_id, FALLBACK_BACKEND_KEY)
+                .filter(|fallback| Some(fallback) != backend.as_ref());
+
+            let current_dir = match Self::read_session_config(&db, session_id, AGENT_WORKDIR_KEY) {
+                Some(workdir) => {
+                    crate::git::validate_relative_path(&workdir).map_err(|e| {
+                        RalphError::SpawnFailed(format!("Invalid {}: {}", AGENT_WORKDIR_KEY, e))
+                    })?;
+                    let dir = std::path::Path::new(repo_path).join(workdir.trim());
+                    if !dir.is_dir() {
+                        return Err(RalphError::SpawnFailed(format!(
+                            "Working directory {} does not exist",
+                            dir.display()
+                        )));
+                    }
+                    dir.to_string_lossy().into_owned()
+                }
+                None => repo_path.to_string(),
+            };
+
+            // Build the command
+            let mut command = AgentCommand {
+                program: self.program.clone(),
+                args: vec![
+                    "run".to_string(),
+                    "--autonomous".to_string(),
+                    "--prompt".to_string(),
+                    prompt.to_string(),
+                ],
+                backend,
+                model: Self::read_session_config(&db, session_id, MODEL_KEY),
+                env,
+                current_dir,
+                limits,
+                interactive: Self::read_session_config(&db, session_id, INTERACTIVE_INPUT_KEY)
+                    .and_then(|value| value.trim().parse().ok())
+                    .unwrap_or(false),
+            };
+
+            // Record where this run started so it can be undone (before the agent can commit)
+            match GitManager::head_sha(std::path::Path::new(repo_path)) {
+                Ok(Some(sha)) => {
+                    if let Err(e) = db.update_session_checkpoint(session_id, &sha) {
+                        tracing::warn!("Failed to record session checkpoint: {}", e);
+                    }
+                }
+                Ok(None) => {}
+                Err(e) => tracing::warn!("Failed to read HEAD for checkpoint: {}", e),
             }
-        })?;
+            Self::record_branch(session_id, repo_path, &db);
+            let worktree = Self::worktree_snapshot(repo_path).await;
+
+            // Spawn the process, falling back to the secondary backend if it can't start
+            let child = match command.spawn() {
+                Ok(child) => child,
+                Err(e) => match fallback_backend.take() {
+                    Some(fallback) => {
+                        let reason = e.to_string();
+                        Self::notify_fallback(session_id, &fallback, &reason, &db, &connections).await;
+                        command.backend = Some(fallback);
+                        command.spawn()?
+                    }
+                    None => return Err(e),
+                },
+            };
+            Ok((child, command, max_restarts, fallback_backend, worktree))
+        }
+        .await;
+        let (mut child, mut command, max_restarts, fallback_backend, worktree) = match spawned {
+            Ok(spawned) => spawned,
+            Err(e) => {
+                self.release(session_id, repo_id).await;
+                return Err(e);
+            }
+        };
 
         // Take stdout and stderr handles
         let stdout = child.stdout.take().expect("stdout was configured");
@@ -148,17 +841,31 @@ impl RalphManager {
             inner.processes.insert(
                 session_id,
                 ProcessHandle {
+                    stdin: take_stdin(&mut child),
                     child,
                     repo_id,
+                    cancelled: false,
+                    restarts: 0,
+                    max_restarts,
+                    started_at: tokio::time::Instant::now(),
+                    run_started_at: Utc::now(),
+                    fallback_backend,
+                    worktree,
                 },
             );
-            inner.active_repos.insert(repo_id, session_id);
+            inner.starting.remove(&session_id);
         }
 
-        // Update session status to running
+        Self::record_command(session_id, &command, &db);
+
+        // Update session status to running; the exit reason is set again when it ends
+        if let Err(e) = db.update_session_exit_reason(session_id, None) {
+            tracing::warn!("Failed to clear exit reason for session {}: {}", session_id, e);
+        }
         if let Err(e) = db.update_session_status(session_id, DbSessionStatus::Running) {
             tracing::error!("Failed to update session status: {}", e);
         }
+        events::status_changed(&db, &connections, session_id, repo_id, DbSessionStatus::Running);
 
         // Broadcast status update
         connections
@@ -171,118 +878,570 @@ impl RalphManager {
             )
             .await;
 
-        // Spawn tasks to read stdout and stderr
-        let manager_clone = self.clone();
-        let db_clone = db.clone();
-        let connections_clone = connections.clone();
-
-        tokio::spawn(async move {
-            let stdout_connections = connections_clone.clone();
-            let stderr_connections = connections_clone.clone();
-            let stdout_db = db_clone.clone();
-            let stderr_db = db_clone.clone();
-
-            // Spawn stdout reader
-            let stdout_handle = tokio::spawn({
-                let session_id = session_id;
-                async move {
-                    let reader = BufReader::new(stdout);
-                    let mut lines = reader.lines();
-                    while let Ok(Some(line)) = lines.next_line().await {
-                        // Persist to database
-                        if let Err(e) =
-                            stdout_db.insert_output_log(session_id, DbOutputStream::Stdout, &line)
-                        {
-                            tracing::warn!("Failed to persist stdout output: {}", e);
-                        }
+        // Stream output until the run finishes, restarting the agent after crashes
+        let manager = self.clone();
+        let repo_path = repo_path.to_string();
+        let supervisor = tokio::spawn(async move {
+            let (mut stdout, mut stderr) = (stdout, stderr);
+            loop {
+                Self::stream_output(session_id, stdout, stderr, &db, &connections).await;
 
-                        // Broadcast to WebSocket subscribers
-                        stdout_connections
-                            .broadcast(
-                                session_id,
-                                ServerMessage::Output {
-                                    session_id,
-                                    stream: OutputStream::Stdout,
-                                    content: line,
-                                },
-                            )
-                            .await;
+                let action = manager
+                    .handle_process_exit(
+                        session_id,
+                        repo_id,
+                        &repo_path,
+                        db.clone(),
+                        connections.clone(),
+                    )
+                    .await;
+                match action {
+                    ExitAction::Finish => break,
+                    ExitAction::Restart => {}
+                    ExitAction::FallBack(backend) => {
+                        command.backend = Some(backend);
+                        Self::record_command(session_id, &command, &db);
                     }
                 }
-            });
 
-            // Spawn stderr reader
-            let stderr_handle = tokio::spawn({
-                let session_id = session_id;
-                async move {
-                    let reader = BufReader::new(stderr);
-                    let mut lines = reader.lines();
-                    while let Ok(Some(line)) = lines.next_line().await {
-                        // Persist to database
-                        if let Err(e) =
-                            stderr_db.insert_output_log(session_id, DbOutputStream::Stderr, &line)
-                        {
+                match manager
+                    .respawn(session_id, repo_id, &command, &db, &connections)
+                    .await
+                {
+                    Some(pipes) => (stdout, stderr) = pipes,
+                    None => break,
+                }
+            }
+        });
+
+        let mut inner = self.inner.write().await;
+        inner.supervisors.retain(|task| !task.is_finished());
+        inner.supervisors.push(supervisor);
+
+        Ok(())
+    }
+
+    /// Read a config value for a session (its override, else the global
+    /// value), treating empty values and read errors as unset
+    fn read_session_config(db: &Database, session_id: Uuid, key: &str) -> Option<String> {
+        match db.resolve_session_config(session_id, key) {
+            Ok(value) => value.filter(|v| !v.trim().is_empty()),
+            Err(e) => {
+                tracing::warn!("Failed to read {}: {}", key, e);
+                None
+            }
+        }
+    }
+
+    /// Record the resolved command line and backend, masking secret config values
+    fn record_command(session_id: Uuid, command: &AgentCommand, db: &Database) {
+        let secrets = match db.list_config() {
+            Ok(config) => config
+                .into_iter()
+                .filter(|(key, value)| is_secret_config_key(key) && !value.is_empty())
+                .filter_map(|(key, value)| db.reveal_config(&key, value).ok())
+                .collect(),
+            Err(e) => {
+                tracing::warn!("Failed to load config for command masking: {}", e);
+                Vec::new()
+            }
+        };
+        let command_line =
+            format_command_line(&command.env, &command.program, &command.full_args(), &secrets);
+        if let Err(e) = db.update_session_command(session_id, &command_line) {
+            tracing::warn!("Failed to record session command: {}", e);
+        }
+        if let Err(e) = db.update_session_backend(session_id, command.backend.as_deref()) {
+            tracing::warn!("Failed to record session backend: {}", e);
+        }
+    }
+
+    /// Record the branch checked out in the session's repo
+    fn record_branch(session_id: Uuid, repo_path: &str, db: &Database) {
+        match GitManager::current_branch(std::path::Path::new(repo_path)) {
+            Ok(branch) => {
+                if let Err(e) = db.update_session_branch(session_id, branch.as_deref()) {
+                    tracing::warn!("Failed to record session branch: {}", e);
+                }
+            }
+            Err(e) => tracing::warn!("Failed to read branch for session: {}", e),
+        }
+    }
+
+    /// Record and broadcast that a session is switching to its fallback backend
+    async fn notify_fallback(
+        session_id: Uuid,
+        fallback: &str,
+        reason: &str,
+        db: &Database,
+        connections: &ConnectionManager,
+    ) {
+        let message = format!(
+            "Backend failed to start ({}); falling back to '{}'",
+            reason, fallback
+        );
+        tracing::warn!("Session {}: {}", session_id, message);
+        if let Err(e) = db.insert_message(session_id, MessageRole::System, &message) {
+            tracing::warn!("Failed to record backend fallback: {}", e);
+        }
+        connections
+            .broadcast(session_id, ServerMessage::Warning { session_id, message })
+            .await;
+    }
+
+    /// Persist and broadcast a process's output until both streams close
+    async fn stream_output(
+        session_id: Uuid,
+        stdout: ChildStdout,
+        stderr: ChildStderr,
+        db: &Arc<Database>,
+        connections: &ConnectionManager,
+    ) {
+        let stdout_connections = connections.clone();
+        let stderr_connections = connections.clone();
+        let stdout_db = db.clone();
+        let stderr_db = db.clone();
+
+        // Spawn stdout reader
+        let stdout_handle = tokio::spawn(async move {
+            let reader = BufReader::new(stdout);
+            let mut lines = reader.lines();
+            let mut response = ResponseCheckpoint::new(session_id);
+            while let Ok(Some(line)) = lines.next_line().await {
+                // Persist to database
+                let (log_id, content, truncated) =
+                    match stdout_db.insert_output_log(session_id, DbOutputStream::Stdout, &line) {
+                        Ok(log) => (Some(log.id), log.content, log.truncated),
+                        Err(e) => {
+                            tracing::warn!("Failed to persist stdout output: {}", e);
+                            (None, line.clone(), false)
+                        }
+                    };
+                response.push_line(&line, &stdout_db);
+
+                // Broadcast to WebSocket subscribers
+                stdout_connections
+                    .broadcast(
+                        session_id,
+                        ServerMessage::Output {
+                            session_id,
+                            stream: OutputStream::Stdout,
+                            content,
+                            log_id,
+                            truncated,
+                        },
+                    )
+                    .await;
+            }
+            response.finish(&stdout_db);
+        });
+
+        // Spawn stderr reader
+        let stderr_handle = tokio::spawn(async move {
+            let reader = BufReader::new(stderr);
+            let mut lines = reader.lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                // Persist to database
+                let (log_id, content, truncated) =
+                    match stderr_db.insert_output_log(session_id, DbOutputStream::Stderr, &line) {
+                        Ok(log) => (Some(log.id), log.content, log.truncated),
+                        Err(e) => {
                             tracing::warn!("Failed to persist stderr output: {}", e);
+                            (None, line, false)
                         }
+                    };
 
-                        // Broadcast to WebSocket subscribers
-                        stderr_connections
-                            .broadcast(
-                                session_id,
-                                ServerMessage::Output {
-                                    session_id,
-                                    stream: OutputStream::Stderr,
-                                    content: line,
-                                },
-                            )
-                            .await;
-                    }
+                // Broadcast to WebSocket subscribers
+                stderr_connections
+                    .broadcast(
+                        session_id,
+                        ServerMessage::Output {
+                            session_id,
+                            stream: OutputStream::Stderr,
+                            content,
+                            log_id,
+                            truncated,
+                        },
+                    )
+                    .await;
+            }
+        });
+
+        // Wait for both readers to finish
+        let _ = tokio::join!(stdout_handle, stderr_handle);
+    }
+
+    /// Replace a crashed session's process with a fresh one
+    ///
+    /// Returns the new process's output pipes, or None if the session was
+    /// cancelled meanwhile or the agent could not be spawned again.
+    async fn respawn(
+        &self,
+        session_id: Uuid,
+        repo_id: Uuid,
+        command: &AgentCommand,
+        db: &Database,
+        connections: &ConnectionManager,
+    ) -> Option<(ChildStdout, ChildStderr)> {
+        let spawned = {
+            let mut inner = self.inner.write().await;
+            let handle = match inner.processes.get_mut(&session_id) {
+                Some(handle) if !handle.cancelled => handle,
+                // `cancel` owns the final status
+                _ => return None,
+            };
+            match command.spawn() {
+                Ok(mut child) => {
+                    let stdout = child.stdout.take().expect("stdout was configured");
+                    let stderr = child.stderr.take().expect("stderr was configured");
+                    handle.stdin = take_stdin(&mut child);
+                    handle.child = child;
+                    handle.started_at = tokio::time::Instant::now();
+                    Ok((stdout, stderr))
                 }
-            });
+                Err(e) => {
+                    inner.processes.remove(&session_id);
+                    inner.active_repos.remove(&repo_id);
+                    Err(e)
+                }
+            }
+        };
 
-            // Wait for both readers to finish
-            let _ = tokio::join!(stdout_handle, stderr_handle);
+        let status = match spawned {
+            Ok(pipes) => {
+                connections
+                    .broadcast(
+                        session_id,
+                        ServerMessage::Status {
+                            session_id,
+                            status: WsSessionStatus::Running,
+                        },
+                    )
+                    .await;
+                return Some(pipes);
+            }
+            Err(e) => {
+                let message = format!("Failed to restart agent: {}", e);
+                tracing::error!("Session {}: {}", session_id, message);
+                if let Err(e) = db.insert_message(session_id, MessageRole::System, &message) {
+                    tracing::warn!("Failed to record restart failure: {}", e);
+                }
+                DbSessionStatus::Error
+            }
+        };
 
-            // Process has finished - wait for exit status and cleanup
-            manager_clone
-                .handle_process_exit(session_id, repo_id, db_clone, connections_clone)
-                .await;
-        });
+        if let Err(e) = db.update_session_status(session_id, status) {
+            tracing::error!("Failed to update session status: {}", e);
+        }
+        events::run_finished(db, connections, session_id, repo_id, status);
+        connections
+            .broadcast(
+                session_id,
+                ServerMessage::Status {
+                    session_id,
+                    status: status.into(),
+                },
+            )
+            .await;
 
-        Ok(())
+        None
+    }
+
+    /// Check the run's changes against the repo's denied path patterns.
+    ///
+    /// Both uncommitted changes and commits made since the run's checkpoint are
+    /// checked. Violations are recorded as a system message and broadcast as a
+    /// warning; when `revert_denied_paths` is enabled the offending uncommitted
+    /// files are restored. Committed violations are only reported.
+    async fn enforce_denied_paths(
+        session_id: Uuid,
+        repo_id: Uuid,
+        repo_path: &str,
+        db: &Database,
+        connections: &ConnectionManager,
+    ) {
+        let patterns = match db.get_repo_config(repo_id, DENIED_PATHS_KEY) {
+            Ok(Some(raw)) => match parse_pattern_list(&raw) {
+                Ok(patterns) if !patterns.is_empty() => patterns,
+                Ok(_) => return,
+                Err(e) => {
+                    tracing::warn!("Ignoring invalid {} for repo {}: {}", DENIED_PATHS_KEY, repo_id, e);
+                    return;
+                }
+            },
+            Ok(None) => return,
+            Err(e) => {
+                tracing::warn!("Failed to read denied paths for repo {}: {}", repo_id, e);
+                return;
+            }
+        };
+        let revert = matches!(
+            db.get_repo_config(repo_id, REVERT_DENIED_PATHS_KEY),
+            Ok(Some(value)) if value == "true"
+        );
+
+        let checkpoint = match db.get_session_checkpoint(session_id) {
+            Ok(checkpoint) => checkpoint,
+            Err(e) => {
+                tracing::warn!("Failed to read checkpoint for session {}: {}", session_id, e);
+                None
+            }
+        };
+
+        let path = std::path::PathBuf::from(repo_path);
+        let result = tokio::task::spawn_blocking(move || {
+            let changed = GitManager::changed_paths(&path)?;
+            let uncommitted = match_patterns(&patterns, &changed)?;
+            if revert {
+                GitManager::revert_paths(&path, &uncommitted)?;
+            }
+
+            let committed = match checkpoint {
+                Some(checkpoint) => {
+                    let committed: Vec<String> = GitManager::diff_refs(&path, &checkpoint, "HEAD")?
+                        .into_iter()
+                        .map(|delta| delta.path)
+                        .collect();
+                    match_patterns(&patterns, &committed)?
+                }
+                None => Vec::new(),
+            };
+            Ok::<_, crate::git::GitError>((uncommitted, committed))
+        })
+        .await;
+
+        let (uncommitted, committed) = match result {
+            Ok(Ok((uncommitted, committed)))
+                if !uncommitted.is_empty() || !committed.is_empty() =>
+            {
+                (uncommitted, committed)
+            }
+            Ok(Ok(_)) => return,
+            Ok(Err(e)) => {
+                tracing::warn!("Failed to check denied paths for session {}: {}", session_id, e);
+                return;
+            }
+            Err(e) => {
+                tracing::warn!("Denied path check task failed for session {}: {}", session_id, e);
+                return;
+            }
+        };
+
+        let mut parts = Vec::new();
+        if !uncommitted.is_empty() {
+            parts.push(format!(
+                "Agent modified denied paths{}: {}",
+                if revert { " (changes reverted)" } else { "" },
+                uncommitted.join(", ")
+            ));
+        }
+        if !committed.is_empty() {
+            parts.push(format!(
+                "Agent committed changes to denied paths: {}",
+                committed.join(", ")
+            ));
+        }
+        let message = parts.join("; ");
+        tracing::warn!("Session {}: {}", session_id, message);
+
+        if let Err(e) = db.insert_message(session_id, MessageRole::System, &message) {
+            tracing::warn!("Failed to record denied path warning: {}", e);
+        }
+        connections
+            .broadcast(session_id, ServerMessage::Warning { session_id, message })
+            .await;
+    }
+
+    /// Snapshot the repo's uncommitted changes off the async runtime; empty if
+    /// they can't be read
+    async fn worktree_snapshot(repo_path: &str) -> WorktreeSnapshot {
+        let path = std::path::PathBuf::from(repo_path);
+        match tokio::task::spawn_blocking(move || GitManager::worktree_snapshot(&path)).await {
+            Ok(Ok(snapshot)) => snapshot,
+            Ok(Err(e)) => {
+                tracing::warn!("Failed to snapshot working tree: {}", e);
+                Vec::new()
+            }
+            Err(e) => {
+                tracing::warn!("Working tree snapshot task failed: {}", e);
+                Vec::new()
+            }
+        }
+    }
+
+    /// Whether the repo has changes since the run started: new commits, or
+    /// uncommitted changes that differ from the `before` snapshot. `None` if
+    /// it can't be read.
+    fn repo_changed(
+        session_id: Uuid,
+        repo_path: &str,
+        db: &Database,
+        before: &WorktreeSnapshot,
+    ) -> Option<bool> {
+        let path = std::path::Path::new(repo_path);
+        if GitManager::worktree_snapshot(path).ok()? != *before {
+            return Some(true);
+        }
+
+        let checkpoint = db.get_session_checkpoint(session_id).ok()?;
+        match (checkpoint, GitManager::head_sha(path).ok()?) {
+            (Some(checkpoint), Some(head)) => Some(checkpoint != head),
+            // The run made the first commit in an empty repo
+            (None, Some(_)) => Some(true),
+            (None, None) => Some(false),
+            (Some(_), None) => None,
+        }
+    }
+
+    /// Capture the exit code and stderr tail of a failed run
+    fn record_failure(
+        session_id: Uuid,
+        exit_status: Option<std::process::ExitStatus>,
+        db: &Database,
+    ) {
+        let stderr_tail = db
+            .tail_output_lines(session_id, DbOutputStream::Stderr, ERROR_STDERR_TAIL_LINES)
+            .unwrap_or_else(|e| {
+                tracing::warn!("Failed to read stderr for session {}: {}", session_id, e);
+                Vec::new()
+            });
+        let reason = exit_status
+            .map(|status| status.to_string())
+            .unwrap_or_else(|| "exit status unavailable".to_string());
+
+        if let Err(e) = db.upsert_session_error(
+            session_id,
+            exit_status.and_then(|status| status.code()),
+            &reason,
+            &stderr_tail,
+        ) {
+            tracing::warn!("Failed to record error for session {}: {}", session_id, e);
+        }
     }
 
     /// Handle process exit - cleanup and update status
+    ///
+    /// Unless the run is over, the session's handle stays registered for the
+    /// replacement process the returned action asks for.
     async fn handle_process_exit(
         &self,
         session_id: Uuid,
         repo_id: Uuid,
+        repo_path: &str,
         db: Arc<Database>,
         connections: ConnectionManager,
-    ) {
+    ) -> ExitAction {
         // Get the exit status
-        let exit_status = {
+        let (exit_status, outcome, retry, worktree) = {
             let mut inner = self.inner.write().await;
-            if let Some(mut handle) = inner.processes.remove(&session_id) {
-                inner.active_repos.remove(&repo_id);
+            if let Some(handle) = inner.processes.get_mut(&session_id) {
                 // Wait for the child to fully exit
-                handle.child.wait().await.ok()
+                let status = handle.child.wait().await.ok();
+                let outcome = RunOutcome::classify(handle.cancelled, status);
+                let failed_to_start = outcome == RunOutcome::Crashed
+                    && handle.started_at.elapsed() < BACKEND_STARTUP_WINDOW;
+
+                let retry = if failed_to_start
+                    && let Some(fallback) = handle.fallback_backend.take()
+                {
+                    Some((ExitAction::FallBack(fallback), None))
+                } else if outcome == RunOutcome::Crashed && handle.restarts < handle.max_restarts {
+                    handle.restarts += 1;
+                    Some((ExitAction::Restart, Some((handle.restarts, handle.max_restarts))))
+                } else {
+                    None
+                };
+
+                if outcome == RunOutcome::Crashed {
+                    inner.record_abnormal_exit(AbnormalExit {
+                        session_id,
+                        repo_id,
+                        exit_code: status.and_then(|s| s.code()),
+                        exited_at: Utc::now(),
+                    });
+                }
+                let worktree = if retry.is_none() {
+                    inner.active_repos.remove(&repo_id);
+                    inner.processes.remove(&session_id).map(|handle| handle.worktree)
+                } else {
+                    None
+                };
+                (status, outcome, retry, worktree)
             } else {
-                None
+                // Already reaped by `cancel`
+                (None, RunOutcome::Cancelled, None, None)
+            }
+        };
+
+        if let Some((action, attempt)) = retry {
+            let reason = exit_status
+                .map(|status| status.to_string())
+                .unwrap_or_else(|| "exit status unavailable".to_string());
+            match (&action, attempt) {
+                (ExitAction::FallBack(fallback), _) => {
+                    Self::notify_fallback(session_id, fallback, &reason, &db, &connections).await;
+                }
+                (_, Some((attempt, max_restarts))) => {
+                    let message = format!(
+                        "Agent crashed ({}); restarting (attempt {} of {})",
+                        reason, attempt, max_restarts
+                    );
+                    tracing::warn!("Session {}: {}", session_id, message);
+                    if let Err(e) = db.insert_message(session_id, MessageRole::System, &message) {
+                        tracing::warn!("Failed to record restart: {}", e);
+                    }
+                }
+                _ => {}
             }
+            connections
+                .broadcast(
+                    session_id,
+                    ServerMessage::Status {
+                        session_id,
+                        status: WsSessionStatus::Restarting,
+                    },
+                )
+                .await;
+            return action;
+        }
+
+        Self::enforce_denied_paths(session_id, repo_id, repo_path, &db, &connections).await;
+
+        // Determine final status based on how the run ended
+        let final_status = match outcome {
+            RunOutcome::Completed => DbSessionStatus::Completed,
+            RunOutcome::Crashed => DbSessionStatus::Error,
+            // A cancelled run's status is set by `cancel`, not by how it exited
+            RunOutcome::Cancelled => return ExitAction::Finish,
         };
 
-        // Determine final status based on exit code
-        let final_status = match exit_status {
-            Some(status) if status.success() => DbSessionStatus::Completed,
-            Some(_) => DbSessionStatus::Error,
-            None => DbSessionStatus::Error,
+        let changed = match outcome {
+            RunOutcome::Completed => {
+                let (repo_path, db) = (repo_path.to_string(), db.clone());
+                let before = worktree.unwrap_or_default();
+                tokio::task::spawn_blocking(move || {
+                    Self::repo_changed(session_id, &repo_path, &db, &before)
+                })
+                .await
+                .ok()
+                .flatten()
+            }
+            _ => None,
         };
+        let exit_reason = outcome.exit_reason(exit_status, changed);
+        if let Err(e) = db.update_session_exit_reason(session_id, Some(exit_reason)) {
+            tracing::warn!("Failed to record exit reason for session {}: {}", session_id, e);
+        }
+
+        if final_status == DbSessionStatus::Error {
+            Self::record_failure(session_id, exit_status, &db);
+        }
 
         // Update database
         if let Err(e) = db.update_session_status(session_id, final_status) {
             tracing::error!("Failed to update session status: {}", e);
         }
+        events::run_finished(&db, &connections, session_id, repo_id, final_status);
 
         // Broadcast final status
         connections
@@ -300,9 +1459,47 @@ impl RalphManager {
             session_id,
             final_status
         );
+
+        ExitAction::Finish
+    }
+
+    /// Write user input to a running session's agent, followed by a newline
+    /// if the content does not already end with one
+    pub async fn send_input(&self, session_id: Uuid, content: &str) -> Result<(), RalphError> {
+        let stdin = {
+            let inner = self.inner.read().await;
+            match inner.processes.get(&session_id) {
+                Some(handle) if !handle.cancelled => handle.stdin.clone(),
+                _ => return Err(RalphError::NotRunning(session_id)),
+            }
+        };
+        let stdin = stdin.ok_or_else(|| {
+            RalphError::InputFailed(format!(
+                "the agent was not started with {} enabled",
+                INTERACTIVE_INPUT_KEY
+            ))
+        })?;
+
+        let mut line = content.to_string();
+        if !line.ends_with('\n') {
+            line.push('\n');
+        }
+
+        let mut stdin = stdin.lock().await;
+        stdin
+            .write_all(line.as_bytes())
+            .await
+            .map_err(|e| RalphError::InputFailed(e.to_string()))?;
+        stdin
+            .flush()
+            .await
+            .map_err(|e| RalphError::InputFailed(e.to_string()))
     }
 
     /// Cancel a running ralph process
+    ///
+    /// Sends SIGTERM to the process group, escalating to SIGKILL if the process
+    /// is still alive after the grace period, then marks the session cancelled.
     pub async fn cancel(
         &self,
         session_id: Uuid,
@@ -310,8 +1507,9 @@ impl RalphManager {
         connections: ConnectionManager,
     ) -> Result<(), RalphError> {
         let (child_id, repo_id) = {
-            let inner = self.inner.read().await;
-            if let Some(handle) = inner.processes.get(&session_id) {
+            let mut inner = self.inner.write().await;
+            if let Some(handle) = inner.processes.get_mut(&session_id) {
+                handle.cancelled = true;
                 (handle.child.id(), handle.repo_id)
             } else {
                 return Err(RalphError::NotRunning(session_id));
@@ -330,16 +1528,11 @@ impl RalphManager {
                     tracing::warn!("Failed to send SIGTERM to process group: {}", e);
                 }
 
-                // Wait a bit for graceful shutdown
-                tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
-
-                // Check if still running and send SIGKILL if needed
-                let inner = self.inner.read().await;
-                if inner.processes.contains_key(&session_id) {
-                    drop(inner);
-                    if let Err(e) = killpg(pgid, Signal::SIGKILL) {
-                        tracing::warn!("Failed to send SIGKILL to process group: {}", e);
-                    }
+                // Give the process a chance to shut down gracefully
+                if !self.wait_for_exit(session_id, CANCEL_GRACE_PERIOD).await
+                    && let Err(e) = killpg(pgid, Signal::SIGKILL)
+                {
+                    tracing::warn!("Failed to send SIGKILL to process group: {}", e);
                 }
             }
         }
@@ -347,23 +1540,34 @@ impl RalphManager {
         // On non-Unix, just kill the child directly
         #[cfg(not(unix))]
         {
+            let _ = child_id;
             let mut inner = self.inner.write().await;
             if let Some(handle) = inner.processes.get_mut(&session_id) {
                 let _ = handle.child.kill().await;
             }
         }
 
-        // Remove from tracking and update status
-        {
+        // Remove from tracking and reap the child if the exit handler hasn't
+        let handle = {
             let mut inner = self.inner.write().await;
-            inner.processes.remove(&session_id);
-            inner.active_repos.remove(&repo_id);
+            let handle = inner.processes.remove(&session_id);
+            if handle.is_some() {
+                inner.active_repos.remove(&repo_id);
+            }
+            handle
+        };
+        if let Some(mut handle) = handle {
+            let _ = handle.child.wait().await;
         }
 
         // Update database
+        if let Err(e) = db.update_session_exit_reason(session_id, Some(ExitReason::Cancelled)) {
+            tracing::warn!("Failed to record exit reason for session {}: {}", session_id, e);
+        }
         if let Err(e) = db.update_session_status(session_id, DbSessionStatus::Cancelled) {
             tracing::error!("Failed to update session status: {}", e);
         }
+        events::run_finished(&db, &connections, session_id, repo_id, DbSessionStatus::Cancelled);
 
         // Broadcast status
         connections
@@ -381,11 +1585,148 @@ impl RalphManager {
         Ok(())
     }
 
+    /// Poll a tracked process until it exits or `timeout` elapses
+    ///
+    /// Returns true if the process has exited (or is no longer tracked).
+    #[cfg(unix)]
+    async fn wait_for_exit(&self, session_id: Uuid, timeout: std::time::Duration) -> bool {
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            {
+                let mut inner = self.inner.write().await;
+                match inner.processes.get_mut(&session_id) {
+                    Some(handle) => {
+                        if !matches!(handle.child.try_wait(), Ok(None)) {
+                            return true;
+                        }
+                    }
+                    None => return true,
+                }
+            }
+            if tokio::time::Instant::now() >= deadline {
+                return false;
+            }
+            tokio::time::sleep(CANCEL_POLL_INTERVAL).await;
+        }
+    }
+
+    /// Stop every running process for server shutdown
+    ///
+    /// Each session is cancelled as by `cancel`, then the output streams get
+    /// up to `drain_timeout` to finish writing to the database.
+    pub async fn shutdown(
+        &self,
+        db: Arc<Database>,
+        connections: ConnectionManager,
+        drain_timeout: std::time::Duration,
+    ) {
+        let sessions = self.active_sessions().await;
+        if !sessions.is_empty() {
+            tracing::info!("Stopping {} running session(s)", sessions.len());
+        }
+
+        let results = futures::future::join_all(
+            sessions
+                .iter()
+                .map(|&session_id| self.cancel(session_id, db.clone(), connections.clone())),
+        )
+        .await;
+        for (session_id, result) in sessions.iter().zip(results) {
+            // NotRunning just means the process exited on its own meanwhile
+            if let Err(e) = result
+                && !matches!(e, RalphError::NotRunning(_))
+            {
+                tracing::warn!("Failed to stop session {}: {}", session_id, e);
+            }
+        }
+
+        let supervisors = std::mem::take(&mut self.inner.write().await.supervisors);
+        let drained =
+            tokio::time::timeout(drain_timeout, futures::future::join_all(supervisors)).await;
+        if drained.is_err() {
+            tracing::warn!("Timed out waiting for session output to be persisted");
+        }
+    }
+
     /// Get list of active sessions
     pub async fn active_sessions(&self) -> Vec<Uuid> {
         let inner = self.inner.read().await;
         inner.processes.keys().copied().collect()
     }
+
+    /// Snapshot of the running processes, oldest run first
+    pub async fn running_processes(&self) -> Vec<RunningProcess> {
+        let inner = self.inner.read().await;
+        let mut running: Vec<RunningProcess> = inner
+            .processes
+            .iter()
+            .map(|(session_id, handle)| RunningProcess {
+                session_id: *session_id,
+                repo_id: handle.repo_id,
+                started_at: handle.run_started_at,
+                restarts: handle.restarts,
+            })
+            .collect();
+        running.sort_by_key(|p| p.started_at);
+        running
+    }
+
+    /// Summarize the health of the process subsystem
+    ///
+    /// Includes a spawn check that runs a trivial command, so this should not
+    /// be called in a tight loop.
+    pub async fn health(&self) -> ProcessHealth {
+        let (running, recent_abnormal_exits) = {
+            let inner = self.inner.read().await;
+            (
+                inner.processes.len(),
+                inner.abnormal_exits.iter().cloned().collect(),
+            )
+        };
+
+        let spawn_error = check_spawn().await.err();
+
+        ProcessHealth {
+            running,
+            recent_abnormal_exits,
+            spawn_ok: spawn_error.is_none(),
+            spawn_error,
+        }
+    }
+}
+
+impl RalphManagerInner {
+    /// Remember an abnormal exit, discarding the oldest beyond the limit
+    fn record_abnormal_exit(&mut self, exit: AbnormalExit) {
+        self.abnormal_exits.push_front(exit);
+        self.abnormal_exits.truncate(MAX_RECENT_ABNORMAL_EXITS);
+    }
+}
+
+/// Spawn a trivial command to verify processes can be started
+async fn check_spawn() -> Result<(), String> {
+    #[cfg(unix)]
+    let mut cmd = Command::new("true");
+    #[cfg(not(unix))]
+    let mut cmd = {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", "exit 0"]);
+        cmd
+    };
+
+    let status = cmd
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("trivial command exited with {}", status))
+    }
 }
 
 impl Default for RalphManager {
@@ -406,9 +1747,15 @@ pub enum RalphError {
     #[error("Failed to spawn ralph process: {0}")]
     SpawnFailed(String),
 
+    #[error("{running} sessions are already running (limit {limit})")]
+    AtCapacity { running: usize, limit: usize },
+
     #[error("Session {0} has no running process")]
     NotRunning(Uuid),
 
+    #[error("Failed to write input: {0}")]
+    InputFailed(String),
+
     #[error("ralph CLI not found: {message}")]
     NotFound {
         message: String,
@@ -426,6 +1773,534 @@ mod tests {
         assert!(manager.active_sessions().await.is_empty());
     }
 
+    #[tokio::test]
+    async fn test_health_reports_spawn_and_recent_exits() {
+        let manager = RalphManager::new();
+
+        let health = manager.health().await;
+        assert_eq!(health.running, 0);
+        assert!(health.recent_abnormal_exits.is_empty());
+        assert!(health.spawn_ok, "spawn check failed: {:?}", health.spawn_error);
+
+        {
+            let mut inner = manager.inner.write().await;
+            for code in 0..(MAX_RECENT_ABNORMAL_EXITS as i32 + 5) {
+                inner.record_abnormal_exit(AbnormalExit {
+                    session_id: Uuid::new_v4(),
+                    repo_id: Uuid::new_v4(),
+                    exit_code: Some(code),
+                    exited_at: Utc::now(),
+                });
+            }
+        }
+
+        let health = manager.health().await;
+        assert_eq!(health.recent_abnormal_exits.len(), MAX_RECENT_ABNORMAL_EXITS);
+        assert_eq!(
+            health.recent_abnormal_exits[0].exit_code,
+            Some(MAX_RECENT_ABNORMAL_EXITS as i32 + 4)
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_send_input_writes_to_agent_stdin() {
+        let manager = RalphManager::new();
+        let session_id = Uuid::new_v4();
+
+        let err = manager.send_input(session_id, "y").await.unwrap_err();
+        assert!(matches!(err, RalphError::NotRunning(id) if id == session_id));
+
+        let mut child = Command::new("cat")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .unwrap();
+        let stdout = child.stdout.take().unwrap();
+        {
+            let mut inner = manager.inner.write().await;
+            inner.processes.insert(
+                session_id,
+                ProcessHandle {
+                    stdin: take_stdin(&mut child),
+                    child,
+                    repo_id: Uuid::new_v4(),
+                    cancelled: false,
+                    restarts: 0,
+                    max_restarts: 0,
+                    started_at: tokio::time::Instant::now(),
+                    run_started_at: Utc::now(),
+                    fallback_backend: None,
+                    worktree: Vec::new(),
+                },
+            );
+        }
+
+        manager.send_input(session_id, "yes").await.unwrap();
+        manager.send_input(session_id, "no\n").await.unwrap();
+
+        let mut lines = BufReader::new(stdout).lines();
+        assert_eq!(lines.next_line().await.unwrap().as_deref(), Some("yes"));
+        assert_eq!(lines.next_line().await.unwrap().as_deref(), Some("no"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_stdin_is_only_piped_when_interactive() {
+        let command = AgentCommand {
+            program: "sh".to_string(),
+            args: vec!["-c".to_string(), "cat; echo done".to_string()],
+            backend: None,
+            model: None,
+            env: Vec::new(),
+            current_dir: ".".to_string(),
+            limits: ResourceLimits::default(),
+            interactive: false,
+        };
+
+        // A non-interactive agent reading stdin gets EOF rather than hanging
+        let child = command.spawn().unwrap();
+        assert!(child.stdin.is_none());
+        let output = tokio::time::timeout(
+            std::time::Duration::from_secs(10),
+            child.wait_with_output(),
+        )
+        .await
+        .expect("agent blocked on stdin")
+        .unwrap();
+        assert_eq!(String::from_utf8(output.stdout).unwrap(), "done\n");
+
+        let interactive = AgentCommand {
+            interactive: true,
+            ..command
+        };
+        let mut child = interactive.spawn().unwrap();
+        assert!(child.stdin.is_some());
+        child.kill().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_cancel_terminates_process() {
+        use crate::db::models::Orchestrator;
+        use std::time::Instant;
+
+        let db = Arc::new(Database::in_memory().unwrap());
+        let repo = db.insert_repo("/tmp/cancel-test", "cancel-test").unwrap();
+        let session = db.insert_session(repo.id, None, Orchestrator::Ralph).unwrap();
+        let connections = ConnectionManager::new();
+        let manager = RalphManager::new();
+
+        let child = Command::new("sleep")
+            .arg("30")
+            .process_group(0)
+            .kill_on_drop(true)
+            .spawn()
+            .unwrap();
+        {
+            let mut inner = manager.inner.write().await;
+            inner.processes.insert(
+                session.id,
+                ProcessHandle {
+                    child,
+                    stdin: None,
+                    repo_id: repo.id,
+                    cancelled: false,
+                    restarts: 0,
+                    max_restarts: 0,
+                    started_at: tokio::time::Instant::now(),
+                    run_started_at: Utc::now(),
+                    fallback_backend: None,
+                    worktree: Vec::new(),
+                },
+            );
+            inner.active_repos.insert(repo.id, session.id);
+        }
+
+        let running = manager.running_processes().await;
+        assert_eq!(running.len(), 1);
+        assert_eq!(running[0].session_id, session.id);
+        assert_eq!(running[0].repo_id, repo.id);
+
+        let started = Instant::now();
+        manager
+            .cancel(session.id, db.clone(), connections.clone())
+            .await
+            .unwrap();
+        assert!(started.elapsed() < CANCEL_GRACE_PERIOD);
+        assert!(!manager.is_session_running(session.id).await);
+        assert!(!manager.is_repo_busy(repo.id).await);
+        assert_eq!(
+            db.get_session(session.id).unwrap().status,
+            DbSessionStatus::Cancelled
+        );
+
+        // The exit handler must not overwrite the cancelled status
+        manager
+            .handle_process_exit(session.id, repo.id, &repo.path, db.clone(), connections.clone())
+            .await;
+        assert_eq!(
+            db.get_session(session.id).unwrap().status,
+            DbSessionStatus::Cancelled
+        );
+        assert!(manager.health().await.recent_abnormal_exits.is_empty());
+
+        // Nothing left to cancel
+        assert!(matches!(
+            manager.cancel(session.id, db, connections).await,
+            Err(RalphError::NotRunning(_))
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_response_checkpoint() {
+        use crate::db::models::Orchestrator;
+
+        let db = Database::in_memory().unwrap();
+        let repo = db.insert_repo("/path/to/repo", "repo").unwrap();
+        let session = db.insert_session(repo.id, None, Orchestrator::Ralph).unwrap();
+
+        let mut response = ResponseCheckpoint::new(session.id);
+        response.push_line("short", &db);
+        assert!(db.list_messages(session.id).unwrap().is_empty());
+
+        // Enough output forces a checkpoint marked incomplete
+        response.push_line(&"x".repeat(MESSAGE_CHECKPOINT_BYTES), &db);
+        let messages = db.list_messages(session.id).unwrap();
+        assert_eq!(messages.len(), 1);
+        assert!(messages[0].incomplete);
+        assert!(messages[0].content.starts_with("short\nxxx"));
+
+        // Checkpoints are spaced out as the response grows
+        assert_eq!(response.checkpoint_bytes(), MESSAGE_CHECKPOINT_BYTES);
+        assert_eq!(response.checkpoint_interval(), MESSAGE_CHECKPOINT_INTERVAL);
+        response.push_line(&"y".repeat(MESSAGE_CHECKPOINT_GROWTH_BYTES * 4), &db);
+        assert_eq!(response.checkpoint_bytes(), response.content.len() / 4);
+        assert_eq!(response.checkpoint_interval(), MESSAGE_CHECKPOINT_INTERVAL * 5);
+        response.push_line(&"z".repeat(MESSAGE_CHECKPOINT_BYTES), &db);
+        assert!(db.list_messages(session.id).unwrap()[0].content.ends_with('y'));
+        response.content.push_str(&"z".repeat(MESSAGE_CHECKPOINT_GROWTH_BYTES * 20));
+        assert_eq!(response.checkpoint_interval(), MESSAGE_CHECKPOINT_MAX_INTERVAL);
+
+        response.push_line("done", &db);
+        response.finish(&db);
+        let messages = db.list_messages(session.id).unwrap();
+        assert_eq!(messages.len(), 1);
+        assert!(!messages[0].incomplete);
+        assert!(messages[0].content.ends_with("\ndone"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_shutdown_stops_sessions_and_flushes_output() {
+        use crate::db::models::Orchestrator;
+        use std::time::Duration;
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(temp_dir.path().join("run"), "echo started\nsleep 30\n").unwrap();
+        let repo_path = temp_dir.path().to_str().unwrap();
+
+        let db = Arc::new(Database::in_memory().unwrap());
+        let repo = db.insert_repo(repo_path, "shutdown-test").unwrap();
+        let session = db.insert_session(repo.id, None, Orchestrator::Ralph).unwrap();
+        let connections = ConnectionManager::new();
+        let manager = RalphManager::with_program("sh");
+
+        manager
+            .run(session.id, repo.id, repo_path, "go", db.clone(), connections.clone())
+            .await
+            .unwrap();
+        tokio::time::timeout(Duration::from_secs(10), async {
+            while db.list_output_logs(session.id, None, None, None).unwrap().is_empty() {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+        })
+        .await
+        .expect("agent produced no output");
+
+        manager
+            .shutdown(db.clone(), connections, Duration::from_secs(5))
+            .await;
+
+        assert!(manager.active_sessions().await.is_empty());
+        let session = db.get_session(session.id).unwrap();
+        assert_eq!(session.status, DbSessionStatus::Cancelled);
+        assert_eq!(session.exit_reason, Some(ExitReason::Cancelled));
+        let messages = db.list_messages(session.id).unwrap();
+        let response = messages
+            .iter()
+            .find(|m| m.role == MessageRole::Assistant)
+            .expect("response was persisted");
+        assert_eq!(response.content, "started");
+        assert!(!response.incomplete);
+    }
+
+    #[tokio::test]
+    async fn test_crashed_agent_is_restarted() {
+        use crate::db::models::Orchestrator;
+        use std::time::Duration;
+
+        // `sh run ...` executes the script named `run` in the repo directory:
+        // it crashes on its first invocation and succeeds afterwards
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            temp_dir.path().join("run"),
+            "if [ -e crashed ]; then echo recovered; exit 0; fi\n\
+             touch crashed\n\
+             echo boom >&2\n\
+             exit 3\n",
+        )
+        .unwrap();
+        let repo_path = temp_dir.path().to_str().unwrap();
+
+        let db = Arc::new(Database::in_memory().unwrap());
+        db.set_config(MAX_CRASH_RESTARTS_KEY, "2").unwrap();
+        let repo = db.insert_repo(repo_path, "restart-test").unwrap();
+        let session = db.insert_session(repo.id, None, Orchestrator::Ralph).unwrap();
+        let connections = ConnectionManager::new();
+        let mut rx = connections.subscribe(Uuid::new_v4(), session.id).await;
+        let manager = RalphManager::with_program("sh");
+
+        manager
+            .run(session.id, repo.id, repo_path, "go", db.clone(), connections.clone())
+            .await
+            .unwrap();
+
+        let mut statuses = Vec::new();
+        while !matches!(
+            statuses.last(),
+            Some(WsSessionStatus::Completed | WsSessionStatus::Error)
+        ) {
+            let message = tokio::time::timeout(Duration::from_secs(10), rx.recv())
+                .await
+                .expect("run did not finish")
+                .unwrap();
+            if let ServerMessage::Status { status, .. } = message {
+                statuses.push(status);
+            }
+        }
+        assert_eq!(
+            statuses,
+            vec![
+                WsSessionStatus::Running,
+                WsSessionStatus::Restarting,
+                WsSessionStatus::Running,
+                WsSessionStatus::Completed,
+            ]
+        );
+        assert_eq!(
+            db.get_session(session.id).unwrap().status,
+            DbSessionStatus::Completed
+        );
+        assert!(!manager.is_repo_busy(repo.id).await);
+        assert_eq!(manager.health().await.recent_abnormal_exits.len(), 1);
+
+        let messages = db.list_messages(session.id).unwrap();
+        assert!(messages[0].content.contains("restarting (attempt 1 of 2)"));
+        let response = messages
+            .iter()
+            .find(|m| m.role == MessageRole::Assistant)
+            .expect("assistant response");
+        assert_eq!(response.content, "recovered");
+        assert!(!response.incomplete);
+
+        // Without restarts configured, a crash ends the session
+        std::fs::remove_file(temp_dir.path().join("crashed")).unwrap();
+        db.delete_config(MAX_CRASH_RESTARTS_KEY).unwrap();
+        manager
+            .run(session.id, repo.id, repo_path, "go", db.clone(), connections)
+            .await
+            .unwrap();
+        let status = loop {
+            let message = tokio::time::timeout(Duration::from_secs(10), rx.recv())
+                .await
+                .expect("run did not finish")
+                .unwrap();
+            match message {
+                ServerMessage::Status {
+                    status: WsSessionStatus::Running,
+                    ..
+                } => {}
+                ServerMessage::Status { status, .. } => break status,
+                _ => {}
+            }
+        };
+        assert_eq!(status, WsSessionStatus::Error);
+        let session = db.get_session(session.id).unwrap();
+        assert_eq!(session.status, DbSessionStatus::Error);
+        assert_eq!(session.exit_reason, Some(ExitReason::Crashed));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_failing_backend_falls_back() {
+        use crate::db::models::Orchestrator;
+        use std::time::Duration;
+
+        // `sh run --autonomous --prompt go --backend <id>`: only the fallback works
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            temp_dir.path().join("run"),
+            "if [ \"$5\" = vertex ]; then echo ok; exit 0; fi\n\
+             echo \"invalid API key for $5\" >&2\n\
+             exit 1\n",
+        )
+        .unwrap();
+        let repo_path = temp_dir.path().to_str().unwrap();
+
+        let db = Arc::new(Database::in_memory().unwrap());
+        db.set_config(BACKEND_KEY, "bedrock").unwrap();
+        db.set_config(FALLBACK_BACKEND_KEY, "vertex").unwrap();
+        let repo = db.insert_repo(repo_path, "fallback-test").unwrap();
+        let session = db.insert_session(repo.id, None, Orchestrator::Ralph).unwrap();
+        let connections = ConnectionManager::new();
+        let mut rx = connections.subscribe(Uuid::new_v4(), session.id).await;
+        let manager = RalphManager::with_program("sh");
+
+        manager
+            .run(session.id, repo.id, repo_path, "go", db.clone(), connections.clone())
+            .await
+            .unwrap();
+
+        let mut statuses = Vec::new();
+        let mut warnings = Vec::new();
+        while !matches!(
+            statuses.last(),
+            Some(WsSessionStatus::Completed | WsSessionStatus::Error)
+        ) {
+            let message = tokio::time::timeout(Duration::from_secs(10), rx.recv())
+                .await
+                .expect("run did not finish")
+                .unwrap();
+            match message {
+                ServerMessage::Status { status, .. } => statuses.push(status),
+                ServerMessage::Warning { message, .. } => warnings.push(message),
+                _ => {}
+            }
+        }
+        assert_eq!(statuses.last(), Some(&WsSessionStatus::Completed));
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("falling back to 'vertex'"));
+        assert_eq!(
+            db.get_session_backend(session.id).unwrap().as_deref(),
+            Some("vertex")
+        );
+        assert!(db
+            .get_session_command(session.id)
+            .unwrap()
+            .unwrap()
+            .ends_with("--backend vertex"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_beyond_concurrent_limit_is_rejected() {
+        use crate::db::models::Orchestrator;
+        use std::time::Duration;
+
+        let db = Arc::new(Database::in_memory().unwrap());
+        db.set_config(MAX_CONCURRENT_SESSIONS_KEY, "2").unwrap();
+        let connections = ConnectionManager::new();
+        let manager = RalphManager::with_program("sh");
+
+        let mut dirs = Vec::new();
+        let mut results = Vec::new();
+        for i in 0..3 {
+            let temp_dir = tempfile::TempDir::new().unwrap();
+            std::fs::write(temp_dir.path().join("run"), "sleep 30\n").unwrap();
+            let repo_path = temp_dir.path().to_str().unwrap();
+            let repo = db.insert_repo(repo_path, &format!("limit-test-{}", i)).unwrap();
+            let session = db.insert_session(repo.id, None, Orchestrator::Ralph).unwrap();
+
+            let result = manager
+                .run(session.id, repo.id, repo_path, "go", db.clone(), connections.clone())
+                .await;
+            results.push(result);
+            dirs.push(temp_dir);
+        }
+
+        assert!(results[0].is_ok());
+        assert!(results[1].is_ok());
+        assert!(matches!(
+            results[2],
+            Err(RalphError::AtCapacity { running: 2, limit: 2 })
+        ));
+        assert_eq!(manager.running_count().await, 2);
+
+        manager
+            .shutdown(db.clone(), connections, Duration::from_secs(5))
+            .await;
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_concurrent_starts_respect_limit() {
+        use crate::db::models::Orchestrator;
+        use std::time::Duration;
+
+        let db = Arc::new(Database::in_memory().unwrap());
+        db.set_config(MAX_CONCURRENT_SESSIONS_KEY, "1").unwrap();
+        let connections = ConnectionManager::new();
+        let manager = RalphManager::with_program("sh");
+
+        let mut dirs = Vec::new();
+        let mut starts = Vec::new();
+        for i in 0..4 {
+            let temp_dir = tempfile::TempDir::new().unwrap();
+            std::fs::write(temp_dir.path().join("run"), "sleep 30\n").unwrap();
+            let repo_path = temp_dir.path().to_str().unwrap().to_string();
+            let repo = db.insert_repo(&repo_path, &format!("race-test-{}", i)).unwrap();
+            let session = db.insert_session(repo.id, None, Orchestrator::Ralph).unwrap();
+            dirs.push(temp_dir);
+            starts.push((session.id, repo.id, repo_path));
+        }
+        // The same session twice, too
+        starts.push(starts[0].clone());
+
+        let results = futures::future::join_all(starts.iter().map(|(session_id, repo_id, path)| {
+            manager.run(*session_id, *repo_id, path, "go", db.clone(), connections.clone())
+        }))
+        .await;
+
+        assert_eq!(results.iter().filter(|r| r.is_ok()).count(), 1);
+        assert_eq!(manager.running_count().await, 1);
+
+        manager
+            .shutdown(db.clone(), connections, Duration::from_secs(5))
+            .await;
+    }
+
+    #[test]
+    fn test_run_outcome_classify() {
+        use std::os::unix::process::ExitStatusExt;
+        use std::process::ExitStatus;
+
+        let ok = ExitStatus::from_raw(0);
+        let failed = ExitStatus::from_raw(1 << 8);
+        assert_eq!(RunOutcome::classify(false, Some(ok)), RunOutcome::Completed);
+        assert_eq!(RunOutcome::classify(false, Some(failed)), RunOutcome::Crashed);
+        assert_eq!(RunOutcome::classify(false, None), RunOutcome::Crashed);
+        assert_eq!(RunOutcome::classify(true, Some(ok)), RunOutcome::Cancelled);
+        assert_eq!(RunOutcome::classify(true, Some(failed)), RunOutcome::Cancelled);
+
+        let timed_out = ExitStatus::from_raw(TIMEOUT_EXIT_CODE << 8);
+        let cpu_limit = ExitStatus::from_raw(libc::SIGXCPU);
+        let completed = RunOutcome::Completed;
+        assert_eq!(completed.exit_reason(Some(ok), None), ExitReason::CompletedSuccess);
+        assert_eq!(completed.exit_reason(Some(ok), Some(true)), ExitReason::CompletedWithChanges);
+        assert_eq!(completed.exit_reason(Some(ok), Some(false)), ExitReason::NoChanges);
+        assert_eq!(RunOutcome::Cancelled.exit_reason(Some(failed), None), ExitReason::Cancelled);
+        assert_eq!(RunOutcome::Crashed.exit_reason(Some(timed_out), None), ExitReason::TimedOut);
+        assert_eq!(RunOutcome::Crashed.exit_reason(Some(cpu_limit), None), ExitReason::TimedOut);
+        assert_eq!(RunOutcome::Crashed.exit_reason(Some(failed), None), ExitReason::Crashed);
+        assert_eq!(RunOutcome::Crashed.exit_reason(None, None), ExitReason::Crashed);
+
+        assert_eq!(parse_max_crash_restarts(" 3 ").unwrap(), 3);
+        assert!(parse_max_crash_restarts("-1").is_err());
+        assert!(parse_max_crash_restarts("many").is_err());
+    }
+
     #[tokio::test]
     async fn test_repo_busy_detection() {
         let manager = RalphManager::new();
@@ -441,4 +2316,213 @@ mod tests {
 
         assert!(!manager.is_session_running(session_id).await);
     }
+
+    #[test]
+    fn test_format_command_line_quotes_and_masks() {
+        let args = vec![
+            "run".to_string(),
+            "--prompt".to_string(),
+            "fix the bug's cause".to_string(),
+            "--key=sk-123".to_string(),
+        ];
+
+        let line = format_command_line(&[], "ralph", &args, &[]);
+        assert_eq!(line, "ralph run --prompt 'fix the bug'\\''s cause' --key=sk-123");
+
+        let line = format_command_line(&[], "ralph", &args, &["sk-123".to_string()]);
+        assert_eq!(line, "ralph run --prompt 'fix the bug'\\''s cause' ****");
+
+        let env = [
+            (ALLOWED_COMMANDS_ENV.to_string(), "git,cargo".to_string()),
+            ("GITHUB_TOKEN".to_string(), "ghp-123".to_string()),
+        ];
+        let line = format_command_line(&env, "ralph", &args[..1], &[]);
+        assert_eq!(line, "RALPH_ALLOWED_COMMANDS=git,cargo GITHUB_TOKEN=**** ralph run");
+    }
+
+    #[test]
+    fn test_parse_command_allowlist() {
+        assert_eq!(
+            parse_command_allowlist(r#"["git", "cargo"]"#).unwrap(),
+            vec!["git", "cargo"]
+        );
+        assert!(parse_command_allowlist(r#"[]"#).unwrap().is_empty());
+        assert!(parse_command_allowlist(r#"["rm -rf"]"#).is_err());
+        assert!(parse_command_allowlist(r#"["a,b"]"#).is_err());
+        assert!(parse_command_allowlist("git").is_err());
+    }
+
+    #[test]
+    fn test_resource_limits_from_config() {
+        let db = Database::in_memory().unwrap();
+        assert_eq!(ResourceLimits::from_config(&db).unwrap(), ResourceLimits::default());
+
+        db.set_config(MEMORY_LIMIT_MB_KEY, "512").unwrap();
+        db.set_config(CPU_TIME_LIMIT_SECS_KEY, "60").unwrap();
+        let limits = ResourceLimits::from_config(&db).unwrap();
+        assert_eq!(limits.memory_bytes, Some(512 * 1024 * 1024));
+        assert_eq!(limits.cpu_seconds, Some(60));
+
+        db.set_config(CPU_TIME_LIMIT_SECS_KEY, "0").unwrap();
+        assert!(matches!(
+            ResourceLimits::from_config(&db),
+            Err(RalphError::SpawnFailed(_))
+        ));
+        assert!(parse_resource_limit("-1").is_err());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_resource_limits_are_applied_to_agent() {
+        let command = AgentCommand {
+            program: "sh".to_string(),
+            args: vec!["-c".to_string(), "ulimit -t; ulimit -v".to_string()],
+            backend: None,
+            model: None,
+            env: Vec::new(),
+            current_dir: ".".to_string(),
+            limits: ResourceLimits {
+                memory_bytes: Some(1024 * 1024 * 1024),
+                cpu_seconds: Some(30),
+            },
+            interactive: false,
+        };
+
+        let output = command.spawn().unwrap().wait_with_output().await.unwrap();
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert_eq!(stdout.split_whitespace().collect::<Vec<_>>(), vec!["30", "1048576"]);
+    }
+
+    #[test]
+    fn test_is_secret_config_key() {
+        assert!(is_secret_config_key("anthropic_api_key"));
+        assert!(is_secret_config_key("GITHUB_TOKEN"));
+        assert!(!is_secret_config_key("backend"));
+    }
+
+    #[tokio::test]
+    async fn test_enforce_denied_paths_reports_and_reverts() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let repo = git2::Repository::init(temp_dir.path()).unwrap();
+        let sig = git2::Signature::now("Test User", "test@example.com").unwrap();
+        let tree = repo.find_tree(repo.index().unwrap().write_tree().unwrap()).unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "Initial commit", &tree, &[])
+            .unwrap();
+
+        std::fs::create_dir_all(temp_dir.path().join(".github")).unwrap();
+        std::fs::write(temp_dir.path().join(".github/ci.yml"), "x").unwrap();
+        std::fs::write(temp_dir.path().join("main.rs"), "x").unwrap();
+
+        let db = Database::in_memory().unwrap();
+        let repo_path = temp_dir.path().to_string_lossy().to_string();
+        let db_repo = db.insert_repo(&repo_path, "repo").unwrap();
+        let session = db
+            .insert_session(db_repo.id, None, crate::db::models::Orchestrator::Ralph)
+            .unwrap();
+        db.set_repo_config(db_repo.id, DENIED_PATHS_KEY, r#"[".github/**"]"#)
+            .unwrap();
+        db.set_repo_config(db_repo.id, REVERT_DENIED_PATHS_KEY, "true")
+            .unwrap();
+
+        let connections = ConnectionManager::new();
+        RalphManager::enforce_denied_paths(session.id, db_repo.id, &repo_path, &db, &connections)
+            .await;
+
+        let messages = db.list_messages(session.id).unwrap();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].role, MessageRole::System);
+        assert!(messages[0].content.contains(".github/ci.yml"));
+        assert!(!messages[0].content.contains("main.rs"));
+
+        assert!(!temp_dir.path().join(".github/ci.yml").exists());
+        assert!(temp_dir.path().join("main.rs").exists());
+    }
+
+    #[tokio::test]
+    async fn test_enforce_denied_paths_checks_commits() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let repo = git2::Repository::init(temp_dir.path()).unwrap();
+        let sig = git2::Signature::now("Test User", "test@example.com").unwrap();
+        let tree = repo.find_tree(repo.index().unwrap().write_tree().unwrap()).unwrap();
+        let first = repo
+            .commit(Some("HEAD"), &sig, &sig, "Initial commit", &tree, &[])
+            .unwrap();
+
+        let db = Database::in_memory().unwrap();
+        let repo_path = temp_dir.path().to_string_lossy().to_string();
+        let db_repo = db.insert_repo(&repo_path, "repo").unwrap();
+        let session = db
+            .insert_session(db_repo.id, None, crate::db::models::Orchestrator::Ralph)
+            .unwrap();
+        db.update_session_checkpoint(session.id, &first.to_string()).unwrap();
+        db.set_repo_config(db_repo.id, DENIED_PATHS_KEY, r#"["secrets/**"]"#)
+            .unwrap();
+
+        // The agent commits a denied file, leaving the working tree clean
+        std::fs::create_dir_all(temp_dir.path().join("secrets")).unwrap();
+        std::fs::write(temp_dir.path().join("secrets/key.pem"), "x").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(std::path::Path::new("secrets/key.pem")).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let parent = repo.find_commit(first).unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "Add key", &tree, &[&parent])
+            .unwrap();
+
+        let connections = ConnectionManager::new();
+        RalphManager::enforce_denied_paths(session.id, db_repo.id, &repo_path, &db, &connections)
+            .await;
+
+        let messages = db.list_messages(session.id).unwrap();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(
+            messages[0].content,
+            "Agent committed changes to denied paths: secrets/key.pem"
+        );
+    }
+
+    #[test]
+    fn test_repo_changed_since_checkpoint() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let repo = git2::Repository::init(temp_dir.path()).unwrap();
+        let sig = git2::Signature::now("Test User", "test@example.com").unwrap();
+        let tree = repo.find_tree(repo.index().unwrap().write_tree().unwrap()).unwrap();
+        let first = repo
+            .commit(Some("HEAD"), &sig, &sig, "Initial commit", &tree, &[])
+            .unwrap();
+
+        let db = Database::in_memory().unwrap();
+        let repo_path = temp_dir.path().to_string_lossy().to_string();
+        let db_repo = db.insert_repo(&repo_path, "repo").unwrap();
+        let session = db
+            .insert_session(db_repo.id, None, crate::db::models::Orchestrator::Ralph)
+            .unwrap();
+        db.update_session_checkpoint(session.id, &first.to_string()).unwrap();
+
+        let clean = Vec::new();
+        assert_eq!(RalphManager::repo_changed(session.id, &repo_path, &db, &clean), Some(false));
+
+        // Uncommitted work counts as a change
+        std::fs::write(temp_dir.path().join("main.rs"), "x").unwrap();
+        assert_eq!(RalphManager::repo_changed(session.id, &repo_path, &db, &clean), Some(true));
+
+        // Unless it was already there when the run started
+        let dirty = GitManager::worktree_snapshot(temp_dir.path()).unwrap();
+        assert_eq!(RalphManager::repo_changed(session.id, &repo_path, &db, &dirty), Some(false));
+        std::fs::write(temp_dir.path().join("main.rs"), "y").unwrap();
+        assert_eq!(RalphManager::repo_changed(session.id, &repo_path, &db, &dirty), Some(true));
+        std::fs::remove_file(temp_dir.path().join("main.rs")).unwrap();
+
+        // So does a commit made during the run
+        let parent = repo.find_commit(first).unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "Agent commit", &tree, &[&parent])
+            .unwrap();
+        assert_eq!(RalphManager::repo_changed(session.id, &repo_path, &db, &clean), Some(true));
+
+        // Outside a git repo the answer is unknown
+        let plain_dir = tempfile::TempDir::new().unwrap();
+        let plain_path = plain_dir.path().to_string_lossy().to_string();
+        assert_eq!(RalphManager::repo_changed(session.id, &plain_path, &db, &clean), None);
+    }
 }
@@ -0,0 +1,94 @@
+//! Request ids for correlating responses, errors and logs
+//!
+//! Every request gets an id: the caller's `X-Request-Id` if it sent a usable
+//! one, otherwise a fresh UUID. The id is echoed in the response header, stored
+//! as a `RequestId` extension, attached to the request's tracing span and
+//! included in internal error bodies, so a failed response can be matched to
+//! the server logs.
+
+use axum::{
+    extract::Request,
+    http::HeaderValue,
+    middleware::Next,
+    response::Response,
+};
+use tracing::Instrument;
+use uuid::Uuid;
+
+/// Header carrying the request id, in both directions
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Longest caller-supplied id that is propagated rather than replaced
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+/// The id of the request being handled, available as a request extension
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestId(pub String);
+
+tokio::task_local! {
+    static CURRENT_REQUEST_ID: String;
+}
+
+/// Id of the request being handled on this task, if any
+pub fn current() -> Option<String> {
+    CURRENT_REQUEST_ID.try_with(Clone::clone).ok()
+}
+
+/// Whether a caller-supplied id is safe to echo back and log
+fn is_valid(id: &str) -> bool {
+    !id.is_empty() && id.len() <= MAX_REQUEST_ID_LEN && id.chars().all(|c| c.is_ascii_graphic())
+}
+
+/// Assign the request an id and handle it within that id's scope
+pub async fn middleware(mut req: Request, next: Next) -> Response {
+    let id = req
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .filter(|id| is_valid(id))
+        .map(str::to_string)
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+
+    req.extensions_mut().insert(RequestId(id.clone()));
+    let span = tracing::info_span!(
+        "request",
+        request_id = %id,
+        method = %req.method(),
+        path = %req.uri().path(),
+    );
+
+    let mut response = CURRENT_REQUEST_ID
+        .scope(id.clone(), next.run(req))
+        .instrument(span)
+        .await;
+
+    if let Ok(value) = HeaderValue::from_str(&id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_valid() {
+        assert!(is_valid("3f2a9c1e-7b7d-4c4e-9a51-0f5e2d7c8b10"));
+        assert!(is_valid("req_123"));
+        assert!(!is_valid(""));
+        assert!(!is_valid("has space"));
+        assert!(!is_valid("line\nbreak"));
+        assert!(!is_valid(&"x".repeat(MAX_REQUEST_ID_LEN + 1)));
+    }
+
+    #[tokio::test]
+    async fn test_current_is_scoped_to_the_request() {
+        assert_eq!(current(), None);
+
+        let inside = CURRENT_REQUEST_ID
+            .scope("abc".to_string(), async { current() })
+            .await;
+        assert_eq!(inside.as_deref(), Some("abc"));
+    }
+}
@@ -1,17 +1,59 @@
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
 use tokio::sync::{broadcast, RwLock};
 use uuid::Uuid;
 
 use super::messages::ServerMessage;
+use crate::db::models::Event;
 
 /// Capacity of the broadcast channel per session
 const CHANNEL_CAPACITY: usize = 256;
 
+/// Capacity of the server-wide lifecycle event channel
+const EVENT_CHANNEL_CAPACITY: usize = 1024;
+
+/// Per-connection keepalive state
+#[derive(Default)]
+struct PingState {
+    /// Next nonce to send
+    next_nonce: u64,
+    /// Outstanding ping: nonce and send time
+    pending: Option<(u64, Instant)>,
+    /// Round-trip time of the last answered ping
+    latency: Option<Duration>,
+}
+
+/// Snapshot of a connection's state for diagnostics
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectionStats {
+    pub connection_id: Uuid,
+    pub subscriptions: usize,
+    /// Last measured round-trip time in milliseconds
+    pub latency_ms: Option<f64>,
+}
+
+/// Aggregate connection counts for metrics
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectionManagerStats {
+    /// Open WebSocket connections
+    pub connections: usize,
+    /// Subscriptions across all connections
+    pub subscriptions: usize,
+    /// Sessions with an output channel (subscribed or written to)
+    pub session_channels: usize,
+    /// Number of subscribed connections per session
+    pub subscriptions_per_session: HashMap<Uuid, usize>,
+}
+
 /// Manages WebSocket connections and session subscriptions
 #[derive(Clone)]
 pub struct ConnectionManager {
     inner: Arc<RwLock<ConnectionManagerInner>>,
+    /// Server-wide lifecycle events (see `crate::events`)
+    events: broadcast::Sender<Event>,
 }
 
 struct ConnectionManagerInner {
@@ -19,6 +61,8 @@ struct ConnectionManagerInner {
     session_channels: HashMap<Uuid, broadcast::Sender<ServerMessage>>,
     /// Map of connection_id -> set of subscribed session_ids
     connection_subscriptions: HashMap<Uuid, HashSet<Uuid>>,
+    /// Map of connection_id -> keepalive ping state
+    connection_pings: HashMap<Uuid, PingState>,
 }
 
 impl ConnectionManager {
@@ -27,7 +71,9 @@ impl ConnectionManager {
             inner: Arc::new(RwLock::new(ConnectionManagerInner {
                 session_channels: HashMap::new(),
                 connection_subscriptions: HashMap::new(),
+                connection_pings: HashMap::new(),
             })),
+            events: broadcast::channel(EVENT_CHANNEL_CAPACITY).0,
         }
     }
 
@@ -37,11 +83,15 @@ impl ConnectionManager {
         inner
             .connection_subscriptions
             .insert(connection_id, HashSet::new());
+        inner
+            .connection_pings
+            .insert(connection_id, PingState::default());
     }
 
     /// Unregister a connection and clean up its subscriptions
     pub async fn unregister_connection(&self, connection_id: Uuid) {
         let mut inner = self.inner.write().await;
+        inner.connection_pings.remove(&connection_id);
         if let Some(subscriptions) = inner.connection_subscriptions.remove(&connection_id) {
             // Clean up empty channels
             for session_id in subscriptions {
@@ -108,6 +158,86 @@ impl ConnectionManager {
             .clone()
     }
 
+    /// Record that a keepalive ping is being sent, returning its nonce.
+    ///
+    /// Only the latest ping is tracked; an unanswered earlier ping is dropped.
+    pub async fn start_ping(&self, connection_id: Uuid) -> Option<u64> {
+        let mut inner = self.inner.write().await;
+        let state = inner.connection_pings.get_mut(&connection_id)?;
+        let nonce = state.next_nonce;
+        state.next_nonce = state.next_nonce.wrapping_add(1);
+        state.pending = Some((nonce, Instant::now()));
+        Some(nonce)
+    }
+
+    /// Match a pong against the outstanding ping and record the round-trip time
+    pub async fn complete_ping(&self, connection_id: Uuid, nonce: u64) -> Option<Duration> {
+        let mut inner = self.inner.write().await;
+        let state = inner.connection_pings.get_mut(&connection_id)?;
+        match state.pending {
+            Some((pending, sent_at)) if pending == nonce => {
+                let latency = sent_at.elapsed();
+                state.pending = None;
+                state.latency = Some(latency);
+                Some(latency)
+            }
+            _ => None,
+        }
+    }
+
+    /// Snapshot of all registered connections
+    pub async fn connection_stats(&self) -> Vec<ConnectionStats> {
+        let inner = self.inner.read().await;
+        let mut stats: Vec<ConnectionStats> = inner
+            .connection_subscriptions
+            .iter()
+            .map(|(id, subs)| ConnectionStats {
+                connection_id: *id,
+                subscriptions: subs.len(),
+                latency_ms: inner
+                    .connection_pings
+                    .get(id)
+                    .and_then(|p| p.latency)
+                    .map(|d| d.as_secs_f64() * 1000.0),
+            })
+            .collect();
+        stats.sort_by_key(|s| s.connection_id);
+        stats
+    }
+
+    /// Number of connections subscribed to each session with at least one
+    pub async fn subscriber_counts(&self) -> HashMap<Uuid, usize> {
+        let inner = self.inner.read().await;
+        let mut counts = HashMap::new();
+        for session_id in inner.connection_subscriptions.values().flatten() {
+            *counts.entry(*session_id).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// Aggregate counts of connections, subscriptions and channels
+    pub async fn stats(&self) -> ConnectionManagerStats {
+        let subscriptions_per_session = self.subscriber_counts().await;
+        let inner = self.inner.read().await;
+        ConnectionManagerStats {
+            connections: inner.connection_subscriptions.len(),
+            subscriptions: subscriptions_per_session.values().sum(),
+            session_channels: inner.session_channels.len(),
+            subscriptions_per_session,
+        }
+    }
+
+    /// Publish a lifecycle event to all event stream subscribers
+    pub fn publish_event(&self, event: Event) {
+        // Ignore send errors (no receivers)
+        let _ = self.events.send(event);
+    }
+
+    /// Subscribe to server-wide lifecycle events
+    pub fn subscribe_events(&self) -> broadcast::Receiver<Event> {
+        self.events.subscribe()
+    }
+
     /// Check if a session has any subscribers
     pub async fn has_subscribers(&self, session_id: Uuid) -> bool {
         let inner = self.inner.read().await;
@@ -129,6 +259,25 @@ mod tests {
     use super::*;
     use crate::ws::messages::OutputStream;
 
+    #[tokio::test]
+    async fn test_stats() {
+        let manager = ConnectionManager::new();
+        let (first, second) = (Uuid::new_v4(), Uuid::new_v4());
+        let session_id = Uuid::new_v4();
+
+        manager.register_connection(first).await;
+        manager.register_connection(second).await;
+        let _rx1 = manager.subscribe(first, session_id).await;
+        let _rx2 = manager.subscribe(second, session_id).await;
+        let _rx3 = manager.subscribe(second, Uuid::new_v4()).await;
+
+        let stats = manager.stats().await;
+        assert_eq!(stats.connections, 2);
+        assert_eq!(stats.subscriptions, 3);
+        assert_eq!(stats.session_channels, 2);
+        assert_eq!(stats.subscriptions_per_session[&session_id], 2);
+    }
+
     #[tokio::test]
     async fn test_subscribe_and_broadcast() {
         let manager = ConnectionManager::new();
@@ -142,6 +291,8 @@ mod tests {
             session_id,
             stream: OutputStream::Stdout,
             content: "Hello".to_string(),
+            log_id: None,
+            truncated: false,
         };
 
         manager.broadcast(session_id, msg.clone()).await;
@@ -186,6 +337,8 @@ mod tests {
             session_id,
             stream: OutputStream::Stdout,
             content: "Hello both".to_string(),
+            log_id: None,
+            truncated: false,
         };
 
         manager.broadcast(session_id, msg).await;
@@ -203,6 +356,30 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_subscriber_counts() {
+        let manager = ConnectionManager::new();
+        let conn1 = Uuid::new_v4();
+        let conn2 = Uuid::new_v4();
+        let watched = Uuid::new_v4();
+        let other = Uuid::new_v4();
+
+        manager.register_connection(conn1).await;
+        manager.register_connection(conn2).await;
+        let _r1 = manager.subscribe(conn1, watched).await;
+        let _r2 = manager.subscribe(conn2, watched).await;
+        let _r3 = manager.subscribe(conn2, other).await;
+
+        let counts = manager.subscriber_counts().await;
+        assert_eq!(counts[&watched], 2);
+        assert_eq!(counts[&other], 1);
+
+        manager.unregister_connection(conn2).await;
+        let counts = manager.subscriber_counts().await;
+        assert_eq!(counts[&watched], 1);
+        assert!(!counts.contains_key(&other));
+    }
+
     #[tokio::test]
     async fn test_connection_cleanup() {
         let manager = ConnectionManager::new();
@@ -222,4 +399,29 @@ mod tests {
         // Channel should be cleaned up since no receivers
         assert!(!manager.has_subscribers(session_id).await);
     }
+
+    #[tokio::test]
+    async fn test_ping_latency_tracking() {
+        let manager = ConnectionManager::new();
+        let connection_id = Uuid::new_v4();
+
+        assert!(manager.start_ping(connection_id).await.is_none());
+
+        manager.register_connection(connection_id).await;
+        let first = manager.start_ping(connection_id).await.unwrap();
+        let second = manager.start_ping(connection_id).await.unwrap();
+        assert_ne!(first, second);
+
+        // Only the latest ping is outstanding
+        assert!(manager.complete_ping(connection_id, first).await.is_none());
+        assert!(manager.complete_ping(connection_id, second).await.is_some());
+        assert!(manager.complete_ping(connection_id, second).await.is_none());
+
+        let stats = manager.connection_stats().await;
+        assert_eq!(stats.len(), 1);
+        assert!(stats[0].latency_ms.is_some());
+
+        manager.unregister_connection(connection_id).await;
+        assert!(manager.connection_stats().await.is_empty());
+    }
 }
@@ -11,6 +11,19 @@ pub enum ClientMessage {
     Unsubscribe { session_id: Uuid },
     /// Cancel a running session
     Cancel { session_id: Uuid },
+    /// Stop forwarding output for a subscribed session without unsubscribing
+    PauseOutput { session_id: Uuid },
+    /// Resume forwarding output, first catching up on lines missed while paused
+    ResumeOutput { session_id: Uuid },
+    /// Write text to a running session's agent stdin (e.g. to answer a prompt)
+    Input { session_id: Uuid, content: String },
+    /// Only forward the listed server message types (e.g. `["status"]`) for a
+    /// subscribed session; omit `types` to forward everything again
+    SetFilter {
+        session_id: Uuid,
+        #[serde(default)]
+        types: Option<Vec<String>>,
+    },
     /// Ping to keep connection alive
     Ping,
 }
@@ -28,18 +41,77 @@ pub enum ServerMessage {
         session_id: Uuid,
         stream: OutputStream,
         content: String,
+        /// Persisted output log id, used to de-duplicate replayed history
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        log_id: Option<i64>,
+        /// Set when the line was cut to the configured maximum length
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        truncated: bool,
     },
     /// Session status changed
     Status {
         session_id: Uuid,
         status: SessionStatus,
     },
+    /// All persisted output has been replayed after a subscribe; live output follows
+    ReplayComplete { session_id: Uuid },
+    /// Acknowledgment that output forwarding is paused
+    OutputPaused { session_id: Uuid },
+    /// Acknowledgment that output forwarding resumed; missed lines follow,
+    /// terminated by `ReplayComplete`
+    OutputResumed { session_id: Uuid },
+    /// Acknowledgment of a subscription's message filter (`None` = everything)
+    FilterSet {
+        session_id: Uuid,
+        types: Option<Vec<String>>,
+    },
+    /// The subscriber fell behind and `skipped` live messages were dropped;
+    /// forwarding continues with the oldest message still buffered
+    Lagged { session_id: Uuid, skipped: u64 },
     /// Error message
     Error { message: String },
+    /// Non-fatal warning about a session (e.g. a policy violation)
+    Warning { session_id: Uuid, message: String },
     /// Pong response to ping
     Pong,
 }
 
+impl ServerMessage {
+    /// Every message type name, as sent in the `type` field
+    pub const TYPES: &'static [&'static str] = &[
+        "subscribed",
+        "unsubscribed",
+        "output",
+        "status",
+        "replay_complete",
+        "output_paused",
+        "output_resumed",
+        "filter_set",
+        "lagged",
+        "error",
+        "warning",
+        "pong",
+    ];
+
+    /// This message's type name, as sent in the `type` field
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            ServerMessage::Subscribed { .. } => "subscribed",
+            ServerMessage::Unsubscribed { .. } => "unsubscribed",
+            ServerMessage::Output { .. } => "output",
+            ServerMessage::Status { .. } => "status",
+            ServerMessage::ReplayComplete { .. } => "replay_complete",
+            ServerMessage::OutputPaused { .. } => "output_paused",
+            ServerMessage::OutputResumed { .. } => "output_resumed",
+            ServerMessage::FilterSet { .. } => "filter_set",
+            ServerMessage::Lagged { .. } => "lagged",
+            ServerMessage::Error { .. } => "error",
+            ServerMessage::Warning { .. } => "warning",
+            ServerMessage::Pong => "pong",
+        }
+    }
+}
+
 /// Output stream type
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
@@ -57,6 +129,8 @@ pub enum SessionStatus {
     Completed,
     Error,
     Cancelled,
+    /// The agent crashed and is being restarted (never persisted)
+    Restarting,
 }
 
 impl From<crate::db::models::SessionStatus> for SessionStatus {
@@ -100,6 +174,8 @@ mod tests {
             session_id: Uuid::nil(),
             stream: OutputStream::Stdout,
             content: "Hello".to_string(),
+            log_id: None,
+            truncated: false,
         };
         let json = serde_json::to_string(&msg).unwrap();
         assert!(json.contains("\"type\":\"output\""));
@@ -112,4 +188,36 @@ mod tests {
         let msg: ClientMessage = serde_json::from_str(json).unwrap();
         matches!(msg, ClientMessage::Subscribe { .. });
     }
+
+    #[test]
+    fn test_type_name_matches_serialized_tag() {
+        let session_id = Uuid::nil();
+        for msg in [
+            ServerMessage::Subscribed { session_id },
+            ServerMessage::ReplayComplete { session_id },
+            ServerMessage::FilterSet {
+                session_id,
+                types: None,
+            },
+            ServerMessage::Status {
+                session_id,
+                status: SessionStatus::Running,
+            },
+            ServerMessage::Pong,
+        ] {
+            let json = serde_json::to_value(&msg).unwrap();
+            assert_eq!(json["type"], msg.type_name());
+            assert!(ServerMessage::TYPES.contains(&msg.type_name()));
+        }
+    }
+
+    #[test]
+    fn test_input_message_deserialize() {
+        let json = r#"{"type":"input","session_id":"00000000-0000-0000-0000-000000000000","content":"y"}"#;
+        let msg: ClientMessage = serde_json::from_str(json).unwrap();
+        match msg {
+            ClientMessage::Input { content, .. } => assert_eq!(content, "y"),
+            other => panic!("Unexpected message: {:?}", other),
+        }
+    }
 }
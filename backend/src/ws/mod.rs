@@ -1,36 +1,346 @@
 pub mod connections;
 pub mod messages;
 
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+
 use axum::{
     extract::{
         ws::{Message, WebSocket},
-        State, WebSocketUpgrade,
+        Query, State, WebSocketUpgrade,
     },
-    response::IntoResponse,
+    http::{header::AUTHORIZATION, HeaderMap},
+    response::Response,
     routing::get,
-    Router,
+    Json, Router,
 };
 use futures::stream::StreamExt;
 use futures::SinkExt;
+use serde::Deserialize;
+use tokio::sync::{broadcast, mpsc, watch, Notify};
 use uuid::Uuid;
 
-pub use connections::ConnectionManager;
+pub use connections::{ConnectionManager, ConnectionManagerStats, ConnectionStats};
 pub use messages::{ClientMessage, OutputStream, ServerMessage, SessionStatus};
 
-use crate::api::AppState;
+use crate::api::{constant_time_eq, AppState};
+use crate::error::{AppError, AppResult};
+use crate::ralph::RalphError;
 
 /// Create the WebSocket router
 pub fn router() -> Router<AppState> {
-    Router::new().route("/ws", get(ws_handler))
+    Router::new()
+        .route("/ws", get(ws_handler))
+        .route("/ws/connections", get(list_connections))
+}
+
+/// Interval between server-initiated keepalive pings
+const PING_INTERVAL: Duration = Duration::from_secs(30);
+
+/// List open WebSocket connections with their measured latency
+async fn list_connections(State(state): State<AppState>) -> Json<Vec<ConnectionStats>> {
+    Json(state.connections.connection_stats().await)
+}
+
+/// Config key for the maximum accepted size (in bytes) of an incoming message
+pub const WS_MAX_MESSAGE_SIZE_KEY: &str = "ws_max_message_size";
+
+/// Default maximum incoming message size (1 MiB)
+const DEFAULT_WS_MAX_MESSAGE_SIZE: usize = 1024 * 1024;
+
+/// Parse an incoming message size limit in bytes; it must be positive
+pub fn parse_max_message_size(value: &str) -> Result<usize, String> {
+    match value.trim().parse::<usize>() {
+        Ok(size) if size > 0 => Ok(size),
+        _ => Err(format!("expected a positive integer, got '{}'", value)),
+    }
+}
+
+/// Read the configured incoming message size limit, falling back to the default
+fn max_message_size(state: &AppState) -> usize {
+    match state.config.get(WS_MAX_MESSAGE_SIZE_KEY) {
+        Ok(Some(value)) => parse_max_message_size(&value).unwrap_or_else(|_| {
+            tracing::warn!("Invalid {} value '{}', using default", WS_MAX_MESSAGE_SIZE_KEY, value);
+            DEFAULT_WS_MAX_MESSAGE_SIZE
+        }),
+        Ok(None) => DEFAULT_WS_MAX_MESSAGE_SIZE,
+        Err(e) => {
+            tracing::warn!("Failed to read {}: {}", WS_MAX_MESSAGE_SIZE_KEY, e);
+            DEFAULT_WS_MAX_MESSAGE_SIZE
+        }
+    }
+}
+
+/// Config key for how long (in seconds) a connection may stay silent before it
+/// is probed with a ping and, if still silent after as long again, closed
+pub const WS_HEARTBEAT_TIMEOUT_KEY: &str = "ws_heartbeat_timeout_secs";
+
+/// Default heartbeat timeout; well above `PING_INTERVAL`, so a client answering
+/// the regular keepalive pings never hits it
+const DEFAULT_WS_HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// Parse a heartbeat timeout in whole seconds; it must be positive
+fn parse_heartbeat_timeout(value: &str) -> Option<Duration> {
+    match value.trim().parse::<u64>() {
+        Ok(secs) if secs > 0 => Some(Duration::from_secs(secs)),
+        _ => None,
+    }
+}
+
+/// Read the configured heartbeat timeout, falling back to the default
+fn heartbeat_timeout(state: &AppState) -> Duration {
+    match state.config.get(WS_HEARTBEAT_TIMEOUT_KEY) {
+        Ok(Some(value)) => parse_heartbeat_timeout(&value).unwrap_or_else(|| {
+            tracing::warn!("Invalid {} value '{}', using default", WS_HEARTBEAT_TIMEOUT_KEY, value);
+            DEFAULT_WS_HEARTBEAT_TIMEOUT
+        }),
+        Ok(None) => DEFAULT_WS_HEARTBEAT_TIMEOUT,
+        Err(e) => {
+            tracing::warn!("Failed to read {}: {}", WS_HEARTBEAT_TIMEOUT_KEY, e);
+            DEFAULT_WS_HEARTBEAT_TIMEOUT
+        }
+    }
+}
+
+/// Config key: when "true", upgrades must present the `ws_auth_token` secret.
+/// Protected: redacted on reads and writable only with the admin token.
+pub const WS_AUTH_REQUIRED_KEY: &str = "ws_auth_required";
+
+/// Config key for the shared secret WebSocket clients authenticate with.
+/// Protected like `ws_auth_required`.
+pub const WS_AUTH_TOKEN_KEY: &str = "ws_auth_token";
+
+/// Query parameters accepted on the upgrade request
+#[derive(Debug, Default, Deserialize)]
+struct WsParams {
+    /// Auth token, for clients (like browsers) that can't set headers on the upgrade
+    token: Option<String>,
+}
+
+/// Check the upgrade request's token when WebSocket auth is enabled
+///
+/// The token may come from the `token` query parameter or an
+/// `Authorization: Bearer` header. With auth enabled but no token configured,
+/// every connection is refused. The bundled UI sends the token it was opened
+/// with (`/?token=...`), so open it that way once after enabling auth.
+fn authorize(state: &AppState, headers: &HeaderMap, query_token: Option<&str>) -> AppResult<()> {
+    if !state.config.get_as::<bool>(WS_AUTH_REQUIRED_KEY)?.unwrap_or(false) {
+        return Ok(());
+    }
+
+    let expected = state
+        .config
+        .get(WS_AUTH_TOKEN_KEY)?
+        .filter(|token| !token.is_empty())
+        .ok_or_else(|| AppError::Unauthorized("WebSocket token is not configured".to_string()))?;
+
+    let header_token = headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    let provided = query_token
+        .or(header_token)
+        .ok_or_else(|| AppError::Unauthorized("Missing WebSocket token".to_string()))?;
+
+    if !constant_time_eq(provided.as_bytes(), expected.as_bytes()) {
+        return Err(AppError::Unauthorized("Invalid WebSocket token".to_string()));
+    }
+
+    Ok(())
 }
 
 /// WebSocket upgrade handler
-async fn ws_handler(ws: WebSocketUpgrade, State(state): State<AppState>) -> impl IntoResponse {
-    ws.on_upgrade(move |socket| handle_socket(socket, state))
+async fn ws_handler(
+    ws: WebSocketUpgrade,
+    State(state): State<AppState>,
+    Query(params): Query<WsParams>,
+    headers: HeaderMap,
+) -> AppResult<Response> {
+    authorize(&state, &headers, params.token.as_deref())?;
+
+    let max_size = max_message_size(&state);
+
+    // Messages over the configured limit are answered with an error by
+    // handle_socket; the protocol layer drops the connection only for frames
+    // far beyond it, so memory use stays bounded either way.
+    let hard_limit = max_size.saturating_mul(2);
+    Ok(ws
+        .max_frame_size(hard_limit)
+        .max_message_size(hard_limit)
+        .on_upgrade(move |socket| handle_socket(socket, state, max_size)))
+}
+
+/// Output log entries loaded from the database per replay query
+const REPLAY_BATCH_SIZE: i64 = 500;
+
+/// Most output lines one replay sends; older history is left to the logs API
+const MAX_REPLAY_LINES: i64 = 10_000;
+
+/// Send a session's persisted output after `after` (or all of it) followed by a
+/// `ReplayComplete` marker.
+///
+/// Logs are read in batches off the async runtime, and at most the newest
+/// `MAX_REPLAY_LINES` are sent. Returns the id of the last replayed log entry,
+/// or `after` if none were sent.
+async fn replay_output(
+    state: &AppState,
+    session_id: Uuid,
+    after: Option<i64>,
+    tx: &mpsc::Sender<ServerMessage>,
+) -> Option<i64> {
+    let db = state.db.clone();
+    let tail_start =
+        tokio::task::spawn_blocking(move || db.output_log_tail_start(session_id, MAX_REPLAY_LINES))
+            .await
+            .map_err(|e| e.to_string())
+            .and_then(|r| r.map_err(|e| e.to_string()));
+    let mut cursor = match tail_start {
+        Ok(start) => start.max(after.unwrap_or(0)),
+        Err(e) => {
+            tracing::warn!("Failed to load output history for session {}: {}", session_id, e);
+            after.unwrap_or(0)
+        }
+    };
+
+    let mut last = after;
+    loop {
+        let db = state.db.clone();
+        let batch = tokio::task::spawn_blocking(move || {
+            db.list_output_logs_batch(session_id, None, cursor, REPLAY_BATCH_SIZE)
+        })
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|r| r.map_err(|e| e.to_string()));
+        let logs = match batch {
+            Ok(logs) => logs,
+            Err(e) => {
+                tracing::warn!("Failed to load output history for session {}: {}", session_id, e);
+                break;
+            }
+        };
+
+        let done = (logs.len() as i64) < REPLAY_BATCH_SIZE;
+        for log in logs {
+            cursor = log.id;
+            last = Some(log.id);
+            let msg = ServerMessage::Output {
+                session_id,
+                stream: log.stream.into(),
+                content: log.content,
+                log_id: Some(log.id),
+                truncated: log.truncated,
+            };
+            if tx.send(msg).await.is_err() {
+                return last;
+            }
+        }
+        if done {
+            break;
+        }
+    }
+
+    let _ = tx.send(ServerMessage::ReplayComplete { session_id }).await;
+    last
+}
+
+/// Server message types a client wants from one subscription; `None` forwards all
+type MessageFilter = Option<HashSet<String>>;
+
+fn filter_allows(filter: &MessageFilter, type_name: &str) -> bool {
+    filter.as_ref().is_none_or(|types| types.contains(type_name))
+}
+
+/// Switches controlling one subscription's forwarder; dropping them ends it
+struct SubscriptionControls {
+    paused: watch::Sender<bool>,
+    filter: watch::Sender<MessageFilter>,
+}
+
+/// Forward one subscription's live messages to the connection.
+///
+/// Output lines already delivered (by id) are skipped. While `paused` is set,
+/// output is dropped; on resume the missed lines are replayed from the
+/// persisted logs before live forwarding continues. Message types excluded by
+/// `filter` are dropped without catch-up. A subscriber that falls behind the
+/// live channel is sent a `Lagged` notice and keeps receiving. Ends when the
+/// live channel closes or the controls are dropped (unsubscribe).
+async fn forward_subscription(
+    state: AppState,
+    session_id: Uuid,
+    mut rx: broadcast::Receiver<ServerMessage>,
+    mut paused: watch::Receiver<bool>,
+    filter: watch::Receiver<MessageFilter>,
+    tx: mpsc::Sender<ServerMessage>,
+    mut last_delivered: Option<i64>,
+) {
+    loop {
+        tokio::select! {
+            msg = rx.recv() => {
+                let msg = match msg {
+                    Ok(msg) => msg,
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::warn!(
+                            "Subscriber to session {} lagged; skipped {} messages",
+                            session_id,
+                            skipped
+                        );
+                        ServerMessage::Lagged { session_id, skipped }
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
+                if let ServerMessage::Output { log_id, .. } = &msg {
+                    if *paused.borrow() {
+                        continue;
+                    }
+                    if let Some(id) = *log_id {
+                        if last_delivered.is_some_and(|last| id <= last) {
+                            continue;
+                        }
+                        last_delivered = Some(id);
+                    }
+                }
+                if !filter_allows(&filter.borrow(), msg.type_name()) {
+                    continue;
+                }
+                if tx.send(msg).await.is_err() {
+                    break;
+                }
+            }
+            changed = paused.changed() => {
+                if changed.is_err() {
+                    break;
+                }
+                let is_paused = *paused.borrow_and_update();
+                let wants_output = filter_allows(&filter.borrow(), "output");
+                if !is_paused && wants_output {
+                    last_delivered = replay_output(&state, session_id, last_delivered, &tx).await;
+                }
+            }
+        }
+    }
+}
+
+/// Parse a client's requested filter, rejecting unknown message types
+fn parse_filter(types: Option<Vec<String>>) -> Result<MessageFilter, String> {
+    let Some(types) = types else {
+        return Ok(None);
+    };
+    if let Some(unknown) = types
+        .iter()
+        .find(|t| !ServerMessage::TYPES.contains(&t.as_str()))
+    {
+        return Err(format!(
+            "Unknown message type '{}'; expected one of: {}",
+            unknown,
+            ServerMessage::TYPES.join(", ")
+        ));
+    }
+    Ok(Some(types.into_iter().collect()))
 }
 
 /// Handle an individual WebSocket connection
-async fn handle_socket(socket: WebSocket, state: AppState) {
+async fn handle_socket(socket: WebSocket, state: AppState, max_size: usize) {
     let connection_id = Uuid::new_v4();
     tracing::info!("WebSocket connection established: {}", connection_id);
 
@@ -39,27 +349,77 @@ async fn handle_socket(socket: WebSocket, state: AppState) {
     let (mut sender, mut receiver) = socket.split();
 
     // Use a channel to send messages from multiple sources to the WebSocket
-    let (tx, mut ws_rx) = tokio::sync::mpsc::channel::<ServerMessage>(256);
+    let (tx, mut ws_rx) = mpsc::channel::<ServerMessage>(256);
+
+    // Wakes the sender task to probe a silent connection with an immediate ping
+    let ping_now = Arc::new(Notify::new());
 
-    // Task to forward from mpsc channel to WebSocket
+    // Task to forward from mpsc channel to WebSocket, interleaving keepalive
+    // pings whose nonce is echoed back in the client's pong frame
+    let connections = state.connections.clone();
+    let probe = ping_now.clone();
     let sender_task = tokio::spawn(async move {
-        while let Some(msg) = ws_rx.recv().await {
-            let json = match serde_json::to_string(&msg) {
-                Ok(j) => j,
-                Err(e) => {
-                    tracing::error!("Failed to serialize message: {}", e);
-                    continue;
+        let mut ping_interval =
+            tokio::time::interval_at(tokio::time::Instant::now() + PING_INTERVAL, PING_INTERVAL);
+
+        loop {
+            let outgoing = tokio::select! {
+                msg = ws_rx.recv() => {
+                    let Some(msg) = msg else { break };
+                    match serde_json::to_string(&msg) {
+                        Ok(json) => Message::Text(json.into()),
+                        Err(e) => {
+                            tracing::error!("Failed to serialize message: {}", e);
+                            continue;
+                        }
+                    }
+                }
+                _ = async {
+                    tokio::select! {
+                        _ = ping_interval.tick() => {}
+                        _ = probe.notified() => {}
+                    }
+                } => {
+                    let Some(nonce) = connections.start_ping(connection_id).await else {
+                        break;
+                    };
+                    Message::Ping(nonce.to_be_bytes().to_vec().into())
                 }
             };
 
-            if sender.send(Message::Text(json.into())).await.is_err() {
+            if sender.send(outgoing).await.is_err() {
                 break;
             }
         }
     });
 
-    // Handle incoming messages
-    while let Some(msg) = receiver.next().await {
+    // Controls of each subscription's forwarder, keyed by session
+    let mut subscriptions: HashMap<Uuid, SubscriptionControls> = HashMap::new();
+
+    // Handle incoming messages. A connection silent for the heartbeat timeout
+    // is probed with a ping; if it stays silent as long again it is presumed
+    // dead (e.g. half-open TCP) and closed.
+    let heartbeat = heartbeat_timeout(&state);
+    let mut probed = false;
+    loop {
+        let msg = match tokio::time::timeout(heartbeat, receiver.next()).await {
+            Ok(Some(msg)) => msg,
+            Ok(None) => break,
+            Err(_) if !probed => {
+                probed = true;
+                ping_now.notify_one();
+                continue;
+            }
+            Err(_) => {
+                tracing::warn!(
+                    "Connection {} missed its heartbeat deadline; closing",
+                    connection_id
+                );
+                break;
+            }
+        };
+        probed = false;
+
         let msg = match msg {
             Ok(m) => m,
             Err(e) => {
@@ -69,6 +429,23 @@ async fn handle_socket(socket: WebSocket, state: AppState) {
         };
 
         match msg {
+            Message::Text(text) if text.len() > max_size => {
+                tracing::warn!(
+                    "Connection {} sent an oversized message ({} bytes)",
+                    connection_id,
+                    text.len()
+                );
+                let _ = tx
+                    .send(ServerMessage::Error {
+                        message: format!(
+                            "Message too large: {} bytes exceeds the {} byte limit",
+                            text.len(),
+                            max_size
+                        ),
+                    })
+                    .await;
+            }
+
             Message::Text(text) => {
                 let client_msg: ClientMessage = match serde_json::from_str(&text) {
                     Ok(m) => m,
@@ -90,20 +467,35 @@ async fn handle_socket(socket: WebSocket, state: AppState) {
                             session_id
                         );
 
-                        // Get a receiver for this session's broadcast channel
-                        let mut rx = state.connections.subscribe(connection_id, session_id).await;
-
-                        // Spawn a task to forward messages from this subscription
-                        let tx_inner = tx.clone();
-                        tokio::spawn(async move {
-                            while let Ok(msg) = rx.recv().await {
-                                if tx_inner.send(msg).await.is_err() {
-                                    break;
-                                }
-                            }
-                        });
+                        // Attach to the live channel before reading history so
+                        // nothing emitted during the replay is missed
+                        let rx = state.connections.subscribe(connection_id, session_id).await;
 
                         let _ = tx.send(ServerMessage::Subscribed { session_id }).await;
+
+                        let last_replayed = replay_output(&state, session_id, None, &tx).await;
+
+                        // Spawn a task to forward messages from this subscription,
+                        // skipping lines already delivered by the replay. Replacing
+                        // the controls ends any previous forwarder.
+                        let (pause_tx, pause_rx) = watch::channel(false);
+                        let (filter_tx, filter_rx) = watch::channel(None);
+                        subscriptions.insert(
+                            session_id,
+                            SubscriptionControls {
+                                paused: pause_tx,
+                                filter: filter_tx,
+                            },
+                        );
+                        tokio::spawn(forward_subscription(
+                            state.clone(),
+                            session_id,
+                            rx,
+                            pause_rx,
+                            filter_rx,
+                            tx.clone(),
+                            last_replayed,
+                        ));
                     }
 
                     ClientMessage::Unsubscribe { session_id } => {
@@ -117,6 +509,7 @@ async fn handle_socket(socket: WebSocket, state: AppState) {
                             .connections
                             .unsubscribe(connection_id, session_id)
                             .await;
+                        subscriptions.remove(&session_id);
 
                         let _ = tx.send(ServerMessage::Unsubscribed { session_id }).await;
                     }
@@ -135,11 +528,79 @@ async fn handle_socket(socket: WebSocket, state: AppState) {
                             .await
                         {
                             tracing::warn!("Failed to cancel session {}: {}", session_id, e);
+                            let message = match e {
+                                RalphError::NotRunning(_) => {
+                                    format!("Nothing to cancel: {}", e)
+                                }
+                                e => format!("Failed to cancel: {}", e),
+                            };
+                            let _ = tx.send(ServerMessage::Error { message }).await;
+                        }
+                    }
+
+                    ClientMessage::PauseOutput { session_id } => {
+                        match subscriptions.get(&session_id) {
+                            Some(controls) => {
+                                controls.paused.send_replace(true);
+                                let _ = tx.send(ServerMessage::OutputPaused { session_id }).await;
+                            }
+                            None => {
+                                let _ = tx
+                                    .send(ServerMessage::Error {
+                                        message: format!("Not subscribed to session {}", session_id),
+                                    })
+                                    .await;
+                            }
+                        }
+                    }
+
+                    ClientMessage::ResumeOutput { session_id } => {
+                        match subscriptions.get(&session_id) {
+                            Some(controls) => {
+                                // Acknowledge first so the catch-up follows it
+                                let _ = tx.send(ServerMessage::OutputResumed { session_id }).await;
+                                controls
+                                    .paused
+                                    .send_if_modified(|paused| std::mem::replace(paused, false));
+                            }
+                            None => {
+                                let _ = tx
+                                    .send(ServerMessage::Error {
+                                        message: format!("Not subscribed to session {}", session_id),
+                                    })
+                                    .await;
+                            }
+                        }
+                    }
+
+                    ClientMessage::Input { session_id, content } => {
+                        if let Err(e) = state.ralph_manager.send_input(session_id, &content).await {
+                            tracing::warn!("Failed to send input to session {}: {}", session_id, e);
+                            let _ = tx
+                                .send(ServerMessage::Error {
+                                    message: format!("Failed to send input: {}", e),
+                                })
+                                .await;
+                        }
+                    }
+
+                    ClientMessage::SetFilter { session_id, types } => {
+                        let Some(controls) = subscriptions.get(&session_id) else {
                             let _ = tx
                                 .send(ServerMessage::Error {
-                                    message: format!("Failed to cancel: {}", e),
+                                    message: format!("Not subscribed to session {}", session_id),
                                 })
                                 .await;
+                            continue;
+                        };
+                        match parse_filter(types.clone()) {
+                            Ok(filter) => {
+                                controls.filter.send_replace(filter);
+                                let _ = tx.send(ServerMessage::FilterSet { session_id, types }).await;
+                            }
+                            Err(message) => {
+                                let _ = tx.send(ServerMessage::Error { message }).await;
+                            }
                         }
                     }
 
@@ -149,6 +610,19 @@ async fn handle_socket(socket: WebSocket, state: AppState) {
                 }
             }
 
+            Message::Pong(payload) => {
+                let Ok(nonce) = <[u8; 8]>::try_from(payload.as_ref()) else {
+                    continue;
+                };
+                if let Some(latency) = state
+                    .connections
+                    .complete_ping(connection_id, u64::from_be_bytes(nonce))
+                    .await
+                {
+                    tracing::debug!("Connection {} round-trip: {:?}", connection_id, latency);
+                }
+            }
+
             Message::Close(_) => {
                 tracing::info!("WebSocket connection closing: {}", connection_id);
                 break;
@@ -163,3 +637,340 @@ async fn handle_socket(socket: WebSocket, state: AppState) {
     state.connections.unregister_connection(connection_id).await;
     tracing::info!("WebSocket connection closed: {}", connection_id);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::models::{Orchestrator, OutputStream as DbOutputStream};
+    use crate::db::Database;
+
+    #[tokio::test]
+    async fn test_replay_output_sends_history_then_marker() {
+        let state = AppState::new(Database::in_memory().unwrap());
+        let repo = state.db.insert_repo("/path/to/repo", "repo").unwrap();
+        let session = state
+            .db
+            .insert_session(repo.id, None, Orchestrator::Ralph)
+            .unwrap();
+        state
+            .db
+            .insert_output_log(session.id, DbOutputStream::Stdout, "first")
+            .unwrap();
+        let second = state
+            .db
+            .insert_output_log(session.id, DbOutputStream::Stderr, "second")
+            .unwrap();
+
+        let (tx, mut rx) = mpsc::channel(16);
+        let last = replay_output(&state, session.id, None, &tx).await;
+        assert_eq!(last, Some(second.id));
+        drop(tx);
+
+        let mut received = Vec::new();
+        while let Some(msg) = rx.recv().await {
+            received.push(msg);
+        }
+        assert_eq!(received.len(), 3);
+        match (&received[0], &received[1], &received[2]) {
+            (
+                ServerMessage::Output { content: a, .. },
+                ServerMessage::Output {
+                    content: b,
+                    stream: OutputStream::Stderr,
+                    ..
+                },
+                ServerMessage::ReplayComplete { session_id },
+            ) => {
+                assert_eq!(a, "first");
+                assert_eq!(b, "second");
+                assert_eq!(*session_id, session.id);
+            }
+            other => panic!("Unexpected replay: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_replay_output_pages_through_history() {
+        let state = AppState::new(Database::in_memory().unwrap());
+        let repo = state.db.insert_repo("/path/to/repo", "repo").unwrap();
+        let session = state
+            .db
+            .insert_session(repo.id, None, Orchestrator::Ralph)
+            .unwrap();
+        let first = state
+            .db
+            .insert_output_log(session.id, DbOutputStream::Stdout, "line 0")
+            .unwrap();
+        for i in 1..=REPLAY_BATCH_SIZE + 1 {
+            state
+                .db
+                .insert_output_log(session.id, DbOutputStream::Stdout, &format!("line {}", i))
+                .unwrap();
+        }
+
+        // Resuming after the first line replays every later batch in order
+        let (tx, mut rx) = mpsc::channel(1024);
+        let last = replay_output(&state, session.id, Some(first.id), &tx).await;
+        drop(tx);
+
+        let mut contents = Vec::new();
+        while let Some(msg) = rx.recv().await {
+            if let ServerMessage::Output { content, .. } = msg {
+                contents.push(content);
+            }
+        }
+        let expected: Vec<String> = (1..=REPLAY_BATCH_SIZE + 1)
+            .map(|i| format!("line {}", i))
+            .collect();
+        assert_eq!(contents, expected);
+        assert_eq!(last, Some(first.id + REPLAY_BATCH_SIZE + 1));
+    }
+
+    #[tokio::test]
+    async fn test_paused_subscription_catches_up_on_resume() {
+        let state = AppState::new(Database::in_memory().unwrap());
+        let repo = state.db.insert_repo("/path/to/repo", "repo").unwrap();
+        let session = state
+            .db
+            .insert_session(repo.id, None, Orchestrator::Ralph)
+            .unwrap();
+
+        let (live_tx, live_rx) = broadcast::channel(16);
+        let (pause_tx, pause_rx) = watch::channel(false);
+        let (_filter_tx, filter_rx) = watch::channel(None);
+        let (tx, mut rx) = mpsc::channel(16);
+        tokio::spawn(forward_subscription(
+            state.clone(),
+            session.id,
+            live_rx,
+            pause_rx,
+            filter_rx,
+            tx,
+            None,
+        ));
+
+        let emit = |content: &str| {
+            let log = state
+                .db
+                .insert_output_log(session.id, DbOutputStream::Stdout, content)
+                .unwrap();
+            live_tx
+                .send(ServerMessage::Output {
+                    session_id: session.id,
+                    stream: OutputStream::Stdout,
+                    content: content.to_string(),
+                    log_id: Some(log.id),
+                    truncated: false,
+                })
+                .unwrap();
+        };
+        let next_content = |msg: Option<ServerMessage>| match msg {
+            Some(ServerMessage::Output { content, .. }) => content,
+            other => panic!("Expected output, got {:?}", other),
+        };
+
+        emit("live");
+        assert_eq!(next_content(rx.recv().await), "live");
+
+        pause_tx.send_replace(true);
+        tokio::task::yield_now().await;
+        emit("missed 1");
+        emit("missed 2");
+        live_tx
+            .send(ServerMessage::Status {
+                session_id: session.id,
+                status: SessionStatus::Completed,
+            })
+            .unwrap();
+
+        // Status updates still flow while output is paused
+        assert!(matches!(rx.recv().await, Some(ServerMessage::Status { .. })));
+
+        pause_tx.send_replace(false);
+        assert_eq!(next_content(rx.recv().await), "missed 1");
+        assert_eq!(next_content(rx.recv().await), "missed 2");
+        assert!(matches!(
+            rx.recv().await,
+            Some(ServerMessage::ReplayComplete { .. })
+        ));
+
+        emit("after");
+        assert_eq!(next_content(rx.recv().await), "after");
+
+        // Dropping the pause switch (unsubscribe) ends the forwarder
+        drop(pause_tx);
+        assert!(rx.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_filter_drops_excluded_message_types() {
+        let state = AppState::new(Database::in_memory().unwrap());
+        let session_id = Uuid::new_v4();
+
+        let (live_tx, live_rx) = broadcast::channel(16);
+        let (_pause_tx, pause_rx) = watch::channel(false);
+        let (filter_tx, filter_rx) = watch::channel(None);
+        let (tx, mut rx) = mpsc::channel(16);
+        tokio::spawn(forward_subscription(
+            state,
+            session_id,
+            live_rx,
+            pause_rx,
+            filter_rx,
+            tx,
+            None,
+        ));
+
+        let output = |content: &str| ServerMessage::Output {
+            session_id,
+            stream: OutputStream::Stdout,
+            content: content.to_string(),
+            log_id: None,
+            truncated: false,
+        };
+        let status = ServerMessage::Status {
+            session_id,
+            status: SessionStatus::Completed,
+        };
+
+        filter_tx.send_replace(parse_filter(Some(vec!["status".to_string()])).unwrap());
+        live_tx.send(output("hidden")).unwrap();
+        live_tx.send(status.clone()).unwrap();
+        assert!(matches!(rx.recv().await, Some(ServerMessage::Status { .. })));
+
+        // Clearing the filter forwards everything again
+        filter_tx.send_replace(parse_filter(None).unwrap());
+        live_tx.send(output("shown")).unwrap();
+        match rx.recv().await {
+            Some(ServerMessage::Output { content, .. }) => assert_eq!(content, "shown"),
+            other => panic!("Expected output, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_lagging_subscriber_keeps_receiving() {
+        let state = AppState::new(Database::in_memory().unwrap());
+        let session_id = Uuid::new_v4();
+
+        let (live_tx, live_rx) = broadcast::channel(2);
+        let (_pause_tx, pause_rx) = watch::channel(false);
+        let (_filter_tx, filter_rx) = watch::channel(None);
+        let (tx, mut rx) = mpsc::channel(16);
+
+        let status = |status| ServerMessage::Status { session_id, status };
+        // Overflow the channel before the forwarder reads anything
+        for _ in 0..3 {
+            live_tx.send(status(SessionStatus::Running)).unwrap();
+        }
+        live_tx.send(status(SessionStatus::Completed)).unwrap();
+        live_tx.send(status(SessionStatus::Idle)).unwrap();
+
+        tokio::spawn(forward_subscription(
+            state,
+            session_id,
+            live_rx,
+            pause_rx,
+            filter_rx,
+            tx,
+            None,
+        ));
+
+        assert!(matches!(
+            rx.recv().await,
+            Some(ServerMessage::Lagged { skipped: 3, .. })
+        ));
+        assert!(matches!(
+            rx.recv().await,
+            Some(ServerMessage::Status { status: SessionStatus::Completed, .. })
+        ));
+        assert!(matches!(
+            rx.recv().await,
+            Some(ServerMessage::Status { status: SessionStatus::Idle, .. })
+        ));
+
+        live_tx.send(status(SessionStatus::Running)).unwrap();
+        assert!(matches!(
+            rx.recv().await,
+            Some(ServerMessage::Status { status: SessionStatus::Running, .. })
+        ));
+
+        // Only a closed channel ends the forwarder
+        drop(live_tx);
+        assert!(rx.recv().await.is_none());
+    }
+
+    #[test]
+    fn test_parse_max_message_size() {
+        assert_eq!(parse_max_message_size(" 4096 "), Ok(4096));
+        assert!(parse_max_message_size("0").is_err());
+        assert!(parse_max_message_size("-1").is_err());
+        assert!(parse_max_message_size("big").is_err());
+    }
+
+    #[test]
+    fn test_parse_heartbeat_timeout() {
+        assert_eq!(parse_heartbeat_timeout(" 45 "), Some(Duration::from_secs(45)));
+        assert_eq!(parse_heartbeat_timeout("0"), None);
+        assert_eq!(parse_heartbeat_timeout("-5"), None);
+        assert_eq!(parse_heartbeat_timeout("soon"), None);
+
+        let state = AppState::new(Database::in_memory().unwrap());
+        assert_eq!(heartbeat_timeout(&state), DEFAULT_WS_HEARTBEAT_TIMEOUT);
+        state.config.set(WS_HEARTBEAT_TIMEOUT_KEY, "10").unwrap();
+        assert_eq!(heartbeat_timeout(&state), Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_authorize_checks_token_when_required() {
+        let state = AppState::new(Database::in_memory().unwrap());
+        let mut headers = HeaderMap::new();
+
+        // Open by default
+        assert!(authorize(&state, &headers, None).is_ok());
+
+        state.config.set(WS_AUTH_REQUIRED_KEY, "true").unwrap();
+        assert!(matches!(
+            authorize(&state, &headers, Some("s3cret")),
+            Err(AppError::Unauthorized(_))
+        ));
+
+        state.config.set(WS_AUTH_TOKEN_KEY, "s3cret").unwrap();
+        assert!(authorize(&state, &headers, None).is_err());
+        assert!(authorize(&state, &headers, Some("wrong")).is_err());
+        assert!(authorize(&state, &headers, Some("s3cret")).is_ok());
+
+        headers.insert(AUTHORIZATION, "Bearer s3cret".parse().unwrap());
+        assert!(authorize(&state, &headers, None).is_ok());
+        headers.insert(AUTHORIZATION, "Bearer wrong".parse().unwrap());
+        assert!(authorize(&state, &headers, None).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_auth_settings_are_protected() {
+        let state = AppState::new(Database::in_memory().unwrap());
+        state.config.set(WS_AUTH_REQUIRED_KEY, "true").unwrap();
+        state.config.set(WS_AUTH_TOKEN_KEY, "s3cret").unwrap();
+        let server = axum_test::TestServer::new(crate::create_app(state.clone())).unwrap();
+
+        server
+            .put(&format!("/api/config/{}", WS_AUTH_REQUIRED_KEY))
+            .json(&serde_json::json!({ "value": "false" }))
+            .await
+            .assert_status_unauthorized();
+        assert!(authorize(&state, &HeaderMap::new(), None).is_err());
+
+        let config: serde_json::Value = server.get("/api/config").await.json();
+        assert_eq!(config["config"][WS_AUTH_TOKEN_KEY], crate::db::secrets::REDACTED);
+        assert_eq!(config["config"][WS_AUTH_REQUIRED_KEY], crate::db::secrets::REDACTED);
+        let value: serde_json::Value =
+            server.get(&format!("/api/config/{}", WS_AUTH_TOKEN_KEY)).await.json();
+        assert_eq!(value["value"], crate::db::secrets::REDACTED);
+    }
+
+    #[test]
+    fn test_parse_filter_rejects_unknown_types() {
+        let err = parse_filter(Some(vec!["status".to_string(), "bogus".to_string()])).unwrap_err();
+        assert!(err.contains("'bogus'"));
+        assert_eq!(parse_filter(None).unwrap(), None);
+    }
+}
@@ -3,23 +3,38 @@
 import type {
   Repo,
   AddRepoRequest,
+  AddRepoWithSessionRequest,
+  RepoWithSessionResponse,
+  RepoTagsResponse,
   ScanRequest,
   ScanResponse,
   CloneRepoRequest,
   CloneRepoResponse,
   Session,
+  SessionSummary,
   SessionDetails,
   CreateSessionRequest,
+  RerunSessionRequest,
+  PruneSessionsRequest,
+  SessionCapacityResponse,
+  PruneSessionsResponse,
   RunSessionRequest,
   RunSessionResponse,
   CancelSessionResponse,
   OutputResponse,
+  SessionBundle,
+  MessageSearchResponse,
   GitStatusResponse,
   GitLogResponse,
   GitBranchesResponse,
+  GitBranchesDetailedResponse,
+  GitRemotesResponse,
+  AddRemoteRequest,
   GitDiffResponse,
   GitCommandResponse,
+  GitPullResponse,
   CommitRequest,
+  PushRequest,
   ResetRequest,
   CheckoutRequest,
   ConfigResponse,
@@ -27,7 +42,9 @@ import type {
   ConfigValueResponse,
   SetConfigValueRequest,
   BackendsResponse,
+  Preset,
   PresetsResponse,
+  CreatePresetRequest,
 } from "./types";
 
 const API_BASE = "/api";
@@ -71,8 +88,8 @@ async function request<T>(
 
 // --- Repos ---
 
-export async function listRepos(): Promise<Repo[]> {
-  return request<Repo[]>("/repos");
+export async function listRepos(tag?: string): Promise<Repo[]> {
+  return request<Repo[]>(`/repos${tag ? `?tag=${encodeURIComponent(tag)}` : ""}`);
 }
 
 export async function addRepo(req: AddRepoRequest): Promise<Repo> {
@@ -82,10 +99,31 @@ export async function addRepo(req: AddRepoRequest): Promise<Repo> {
   });
 }
 
+export async function addRepoWithSession(
+  req: AddRepoWithSessionRequest
+): Promise<RepoWithSessionResponse> {
+  return request<RepoWithSessionResponse>("/repos/with-session", {
+    method: "POST",
+    body: JSON.stringify(req),
+  });
+}
+
 export async function deleteRepo(id: string): Promise<void> {
   await request<void>(`/repos/${id}`, { method: "DELETE" });
 }
 
+export async function getRepoTags(id: string): Promise<RepoTagsResponse> {
+  return request<RepoTagsResponse>(`/repos/${id}/tags`);
+}
+
+// Replace the repo's tags; the response has them trimmed, de-duplicated and sorted
+export async function setRepoTags(id: string, tags: string[]): Promise<RepoTagsResponse> {
+  return request<RepoTagsResponse>(`/repos/${id}/tags`, {
+    method: "PUT",
+    body: JSON.stringify({ tags }),
+  });
+}
+
 export async function scanRepos(req: ScanRequest): Promise<ScanResponse> {
   return request<ScanResponse>("/repos/scan", {
     method: "POST",
@@ -102,8 +140,8 @@ export async function cloneRepo(req: CloneRepoRequest): Promise<CloneRepoRespons
 
 // --- Sessions ---
 
-export async function listSessions(): Promise<Session[]> {
-  return request<Session[]>("/sessions");
+export async function listSessions(includeArchived = false): Promise<SessionSummary[]> {
+  return request<SessionSummary[]>(`/sessions${includeArchived ? "?include_archived=true" : ""}`);
 }
 
 export async function getSession(id: string): Promise<SessionDetails> {
@@ -117,10 +155,41 @@ export async function createSession(req: CreateSessionRequest): Promise<Session>
   });
 }
 
+export async function rerunSession(
+  id: string,
+  req: RerunSessionRequest = {}
+): Promise<SessionDetails> {
+  return request<SessionDetails>(`/sessions/${id}/rerun`, {
+    method: "POST",
+    body: JSON.stringify(req),
+  });
+}
+
 export async function deleteSession(id: string): Promise<void> {
   await request<void>(`/sessions/${id}`, { method: "DELETE" });
 }
 
+// Running sessions against the configured limit, e.g. to disable starting more
+export async function getSessionCapacity(): Promise<SessionCapacityResponse> {
+  return request<SessionCapacityResponse>("/sessions/capacity");
+}
+
+// Delete every session with the given status; resolves to the number removed
+export async function pruneSessions(req: PruneSessionsRequest): Promise<PruneSessionsResponse> {
+  return request<PruneSessionsResponse>("/sessions/prune", {
+    method: "POST",
+    body: JSON.stringify(req),
+  });
+}
+
+export async function archiveSession(id: string): Promise<Session> {
+  return request<Session>(`/sessions/${id}/archive`, { method: "POST" });
+}
+
+export async function unarchiveSession(id: string): Promise<Session> {
+  return request<Session>(`/sessions/${id}/unarchive`, { method: "POST" });
+}
+
 export async function runSession(
   id: string,
   req: RunSessionRequest
@@ -150,6 +219,43 @@ export async function getSessionOutput(
   return request<OutputResponse>(`/sessions/${id}/output${query ? `?${query}` : ""}`);
 }
 
+// URL of the session's output as a downloadable .log file (for use as a link href)
+export function sessionOutputDownloadUrl(
+  id: string,
+  params?: { stream?: "all" | "stdout" | "stderr"; timestamps?: boolean }
+): string {
+  const searchParams = new URLSearchParams();
+  if (params?.stream) searchParams.set("stream", params.stream);
+  if (params?.timestamps) searchParams.set("timestamps", "true");
+
+  const query = searchParams.toString();
+  return `${API_BASE}/sessions/${id}/output/download${query ? `?${query}` : ""}`;
+}
+
+// URL of the session as a downloadable JSON bundle (for use as a link href)
+export function sessionExportUrl(id: string): string {
+  return `${API_BASE}/sessions/${id}/export`;
+}
+
+// Recreate an exported session; without repoId, the bundle's repo is matched by path or name
+export async function importSession(bundle: SessionBundle, repoId?: string): Promise<Session> {
+  const query = repoId ? `?repo_id=${encodeURIComponent(repoId)}` : "";
+  return request<Session>(`/sessions/import${query}`, {
+    method: "POST",
+    body: JSON.stringify(bundle),
+  });
+}
+
+export async function searchMessages(
+  q: string,
+  limit?: number
+): Promise<MessageSearchResponse> {
+  const searchParams = new URLSearchParams({ q });
+  if (limit) searchParams.set("limit", String(limit));
+
+  return request<MessageSearchResponse>(`/messages/search?${searchParams}`);
+}
+
 // --- Git ---
 
 export async function getGitStatus(sessionId: string): Promise<GitStatusResponse> {
@@ -168,19 +274,53 @@ export async function getGitBranches(sessionId: string): Promise<GitBranchesResp
   return request<GitBranchesResponse>(`/sessions/${sessionId}/git/branches`);
 }
 
+export async function getGitBranchesDetailed(
+  sessionId: string
+): Promise<GitBranchesDetailedResponse> {
+  return request<GitBranchesDetailedResponse>(`/sessions/${sessionId}/git/branches/detailed`);
+}
+
+export async function getGitRemotes(sessionId: string): Promise<GitRemotesResponse> {
+  return request<GitRemotesResponse>(`/sessions/${sessionId}/git/remotes`);
+}
+
+export async function addGitRemote(
+  sessionId: string,
+  req: AddRemoteRequest
+): Promise<GitRemotesResponse> {
+  return request<GitRemotesResponse>(`/sessions/${sessionId}/git/remotes`, {
+    method: "POST",
+    body: JSON.stringify(req),
+  });
+}
+
+export async function removeGitRemote(
+  sessionId: string,
+  name: string
+): Promise<GitRemotesResponse> {
+  return request<GitRemotesResponse>(
+    `/sessions/${sessionId}/git/remotes/${encodeURIComponent(name)}`,
+    { method: "DELETE" }
+  );
+}
+
 export async function getGitDiff(sessionId: string): Promise<GitDiffResponse> {
   return request<GitDiffResponse>(`/sessions/${sessionId}/git/diff`);
 }
 
-export async function gitPull(sessionId: string): Promise<GitCommandResponse> {
-  return request<GitCommandResponse>(`/sessions/${sessionId}/git/pull`, {
+export async function gitPull(sessionId: string): Promise<GitPullResponse> {
+  return request<GitPullResponse>(`/sessions/${sessionId}/git/pull`, {
     method: "POST",
   });
 }
 
-export async function gitPush(sessionId: string): Promise<GitCommandResponse> {
+export async function gitPush(
+  sessionId: string,
+  req: PushRequest = {}
+): Promise<GitCommandResponse> {
   return request<GitCommandResponse>(`/sessions/${sessionId}/git/push`, {
     method: "POST",
+    body: JSON.stringify(req),
   });
 }
 
@@ -245,6 +385,28 @@ export async function deleteConfigValue(key: string): Promise<void> {
   await request<void>(`/config/${encodeURIComponent(key)}`, { method: "DELETE" });
 }
 
+// Per-session overrides; keys not set on the session fall back to global config
+export async function getSessionConfig(id: string): Promise<ConfigResponse> {
+  return request<ConfigResponse>(`/sessions/${id}/config`);
+}
+
+export async function setSessionConfigValue(
+  id: string,
+  key: string,
+  req: SetConfigValueRequest
+): Promise<ConfigValueResponse> {
+  return request<ConfigValueResponse>(`/sessions/${id}/config/${encodeURIComponent(key)}`, {
+    method: "PUT",
+    body: JSON.stringify(req),
+  });
+}
+
+export async function deleteSessionConfigValue(id: string, key: string): Promise<void> {
+  await request<void>(`/sessions/${id}/config/${encodeURIComponent(key)}`, {
+    method: "DELETE",
+  });
+}
+
 export async function listBackends(): Promise<BackendsResponse> {
   return request<BackendsResponse>("/config/backends");
 }
@@ -252,3 +414,14 @@ export async function listBackends(): Promise<BackendsResponse> {
 export async function listPresets(): Promise<PresetsResponse> {
   return request<PresetsResponse>("/config/presets");
 }
+
+export async function createPreset(req: CreatePresetRequest): Promise<Preset> {
+  return request<Preset>("/config/presets", {
+    method: "POST",
+    body: JSON.stringify(req),
+  });
+}
+
+export async function deletePreset(id: string): Promise<void> {
+  await request<void>(`/config/presets/${encodeURIComponent(id)}`, { method: "DELETE" });
+}
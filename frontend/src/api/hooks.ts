@@ -35,7 +35,7 @@ export const queryKeys = {
 export function useRepos() {
   return useQuery({
     queryKey: queryKeys.repos,
-    queryFn: api.listRepos,
+    queryFn: () => api.listRepos(),
   });
 }
 
@@ -15,9 +15,27 @@ export interface AddRepoRequest {
   name?: string;
 }
 
+export interface AddRepoWithSessionRequest extends AddRepoRequest {
+  session?: {
+    name?: string;
+    orchestrator?: OrchestratorType;
+  };
+}
+
+export interface RepoWithSessionResponse {
+  repo: Repo;
+  session: Session;
+}
+
+export interface RepoTagsResponse {
+  repo_id: string;
+  tags: string[];
+}
+
 export interface ScanRequest {
   directories: string[];
   depth?: number;
+  exclude?: string[];
 }
 
 export interface FoundRepo {
@@ -31,6 +49,19 @@ export interface ScanResponse {
 
 export interface CloneRepoRequest {
   url: string;
+  dest?: string;
+  name?: string;
+}
+
+export interface CloneCheckRequest {
+  url: string;
+}
+
+export interface RemoteCheck {
+  reachable: boolean;
+  auth_required: boolean;
+  default_branch: string | null;
+  error: string | null;
 }
 
 export interface CloneRepoResponse {
@@ -89,20 +120,60 @@ export interface CloneErrorInfo {
 
 export type SessionStatus = "idle" | "running" | "completed" | "error" | "cancelled";
 
+export type ExitReason =
+  | "completed_success"
+  | "completed_with_changes"
+  | "no_changes"
+  | "cancelled"
+  | "timed_out"
+  | "crashed";
+
 export interface Session {
   id: string;
   repo_id: string;
   name: string | null;
   orchestrator: OrchestratorType;
   status: SessionStatus;
+  archived: boolean;
+  branch: string | null;
+  exit_reason: ExitReason | null;
   created_at: string;
   updated_at: string;
 }
 
+// Session list entry, with message activity for sorting by recency
+export interface SessionSummary extends Session {
+  message_count: number;
+  last_message_at: string | null;
+}
+
 export interface CreateSessionRequest {
   repo_id: string;
   name?: string;
   orchestrator?: OrchestratorType;
+  auto_start?: boolean;
+  prompt?: string;
+  preset?: string;
+}
+
+export interface RerunSessionRequest {
+  name?: string;
+  start?: boolean;
+  prompt?: string;
+}
+
+export interface SessionCapacityResponse {
+  running: number;
+  max_concurrent_sessions: number | null;
+  can_start: boolean;
+}
+
+export interface PruneSessionsRequest {
+  status: Exclude<SessionStatus, "running">;
+}
+
+export interface PruneSessionsResponse {
+  deleted: number;
 }
 
 export type MessageRole = "user" | "assistant" | "system";
@@ -112,9 +183,15 @@ export interface Message {
   session_id: string;
   role: MessageRole;
   content: string;
+  incomplete: boolean;
   created_at: string;
 }
 
+export interface MessageSearchResponse {
+  query: string;
+  messages: Message[];
+}
+
 export interface SessionDetails {
   id: string;
   repo_id: string;
@@ -149,6 +226,7 @@ export interface OutputLog {
   session_id: string;
   stream: OutputStream;
   content: string;
+  truncated: boolean;
   created_at: string;
 }
 
@@ -158,10 +236,20 @@ export interface OutputResponse {
   total: number;
 }
 
+// A session exported with its repo, messages and output, for import elsewhere
+export interface SessionBundle {
+  version: number;
+  session: Session;
+  repo: Repo;
+  messages: Message[];
+  output_logs: OutputLog[];
+}
+
 // --- Git ---
 
 export interface GitStatus {
   branch: string;
+  upstream: string | null;
   ahead: number;
   behind: number;
   staged: string[];
@@ -172,6 +260,7 @@ export interface GitStatus {
 export interface GitStatusResponse {
   session_id: string;
   branch: string;
+  upstream: string | null;
   ahead: number;
   behind: number;
   staged: string[];
@@ -184,6 +273,7 @@ export interface Commit {
   message: string;
   author: string;
   time: string;
+  session_id: string | null;
 }
 
 export interface GitLogResponse {
@@ -191,6 +281,14 @@ export interface GitLogResponse {
   commits: Commit[];
 }
 
+export interface GitSearchCommitsResponse {
+  session_id: string;
+  query: string;
+  commits: Commit[];
+  scanned: number;
+  truncated: boolean;
+}
+
 export interface Branch {
   name: string;
   is_current: boolean;
@@ -202,6 +300,40 @@ export interface GitBranchesResponse {
   branches: Branch[];
 }
 
+export interface BranchTip {
+  id: string;
+  short_id: string;
+  summary: string;
+  author: string;
+  timestamp: string;
+}
+
+export interface BranchDetail extends Branch {
+  tip: BranchTip | null;
+}
+
+export interface GitBranchesDetailedResponse {
+  session_id: string;
+  branches: BranchDetail[];
+}
+
+export interface GitRemote {
+  name: string;
+  fetch_url: string | null;
+  // The remote's push URL if set, otherwise its fetch URL
+  push_url: string | null;
+}
+
+export interface GitRemotesResponse {
+  session_id: string;
+  remotes: GitRemote[];
+}
+
+export interface AddRemoteRequest {
+  name: string;
+  url: string;
+}
+
 export interface FileDelta {
   path: string;
   added: number;
@@ -215,6 +347,50 @@ export interface GitDiffResponse {
   total_removed: number;
 }
 
+export interface GitCompareChangesResponse {
+  a: string;
+  b: string;
+  files: FileDelta[];
+  total_added: number;
+  total_removed: number;
+  patch: string;
+}
+
+export interface GitSessionSummaryResponse {
+  session_id: string;
+  base_sha: string;
+  head_sha: string;
+  commits: Commit[];
+  files: FileDelta[];
+  files_changed: number;
+  total_added: number;
+  total_removed: number;
+  description: string;
+}
+
+export interface CommitSignature {
+  name: string;
+  email: string;
+  timestamp: string;
+}
+
+export interface CommitDetail {
+  id: string;
+  short_id: string;
+  message: string;
+  author: CommitSignature;
+  committer: CommitSignature;
+  parents: string[];
+  session_id: string | null;
+  files: FileDelta[];
+  diff: string;
+}
+
+export interface GitCommitDetailResponse {
+  session_id: string;
+  commit: CommitDetail;
+}
+
 export interface CommandOutput {
   success: boolean;
   stdout: string;
@@ -228,11 +404,26 @@ export interface GitCommandResponse {
   stderr: string;
 }
 
+export interface GitPullResponse extends GitCommandResponse {
+  conflicts: string[];
+  needs_resolution: boolean;
+}
+
 export interface CommitRequest {
   message: string;
   stage_all?: boolean;
 }
 
+export interface PushRequest {
+  set_upstream?: boolean;
+  // Sent as --force-with-lease, so others' pushes since the last fetch are never clobbered
+  force?: boolean;
+  // Defaults to the branch's upstream remote, else origin
+  remote?: string;
+  // Defaults to the current branch
+  branch?: string;
+}
+
 export interface ResetRequest {
   confirm: boolean;
 }
@@ -241,6 +432,27 @@ export interface CheckoutRequest {
   branch: string;
 }
 
+export interface StashEntry {
+  index: number;
+  message: string;
+  created_at: string;
+}
+
+export interface GitStashResponse {
+  session_id: string;
+  entries: StashEntry[];
+}
+
+export interface CreateBranchRequest {
+  name: string;
+  from?: string;
+}
+
+export interface GitBranchResponse {
+  session_id: string;
+  branch: Branch;
+}
+
 // --- Config ---
 
 export interface ConfigResponse {
@@ -274,6 +486,15 @@ export interface Preset {
   id: string;
   name: string;
   description: string;
+  prompt?: string;
+  builtin: boolean;
+}
+
+export interface CreatePresetRequest {
+  id: string;
+  name: string;
+  description?: string;
+  prompt: string;
 }
 
 export interface PresetsResponse {
@@ -287,13 +508,23 @@ export type WsClientMessage =
   | { type: "subscribe"; session_id: string }
   | { type: "unsubscribe"; session_id: string }
   | { type: "cancel"; session_id: string }
+  | { type: "pause_output"; session_id: string }
+  | { type: "resume_output"; session_id: string }
+  | { type: "input"; session_id: string; content: string }
+  | { type: "set_filter"; session_id: string; types?: WsServerMessage["type"][] | null }
   | { type: "ping" };
 
 // Server → Client messages
 export type WsServerMessage =
   | { type: "subscribed"; session_id: string }
   | { type: "unsubscribed"; session_id: string }
-  | { type: "output"; session_id: string; stream: OutputStream; content: string }
-  | { type: "status"; session_id: string; status: SessionStatus }
+  | { type: "output"; session_id: string; stream: OutputStream; content: string; log_id?: number; truncated?: boolean }
+  | { type: "replay_complete"; session_id: string }
+  | { type: "output_paused"; session_id: string }
+  | { type: "output_resumed"; session_id: string }
+  | { type: "filter_set"; session_id: string; types: WsServerMessage["type"][] | null }
+  | { type: "status"; session_id: string; status: SessionStatus | "restarting" }
+  | { type: "lagged"; session_id: string; skipped: number }
   | { type: "error"; message: string }
+  | { type: "warning"; session_id: string; message: string }
   | { type: "pong" };
@@ -9,6 +9,30 @@ import type {
 const WS_URL = "ws://localhost:3000/api/ws";
 const RECONNECT_INTERVAL = 3000;
 const PING_INTERVAL = 30000;
+const WS_TOKEN_STORAGE_KEY = "ralphtown.wsToken";
+
+/**
+ * WebSocket URL, with the token for servers that set `ws_auth_required`.
+ * Open the UI once with `?token=...` to store it; later visits reuse it.
+ */
+function wsUrl(): string {
+  const params = new URLSearchParams(window.location.search);
+  const pageToken = params.get("token");
+  if (pageToken) {
+    localStorage.setItem(WS_TOKEN_STORAGE_KEY, pageToken);
+    // Keep the token out of the address bar and history
+    params.delete("token");
+    const search = params.toString();
+    window.history.replaceState(
+      null,
+      "",
+      `${window.location.pathname}${search ? `?${search}` : ""}${window.location.hash}`
+    );
+  }
+
+  const token = pageToken ?? localStorage.getItem(WS_TOKEN_STORAGE_KEY);
+  return token ? `${WS_URL}?token=${encodeURIComponent(token)}` : WS_URL;
+}
 
 export interface OutputLine {
   stream: OutputStream;
@@ -96,7 +120,7 @@ export function useWebSocket(options: UseWebSocketOptions = {}): UseWebSocketRet
       return;
     }
 
-    const ws = new WebSocket(WS_URL);
+    const ws = new WebSocket(wsUrl());
 
     ws.onopen = () => {
       setIsConnected(true);